@@ -38,7 +38,7 @@ pub async fn cli() -> Result<bool, anyhow::Error> {
                         .short('c')
                         .long("component")
                         .help(
-                            "reset data of the component: root, user, alert, dashboard, function, stream-stats",
+                            "reset data of the component: root, user, alert, dashboard, function, sql-macro, stream-stats",
                         ),
                 ),
             clap::Command::new("import")
@@ -234,6 +234,11 @@ pub async fn cli() -> Result<bool, anyhow::Error> {
                 }
                 "alert" => {
                     db::alerts::alert::reset().await?;
+                    db::alerts::notification_policies::reset().await?;
+                    db::alerts::silences::reset().await?;
+                    db::alerts::deliveries::reset().await?;
+                    db::alerts::notifier::reset().await?;
+                    db::alerts::anomaly::reset().await?;
                 }
                 "dashboard" => {
                     table::dashboards::delete_all().await?;
@@ -244,6 +249,9 @@ pub async fn cli() -> Result<bool, anyhow::Error> {
                 "function" => {
                     db::functions::reset().await?;
                 }
+                "sql-macro" => {
+                    db::sql_macros::reset().await?;
+                }
                 "stream-stats" => {
                     // reset stream stats update offset
                     db::compact::stats::set_offset(0, None).await?;
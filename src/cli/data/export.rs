@@ -65,6 +65,9 @@ impl Context for Export {
             skip_wal: false,
             streaming_output: false,
             streaming_id: None,
+            use_cursor: false,
+            cursor: None,
+            params: Default::default(),
         };
 
         let req = search::Request {
@@ -18,11 +18,13 @@ use std::sync::Arc;
 use config::{
     RwAHashMap, RwHashMap,
     meta::{
-        alerts::alert::Alert,
+        alerts::{alert::Alert, routing::NotificationPolicy, silence::Silence},
         dashboards::reports,
         destinations::{Destination, Template},
         function::Transform,
         promql::ClusterLeader,
+        redaction::MaskingPolicy,
+        sql_macro::SqlMacro,
         stream::StreamParams,
     },
 };
@@ -47,6 +49,12 @@ use crate::{
 // global cache variables
 pub static KVS: Lazy<RwHashMap<String, bytes::Bytes>> = Lazy::new(Default::default);
 pub static QUERY_FUNCTIONS: Lazy<RwHashMap<String, Transform>> = Lazy::new(DashMap::default);
+pub static SQL_MACROS: Lazy<RwHashMap<String, SqlMacro>> = Lazy::new(DashMap::default);
+pub static NOTIFICATION_POLICIES: Lazy<RwHashMap<String, NotificationPolicy>> =
+    Lazy::new(DashMap::default);
+pub static SILENCES: Lazy<RwHashMap<String, Silence>> = Lazy::new(DashMap::default);
+pub static MASKING_POLICIES: Lazy<RwHashMap<String, MaskingPolicy>> = Lazy::new(DashMap::default);
+pub static REDACTION_SALTS: Lazy<RwHashMap<String, String>> = Lazy::new(DashMap::default);
 pub static USERS: Lazy<RwHashMap<String, User>> = Lazy::new(DashMap::default);
 pub static USERS_RUM_TOKEN: Lazy<Arc<RwHashMap<String, User>>> =
     Lazy::new(|| Arc::new(DashMap::default()));
@@ -153,6 +153,18 @@ pub struct OrganizationSettingPayload {
     pub enable_websocket_search: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_auto_refresh_interval: Option<u32>,
+    /// Overrides `ZO_QUERY_MAX_CONCURRENT_PER_ORG`, 0 means no limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_max_concurrent_per_org: Option<usize>,
+    /// Overrides `ZO_QUERY_MAX_CONCURRENT_PER_USER`, 0 means no limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_max_concurrent_per_user: Option<usize>,
+    /// Overrides `ZO_QUERY_MAX_SCAN_SIZE` (in MB), 0 means no limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_max_scan_size: Option<usize>,
+    /// Overrides `ZO_QUERY_MAX_TIME_RANGE` (in hours), 0 means no limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_max_time_range: Option<i64>,
 }
 
 #[derive(Serialize, ToSchema, Deserialize, Debug, Clone)]
@@ -171,6 +183,15 @@ pub struct OrganizationSetting {
     pub enable_websocket_search: bool,
     #[serde(default = "default_auto_refresh_interval")]
     pub min_auto_refresh_interval: u32,
+    /// Query limits of the org, the config applies when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_max_concurrent_per_org: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_max_concurrent_per_user: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_max_scan_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_max_time_range: Option<i64>,
 }
 
 impl Default for OrganizationSetting {
@@ -182,6 +203,10 @@ impl Default for OrganizationSetting {
             toggle_ingestion_logs: default_toggle_ingestion_logs(),
             enable_websocket_search: default_enable_websocket_search(),
             min_auto_refresh_interval: default_auto_refresh_interval(),
+            query_max_concurrent_per_org: None,
+            query_max_concurrent_per_user: None,
+            query_max_scan_size: None,
+            query_max_time_range: None,
         }
     }
 }
@@ -240,6 +240,7 @@ async fn migrate_pipelines() -> Result<(), anyhow::Error> {
                         ider::uuid(),
                         NodeData::Condition(ConditionParams {
                             conditions: routing_conditions,
+                            condition: None,
                         }),
                         pos_x,
                         pos_y,
@@ -3047,6 +3047,7 @@ mod tests {
                 feature_query_streaming_aggs: bool::default(),
                 feature_join_match_one_enabled: bool::default(),
                 feature_join_right_side_max_rows: usize::default(),
+                feature_enrichment_lookup_join_enabled: bool::default(),
                 feature_query_skip_wal: bool::default(),
                 wal_write_queue_enabled: bool::default(),
                 wal_write_queue_full_reject: bool::default(),
@@ -3162,6 +3163,12 @@ mod tests {
                 query_default_limit: i64::default(),
                 query_partition_by_secs: usize::default(),
                 query_group_base_speed: usize::default(),
+                query_max_concurrent_per_org: usize::default(),
+                query_max_concurrent_per_user: usize::default(),
+                query_quota_wait_timeout: u64::default(),
+                query_max_scan_size: usize::default(),
+                query_max_time_range: i64::default(),
+                query_memory_limit: usize::default(),
                 circuit_breaker_enabled: bool::default(),
                 circuit_breaker_watching_window: i64::default(),
                 circuit_breaker_reset_window_num: i64::default(),
@@ -3197,6 +3204,10 @@ mod tests {
                 alert_schedule_concurrency: i64::default(),
                 alert_schedule_timeout: i64::default(),
                 report_schedule_timeout: i64::default(),
+                alert_notification_max_retries: u32::default(),
+                alert_notification_retry_backoff: i64::default(),
+                alert_notification_rate_limit: u32::default(),
+                alert_delivery_log_retention: i64::default(),
                 derived_stream_schedule_interval: i64::default(),
                 scheduler_max_retries: i32::default(),
                 pause_alerts_on_retries: bool::default(),
@@ -3420,6 +3431,12 @@ mod tests {
                 offset_flush_interval: u64::default(),
                 remote_request_timeout: u64::default(),
                 remote_request_max_retry_time: u64::default(),
+                remote_batch_size: usize::default(),
+                max_versions: usize::default(),
+                backfill_batch_size: i64::default(),
+                backfill_max_records_per_sec: u64::default(),
+                backfill_interval: u64::default(),
+                backfill_timeout: i64::default(),
                 max_connections: usize::default(),
                 wal_size_limit: u64::default(),
             },
@@ -725,6 +725,12 @@ pub struct Common {
         help = "Default to 50_000 when ZO_FEATURE_JOIN_MATCH_ONE_ENABLED is true"
     )]
     pub feature_join_right_side_max_rows: usize,
+    #[env_config(
+        name = "ZO_FEATURE_ENRICHMENT_LOOKUP_JOIN_ENABLED",
+        default = true,
+        help = "Execute joins against enrichment tables as in-memory hash lookups on each querier"
+    )]
+    pub feature_enrichment_lookup_join_enabled: bool,
     #[env_config(
         name = "ZO_FEATURE_QUERY_SKIP_WAL",
         default = false,
@@ -1139,6 +1145,42 @@ pub struct Limit {
     pub query_partition_by_secs: usize,
     #[env_config(name = "ZO_QUERY_GROUP_BASE_SPEED", default = 768)] // MB/s/core
     pub query_group_base_speed: usize,
+    #[env_config(
+        name = "ZO_QUERY_MAX_CONCURRENT_PER_ORG",
+        default = 0,
+        help = "Max concurrent queries of an organization led by one node, the limit is not shared by the nodes, 0 means no limit"
+    )]
+    pub query_max_concurrent_per_org: usize,
+    #[env_config(
+        name = "ZO_QUERY_MAX_CONCURRENT_PER_USER",
+        default = 0,
+        help = "Max concurrent queries of a user led by one node, the limit is not shared by the nodes, 0 means no limit"
+    )]
+    pub query_max_concurrent_per_user: usize,
+    #[env_config(
+        name = "ZO_QUERY_QUOTA_WAIT_TIMEOUT",
+        default = 0,
+        help = "Seconds a query over the concurrency limit waits in queue before it is rejected, 0 means reject immediately"
+    )]
+    pub query_quota_wait_timeout: u64,
+    #[env_config(
+        name = "ZO_QUERY_MAX_SCAN_SIZE",
+        default = 0,
+        help = "Max size in MB of the files a query can scan, 0 means no limit"
+    )]
+    pub query_max_scan_size: usize,
+    #[env_config(
+        name = "ZO_QUERY_MAX_TIME_RANGE",
+        default = 0,
+        help = "Max time range in hours of a query, 0 means no limit"
+    )]
+    pub query_max_time_range: i64,
+    #[env_config(
+        name = "ZO_QUERY_MEMORY_LIMIT",
+        default = 0,
+        help = "Max memory in MB DataFusion can use for one query on a node, 0 means use the whole datafusion memory pool, needs a datafusion memory pool"
+    )]
+    pub query_memory_limit: usize,
     #[env_config(name = "ZO_INGEST_ALLOWED_UPTO", default = 5)] // in hours - in past
     pub ingest_allowed_upto: i64,
     #[env_config(name = "ZO_INGEST_FLATTEN_LEVEL", default = 3)] // default flatten level
@@ -1215,6 +1257,26 @@ pub struct Limit {
     pub alert_schedule_timeout: i64,
     #[env_config(name = "ZO_REPORT_SCHEDULE_TIMEOUT", default = 300)] // seconds
     pub report_schedule_timeout: i64,
+    #[env_config(
+        name = "ZO_ALERT_NOTIFICATION_MAX_RETRIES",
+        default = 5,
+        help = "Times a failed alert notification is retried before it goes to the dead-letter list"
+    )]
+    pub alert_notification_max_retries: u32,
+    #[env_config(
+        name = "ZO_ALERT_NOTIFICATION_RETRY_BACKOFF",
+        default = 30,
+        help = "Wait in seconds before the first retry of a failed alert notification, doubled on every retry"
+    )]
+    pub alert_notification_retry_backoff: i64,
+    #[env_config(
+        name = "ZO_ALERT_NOTIFICATION_RATE_LIMIT",
+        default = 0,
+        help = "Notifications sent to a destination per minute, the others are queued. 0 is unlimited"
+    )]
+    pub alert_notification_rate_limit: u32,
+    #[env_config(name = "ZO_ALERT_DELIVERY_LOG_RETENTION", default = 7)] // days
+    pub alert_delivery_log_retention: i64,
     #[env_config(name = "ZO_DERIVED_STREAM_SCHEDULE_INTERVAL", default = 300)] // seconds
     pub derived_stream_schedule_interval: i64,
     #[env_config(name = "ZO_SCHEDULER_MAX_RETRIES", default = 3)]
@@ -1722,6 +1784,42 @@ pub struct Pipeline {
         help = "pipeline exporter client request max retry times, default 1440 minutes(24 hours)， unit is seconds"
     )]
     pub remote_request_max_retry_time: u64,
+    #[env_config(
+        name = "ZO_PIPELINE_REMOTE_BATCH_SIZE",
+        default = 1000,
+        help = "max number of records sent to a remote destination in a request"
+    )]
+    pub remote_batch_size: usize,
+    #[env_config(
+        name = "ZO_PIPELINE_MAX_VERSIONS",
+        default = 50,
+        help = "max number of versions kept in the history of a pipeline, 0 keeps all of them"
+    )]
+    pub max_versions: usize,
+    #[env_config(
+        name = "ZO_PIPELINE_BACKFILL_BATCH_SIZE",
+        default = 1000,
+        help = "number of records a backfill job reads from the source stream at once"
+    )]
+    pub backfill_batch_size: i64,
+    #[env_config(
+        name = "ZO_PIPELINE_BACKFILL_MAX_RECORDS_PER_SEC",
+        default = 10000,
+        help = "default and max number of records a backfill job processes per second, 0 is unlimited"
+    )]
+    pub backfill_max_records_per_sec: u64,
+    #[env_config(
+        name = "ZO_PIPELINE_BACKFILL_INTERVAL",
+        default = 10,
+        help = "interval in seconds at which the alert manager looks for backfill jobs to run"
+    )]
+    pub backfill_interval: u64,
+    #[env_config(
+        name = "ZO_PIPELINE_BACKFILL_TIMEOUT",
+        default = 600,
+        help = "seconds without progress after which a running backfill job is resumed by another node"
+    )]
+    pub backfill_timeout: i64,
     #[env_config(
         name = "ZO_PIPELINE_WAL_SIZE_LIMIT",
         default = 0,
@@ -2299,6 +2397,20 @@ fn check_memory_config(cfg: &mut Config) -> Result<(), anyhow::Error> {
     if cfg.limit.query_default_limit == 0 {
         cfg.limit.query_default_limit = 1000;
     }
+    // the memory limit of a query is the size of its memory pool
+    if cfg.limit.query_memory_limit > 0
+        && matches!(
+            cfg.memory_cache
+                .datafusion_memory_pool
+                .to_lowercase()
+                .as_str(),
+            "none" | "off"
+        )
+    {
+        return Err(anyhow::anyhow!(
+            "ZO_QUERY_MEMORY_LIMIT needs a datafusion memory pool, please set ZO_MEMORY_CACHE_DATAFUSION_MEMORY_POOL to fair or greedy"
+        ));
+    }
     Ok(())
 }
 
@@ -2538,6 +2650,9 @@ fn check_pipeline_config(cfg: &mut Config) -> Result<(), anyhow::Error> {
     if cfg.pipeline.offset_flush_interval == 0 {
         cfg.pipeline.offset_flush_interval = 10;
     }
+    if cfg.pipeline.remote_batch_size == 0 {
+        cfg.pipeline.remote_batch_size = 1000;
+    }
     if cfg.pipeline.remote_request_max_retry_time == 0 {
         cfg.pipeline.remote_request_max_retry_time = 86400; // 24 hours, in seconds
     }
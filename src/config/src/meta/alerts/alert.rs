@@ -20,8 +20,10 @@ use svix_ksuid::Ksuid;
 use utoipa::ToSchema;
 
 use crate::{
+    TIMESTAMP_COL_NAME,
     meta::{
-        alerts::{QueryCondition, TriggerCondition},
+        alerts::{QueryCondition, QueryType, TriggerCondition},
+        sql::Sql,
         stream::StreamType,
         triggers::{ScheduledTriggerData, Trigger},
     },
@@ -178,6 +180,51 @@ impl Alert {
     pub fn set_last_triggered_at(&mut self, last_triggered_at: Option<i64>) {
         self.last_triggered_at = last_triggered_at;
     }
+
+    /// Returns the fields which identify a group in the evaluation results,
+    /// the state of each group of a stateful alert is tracked separately.
+    /// PromQL alerts are grouped by the labels of the series in `row` and
+    /// composite alerts by their join labels.
+    pub fn get_group_by_fields(&self, row: Option<&json::Map<String, json::Value>>) -> Vec<String> {
+        match self.query_condition.query_type {
+            QueryType::Custom => self
+                .query_condition
+                .aggregation
+                .as_ref()
+                .and_then(|agg| agg.group_by.clone())
+                .unwrap_or_default(),
+            QueryType::SQL => {
+                let Some(Ok(sql)) = self.query_condition.sql.as_deref().map(Sql::new) else {
+                    return vec![];
+                };
+                // time buckets are not groups, every bucket would resolve on its own
+                sql.group_by
+                    .into_iter()
+                    .filter(|field| {
+                        field != TIMESTAMP_COL_NAME
+                            && !sql
+                                .field_alias
+                                .iter()
+                                .any(|(expr, alias)| alias == field && expr.contains("histogram"))
+                    })
+                    .collect()
+            }
+            QueryType::PromQL => row
+                .map(|row| {
+                    row.keys()
+                        .filter(|k| *k != TIMESTAMP_COL_NAME && *k != "value")
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default(),
+            QueryType::Composite => self
+                .query_condition
+                .composite
+                .as_ref()
+                .map(|c| c.join_on.clone())
+                .unwrap_or_default(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
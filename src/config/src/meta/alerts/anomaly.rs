@@ -0,0 +1,237 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{QueryCondition, QueryType};
+
+/// Anomaly detection of a scheduled alert. Instead of a fixed threshold, the
+/// value of every group of the query result is compared with the baseline
+/// learnt from the previous evaluations in the same season, e.g. the same
+/// hour of the same day of the week, and the group fires when the value
+/// deviates from the baseline by more than `sensitivity` sigmas.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AnomalyDetection {
+    #[serde(default)]
+    pub method: AnomalyMethod,
+    #[serde(default)]
+    pub seasonality: Seasonality,
+    /// Deviation from the baseline, in sigmas, above which a value is an anomaly
+    #[serde(default = "default_sensitivity")]
+    pub sensitivity: f64,
+    #[serde(default)]
+    pub direction: AnomalyDirection,
+    /// Column of the query result holding the value, `alert_agg_value` for
+    /// custom queries and `value` for PromQL queries by default
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_column: Option<String>,
+    /// Occurrences a season needs before its values are checked, the alert
+    /// only learns until then
+    #[serde(default = "default_min_samples")]
+    pub min_samples: usize,
+    /// Occurrences kept for each season, the oldest are forgotten. Every
+    /// occurrence of a season, e.g. one hour, is a single sample: the median of
+    /// the values of the evaluations during the occurrence.
+    #[serde(default = "default_max_samples")]
+    pub max_samples: usize,
+}
+
+fn default_sensitivity() -> f64 {
+    3.0
+}
+
+fn default_min_samples() -> usize {
+    5
+}
+
+fn default_max_samples() -> usize {
+    30
+}
+
+impl Default for AnomalyDetection {
+    fn default() -> Self {
+        Self {
+            method: AnomalyMethod::default(),
+            seasonality: Seasonality::default(),
+            sensitivity: default_sensitivity(),
+            direction: AnomalyDirection::default(),
+            value_column: None,
+            min_samples: default_min_samples(),
+            max_samples: default_max_samples(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyMethod {
+    /// Median of the season, the sigma is estimated from the median absolute
+    /// deviation, robust to the previous anomalies
+    #[default]
+    MedianMad,
+    /// Holt-Winters smoothing of the values of the season, follows trends
+    HoltWinters,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Seasonality {
+    /// A single baseline
+    None,
+    /// A baseline for every hour of the day
+    HourOfDay,
+    /// A baseline for every hour of every day of the week
+    #[default]
+    HourOfWeek,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyDirection {
+    #[default]
+    Both,
+    /// Only values above the baseline are anomalies
+    Above,
+    /// Only values below the baseline are anomalies
+    Below,
+}
+
+impl AnomalyDetection {
+    pub fn validate(&self, query: &QueryCondition) -> Result<(), String> {
+        if !self.sensitivity.is_finite() || self.sensitivity <= 0.0 {
+            return Err("anomaly sensitivity should be greater than 0".to_string());
+        }
+        if self.min_samples < 2 {
+            return Err("anomaly min_samples should be at least 2".to_string());
+        }
+        if self.max_samples < self.min_samples {
+            return Err("anomaly max_samples can't be less than min_samples".to_string());
+        }
+        match query.query_type {
+            QueryType::Custom if query.aggregation.is_none() => {
+                Err("anomaly detection of a custom query needs an aggregation".to_string())
+            }
+            QueryType::SQL if self.value_column.as_deref().is_none_or(str::is_empty) => {
+                Err("anomaly detection of a SQL query needs a value_column".to_string())
+            }
+            QueryType::Composite => {
+                Err("composite alerts don't support anomaly detection".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn value_column<'a>(&'a self, query_type: &QueryType) -> &'a str {
+        match self.value_column.as_deref() {
+            Some(v) if !v.is_empty() => v,
+            _ if *query_type == QueryType::PromQL => "value",
+            _ => "alert_agg_value",
+        }
+    }
+}
+
+/// Learnt baselines of the groups of an anomaly alert, saved apart from the
+/// trigger between the evaluations
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyModel {
+    #[serde(default)]
+    #[serde(rename = "g")]
+    pub groups: HashMap<String, GroupBaseline>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GroupBaseline {
+    /// (microseconds) last evaluation the group had a value
+    #[serde(rename = "t")]
+    pub last_seen: i64,
+    /// sample of every past occurrence of the seasons, oldest first
+    #[serde(default)]
+    #[serde(rename = "s")]
+    pub seasons: HashMap<u32, Vec<f32>>,
+    /// values of the evaluations of the current occurrence, which become a
+    /// sample of its season once it is over
+    #[serde(default)]
+    #[serde(rename = "c")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<Occurrence>,
+}
+
+/// Values of one occurrence of a season
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Occurrence {
+    /// season of the occurrence
+    #[serde(rename = "s")]
+    pub season: u32,
+    /// hours since the epoch of the occurrence, tells the occurrences of the
+    /// same season apart
+    #[serde(rename = "h")]
+    pub hour: i64,
+    #[serde(rename = "v")]
+    pub values: Vec<f32>,
+}
+
+impl AnomalyModel {
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::alerts::{AggFunction, Aggregation, Condition, Operator};
+
+    #[test]
+    fn test_validate() {
+        let detection = AnomalyDetection::default();
+        let mut query = QueryCondition {
+            anomaly_detection: Some(detection.clone()),
+            ..Default::default()
+        };
+        assert!(detection.validate(&query).is_err());
+        query.aggregation = Some(Aggregation {
+            group_by: Some(vec!["host".to_string()]),
+            function: AggFunction::Avg,
+            having: Condition {
+                column: "latency".to_string(),
+                operator: Operator::GreaterThan,
+                value: 100.into(),
+                ignore_case: false,
+            },
+        });
+        assert!(detection.validate(&query).is_ok());
+        assert_eq!(detection.value_column(&query.query_type), "alert_agg_value");
+
+        query.query_type = QueryType::SQL;
+        assert!(detection.validate(&query).is_err());
+        let detection = AnomalyDetection {
+            value_column: Some("latency".to_string()),
+            ..Default::default()
+        };
+        assert!(detection.validate(&query).is_ok());
+        assert_eq!(detection.value_column(&query.query_type), "latency");
+
+        let detection = AnomalyDetection {
+            min_samples: 10,
+            max_samples: 5,
+            ..Default::default()
+        };
+        assert!(detection.validate(&query).is_err());
+    }
+}
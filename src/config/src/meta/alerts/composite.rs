@@ -0,0 +1,344 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::state::{AlertGroupState, AlertState, group_of};
+use crate::utils::json::{Map, Value};
+
+/// Max number of alerts a composite alert refers to
+pub const MAX_COMPOSITE_ALERTS: usize = 20;
+
+/// Condition of a composite alert: a boolean combination of the current states
+/// of other scheduled alerts of the organization, e.g. "error rate high AND
+/// latency high" or "A firing for 10 minutes AND NOT B".
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CompositeCondition {
+    pub expression: CompositeExpr,
+    /// Group by labels shared by the alerts. The expression is evaluated for
+    /// every value of the labels, the groups of an alert without these labels
+    /// count for every value.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub join_on: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CompositeExpr {
+    And {
+        conditions: Vec<CompositeExpr>,
+    },
+    Or {
+        conditions: Vec<CompositeExpr>,
+    },
+    Not {
+        condition: Box<CompositeExpr>,
+    },
+    /// Holds when a group of the alert is in the state
+    Alert {
+        alert_id: String,
+        #[serde(default)]
+        state: CompositeState,
+        /// (seconds) how long the group has to be in the state
+        #[serde(default)]
+        for_duration: i64,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CompositeState {
+    Pending,
+    #[default]
+    Firing,
+}
+
+impl From<CompositeState> for AlertState {
+    fn from(value: CompositeState) -> Self {
+        match value {
+            CompositeState::Pending => AlertState::Pending,
+            CompositeState::Firing => AlertState::Firing,
+        }
+    }
+}
+
+impl CompositeCondition {
+    /// IDs of the alerts the condition refers to, without duplicates
+    pub fn alert_ids(&self) -> Vec<&str> {
+        let mut ids = vec![];
+        self.expression.collect_alert_ids(&mut ids);
+        ids
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.expression.validate()?;
+        let ids = self.alert_ids();
+        if ids.len() > MAX_COMPOSITE_ALERTS {
+            return Err(format!(
+                "a composite alert refers to at most {MAX_COMPOSITE_ALERTS} alerts"
+            ));
+        }
+        if self.join_on.iter().any(|label| label.trim().is_empty()) {
+            return Err("join labels can't be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Evaluates the expression at `now` (microseconds) on the pending and
+    /// firing groups of every alert, given by alert ID. Returns a row with the
+    /// join labels for every value of the join labels the expression holds
+    /// for, `matched_alerts` lists the alerts found in their state.
+    pub fn evaluate(
+        &self,
+        groups: &HashMap<String, Vec<AlertGroupState>>,
+        now: i64,
+    ) -> Vec<Map<String, Value>> {
+        let mut keys = groups
+            .values()
+            .flatten()
+            .filter_map(|group| self.join_key(group))
+            .collect::<BTreeMap<_, _>>();
+        if keys.is_empty() {
+            keys.insert(String::new(), Map::new());
+        }
+        let mut rows = vec![];
+        for (key, labels) in keys {
+            let mut matched = vec![];
+            if !self.expression.eval(&mut |alert_id, state, for_duration| {
+                let holds = groups.get(alert_id).is_some_and(|groups| {
+                    groups.iter().any(|group| {
+                        group.state == AlertState::from(state)
+                            && now - group.since >= for_duration * 1_000_000
+                            && self.join_key(group).is_none_or(|(k, _)| k == key)
+                    })
+                });
+                if holds && !matched.contains(&alert_id) {
+                    matched.push(alert_id);
+                }
+                holds
+            }) {
+                continue;
+            }
+            let mut row = labels;
+            row.insert(
+                "matched_alerts".to_string(),
+                Value::String(matched.join(",")),
+            );
+            rows.push(row);
+        }
+        rows
+    }
+
+    /// Key and values of the join labels of the group, none when the group
+    /// misses one of the labels
+    fn join_key(&self, group: &AlertGroupState) -> Option<(String, Map<String, Value>)> {
+        self.join_on
+            .iter()
+            .all(|label| group.labels.contains_key(label))
+            .then(|| group_of(&group.labels, &self.join_on))
+    }
+}
+
+impl CompositeExpr {
+    fn collect_alert_ids<'a>(&'a self, ids: &mut Vec<&'a str>) {
+        match self {
+            CompositeExpr::And { conditions } | CompositeExpr::Or { conditions } => {
+                for condition in conditions {
+                    condition.collect_alert_ids(ids);
+                }
+            }
+            CompositeExpr::Not { condition } => condition.collect_alert_ids(ids),
+            CompositeExpr::Alert { alert_id, .. } => {
+                if !ids.contains(&alert_id.as_str()) {
+                    ids.push(alert_id);
+                }
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            CompositeExpr::And { conditions } | CompositeExpr::Or { conditions } => {
+                if conditions.is_empty() {
+                    return Err("and/or expressions need at least one condition".to_string());
+                }
+                conditions.iter().try_for_each(|c| c.validate())
+            }
+            CompositeExpr::Not { condition } => condition.validate(),
+            CompositeExpr::Alert {
+                alert_id,
+                for_duration,
+                ..
+            } => {
+                if alert_id.trim().is_empty() {
+                    return Err("alert ID is missing".to_string());
+                }
+                if *for_duration < 0 {
+                    return Err("for duration can not be negative".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Evaluates the expression, `holds` tells whether an alert is in a state
+    /// for a duration
+    fn eval<'a, F>(&'a self, holds: &mut F) -> bool
+    where
+        F: FnMut(&'a str, CompositeState, i64) -> bool,
+    {
+        match self {
+            CompositeExpr::And { conditions } => conditions.iter().all(|c| c.eval(holds)),
+            CompositeExpr::Or { conditions } => conditions.iter().any(|c| c.eval(holds)),
+            CompositeExpr::Not { condition } => !condition.eval(holds),
+            CompositeExpr::Alert {
+                alert_id,
+                state,
+                for_duration,
+            } => holds(alert_id, *state, *for_duration),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::json;
+
+    fn group(state: AlertState, since: i64, service: Option<&str>) -> AlertGroupState {
+        let mut labels = Map::new();
+        if let Some(service) = service {
+            labels.insert("service".to_string(), json::json!(service));
+        }
+        AlertGroupState {
+            state,
+            since,
+            labels,
+            ..Default::default()
+        }
+    }
+
+    fn alert(alert_id: &str, for_duration: i64) -> CompositeExpr {
+        CompositeExpr::Alert {
+            alert_id: alert_id.to_string(),
+            state: CompositeState::Firing,
+            for_duration,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let condition: CompositeCondition = json::from_str(
+            r#"{"expression": {"type": "and", "conditions": [
+                {"type": "alert", "alert_id": "a", "for_duration": 600},
+                {"type": "not", "condition": {"type": "alert", "alert_id": "b", "state": "pending"}}
+            ]}}"#,
+        )
+        .unwrap();
+        assert!(condition.validate().is_ok());
+        assert_eq!(condition.alert_ids(), vec!["a", "b"]);
+
+        let condition = CompositeCondition {
+            expression: CompositeExpr::Or { conditions: vec![] },
+            join_on: vec![],
+        };
+        assert!(condition.validate().is_err());
+        let condition = CompositeCondition {
+            expression: alert("a", -1),
+            join_on: vec![],
+        };
+        assert!(condition.validate().is_err());
+    }
+
+    #[test]
+    fn test_evaluate_for_duration_and_not() {
+        // A firing for 10 minutes without B
+        let condition = CompositeCondition {
+            expression: CompositeExpr::And {
+                conditions: vec![
+                    alert("a", 600),
+                    CompositeExpr::Not {
+                        condition: Box::new(alert("b", 0)),
+                    },
+                ],
+            },
+            join_on: vec![],
+        };
+        let now = 1_000_000_000_000;
+        let mut groups = HashMap::from([(
+            "a".to_string(),
+            vec![group(AlertState::Firing, now - 700_000_000, None)],
+        )]);
+        let rows = condition.evaluate(&groups, now);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["matched_alerts"], json::json!("a"));
+        assert!(condition.evaluate(&groups, now - 200_000_000).is_empty());
+
+        groups.insert("b".to_string(), vec![group(AlertState::Pending, now, None)]);
+        assert_eq!(condition.evaluate(&groups, now).len(), 1);
+        groups.insert("b".to_string(), vec![group(AlertState::Firing, now, None)]);
+        assert!(condition.evaluate(&groups, now).is_empty());
+    }
+
+    #[test]
+    fn test_evaluate_join_on() {
+        // error rate high and latency high on the same service
+        let condition = CompositeCondition {
+            expression: CompositeExpr::And {
+                conditions: vec![alert("errors", 0), alert("latency", 0)],
+            },
+            join_on: vec!["service".to_string()],
+        };
+        let groups = HashMap::from([
+            (
+                "errors".to_string(),
+                vec![
+                    group(AlertState::Firing, 0, Some("api")),
+                    group(AlertState::Firing, 0, Some("web")),
+                ],
+            ),
+            (
+                "latency".to_string(),
+                vec![
+                    group(AlertState::Firing, 0, Some("api")),
+                    group(AlertState::Pending, 0, Some("web")),
+                ],
+            ),
+        ]);
+        let rows = condition.evaluate(&groups, 0);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["service"], json::json!("api"));
+        assert_eq!(rows[0]["matched_alerts"], json::json!("errors,latency"));
+
+        // a group without the join labels counts for every service
+        let groups = HashMap::from([
+            (
+                "errors".to_string(),
+                vec![group(AlertState::Firing, 0, Some("api"))],
+            ),
+            (
+                "latency".to_string(),
+                vec![group(AlertState::Firing, 0, None)],
+            ),
+        ]);
+        let rows = condition.evaluate(&groups, 0);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["service"], json::json!("api"));
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::state::AlertState;
+use crate::utils::json::{Map, Value};
+
+/// Upper bound of the wait between two attempts of a delivery
+const MAX_BACKOFF_SECS: i64 = 3600;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Waiting for its next attempt
+    #[default]
+    Pending,
+    Delivered,
+    /// Failed permanently, in the dead-letter list until it is re-sent
+    Dead,
+}
+
+impl std::fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeliveryStatus::Pending => write!(f, "pending"),
+            DeliveryStatus::Delivered => write!(f, "delivered"),
+            DeliveryStatus::Dead => write!(f, "dead"),
+        }
+    }
+}
+
+impl From<&str> for DeliveryStatus {
+    fn from(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "delivered" => DeliveryStatus::Delivered,
+            "dead" => DeliveryStatus::Dead,
+            _ => DeliveryStatus::Pending,
+        }
+    }
+}
+
+/// One attempt to send a notification to its destination
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DeliveryAttempt {
+    /// (microseconds)
+    pub timestamp: i64,
+    /// (milliseconds) time taken by the destination to answer
+    pub latency_ms: i64,
+    /// HTTP status of the response, none for email and SNS destinations or
+    /// when the destination couldn't be reached
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    pub success: bool,
+    /// Response of the destination or the error
+    #[serde(default)]
+    pub response: String,
+    /// (seconds) wait asked by the `Retry-After` header of the response
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<i64>,
+}
+
+/// A notification of an alert to one of its destinations, kept with its
+/// attempts for the delivery log
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct Delivery {
+    pub id: String,
+    pub org_id: String,
+    pub alert_name: String,
+    pub destination: String,
+    pub destination_type: String,
+    pub alert_status: AlertState,
+    pub status: DeliveryStatus,
+    /// (microseconds)
+    pub created_at: i64,
+    /// (microseconds) when a pending delivery is attempted next
+    #[serde(default)]
+    pub next_attempt_at: i64,
+    /// Failed attempts since the delivery was queued or re-sent
+    #[serde(default)]
+    pub retries: u32,
+    #[serde(default)]
+    pub attempts: Vec<DeliveryAttempt>,
+    /// Rendered notification, sent as is by every attempt
+    pub title: String,
+    pub message: String,
+    /// The alert is loaded again by every retry
+    #[serde(default)]
+    pub alert_id: String,
+    /// First row of each group of the alert, only for the destinations which
+    /// send an event per group
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[schema(value_type = Vec<Object>)]
+    pub rows: Vec<Map<String, Value>>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct DeliveryList {
+    pub list: Vec<Delivery>,
+    /// `after` of the next page, none on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+impl Delivery {
+    /// Records the attempt and schedules the next one. Failed attempts are
+    /// retried `max_retries` times, waiting `backoff_secs` doubled on every
+    /// retry or the `retry_after` of the attempt when longer, unless they are
+    /// not `retryable`, then the delivery is dead.
+    pub fn record(
+        &mut self,
+        attempt: DeliveryAttempt,
+        retryable: bool,
+        max_retries: u32,
+        backoff_secs: i64,
+    ) {
+        let now = attempt.timestamp;
+        if attempt.success {
+            self.status = DeliveryStatus::Delivered;
+        } else if retryable && self.retries < max_retries {
+            self.status = DeliveryStatus::Pending;
+            let retry_after = attempt
+                .retry_after
+                .unwrap_or_default()
+                .min(MAX_BACKOFF_SECS);
+            let wait = backoff(self.retries, backoff_secs).max(retry_after);
+            self.next_attempt_at = now + wait * 1_000_000;
+            self.retries += 1;
+        } else {
+            self.status = DeliveryStatus::Dead;
+        }
+        self.attempts.push(attempt);
+    }
+}
+
+/// Seconds to wait before the retry following `retries` failed retries
+pub fn backoff(retries: u32, backoff_secs: i64) -> i64 {
+    backoff_secs
+        .max(1)
+        .saturating_mul(1 << retries.min(20))
+        .min(MAX_BACKOFF_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(timestamp: i64, success: bool) -> DeliveryAttempt {
+        DeliveryAttempt {
+            timestamp,
+            success,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(0, 30), 30);
+        assert_eq!(backoff(1, 30), 60);
+        assert_eq!(backoff(3, 30), 240);
+        assert_eq!(backoff(10, 30), MAX_BACKOFF_SECS);
+        assert_eq!(backoff(u32::MAX, 30), MAX_BACKOFF_SECS);
+    }
+
+    #[test]
+    fn test_record() {
+        let mut delivery = Delivery::default();
+        delivery.record(attempt(0, false), true, 2, 10);
+        assert_eq!(delivery.status, DeliveryStatus::Pending);
+        assert_eq!(delivery.next_attempt_at, 10_000_000);
+        delivery.record(attempt(10_000_000, false), true, 2, 10);
+        assert_eq!(delivery.status, DeliveryStatus::Pending);
+        assert_eq!(delivery.next_attempt_at, 30_000_000);
+        delivery.record(attempt(30_000_000, false), true, 2, 10);
+        assert_eq!(delivery.status, DeliveryStatus::Dead);
+        assert_eq!(delivery.attempts.len(), 3);
+
+        let mut delivery = Delivery::default();
+        delivery.record(attempt(0, false), false, 2, 10);
+        assert_eq!(delivery.status, DeliveryStatus::Dead);
+
+        let mut delivery = Delivery::default();
+        delivery.record(attempt(0, false), true, 2, 10);
+        delivery.record(attempt(10_000_000, true), true, 2, 10);
+        assert_eq!(delivery.status, DeliveryStatus::Delivered);
+
+        let mut delivery = Delivery::default();
+        let mut rate_limited = attempt(0, false);
+        rate_limited.retry_after = Some(120);
+        delivery.record(rate_limited, true, 2, 10);
+        assert_eq!(delivery.next_attempt_at, 120_000_000);
+    }
+}
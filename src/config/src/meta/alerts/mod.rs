@@ -22,6 +22,12 @@ use crate::{
 };
 
 pub mod alert;
+pub mod anomaly;
+pub mod composite;
+pub mod delivery;
+pub mod routing;
+pub mod silence;
+pub mod state;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct TriggerCondition {
@@ -46,6 +52,20 @@ pub struct TriggerCondition {
     /// (seconds)
     #[serde(default)]
     pub tolerance_in_secs: Option<i64>,
+    /// (seconds) the condition has to hold this long before the alert fires
+    #[serde(default)]
+    pub for_duration: i64,
+    /// notify the destinations when a firing alert is resolved
+    #[serde(default)]
+    pub notify_on_resolve: bool,
+}
+
+impl TriggerCondition {
+    /// Whether the alert goes through the pending, firing and resolved
+    /// states, instead of notifying and silencing on every match.
+    pub fn is_stateful(&self) -> bool {
+        self.for_duration > 0 || self.notify_on_resolve
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -86,6 +106,15 @@ pub struct QueryCondition {
     pub search_event_type: Option<SearchEventType>,
     #[serde(default)]
     pub multi_time_range: Option<Vec<CompareHistoricData>>,
+    /// Fires on the values deviating from their seasonal baseline instead of
+    /// the threshold of the trigger condition
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anomaly_detection: Option<anomaly::AnomalyDetection>,
+    /// Condition of a composite alert, on the states of other alerts
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub composite: Option<composite::CompositeCondition>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema, PartialEq)]
@@ -168,6 +197,9 @@ pub enum QueryType {
     SQL,
     #[serde(rename = "promql")]
     PromQL,
+    /// Combines the states of other alerts instead of querying a stream
+    #[serde(rename = "composite")]
+    Composite,
 }
 
 impl std::fmt::Display for QueryType {
@@ -176,6 +208,7 @@ impl std::fmt::Display for QueryType {
             QueryType::Custom => write!(f, "custom"),
             QueryType::SQL => write!(f, "sql"),
             QueryType::PromQL => write!(f, "promql"),
+            QueryType::Composite => write!(f, "composite"),
         }
     }
 }
@@ -186,6 +219,7 @@ impl From<&str> for QueryType {
             "custom" => QueryType::Custom,
             "sql" => QueryType::SQL,
             "promql" => QueryType::PromQL,
+            "composite" => QueryType::Composite,
             _ => QueryType::Custom,
         }
     }
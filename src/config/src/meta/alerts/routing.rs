@@ -0,0 +1,413 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    meta::stream::StreamType,
+    utils::json::{Map, Value},
+};
+
+/// Labels of a firing alert, sorted by name
+pub type Labels = BTreeMap<String, String>;
+
+/// (seconds)
+pub const DEFAULT_GROUP_WAIT: i64 = 30;
+/// (seconds)
+pub const DEFAULT_GROUP_INTERVAL: i64 = 300;
+/// (seconds)
+pub const DEFAULT_REPEAT_INTERVAL: i64 = 4 * 3600;
+
+/// Org level notification policy: the routing tree which picks the
+/// destinations of a firing alert by its labels, and the inhibition rules.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct NotificationPolicy {
+    #[serde(default)]
+    pub route: Route,
+    #[serde(default)]
+    pub inhibit_rules: Vec<InhibitRule>,
+}
+
+/// A node of the routing tree. Fields which are not set are inherited from
+/// the parent route, the root route falls back to the defaults. An empty
+/// destination list sends to the destinations of the alert itself.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Route {
+    #[serde(default)]
+    pub matchers: Vec<LabelMatcher>,
+    #[serde(default)]
+    pub destinations: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_by: Option<Vec<String>>,
+    /// (seconds) how long to wait for more alerts before the first
+    /// notification of a new group
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_wait: Option<i64>,
+    /// (seconds) how long to wait before notifying the alerts added to a group
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_interval: Option<i64>,
+    /// (seconds) how long to wait before notifying a group again
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_interval: Option<i64>,
+    /// keep matching the sibling routes after this one matched
+    #[serde(default)]
+    #[serde(rename = "continue")]
+    pub continue_matching: bool,
+    #[serde(default)]
+    pub routes: Vec<Route>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LabelMatcher {
+    pub name: String,
+    #[serde(default)]
+    pub op: MatchOperator,
+    #[serde(default)]
+    pub value: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum MatchOperator {
+    #[default]
+    #[serde(rename = "=")]
+    Equal,
+    #[serde(rename = "!=")]
+    NotEqual,
+    #[serde(rename = "=~")]
+    Regex,
+    #[serde(rename = "!~")]
+    NotRegex,
+}
+
+/// Mutes the alerts matching `target_matchers` while an alert matching
+/// `source_matchers` is firing with the same values of the `equal` labels.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct InhibitRule {
+    #[serde(default)]
+    pub source_matchers: Vec<LabelMatcher>,
+    #[serde(default)]
+    pub target_matchers: Vec<LabelMatcher>,
+    #[serde(default)]
+    pub equal: Vec<String>,
+}
+
+/// Settings of a matched route, with the inherited values resolved
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RouteOpts {
+    /// position of the route in the tree, e.g. `0.2.1`
+    pub id: String,
+    pub destinations: Vec<String>,
+    pub group_by: Vec<String>,
+    /// (seconds)
+    pub group_wait: i64,
+    /// (seconds)
+    pub group_interval: i64,
+    /// (seconds)
+    pub repeat_interval: i64,
+}
+
+/// Aggregation group of the notifier: the alert instances sent to a route
+/// with the same values of its `group_by` labels, notified together
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AggrGroup {
+    pub org_id: String,
+    pub route: RouteOpts,
+    /// instances by fingerprint
+    pub instances: HashMap<String, AlertInstance>,
+    /// (microseconds)
+    pub next_flush_at: i64,
+    /// (microseconds)
+    pub last_sent_at: Option<i64>,
+    /// instances were added or resolved since the last notification
+    pub has_changes: bool,
+}
+
+/// A firing group of an alert, with its labels and rows
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AlertInstance {
+    pub stream_type: StreamType,
+    pub stream_name: String,
+    pub alert_name: String,
+    pub labels: Labels,
+    pub rows: Vec<Map<String, Value>>,
+    pub resolved: bool,
+    /// (microseconds) a firing instance which is not evaluated again is
+    /// dropped silently
+    pub expires_at: i64,
+}
+
+/// A firing instance of an alert, the source of the inhibitions
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FiringInstance {
+    pub labels: Labels,
+    /// (microseconds)
+    pub expires_at: i64,
+}
+
+impl NotificationPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.route.matchers.is_empty() {
+            return Err("the root route can't have matchers, it matches every alert".to_string());
+        }
+        self.route.validate()?;
+        for rule in self.inhibit_rules.iter() {
+            if rule.source_matchers.is_empty() || rule.target_matchers.is_empty() {
+                return Err("inhibit rule needs both source and target matchers".to_string());
+            }
+            for m in rule
+                .source_matchers
+                .iter()
+                .chain(rule.target_matchers.iter())
+            {
+                m.validate()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns all the destinations referenced by the routes
+    pub fn destinations(&self) -> Vec<&str> {
+        let mut dests = Vec::new();
+        let mut stack = vec![&self.route];
+        while let Some(route) = stack.pop() {
+            dests.extend(route.destinations.iter().map(|v| v.as_str()));
+            stack.extend(route.routes.iter());
+        }
+        dests.sort();
+        dests.dedup();
+        dests
+    }
+
+    /// Whether the alert with `target` labels is muted by a firing alert
+    /// with `source` labels
+    pub fn is_inhibited(&self, source: &Labels, target: &Labels) -> bool {
+        self.inhibit_rules.iter().any(|rule| {
+            source != target
+                && matches_all(&rule.source_matchers, source)
+                && matches_all(&rule.target_matchers, target)
+                && rule
+                    .equal
+                    .iter()
+                    .all(|name| source.get(name) == target.get(name))
+        })
+    }
+}
+
+impl Route {
+    fn validate(&self) -> Result<(), String> {
+        for m in self.matchers.iter() {
+            m.validate()?;
+        }
+        for v in [self.group_wait, self.group_interval, self.repeat_interval] {
+            if v.is_some_and(|v| v < 0) {
+                return Err("route intervals can't be negative".to_string());
+            }
+        }
+        if self.group_interval == Some(0) || self.repeat_interval == Some(0) {
+            return Err("route group_interval and repeat_interval can't be 0".to_string());
+        }
+        for route in self.routes.iter() {
+            route.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the routes the alert with the labels is sent to. The children
+    /// of a matching route are tried in order, the first match wins unless it
+    /// has `continue` set. A route without matching children is used itself.
+    pub fn find(&self, labels: &Labels) -> Vec<RouteOpts> {
+        let root = RouteOpts {
+            id: "0".to_string(),
+            destinations: self.destinations.clone(),
+            group_by: self.group_by.clone().unwrap_or_default(),
+            group_wait: self.group_wait.unwrap_or(DEFAULT_GROUP_WAIT),
+            group_interval: self.group_interval.unwrap_or(DEFAULT_GROUP_INTERVAL),
+            repeat_interval: self.repeat_interval.unwrap_or(DEFAULT_REPEAT_INTERVAL),
+        };
+        let mut matched = Vec::new();
+        self.find_children(root, labels, &mut matched);
+        matched
+    }
+
+    fn find_children(&self, opts: RouteOpts, labels: &Labels, matched: &mut Vec<RouteOpts>) {
+        let mut found = false;
+        for (i, route) in self.routes.iter().enumerate() {
+            if !matches_all(&route.matchers, labels) {
+                continue;
+            }
+            let child = RouteOpts {
+                id: format!("{}.{i}", opts.id),
+                destinations: if route.destinations.is_empty() {
+                    opts.destinations.clone()
+                } else {
+                    route.destinations.clone()
+                },
+                group_by: route.group_by.clone().unwrap_or(opts.group_by.clone()),
+                group_wait: route.group_wait.unwrap_or(opts.group_wait),
+                group_interval: route.group_interval.unwrap_or(opts.group_interval),
+                repeat_interval: route.repeat_interval.unwrap_or(opts.repeat_interval),
+            };
+            route.find_children(child, labels, matched);
+            found = true;
+            if !route.continue_matching {
+                break;
+            }
+        }
+        if !found {
+            matched.push(opts);
+        }
+    }
+}
+
+impl LabelMatcher {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("label matcher needs a label name".to_string());
+        }
+        if matches!(self.op, MatchOperator::Regex | MatchOperator::NotRegex) {
+            regex::Regex::new(&format!("^(?:{})$", self.value))
+                .map_err(|e| format!("invalid regex of label matcher {}: {e}", self.name))?;
+        }
+        Ok(())
+    }
+
+    /// A missing label matches as an empty value
+    pub fn matches(&self, labels: &Labels) -> bool {
+        let value = labels
+            .get(&self.name)
+            .map(|v| v.as_str())
+            .unwrap_or_default();
+        match self.op {
+            MatchOperator::Equal => value == self.value,
+            MatchOperator::NotEqual => value != self.value,
+            MatchOperator::Regex | MatchOperator::NotRegex => {
+                let is_match = regex::Regex::new(&format!("^(?:{})$", self.value))
+                    .is_ok_and(|re| re.is_match(value));
+                is_match == (self.op == MatchOperator::Regex)
+            }
+        }
+    }
+}
+
+pub fn matches_all(matchers: &[LabelMatcher], labels: &Labels) -> bool {
+    matchers.iter().all(|m| m.matches(labels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(name: &str, op: MatchOperator, value: &str) -> LabelMatcher {
+        LabelMatcher {
+            name: name.to_string(),
+            op,
+            value: value.to_string(),
+        }
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_routes() {
+        let route = Route {
+            destinations: vec!["default".to_string()],
+            group_by: Some(vec!["alertname".to_string()]),
+            routes: vec![
+                Route {
+                    matchers: vec![matcher("team", MatchOperator::Equal, "db")],
+                    destinations: vec!["db-pager".to_string()],
+                    group_wait: Some(10),
+                    continue_matching: true,
+                    routes: vec![Route {
+                        matchers: vec![matcher("severity", MatchOperator::Regex, "crit.*")],
+                        destinations: vec!["db-oncall".to_string()],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                Route {
+                    matchers: vec![matcher("env", MatchOperator::NotEqual, "dev")],
+                    destinations: vec!["ops".to_string()],
+                    group_by: Some(vec![]),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let found = route.find(&labels(&[("team", "web"), ("env", "dev")]));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "0");
+        assert_eq!(found[0].destinations, vec!["default"]);
+        assert_eq!(found[0].group_wait, DEFAULT_GROUP_WAIT);
+
+        let found = route.find(&labels(&[("team", "db"), ("severity", "critical")]));
+        let ids = found.iter().map(|r| r.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["0.0.0", "0.1"]);
+        assert_eq!(found[0].destinations, vec!["db-oncall"]);
+        assert_eq!(found[0].group_wait, 10);
+        assert_eq!(found[0].group_by, vec!["alertname"]);
+        assert!(found[1].group_by.is_empty());
+
+        let found = route.find(&labels(&[("team", "db"), ("env", "dev")]));
+        let ids = found.iter().map(|r| r.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["0.0"]);
+    }
+
+    #[test]
+    fn test_inhibit() {
+        let policy = NotificationPolicy {
+            inhibit_rules: vec![InhibitRule {
+                source_matchers: vec![matcher("alertname", MatchOperator::Equal, "node_down")],
+                target_matchers: vec![matcher("alertname", MatchOperator::NotEqual, "node_down")],
+                equal: vec!["host".to_string()],
+            }],
+            ..Default::default()
+        };
+        let source = labels(&[("alertname", "node_down"), ("host", "a")]);
+        let target = labels(&[("alertname", "high_latency"), ("host", "a")]);
+        let other = labels(&[("alertname", "high_latency"), ("host", "b")]);
+        assert!(policy.is_inhibited(&source, &target));
+        assert!(!policy.is_inhibited(&source, &other));
+        assert!(!policy.is_inhibited(&target, &source));
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut policy = NotificationPolicy::default();
+        assert!(policy.validate().is_ok());
+        policy.route.routes.push(Route {
+            matchers: vec![matcher("a", MatchOperator::Regex, "(")],
+            ..Default::default()
+        });
+        assert!(policy.validate().is_err());
+        policy.route.routes[0].matchers[0].value = "a|b".to_string();
+        assert!(policy.validate().is_ok());
+        policy.route.routes[0].repeat_interval = Some(0);
+        assert!(policy.validate().is_err());
+    }
+}
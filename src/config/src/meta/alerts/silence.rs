@@ -0,0 +1,304 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::routing::{LabelMatcher, Labels, matches_all};
+
+/// Org level silence, mutes the scheduled alerts, reports and derived streams
+/// whose labels match all the matchers while it is active.
+///
+/// The labels are `module` (`alert`, `report` or `derived_stream`), `name`,
+/// `folder` (the folder id of alerts and reports), `stream_type` and
+/// `stream_name`. Alerts also have `alertname`, their context attributes and
+/// the group by values of the firing group.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Silence {
+    /// assigned when the silence is created
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub matchers: Vec<LabelMatcher>,
+    /// (microseconds) start of the silence, a recurring silence is active
+    /// in the windows of its schedule from then on
+    pub start_time: i64,
+    /// (microseconds) end of the silence, 0 for a silence without end
+    #[serde(default)]
+    pub end_time: i64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<SilenceSchedule>,
+    #[serde(default)]
+    pub created_by: String,
+}
+
+/// Recurring maintenance window, e.g. every Sunday from 02:00 to 04:00. A
+/// window whose end is not after its start ends on the next day.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SilenceSchedule {
+    /// days of the week the window starts on, 0 is Sunday, empty for every day
+    #[serde(default)]
+    pub days: Vec<u32>,
+    /// local start time of the window, `HH:MM`
+    pub start: String,
+    /// local end time of the window, `HH:MM`
+    pub end: String,
+    /// IANA timezone of the window, e.g. `Europe/Helsinki`, following its
+    /// daylight saving changes. Takes precedence over `tz_offset`
+    #[serde(default)]
+    pub timezone: String,
+    /// Fixed timezone offset in minutes, used when `timezone` is empty
+    #[serde(default)]
+    pub tz_offset: i32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct SilenceList {
+    pub list: Vec<Silence>,
+}
+
+impl Silence {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("silence name can't be empty".to_string());
+        }
+        if self.matchers.is_empty() {
+            return Err("silence needs at least one matcher".to_string());
+        }
+        for m in self.matchers.iter() {
+            m.validate()?;
+        }
+        if self.end_time > 0 && self.end_time <= self.start_time {
+            return Err("silence end_time should be after start_time".to_string());
+        }
+        if let Some(schedule) = self.schedule.as_ref() {
+            schedule.validate()?;
+        }
+        Ok(())
+    }
+
+    /// A silence is expired once it can't be active anymore
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.end_time > 0 && now >= self.end_time
+    }
+
+    pub fn matches(&self, labels: &Labels) -> bool {
+        matches_all(&self.matchers, labels)
+    }
+
+    /// Returns when the silence active at `now` ends, `None` if it is not
+    /// active. A silence without end is active until `i64::MAX`.
+    pub fn active_until(&self, now: i64) -> Option<i64> {
+        if now < self.start_time || self.is_expired(now) {
+            return None;
+        }
+        let end_time = if self.end_time > 0 {
+            self.end_time
+        } else {
+            i64::MAX
+        };
+        match self.schedule.as_ref() {
+            None => Some(end_time),
+            Some(schedule) => schedule
+                .window_end(now)
+                .map(|window_end| window_end.min(end_time)),
+        }
+    }
+
+    pub fn is_active(&self, now: i64) -> bool {
+        self.active_until(now).is_some()
+    }
+}
+
+impl SilenceSchedule {
+    fn validate(&self) -> Result<(), String> {
+        let (start, end) = self.times()?;
+        if start == end {
+            return Err("silence schedule start and end can't be the same".to_string());
+        }
+        if self.days.iter().any(|d| *d > 6) {
+            return Err("silence schedule days should be between 0 (Sunday) and 6".to_string());
+        }
+        if FixedOffset::east_opt(self.tz_offset * 60).is_none() {
+            return Err(format!("invalid timezone offset {}", self.tz_offset));
+        }
+        if !self.timezone.is_empty() && self.timezone.parse::<chrono_tz::Tz>().is_err() {
+            return Err(format!("invalid timezone {}", self.timezone));
+        }
+        Ok(())
+    }
+
+    fn times(&self) -> Result<(NaiveTime, NaiveTime), String> {
+        let parse = |v: &str| {
+            NaiveTime::parse_from_str(v, "%H:%M")
+                .map_err(|_| format!("invalid silence schedule time {v}, expected HH:MM"))
+        };
+        Ok((parse(&self.start)?, parse(&self.end)?))
+    }
+
+    /// Returns the end of the window containing `now`, both in microseconds
+    fn window_end(&self, now: i64) -> Option<i64> {
+        if self.timezone.is_empty() {
+            self.window_end_in(now, FixedOffset::east_opt(self.tz_offset * 60)?)
+        } else {
+            self.window_end_in(now, self.timezone.parse::<chrono_tz::Tz>().ok()?)
+        }
+    }
+
+    fn window_end_in<Tz: TimeZone>(&self, now: i64, tz: Tz) -> Option<i64> {
+        let (start, end) = self.times().ok()?;
+        let local = DateTime::from_timestamp_micros(now)?.with_timezone(&tz);
+        // a local time skipped by a daylight saving change starts an hour later
+        let at = |day: NaiveDate, time: NaiveTime| {
+            let time = day.and_time(time);
+            tz.from_local_datetime(&time).earliest().or_else(|| {
+                tz.from_local_datetime(&(time + Duration::hours(1)))
+                    .earliest()
+            })
+        };
+        // the window containing now started today or, crossing midnight, yesterday
+        for days_ago in 0..2 {
+            let day = local.date_naive() - Duration::days(days_ago);
+            let weekday = day.weekday().num_days_from_sunday();
+            if !self.days.is_empty() && !self.days.contains(&weekday) {
+                continue;
+            }
+            let window_start = at(day, start)?;
+            let end_day = if end <= start {
+                day + Duration::days(1)
+            } else {
+                day
+            };
+            let window_end = at(end_day, end)?;
+            if window_start <= local && local < window_end {
+                return Some(window_end.timestamp_micros());
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::alerts::routing::MatchOperator;
+
+    fn micros(v: &str) -> i64 {
+        DateTime::parse_from_rfc3339(v).unwrap().timestamp_micros()
+    }
+
+    fn silence(schedule: Option<SilenceSchedule>) -> Silence {
+        Silence {
+            name: "maintenance".to_string(),
+            matchers: vec![LabelMatcher {
+                name: "stream_name".to_string(),
+                op: MatchOperator::Equal,
+                value: "k8s".to_string(),
+            }],
+            start_time: micros("2025-03-01T00:00:00Z"),
+            schedule,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_active_until() {
+        let mut s = silence(None);
+        s.end_time = micros("2025-03-02T00:00:00Z");
+        assert!(s.validate().is_ok());
+        assert_eq!(s.active_until(micros("2025-02-28T12:00:00Z")), None);
+        assert_eq!(
+            s.active_until(micros("2025-03-01T12:00:00Z")),
+            Some(s.end_time)
+        );
+        assert!(s.is_expired(micros("2025-03-02T00:00:00Z")));
+        assert!(!s.is_active(micros("2025-03-02T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_recurring_window() {
+        // every Sunday 23:00 - 01:00 at UTC+2, 2025-03-02 is a Sunday
+        let s = silence(Some(SilenceSchedule {
+            days: vec![0],
+            start: "23:00".to_string(),
+            end: "01:00".to_string(),
+            timezone: "Europe/Helsinki".to_string(),
+            tz_offset: 120,
+        }));
+        assert!(s.validate().is_ok());
+        assert!(!s.is_active(micros("2025-03-02T20:59:00Z")));
+        let window_end = micros("2025-03-02T23:00:00Z");
+        assert_eq!(
+            s.active_until(micros("2025-03-02T21:00:00Z")),
+            Some(window_end)
+        );
+        // Monday 00:30 local still belongs to the Sunday window
+        assert_eq!(
+            s.active_until(micros("2025-03-02T22:30:00Z")),
+            Some(window_end)
+        );
+        assert!(!s.is_active(window_end));
+        // Saturday
+        assert!(!s.is_active(micros("2025-03-08T21:30:00Z")));
+        assert!(s.is_active(micros("2025-03-09T21:30:00Z")));
+    }
+
+    #[test]
+    fn test_timezone_window() {
+        // every day 02:00 - 04:00 in Helsinki, UTC+2 in winter and UTC+3 in summer
+        let s = silence(Some(SilenceSchedule {
+            start: "02:00".to_string(),
+            end: "04:00".to_string(),
+            timezone: "Europe/Helsinki".to_string(),
+            ..Default::default()
+        }));
+        assert!(s.validate().is_ok());
+        assert_eq!(
+            s.active_until(micros("2025-03-10T00:30:00Z")),
+            Some(micros("2025-03-10T02:00:00Z"))
+        );
+        assert_eq!(
+            s.active_until(micros("2025-07-10T00:30:00Z")),
+            Some(micros("2025-07-10T01:00:00Z"))
+        );
+        assert!(!s.is_active(micros("2025-07-10T01:30:00Z")));
+
+        let mut s = s;
+        s.schedule.as_mut().unwrap().timezone = "Mars/Olympus".to_string();
+        assert!(s.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut s = silence(None);
+        s.matchers.clear();
+        assert!(s.validate().is_err());
+        let mut s = silence(Some(SilenceSchedule {
+            days: vec![7],
+            start: "02:00".to_string(),
+            end: "04:00".to_string(),
+            ..Default::default()
+        }));
+        assert!(s.validate().is_err());
+        s.schedule.as_mut().unwrap().days = vec![];
+        assert!(s.validate().is_ok());
+        s.schedule.as_mut().unwrap().end = "4pm".to_string();
+        assert!(s.validate().is_err());
+    }
+}
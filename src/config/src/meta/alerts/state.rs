@@ -0,0 +1,281 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::utils::json::{Map, Value};
+
+/// Max number of transitions kept in the history of an alert
+pub const MAX_STATE_HISTORY: usize = 100;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertState {
+    Pending,
+    Firing,
+    #[default]
+    Resolved,
+}
+
+impl std::fmt::Display for AlertState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertState::Pending => write!(f, "pending"),
+            AlertState::Firing => write!(f, "firing"),
+            AlertState::Resolved => write!(f, "resolved"),
+        }
+    }
+}
+
+/// State of one group of an alert, the group is given by the values of the
+/// group by fields of the alert query.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AlertGroupState {
+    pub state: AlertState,
+    /// (microseconds) when the group entered the state
+    pub since: i64,
+    /// (microseconds)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_notified_at: Option<i64>,
+    /// values of the group by fields
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    #[schema(value_type = Object)]
+    pub labels: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AlertStateTransition {
+    pub group_key: String,
+    pub from: AlertState,
+    pub to: AlertState,
+    /// (microseconds)
+    pub timestamp: i64,
+}
+
+/// States of the groups of an alert which are not resolved, and the recent
+/// transitions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertStates {
+    #[serde(default)]
+    pub groups: HashMap<String, AlertGroupState>,
+    #[serde(default)]
+    pub history: Vec<AlertStateTransition>,
+}
+
+/// Groups to notify after an evaluation
+#[derive(Debug, Default, PartialEq)]
+pub struct StateChanges {
+    /// keys of the groups which started firing or are due to be notified again
+    pub firing: HashSet<String>,
+    /// groups which were firing and are resolved now
+    pub resolved: Vec<AlertGroupState>,
+}
+
+impl AlertStates {
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty() && self.history.is_empty()
+    }
+
+    /// Moves the groups to their next state after an evaluation at `now`.
+    ///
+    /// `active` holds the groups matching the alert condition. A new group is
+    /// pending until the condition held for `for_duration`, a firing group is
+    /// notified again once `repeat_interval` passed since the last
+    /// notification, and a group which no longer matches is resolved. All
+    /// durations are in microseconds.
+    pub fn apply(
+        &mut self,
+        active: Vec<(String, Map<String, Value>)>,
+        now: i64,
+        for_duration: i64,
+        repeat_interval: i64,
+    ) -> StateChanges {
+        let mut changes = StateChanges::default();
+        let mut seen = HashSet::with_capacity(active.len());
+        for (key, labels) in active {
+            if seen.contains(&key) {
+                continue;
+            }
+            let group = self.groups.entry(key.clone()).or_insert(AlertGroupState {
+                state: AlertState::Resolved,
+                since: now,
+                last_notified_at: None,
+                labels,
+            });
+            let from = group.state;
+            match group.state {
+                AlertState::Resolved if for_duration > 0 => {
+                    group.state = AlertState::Pending;
+                }
+                AlertState::Resolved => {
+                    group.state = AlertState::Firing;
+                }
+                AlertState::Pending if now - group.since >= for_duration => {
+                    group.state = AlertState::Firing;
+                    group.since = now;
+                }
+                AlertState::Pending => {}
+                AlertState::Firing => {
+                    if group
+                        .last_notified_at
+                        .is_none_or(|t| now - t >= repeat_interval)
+                    {
+                        group.last_notified_at = Some(now);
+                        changes.firing.insert(key.clone());
+                    }
+                }
+            }
+            if group.state != from {
+                if group.state == AlertState::Firing {
+                    group.last_notified_at = Some(now);
+                    changes.firing.insert(key.clone());
+                }
+                self.history.push(AlertStateTransition {
+                    group_key: key.clone(),
+                    from,
+                    to: group.state,
+                    timestamp: now,
+                });
+            }
+            seen.insert(key);
+        }
+
+        let mut stale = self
+            .groups
+            .keys()
+            .filter(|k| !seen.contains(k))
+            .cloned()
+            .collect::<Vec<_>>();
+        stale.sort();
+        for key in stale {
+            let mut group = self.groups.remove(&key).unwrap();
+            self.history.push(AlertStateTransition {
+                group_key: key,
+                from: group.state,
+                to: AlertState::Resolved,
+                timestamp: now,
+            });
+            // a pending group never fired, nobody needs to know it is resolved
+            if group.state == AlertState::Firing {
+                group.state = AlertState::Resolved;
+                group.since = now;
+                changes.resolved.push(group);
+            }
+        }
+
+        if self.history.len() > MAX_STATE_HISTORY {
+            let n = self.history.len() - MAX_STATE_HISTORY;
+            self.history.drain(..n);
+        }
+        changes
+    }
+}
+
+/// Returns the key and the labels of the group the row belongs to. Rows of an
+/// alert without group by fields all belong to the group `""`.
+pub fn group_of(row: &Map<String, Value>, group_by: &[String]) -> (String, Map<String, Value>) {
+    let mut labels = Map::with_capacity(group_by.len());
+    let mut key = Vec::with_capacity(group_by.len());
+    for field in group_by {
+        let value = row.get(field).cloned().unwrap_or(Value::Null);
+        let v = match &value {
+            Value::String(v) => v.to_string(),
+            v => v.to_string(),
+        };
+        key.push(format!("{field}={v}"));
+        labels.insert(field.to_string(), value);
+    }
+    (key.join(","), labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn active(keys: &[&str]) -> Vec<(String, Map<String, Value>)> {
+        keys.iter().map(|k| (k.to_string(), Map::new())).collect()
+    }
+
+    #[test]
+    fn test_apply_with_for_duration() {
+        let mut states = AlertStates::default();
+        let changes = states.apply(active(&["a"]), 0, 10, 0);
+        assert!(changes.firing.is_empty());
+        assert_eq!(states.groups["a"].state, AlertState::Pending);
+
+        // still pending
+        let changes = states.apply(active(&["a", "b"]), 5, 10, 0);
+        assert!(changes.firing.is_empty());
+
+        // a fires, b stays pending
+        let changes = states.apply(active(&["a", "b"]), 10, 10, 0);
+        assert_eq!(changes.firing, HashSet::from(["a".to_string()]));
+        assert_eq!(states.groups["a"].state, AlertState::Firing);
+        assert_eq!(states.groups["b"].state, AlertState::Pending);
+
+        // both stop matching, only a is notified as resolved
+        let changes = states.apply(vec![], 20, 10, 0);
+        assert!(changes.firing.is_empty());
+        assert_eq!(changes.resolved.len(), 1);
+        assert_eq!(changes.resolved[0].state, AlertState::Resolved);
+        assert!(states.groups.is_empty());
+        let to = states.history.iter().map(|t| t.to).collect::<Vec<_>>();
+        assert_eq!(
+            to,
+            vec![
+                AlertState::Pending,
+                AlertState::Pending,
+                AlertState::Firing,
+                AlertState::Resolved,
+                AlertState::Resolved
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_repeat_interval() {
+        let mut states = AlertStates::default();
+        let changes = states.apply(active(&[""]), 0, 0, 10);
+        assert_eq!(changes.firing, HashSet::from(["".to_string()]));
+        let changes = states.apply(active(&[""]), 5, 0, 10);
+        assert!(changes.firing.is_empty());
+        let changes = states.apply(active(&[""]), 10, 0, 10);
+        assert_eq!(changes.firing, HashSet::from(["".to_string()]));
+        assert_eq!(states.history.len(), 1);
+
+        for i in 0..MAX_STATE_HISTORY {
+            states.apply(vec![], 20 + i as i64 * 2, 0, 10);
+            states.apply(active(&[""]), 21 + i as i64 * 2, 0, 10);
+        }
+        assert_eq!(states.history.len(), MAX_STATE_HISTORY);
+    }
+
+    #[test]
+    fn test_group_of() {
+        let row = crate::utils::json::json!({"host": "a", "code": 500, "value": 1})
+            .as_object()
+            .unwrap()
+            .clone();
+        let (key, labels) = group_of(&row, &["host".to_string(), "code".to_string()]);
+        assert_eq!(key, "host=a,code=500");
+        assert_eq!(labels.len(), 2);
+        assert_eq!(group_of(&row, &[]).0, "");
+    }
+}
@@ -19,6 +19,10 @@ use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+/// Placeholder of the secrets of a destination returned by the API, a
+/// destination saved with it keeps its stored secret
+pub const REDACTED_SECRET: &str = "<redacted>";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Destination {
@@ -32,6 +36,65 @@ impl Destination {
     pub fn is_alert_destinations(&self) -> bool {
         matches!(&self.module, Module::Alert { .. })
     }
+
+    /// Puts back the secrets of the `stored` destination in place of the
+    /// [REDACTED_SECRET] placeholders
+    pub fn restore_secrets(&mut self, stored: &Destination) {
+        match (&mut self.module, &stored.module) {
+            (
+                Module::Alert {
+                    destination_type, ..
+                },
+                Module::Alert {
+                    destination_type: stored_type,
+                    ..
+                },
+            ) => match (destination_type, stored_type) {
+                (DestinationType::Http(endpoint), DestinationType::Http(stored)) => {
+                    endpoint.restore_secrets(stored)
+                }
+                (DestinationType::PagerDuty(pagerduty), DestinationType::PagerDuty(stored)) => {
+                    if pagerduty.routing_key == REDACTED_SECRET {
+                        pagerduty.routing_key = stored.routing_key.clone();
+                    }
+                }
+                (DestinationType::Opsgenie(opsgenie), DestinationType::Opsgenie(stored)) => {
+                    if opsgenie.api_key == REDACTED_SECRET {
+                        opsgenie.api_key = stored.api_key.clone();
+                    }
+                }
+                _ => {}
+            },
+            (Module::Pipeline { endpoint }, Module::Pipeline { endpoint: stored }) => {
+                endpoint.restore_secrets(stored)
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether a secret of the destination is a [REDACTED_SECRET] placeholder
+    pub fn has_redacted_secrets(&self) -> bool {
+        match &self.module {
+            Module::Alert {
+                destination_type, ..
+            } => match destination_type {
+                DestinationType::Http(endpoint) => endpoint.has_redacted_secrets(),
+                DestinationType::PagerDuty(pagerduty) => pagerduty.routing_key == REDACTED_SECRET,
+                DestinationType::Opsgenie(opsgenie) => opsgenie.api_key == REDACTED_SECRET,
+                _ => false,
+            },
+            Module::Pipeline { endpoint } => endpoint.has_redacted_secrets(),
+        }
+    }
+}
+
+/// Whether the header carries credentials, its value is redacted like the
+/// secrets of the destination
+pub fn is_secret_header(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ["auth", "token", "key", "secret", "password", "cookie"]
+        .iter()
+        .any(|part| name.contains(part))
 }
 
 #[derive(Serialize, Debug, Deserialize, Clone)]
@@ -63,6 +126,26 @@ pub enum DestinationType {
     Http(Endpoint),
     Email(Email),
     Sns(AwsSns),
+    Slack(Slack),
+    #[serde(rename = "pagerduty")]
+    PagerDuty(PagerDuty),
+    Opsgenie(Opsgenie),
+    Teams(Teams),
+}
+
+impl DestinationType {
+    /// Name of the type, as serialized
+    pub fn name(&self) -> &'static str {
+        match self {
+            DestinationType::Http(_) => "http",
+            DestinationType::Email(_) => "email",
+            DestinationType::Sns(_) => "sns",
+            DestinationType::Slack(_) => "slack",
+            DestinationType::PagerDuty(_) => "pagerduty",
+            DestinationType::Opsgenie(_) => "opsgenie",
+            DestinationType::Teams(_) => "teams",
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -79,6 +162,39 @@ pub struct Endpoint {
     pub skip_tls_verify: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<HashMap<String, String>>,
+    /// Secret the alert notifications are signed with, the HMAC-SHA256 of the
+    /// body is sent in the `X-OpenObserve-Signature` header
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_secret: Option<String>,
+    /// Format of the records forwarded by pipeline destinations
+    #[serde(default)]
+    pub output_format: OutputFormat,
+}
+
+impl Endpoint {
+    fn restore_secrets(&mut self, stored: &Endpoint) {
+        if self.signing_secret.as_deref() == Some(REDACTED_SECRET) {
+            self.signing_secret = stored.signing_secret.clone();
+        }
+        if let Some(headers) = self.headers.as_mut() {
+            for (name, value) in headers.iter_mut() {
+                if value == REDACTED_SECRET {
+                    if let Some(stored) = stored.headers.as_ref().and_then(|h| h.get(name)) {
+                        *value = stored.clone();
+                    }
+                }
+            }
+        }
+    }
+
+    fn has_redacted_secrets(&self) -> bool {
+        self.signing_secret.as_deref() == Some(REDACTED_SECRET)
+            || self
+                .headers
+                .as_ref()
+                .is_some_and(|headers| headers.values().any(|v| v == REDACTED_SECRET))
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -87,6 +203,56 @@ pub struct AwsSns {
     pub aws_region: String,
 }
 
+/// Slack incoming webhook
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Slack {
+    pub webhook_url: String,
+    /// Overrides the channel of the webhook
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+}
+
+/// PagerDuty Events API v2 integration
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PagerDuty {
+    pub routing_key: String,
+    /// `critical`, `error`, `warning` or `info`
+    #[serde(default = "default_pagerduty_severity")]
+    pub severity: String,
+    /// Overrides the default events endpoint
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events_url: Option<String>,
+}
+
+fn default_pagerduty_severity() -> String {
+    "critical".to_string()
+}
+
+/// Opsgenie Alert API integration
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Opsgenie {
+    pub api_key: String,
+    /// Overrides the default api endpoint, e.g. `https://api.eu.opsgenie.com`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// `P1` to `P5`
+    #[serde(default = "default_opsgenie_priority")]
+    pub priority: String,
+}
+
+fn default_opsgenie_priority() -> String {
+    "P3".to_string()
+}
+
+/// Microsoft Teams incoming webhook
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Teams {
+    pub webhook_url: String,
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
 pub enum HTTPType {
     #[default]
@@ -98,6 +264,30 @@ pub enum HTTPType {
     GET,
 }
 
+/// Format of the records a pipeline forwards to a remote destination
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// JSON array of the records
+    #[default]
+    Json,
+    /// OTLP/HTTP logs export request, in JSON
+    Otlp,
+    /// `_json` ingestion API of another OpenObserve instance
+    #[serde(rename = "openobserve")]
+    OpenObserve,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Otlp => write!(f, "otlp"),
+            OutputFormat::OpenObserve => write!(f, "openobserve"),
+        }
+    }
+}
+
 impl fmt::Display for HTTPType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -139,3 +329,71 @@ impl fmt::Display for TemplateType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pagerduty(routing_key: &str) -> Destination {
+        Destination {
+            id: None,
+            org_id: "default".to_string(),
+            name: "pd".to_string(),
+            module: Module::Alert {
+                template: "default".to_string(),
+                destination_type: DestinationType::PagerDuty(PagerDuty {
+                    routing_key: routing_key.to_string(),
+                    severity: "critical".to_string(),
+                    events_url: None,
+                }),
+            },
+        }
+    }
+
+    fn routing_key(dest: &Destination) -> &str {
+        match &dest.module {
+            Module::Alert {
+                destination_type: DestinationType::PagerDuty(pagerduty),
+                ..
+            } => &pagerduty.routing_key,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_restore_secrets() {
+        let stored = pagerduty("stored-key");
+        let mut dest = pagerduty(REDACTED_SECRET);
+        dest.restore_secrets(&stored);
+        assert_eq!(routing_key(&dest), "stored-key");
+
+        let mut dest = pagerduty("new-key");
+        dest.restore_secrets(&stored);
+        assert_eq!(routing_key(&dest), "new-key");
+    }
+
+    #[test]
+    fn test_restore_endpoint_secrets() {
+        let endpoint = |auth: &str| Endpoint {
+            url: "http://localhost".to_string(),
+            method: HTTPType::POST,
+            skip_tls_verify: false,
+            headers: Some(HashMap::from([
+                ("Authorization".to_string(), auth.to_string()),
+                ("Content-Type".to_string(), "application/json".to_string()),
+            ])),
+            signing_secret: None,
+            output_format: OutputFormat::default(),
+        };
+        let stored = endpoint("Bearer abc");
+        let mut redacted = endpoint(REDACTED_SECRET);
+        assert!(redacted.has_redacted_secrets());
+        redacted.restore_secrets(&stored);
+        assert_eq!(redacted, stored);
+        assert!(!redacted.has_redacted_secrets());
+
+        assert!(is_secret_header("X-Api-Key"));
+        assert!(is_secret_header("authorization"));
+        assert!(!is_secret_header("Content-Type"));
+    }
+}
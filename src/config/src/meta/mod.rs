@@ -27,10 +27,12 @@ pub mod meta_store;
 pub mod otlp;
 pub mod pipeline;
 pub mod promql;
+pub mod redaction;
 pub mod search;
 pub mod self_reporting;
 pub mod short_url;
 pub mod sql;
+pub mod sql_macro;
 pub mod stream;
 pub mod timed_annotations;
 pub mod triggers;
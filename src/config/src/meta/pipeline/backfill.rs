@@ -0,0 +1,212 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::Pipeline;
+use crate::meta::stream::StreamParams;
+
+/// Default length of the partitions of a backfill job, in seconds
+pub const DEFAULT_PARTITION_SECS: i64 = 3600;
+/// Max number of partitions of a backfill job
+pub const MAX_PARTITIONS: usize = 10000;
+/// Metadata key of the internal ingestion requests writing replayed records, which
+/// keep their timestamp however old it is
+pub const BACKFILL_INGEST_METADATA_KEY: &str = "pipeline_backfill";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BackfillStatus {
+    #[default]
+    Pending,
+    Running,
+    Finished,
+    Cancelled,
+    Failed,
+}
+
+impl BackfillStatus {
+    /// Whether the job stopped, it only runs again when retried
+    pub fn is_done(&self) -> bool {
+        matches!(self, Self::Finished | Self::Cancelled | Self::Failed)
+    }
+}
+
+/// Time range of the source stream replayed by a backfill job in one go
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BackfillPartition {
+    pub start_time: i64,
+    pub end_time: i64,
+    #[serde(default)]
+    pub finished: bool,
+    /// Cursor of the next page of the source stream, to resume the partition
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Records read from the source stream
+    #[serde(default)]
+    pub records: u64,
+}
+
+/// Replay of a time range of the source stream of a realtime pipeline through
+/// one of its versions, into its destinations
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct BackfillJob {
+    pub id: String,
+    pub org_id: String,
+    pub pipeline_id: String,
+    pub pipeline_version: i32,
+    /// Pipeline as it was at `pipeline_version`
+    pub pipeline: Pipeline,
+    pub source: StreamParams,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Max number of records processed per second, 0 is unlimited
+    pub max_records_per_sec: u64,
+    pub status: BackfillStatus,
+    pub created_by: String,
+    pub created_at: i64,
+    /// Last time the job made progress, a running job is resumed by another
+    /// node when it stops being updated
+    pub updated_at: i64,
+    #[serde(default)]
+    pub started_at: Option<i64>,
+    #[serde(default)]
+    pub ended_at: Option<i64>,
+    /// Node running the job
+    #[serde(default)]
+    pub node: Option<String>,
+    #[serde(default)]
+    pub error_message: Option<String>,
+    /// Percentage of the partitions finished
+    #[serde(default)]
+    pub progress: f64,
+    #[serde(default)]
+    pub records_read: u64,
+    #[serde(default)]
+    pub records_written: u64,
+    pub partitions: Vec<BackfillPartition>,
+}
+
+impl BackfillJob {
+    pub fn update_progress(&mut self) {
+        let finished = self.partitions.iter().filter(|p| p.finished).count();
+        self.progress = if self.partitions.is_empty() {
+            100.0
+        } else {
+            finished as f64 * 100.0 / self.partitions.len() as f64
+        };
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct BackfillJobList {
+    pub list: Vec<BackfillJob>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, ToSchema)]
+pub struct BackfillRequest {
+    /// Start of the time range to replay, in microseconds
+    pub start_time: i64,
+    /// End of the time range to replay, in microseconds
+    pub end_time: i64,
+    /// Version of the pipeline to replay the records through, the current one
+    /// by default
+    #[serde(default)]
+    pub version: Option<i32>,
+    /// Length of the partitions, in seconds
+    #[serde(default)]
+    pub partition_secs: Option<i64>,
+    /// Max number of records processed per second
+    #[serde(default)]
+    pub max_records_per_sec: Option<u64>,
+}
+
+impl BackfillRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start_time <= 0 || self.end_time <= self.start_time {
+            return Err("Backfill end_time must be after start_time".to_string());
+        }
+        if self.partition_secs.is_some_and(|secs| secs <= 0) {
+            return Err("Backfill partition_secs must be positive".to_string());
+        }
+        if self.max_records_per_sec == Some(0) {
+            return Err("Backfill max_records_per_sec must be positive".to_string());
+        }
+        let partitions = self.partitions();
+        if partitions.len() > MAX_PARTITIONS {
+            return Err(format!(
+                "Backfill time range is split in {} partitions, more than the max of {MAX_PARTITIONS}",
+                partitions.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Splits the time range in partitions aligned on their length, like the
+    /// partitions of the streams
+    pub fn partitions(&self) -> Vec<BackfillPartition> {
+        let step = self.partition_secs.unwrap_or(DEFAULT_PARTITION_SECS) * 1_000_000;
+        let mut partitions = Vec::new();
+        let mut start = self.start_time;
+        while start < self.end_time && partitions.len() <= MAX_PARTITIONS {
+            let end = ((start / step + 1) * step).min(self.end_time);
+            partitions.push(BackfillPartition {
+                start_time: start,
+                end_time: end,
+                ..Default::default()
+            });
+            start = end;
+        }
+        partitions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backfill_partitions() {
+        let hour = 3_600_000_000;
+        let req = BackfillRequest {
+            start_time: 10 * hour + 100,
+            end_time: 12 * hour + 200,
+            ..Default::default()
+        };
+        assert!(req.validate().is_ok());
+        let partitions = req.partitions();
+        assert_eq!(partitions.len(), 3);
+        assert_eq!(partitions[0].start_time, 10 * hour + 100);
+        assert_eq!(partitions[0].end_time, 11 * hour);
+        assert_eq!(partitions[1].end_time, 12 * hour);
+        assert_eq!(partitions[2].end_time, 12 * hour + 200);
+
+        let req = BackfillRequest {
+            start_time: hour,
+            end_time: 2 * hour,
+            partition_secs: Some(60),
+            ..Default::default()
+        };
+        assert_eq!(req.partitions().len(), 60);
+
+        let req = BackfillRequest {
+            start_time: hour,
+            end_time: 20000 * hour,
+            ..Default::default()
+        };
+        assert!(req.validate().is_err());
+    }
+}
@@ -18,8 +18,13 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use super::{
+    condition::{CompiledCondition, ConditionExpr},
+    parser::ParserParams,
+};
 use crate::meta::{
     alerts::{QueryCondition, TriggerCondition},
+    redaction::RedactionParams,
     stream::{RemoteStreamParams, RoutingCondition, StreamParams, StreamType},
 };
 
@@ -125,6 +130,11 @@ pub enum NodeData {
     Query(DerivedStream),
     Function(FunctionParams),
     Condition(ConditionParams),
+    Aggregation(AggregationParams),
+    Parser(ParserParams),
+    Redaction(RedactionParams),
+    Dedup(DedupParams),
+    RateLimit(RateLimitParams),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
@@ -140,7 +150,238 @@ pub struct FunctionParams {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ConditionParams {
+    /// Flat list of conditions which must all match, as saved before condition
+    /// trees were supported
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub conditions: Vec<RoutingCondition>,
+    /// Condition tree, evaluated instead of `conditions` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<ConditionExpr>,
+}
+
+impl ConditionParams {
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.condition {
+            Some(condition) => condition.validate(),
+            None if self.conditions.is_empty() => {
+                Err("ConditionNode must have non-empty conditions".to_string())
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn compile(&self) -> Result<CompiledCondition, String> {
+        match &self.condition {
+            Some(condition) => condition.compile(),
+            None => Ok(CompiledCondition::And(
+                self.conditions
+                    .iter()
+                    .cloned()
+                    .map(CompiledCondition::Routing)
+                    .collect(),
+            )),
+        }
+    }
+}
+
+/// Max number of sliding windows a record belongs to
+pub const MAX_WINDOWS_PER_RECORD: i64 = 60;
+
+/// Groups the records by key fields over time windows, and emits a record with
+/// the aggregated values of each group when its window closes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AggregationParams {
+    /// Fields the records are grouped by
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    #[serde(default)]
+    pub window_type: WindowType,
+    /// (seconds) duration of the windows
+    pub window_size: i64,
+    /// (seconds) interval between the starts of two sliding windows
+    #[serde(default)]
+    pub slide: i64,
+    /// (seconds) how long after the end of its window a record is still
+    /// aggregated, the window is emitted once the records reach its end plus
+    /// the allowed lateness
+    #[serde(default)]
+    pub allowed_lateness: i64,
+    pub aggregations: Vec<Aggregation>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowType {
+    #[default]
+    Tumbling,
+    Sliding,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Aggregation {
+    pub function: AggregationFunction,
+    /// Aggregated field, not needed to count the records
+    #[serde(default)]
+    pub field: String,
+    /// Percentile to compute, between 0 and 100
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percentile: Option<f64>,
+    /// Name of the field of the result
+    pub alias: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationFunction {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Avg,
+    Percentile,
+}
+
+impl AggregationParams {
+    /// Interval between the starts of two windows, the size for tumbling
+    /// windows
+    pub fn slide(&self) -> i64 {
+        match self.window_type {
+            WindowType::Tumbling => self.window_size,
+            WindowType::Sliding => self.slide,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window_size <= 0 {
+            return Err("AggregationNode window size must be positive".to_string());
+        }
+        if self.window_type == WindowType::Sliding
+            && (self.slide <= 0
+                || self.slide > self.window_size
+                || self.window_size > self.slide * MAX_WINDOWS_PER_RECORD)
+        {
+            return Err(format!(
+                "AggregationNode slide must be positive, not larger than the window size and at least 1/{MAX_WINDOWS_PER_RECORD} of it"
+            ));
+        }
+        if self.allowed_lateness < 0 {
+            return Err("AggregationNode allowed lateness can't be negative".to_string());
+        }
+        if self.aggregations.is_empty() {
+            return Err("AggregationNode must have non-empty aggregations".to_string());
+        }
+        let mut aliases = std::collections::HashSet::new();
+        for aggregation in self.aggregations.iter() {
+            if aggregation.alias.is_empty() || !aliases.insert(aggregation.alias.as_str()) {
+                return Err("AggregationNode aggregations need distinct aliases".to_string());
+            }
+            if self.group_by.contains(&aggregation.alias) {
+                return Err(format!(
+                    "AggregationNode alias {} is a group by field",
+                    aggregation.alias
+                ));
+            }
+            if aggregation.function != AggregationFunction::Count && aggregation.field.is_empty() {
+                return Err(format!(
+                    "AggregationNode aggregation {} has no field",
+                    aggregation.alias
+                ));
+            }
+            if aggregation.function == AggregationFunction::Percentile
+                && !aggregation
+                    .percentile
+                    .is_some_and(|p| (0.0..=100.0).contains(&p))
+            {
+                return Err(format!(
+                    "AggregationNode aggregation {} needs a percentile between 0 and 100",
+                    aggregation.alias
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Drops the records whose key was already seen within the window, and
+/// optionally emits the number of dropped duplicates of each key when its
+/// window ends
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DedupParams {
+    /// Fields identifying the duplicates, all the fields but the timestamp when
+    /// empty
+    #[serde(default)]
+    pub key_fields: Vec<String>,
+    /// (seconds) how long a key is remembered after its first record
+    pub window: i64,
+    #[serde(default)]
+    pub emit_summary: bool,
+}
+
+impl DedupParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window <= 0 {
+            return Err("DedupNode window must be positive".to_string());
+        }
+        if self.emit_summary && self.key_fields.is_empty() {
+            return Err("DedupNode needs key fields to emit summaries".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Limits the rate of the records of each key with a token bucket, the
+/// records over the limit are dropped or sampled
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RateLimitParams {
+    /// Fields of the key of the buckets, a single bucket for all the records
+    /// when empty
+    #[serde(default)]
+    pub key_fields: Vec<String>,
+    /// Records per second refilling the bucket of a key
+    pub rate: f64,
+    /// Size of the bucket, the number of records let through in a burst. The
+    /// rate rounded up when not set.
+    #[serde(default)]
+    pub burst: u64,
+    #[serde(default)]
+    pub over_limit: OverLimitAction,
+    /// One of this many records over the limit is kept by sampling
+    #[serde(default = "default_sample_one_in")]
+    pub sample_one_in: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverLimitAction {
+    #[default]
+    Drop,
+    Sample,
+}
+
+fn default_sample_one_in() -> u64 {
+    10
+}
+
+impl RateLimitParams {
+    /// Size of the buckets
+    pub fn capacity(&self) -> f64 {
+        if self.burst > 0 {
+            self.burst as f64
+        } else {
+            self.rate.ceil()
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.rate.is_finite() || self.rate <= 0.0 {
+            return Err("RateLimitNode rate must be positive".to_string());
+        }
+        if self.over_limit == OverLimitAction::Sample && self.sample_one_in == 0 {
+            return Err("RateLimitNode sample_one_in must be positive".to_string());
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -240,6 +481,37 @@ mod tests {
         assert_eq!(func_node, node_data);
     }
 
+    #[test]
+    fn test_aggregation_node_serialization() {
+        let payload = json::json!({
+            "node_type": "aggregation",
+            "group_by": ["status"],
+            "window_type": "sliding",
+            "window_size": 300,
+            "slide": 60,
+            "aggregations": [
+                {"function": "count", "alias": "requests"},
+                {"function": "percentile", "field": "took", "percentile": 95, "alias": "p95"}
+            ]
+        });
+        let NodeData::Aggregation(params) = json::from_value::<NodeData>(payload).unwrap() else {
+            panic!("not an aggregation node");
+        };
+        assert_eq!(params.slide(), 60);
+        assert_eq!(params.allowed_lateness, 0);
+        assert!(params.validate().is_ok());
+
+        let mut invalid = params.clone();
+        invalid.slide = 1;
+        assert!(invalid.validate().is_err());
+        let mut invalid = params.clone();
+        invalid.aggregations[1].percentile = None;
+        assert!(invalid.validate().is_err());
+        let mut invalid = params;
+        invalid.aggregations[1].alias = "status".to_string();
+        assert!(invalid.validate().is_err());
+    }
+
     #[test]
     fn test_condition_node_serialization() {
         let payload = json::json!({
@@ -0,0 +1,492 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{borrow::Cow, cmp::Ordering};
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    meta::stream::RoutingCondition,
+    utils::json::{Map, Value},
+};
+
+/// Max nesting depth of a condition tree
+pub const MAX_CONDITION_DEPTH: usize = 16;
+
+const REGEX_SIZE_LIMIT: usize = 1024 * 1024;
+
+/// Boolean condition tree of a ConditionNode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConditionExpr {
+    And { conditions: Vec<ConditionExpr> },
+    Or { conditions: Vec<ConditionExpr> },
+    Not { condition: Box<ConditionExpr> },
+    Condition(FieldCondition),
+}
+
+/// Compares a field of the record against `value`, or against the field
+/// `other_column` of the same record when it is set
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldCondition {
+    pub column: String,
+    pub operator: ConditionOperator,
+    #[serde(default)]
+    pub value: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub other_column: Option<String>,
+    #[serde(default)]
+    pub ignore_case: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConditionOperator {
+    #[serde(rename = "=")]
+    EqualTo,
+    #[serde(rename = "!=")]
+    NotEqualTo,
+    #[serde(rename = ">")]
+    GreaterThan,
+    #[serde(rename = ">=")]
+    GreaterThanEquals,
+    #[serde(rename = "<")]
+    LessThan,
+    #[serde(rename = "<=")]
+    LessThanEquals,
+    #[serde(rename = "contains", alias = "Contains")]
+    Contains,
+    #[serde(rename = "not_contains", alias = "NotContains")]
+    NotContains,
+    #[serde(rename = "regex")]
+    Regex,
+    #[serde(rename = "not_regex")]
+    NotRegex,
+    #[serde(rename = "in")]
+    In,
+    #[serde(rename = "not_in")]
+    NotIn,
+    /// Inclusive range given as `[low, high]`
+    #[serde(rename = "between")]
+    Between,
+    #[serde(rename = "exists")]
+    Exists,
+    #[serde(rename = "not_exists")]
+    NotExists,
+}
+
+impl std::fmt::Display for ConditionOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConditionOperator::EqualTo => write!(f, "="),
+            ConditionOperator::NotEqualTo => write!(f, "!="),
+            ConditionOperator::GreaterThan => write!(f, ">"),
+            ConditionOperator::GreaterThanEquals => write!(f, ">="),
+            ConditionOperator::LessThan => write!(f, "<"),
+            ConditionOperator::LessThanEquals => write!(f, "<="),
+            ConditionOperator::Contains => write!(f, "contains"),
+            ConditionOperator::NotContains => write!(f, "not contains"),
+            ConditionOperator::Regex => write!(f, "regex"),
+            ConditionOperator::NotRegex => write!(f, "not regex"),
+            ConditionOperator::In => write!(f, "in"),
+            ConditionOperator::NotIn => write!(f, "not in"),
+            ConditionOperator::Between => write!(f, "between"),
+            ConditionOperator::Exists => write!(f, "exists"),
+            ConditionOperator::NotExists => write!(f, "not exists"),
+        }
+    }
+}
+
+impl ConditionOperator {
+    fn compares_columns(&self) -> bool {
+        matches!(
+            self,
+            ConditionOperator::EqualTo
+                | ConditionOperator::NotEqualTo
+                | ConditionOperator::GreaterThan
+                | ConditionOperator::GreaterThanEquals
+                | ConditionOperator::LessThan
+                | ConditionOperator::LessThanEquals
+                | ConditionOperator::Contains
+                | ConditionOperator::NotContains
+        )
+    }
+}
+
+impl ConditionExpr {
+    pub fn validate(&self) -> Result<(), String> {
+        self.compile().map(|_| ())
+    }
+
+    /// Builds the evaluator of the tree, parsing the values and regexes once so
+    /// that evaluating records does not repeat it
+    pub fn compile(&self) -> Result<CompiledCondition, String> {
+        self.compile_at(1)
+    }
+
+    fn compile_at(&self, depth: usize) -> Result<CompiledCondition, String> {
+        if depth > MAX_CONDITION_DEPTH {
+            return Err(format!(
+                "Condition tree is nested deeper than {MAX_CONDITION_DEPTH} levels"
+            ));
+        }
+        match self {
+            ConditionExpr::And { conditions } | ConditionExpr::Or { conditions } => {
+                if conditions.is_empty() {
+                    return Err("Condition group must have non-empty conditions".to_string());
+                }
+                let compiled = conditions
+                    .iter()
+                    .map(|cond| cond.compile_at(depth + 1))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(if matches!(self, ConditionExpr::And { .. }) {
+                    CompiledCondition::And(compiled)
+                } else {
+                    CompiledCondition::Or(compiled)
+                })
+            }
+            ConditionExpr::Not { condition } => Ok(CompiledCondition::Not(Box::new(
+                condition.compile_at(depth + 1)?,
+            ))),
+            ConditionExpr::Condition(cond) => Ok(CompiledCondition::Field(cond.compile()?)),
+        }
+    }
+}
+
+impl FieldCondition {
+    fn compile(&self) -> Result<CompiledField, String> {
+        if self.column.is_empty() {
+            return Err("Condition column can not be empty".to_string());
+        }
+        let op = self.operator;
+        if let Some(other_column) = &self.other_column {
+            if other_column.is_empty() {
+                return Err(format!(
+                    "Condition on {} has an empty other_column",
+                    self.column
+                ));
+            }
+            if !op.compares_columns() {
+                return Err(format!(
+                    "Operator {op} can not compare column {} with another column",
+                    self.column
+                ));
+            }
+        }
+
+        let mut targets = vec![];
+        let mut regex = None;
+        match op {
+            ConditionOperator::Exists | ConditionOperator::NotExists => {}
+            ConditionOperator::Regex | ConditionOperator::NotRegex => {
+                let Some(pattern) = self.value.as_str() else {
+                    return Err(format!(
+                        "Operator {op} on {} needs a string pattern",
+                        self.column
+                    ));
+                };
+                regex = Some(
+                    RegexBuilder::new(pattern)
+                        .case_insensitive(self.ignore_case)
+                        .size_limit(REGEX_SIZE_LIMIT)
+                        .build()
+                        .map_err(|e| format!("Invalid regex on {}: {e}", self.column))?,
+                );
+            }
+            ConditionOperator::In | ConditionOperator::NotIn | ConditionOperator::Between => {
+                let values = match &self.value {
+                    Value::Array(values) => values,
+                    _ => {
+                        return Err(format!(
+                            "Operator {op} on {} needs a list of values",
+                            self.column
+                        ));
+                    }
+                };
+                if values.is_empty() || (op == ConditionOperator::Between && values.len() != 2) {
+                    return Err(format!(
+                        "Operator {op} on {} needs {} values",
+                        self.column,
+                        if op == ConditionOperator::Between {
+                            "exactly 2"
+                        } else {
+                            "non-empty"
+                        }
+                    ));
+                }
+                for value in values {
+                    if value.is_array() || value.is_object() {
+                        return Err(format!(
+                            "Operator {op} on {} only supports scalar values",
+                            self.column
+                        ));
+                    }
+                    targets.push(Scalar::owned(value, self.ignore_case));
+                }
+            }
+            _ => {
+                if self.other_column.is_none() {
+                    if self.value.is_array() || self.value.is_object() {
+                        return Err(format!(
+                            "Operator {op} on {} only supports a scalar value",
+                            self.column
+                        ));
+                    }
+                    targets.push(Scalar::owned(&self.value, self.ignore_case));
+                }
+            }
+        }
+
+        Ok(CompiledField {
+            column: self.column.clone(),
+            operator: op,
+            other_column: self.other_column.clone(),
+            ignore_case: self.ignore_case,
+            targets,
+            regex,
+        })
+    }
+}
+
+/// Evaluator of a condition tree, built by [`ConditionExpr::compile`]
+#[derive(Debug)]
+pub enum CompiledCondition {
+    And(Vec<CompiledCondition>),
+    Or(Vec<CompiledCondition>),
+    Not(Box<CompiledCondition>),
+    Field(CompiledField),
+    Routing(RoutingCondition),
+}
+
+impl CompiledCondition {
+    pub fn evaluate(&self, row: &Map<String, Value>) -> bool {
+        match self {
+            CompiledCondition::And(conditions) => conditions.iter().all(|c| c.evaluate(row)),
+            CompiledCondition::Or(conditions) => conditions.iter().any(|c| c.evaluate(row)),
+            CompiledCondition::Not(condition) => !condition.evaluate(row),
+            CompiledCondition::Field(cond) => cond.evaluate(row),
+            CompiledCondition::Routing(cond) => cond.evaluate(row),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CompiledField {
+    column: String,
+    operator: ConditionOperator,
+    other_column: Option<String>,
+    ignore_case: bool,
+    targets: Vec<Scalar<'static>>,
+    regex: Option<Regex>,
+}
+
+impl CompiledField {
+    /// A null field is treated as a missing one, and a missing field only
+    /// matches `not_exists`
+    fn evaluate(&self, row: &Map<String, Value>) -> bool {
+        let field = row.get(&self.column).filter(|v| !v.is_null());
+        let field = match self.operator {
+            ConditionOperator::Exists => return field.is_some(),
+            ConditionOperator::NotExists => return field.is_none(),
+            _ => match field {
+                Some(field) => Scalar::borrowed(field, self.ignore_case),
+                None => return false,
+            },
+        };
+
+        match self.operator {
+            ConditionOperator::Regex | ConditionOperator::NotRegex => {
+                let is_match = self
+                    .regex
+                    .as_ref()
+                    .is_some_and(|re| re.is_match(&field.text));
+                is_match == (self.operator == ConditionOperator::Regex)
+            }
+            ConditionOperator::In => self.targets.iter().any(|t| field.equals(t)),
+            ConditionOperator::NotIn => !self.targets.iter().any(|t| field.equals(t)),
+            ConditionOperator::Between => {
+                field.compare(&self.targets[0]).is_some_and(Ordering::is_ge)
+                    && field.compare(&self.targets[1]).is_some_and(Ordering::is_le)
+            }
+            op => {
+                let other;
+                let target = match &self.other_column {
+                    Some(other_column) => match row.get(other_column).filter(|v| !v.is_null()) {
+                        Some(v) => {
+                            other = Scalar::borrowed(v, self.ignore_case);
+                            &other
+                        }
+                        None => return false,
+                    },
+                    None => &self.targets[0],
+                };
+                match op {
+                    ConditionOperator::EqualTo => field.equals(target),
+                    ConditionOperator::NotEqualTo => !field.equals(target),
+                    ConditionOperator::GreaterThan => {
+                        field.compare(target).is_some_and(Ordering::is_gt)
+                    }
+                    ConditionOperator::GreaterThanEquals => {
+                        field.compare(target).is_some_and(Ordering::is_ge)
+                    }
+                    ConditionOperator::LessThan => {
+                        field.compare(target).is_some_and(Ordering::is_lt)
+                    }
+                    ConditionOperator::LessThanEquals => {
+                        field.compare(target).is_some_and(Ordering::is_le)
+                    }
+                    ConditionOperator::Contains => field.text.contains(target.text.as_ref()),
+                    ConditionOperator::NotContains => !field.text.contains(target.text.as_ref()),
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+/// A scalar value seen as text, and as a number or bool when it parses as one,
+/// so that string-typed numbers compare numerically
+#[derive(Debug)]
+struct Scalar<'a> {
+    text: Cow<'a, str>,
+    number: Option<f64>,
+    boolean: Option<bool>,
+}
+
+impl<'a> Scalar<'a> {
+    fn borrowed(value: &'a Value, ignore_case: bool) -> Self {
+        let text: Cow<'a, str> = match value {
+            Value::String(s) => Cow::Borrowed(s.as_str()),
+            Value::Null => Cow::Borrowed("null"),
+            v => Cow::Owned(v.to_string()),
+        };
+        let number = match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse::<f64>().ok().filter(|n| n.is_finite()),
+            _ => None,
+        };
+        let boolean = match value {
+            Value::Bool(b) => Some(*b),
+            Value::String(s) => s.parse::<bool>().ok(),
+            _ => None,
+        };
+        let text = if ignore_case {
+            Cow::Owned(text.to_lowercase())
+        } else {
+            text
+        };
+        Self {
+            text,
+            number,
+            boolean,
+        }
+    }
+
+    fn owned(value: &Value, ignore_case: bool) -> Scalar<'static> {
+        let scalar = Scalar::borrowed(value, ignore_case);
+        Scalar {
+            text: Cow::Owned(scalar.text.into_owned()),
+            number: scalar.number,
+            boolean: scalar.boolean,
+        }
+    }
+
+    fn equals(&self, other: &Scalar) -> bool {
+        if let (Some(a), Some(b)) = (self.number, other.number) {
+            return a == b;
+        }
+        if let (Some(a), Some(b)) = (self.boolean, other.boolean) {
+            return a == b;
+        }
+        self.text == other.text
+    }
+
+    fn compare(&self, other: &Scalar) -> Option<Ordering> {
+        match (self.number, other.number) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => Some(self.text.as_ref().cmp(other.text.as_ref())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::json;
+
+    fn compile(value: Value) -> CompiledCondition {
+        json::from_value::<ConditionExpr>(value)
+            .unwrap()
+            .compile()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_condition_tree_evaluate() {
+        let cond = compile(json::json!({
+            "type": "or",
+            "conditions": [
+                {
+                    "type": "and",
+                    "conditions": [
+                        {"type": "condition", "column": "status", "operator": "between", "value": [500, "599"]},
+                        {"type": "not", "condition": {"type": "condition", "column": "path", "operator": "regex", "value": "^/health", "ignore_case": true}}
+                    ]
+                },
+                {"type": "condition", "column": "level", "operator": "in", "value": ["error", "fatal"], "ignore_case": true},
+                {"type": "condition", "column": "took", "operator": ">", "other_column": "budget"}
+            ]
+        }));
+        let row = |v: Value| v.as_object().unwrap().clone();
+
+        assert!(cond.evaluate(&row(json::json!({"status": "503", "path": "/api"}))));
+        assert!(!cond.evaluate(&row(json::json!({"status": 503, "path": "/HEALTHZ"}))));
+        assert!(!cond.evaluate(&row(json::json!({"status": "404", "path": "/api"}))));
+        assert!(cond.evaluate(&row(json::json!({"level": "ERROR"}))));
+        assert!(cond.evaluate(&row(json::json!({"took": "120", "budget": 100}))));
+        assert!(!cond.evaluate(&row(json::json!({"took": 80, "budget": 100}))));
+        assert!(!cond.evaluate(&row(json::json!({"took": 80}))));
+    }
+
+    #[test]
+    fn test_condition_exists() {
+        let cond = compile(json::json!({
+            "type": "and",
+            "conditions": [
+                {"type": "condition", "column": "user", "operator": "exists"},
+                {"type": "condition", "column": "error", "operator": "not_exists"}
+            ]
+        }));
+        let row = |v: Value| v.as_object().unwrap().clone();
+
+        assert!(cond.evaluate(&row(json::json!({"user": "a", "error": null}))));
+        assert!(!cond.evaluate(&row(json::json!({"user": "a", "error": "x"}))));
+        assert!(!cond.evaluate(&row(json::json!({"error": "x"}))));
+    }
+
+    #[test]
+    fn test_condition_tree_validate() {
+        let invalid = [
+            json::json!({"type": "and", "conditions": []}),
+            json::json!({"type": "condition", "column": "a", "operator": "regex", "value": "("}),
+            json::json!({"type": "condition", "column": "a", "operator": "between", "value": [1]}),
+            json::json!({"type": "condition", "column": "a", "operator": "in", "other_column": "b"}),
+        ];
+        for value in invalid {
+            let expr: ConditionExpr = json::from_value(value).unwrap();
+            assert!(expr.validate().is_err());
+        }
+    }
+}
@@ -29,7 +29,11 @@ use crate::{
     utils::json,
 };
 
+pub mod backfill;
 pub mod components;
+pub mod condition;
+pub mod parser;
+pub mod version;
 
 // (pipeline, node_map, graph, vrl_map)
 pub type PipelineExecDFS = (
@@ -89,6 +93,10 @@ impl Pipeline {
     /// 7. In the same branch, unchecked `after_flattened` FunctionNode can't follow checked
     ///    `after_flattened` checked FunctionNode
     /// 8. EnrichmentTables can only be used in Scheduled pipelines
+    /// 9. valid AggregationNode parameters, AggregationNode only in Realtime pipelines
+    /// 10. valid ParserNode parameters
+    /// 11. valid RedactionNode parameters
+    /// 12. valid DedupNode and RateLimitNode parameters
     ///
     /// If all satisfies, populates the [Pipeline::source] with the first node in nodes list
     pub fn validate(&mut self) -> Result<()> {
@@ -129,9 +137,8 @@ impl Pipeline {
 
         for node in self.nodes.iter() {
             // ck 4
-            if matches!(&node.data, NodeData::Condition(condition_params) if condition_params.conditions.is_empty())
-            {
-                return Err(anyhow!("ConditionNode must have non-empty conditions"));
+            if let NodeData::Condition(condition_params) = &node.data {
+                condition_params.validate().map_err(|e| anyhow!(e))?;
             }
             // ck 8
             if let NodeData::Stream(stream_params) = &node.data {
@@ -143,6 +150,30 @@ impl Pipeline {
                     ));
                 }
             }
+            // ck 9
+            if let NodeData::Aggregation(aggregation_params) = &node.data {
+                if matches!(&self.source, PipelineSource::Scheduled(_)) {
+                    return Err(anyhow!(
+                        "AggregationNode can only be used in Realtime pipelines, Scheduled pipelines aggregate with their query"
+                    ));
+                }
+                aggregation_params.validate().map_err(|e| anyhow!(e))?;
+            }
+            // ck 10
+            if let NodeData::Parser(parser_params) = &node.data {
+                parser_params.validate().map_err(|e| anyhow!(e))?;
+            }
+            // ck 11
+            if let NodeData::Redaction(redaction_params) = &node.data {
+                redaction_params.validate().map_err(|e| anyhow!(e))?;
+            }
+            // ck 12
+            if let NodeData::Dedup(dedup_params) = &node.data {
+                dedup_params.validate().map_err(|e| anyhow!(e))?;
+            }
+            if let NodeData::RateLimit(rate_limit_params) = &node.data {
+                rate_limit_params.validate().map_err(|e| anyhow!(e))?;
+            }
         }
 
         // ck 5
@@ -360,6 +391,29 @@ where
 #[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
 pub struct PipelineList {
     pub list: Vec<Pipeline>,
+    /// Metrics of the nodes of each pipeline by pipeline id, only when requested
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metrics: HashMap<String, Vec<NodeMetrics>>,
+}
+
+/// Records processed by a pipeline node over a period of time
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct NodeMetrics {
+    pub node_id: String,
+    pub node_type: String,
+    #[serde(default)]
+    pub batches: u64,
+    #[serde(default)]
+    pub received: u64,
+    #[serde(default)]
+    pub emitted: u64,
+    #[serde(default)]
+    pub dropped: u64,
+    #[serde(default)]
+    pub failed: u64,
+    /// Time spent processing the records, in seconds
+    #[serde(default)]
+    pub took_in_secs: f64,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
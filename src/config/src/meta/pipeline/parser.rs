@@ -0,0 +1,674 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Declarative parsers of the pipeline parser nodes, extracting the fields of
+//! unstructured text without the VRL runtime.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::json::{Map, Number, Value};
+
+/// Max depth of the grok patterns referencing other patterns
+pub const MAX_GROK_DEPTH: usize = 16;
+
+const REGEX_SIZE_LIMIT: usize = 10 * 1024 * 1024;
+
+/// Reference to a grok pattern: `%{NAME}`, `%{NAME:field}` or `%{NAME:field:type}`
+static GROK_REFERENCE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"%\{(\w+)(?::([\w.@\[\]-]+))?(?::(int|float|string))?\}").unwrap());
+
+/// Patterns shipped with the grok parser, written for the regex crate which
+/// doesn't support look-around
+static GROK_PATTERNS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    HashMap::from([
+        ("USERNAME", r"[a-zA-Z0-9._-]+"),
+        ("USER", r"%{USERNAME}"),
+        (
+            "EMAILLOCALPART",
+            r"[a-zA-Z0-9!#$%&'*+/=?^_`{|}~-]+(?:\.[a-zA-Z0-9!#$%&'*+/=?^_`{|}~-]+)*",
+        ),
+        ("EMAILADDRESS", r"%{EMAILLOCALPART}@%{HOSTNAME}"),
+        ("INT", r"[+-]?[0-9]+"),
+        ("BASE10NUM", r"[+-]?(?:[0-9]+(?:\.[0-9]+)?|\.[0-9]+)"),
+        ("NUMBER", r"%{BASE10NUM}"),
+        ("BASE16NUM", r"(?:0[xX])?[0-9A-Fa-f]+"),
+        ("POSINT", r"\b[1-9][0-9]*\b"),
+        ("NONNEGINT", r"\b[0-9]+\b"),
+        ("WORD", r"\b\w+\b"),
+        ("NOTSPACE", r"\S+"),
+        ("SPACE", r"\s*"),
+        ("DATA", r".*?"),
+        ("GREEDYDATA", r".*"),
+        ("QUOTEDSTRING", r#""(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'"#),
+        ("QS", r"%{QUOTEDSTRING}"),
+        (
+            "UUID",
+            r"[A-Fa-f0-9]{8}-(?:[A-Fa-f0-9]{4}-){3}[A-Fa-f0-9]{12}",
+        ),
+        ("MAC", r"(?:[A-Fa-f0-9]{2}[:-]){5}[A-Fa-f0-9]{2}"),
+        (
+            "IPV4",
+            r"(?:(?:25[0-5]|2[0-4][0-9]|[01]?[0-9]{1,2})\.){3}(?:25[0-5]|2[0-4][0-9]|[01]?[0-9]{1,2})",
+        ),
+        (
+            "IPV6",
+            r"(?:[0-9A-Fa-f]{0,4}:){2,7}(?:%{IPV4}|[0-9A-Fa-f]{0,4})(?:%[0-9A-Za-z]+)?",
+        ),
+        ("IP", r"%{IPV6}|%{IPV4}"),
+        (
+            "HOSTNAME",
+            r"\b[0-9A-Za-z][0-9A-Za-z-]{0,62}(?:\.[0-9A-Za-z][0-9A-Za-z-]{0,62})*\.?",
+        ),
+        ("IPORHOST", r"%{IP}|%{HOSTNAME}"),
+        ("HOSTPORT", r"%{IPORHOST}:%{POSINT}"),
+        ("UNIXPATH", r"(?:/[\w%!$@:.,+~-]*)+"),
+        ("WINPATH", r"(?:[A-Za-z]+:|\\)(?:\\[^\\?*]*)+"),
+        ("PATH", r"%{UNIXPATH}|%{WINPATH}"),
+        ("URIPROTO", r"[A-Za-z][A-Za-z0-9+.-]+"),
+        ("URIHOST", r"%{IPORHOST}(?::%{POSINT})?"),
+        ("URIPATH", r"(?:/[A-Za-z0-9$.+!*'(){},~:;=@#%&_-]*)+"),
+        ("URIQUERY", r"[A-Za-z0-9$.+!*'|(){},~@#%&/=:;_?\[\]<>-]*"),
+        ("URIPARAM", r"\?%{URIQUERY}"),
+        ("URIPATHPARAM", r"%{URIPATH}(?:%{URIPARAM})?"),
+        (
+            "URI",
+            r"%{URIPROTO}://(?:%{USER}(?::[^@]*)?@)?(?:%{URIHOST})?(?:%{URIPATHPARAM})?",
+        ),
+        (
+            "MONTH",
+            r"\b(?:[Jj]an(?:uary)?|[Ff]eb(?:ruary)?|[Mm]ar(?:ch)?|[Aa]pr(?:il)?|[Mm]ay|[Jj]un(?:e)?|[Jj]ul(?:y)?|[Aa]ug(?:ust)?|[Ss]ep(?:tember)?|[Oo]ct(?:ober)?|[Nn]ov(?:ember)?|[Dd]ec(?:ember)?)\b",
+        ),
+        ("MONTHNUM", r"0?[1-9]|1[0-2]"),
+        ("MONTHDAY", r"0[1-9]|[12][0-9]|3[01]|[1-9]"),
+        (
+            "DAY",
+            r"Mon(?:day)?|Tue(?:sday)?|Wed(?:nesday)?|Thu(?:rsday)?|Fri(?:day)?|Sat(?:urday)?|Sun(?:day)?",
+        ),
+        ("YEAR", r"(?:\d\d){1,2}"),
+        ("HOUR", r"2[0123]|[01]?[0-9]"),
+        ("MINUTE", r"[0-5][0-9]"),
+        ("SECOND", r"(?:[0-5]?[0-9]|60)(?:[:.,][0-9]+)?"),
+        ("TIME", r"%{HOUR}:%{MINUTE}(?::%{SECOND})?"),
+        ("DATE_US", r"%{MONTHNUM}[/-]%{MONTHDAY}[/-]%{YEAR}"),
+        ("DATE_EU", r"%{MONTHDAY}[./-]%{MONTHNUM}[./-]%{YEAR}"),
+        ("ISO8601_TIMEZONE", r"Z|[+-]%{HOUR}(?::?%{MINUTE})"),
+        (
+            "TIMESTAMP_ISO8601",
+            r"%{YEAR}-%{MONTHNUM}-%{MONTHDAY}[T ]%{HOUR}:?%{MINUTE}(?::?%{SECOND})?(?:%{ISO8601_TIMEZONE})?",
+        ),
+        ("HTTPDATE", r"%{MONTHDAY}/%{MONTH}/%{YEAR}:%{TIME} %{INT}"),
+        ("SYSLOGTIMESTAMP", r"%{MONTH} +%{MONTHDAY} %{TIME}"),
+        ("PROG", r"[\x21-\x5a\x5c\x5e-\x7e]+"),
+        ("SYSLOGPROG", r"%{PROG:program}(?:\[%{POSINT:pid}\])?"),
+        ("SYSLOGHOST", r"%{IPORHOST}"),
+        (
+            "SYSLOGBASE",
+            r"%{SYSLOGTIMESTAMP:timestamp} (?:%{SYSLOGHOST:logsource} )?%{SYSLOGPROG}:",
+        ),
+        (
+            "LOGLEVEL",
+            r"[Aa]lert|ALERT|[Tt]race|TRACE|[Dd]ebug|DEBUG|[Nn]otice|NOTICE|[Ii]nfo(?:rmation)?|INFO(?:RMATION)?|[Ww]arn(?:ing)?|WARN(?:ING)?|[Ee]rr(?:or)?|ERR(?:OR)?|[Cc]rit(?:ical)?|CRIT(?:ICAL)?|[Ff]atal|FATAL|[Ss]evere|SEVERE|EMERG(?:ENCY)?|[Ee]merg(?:ency)?",
+        ),
+        ("HTTPDUSER", r"%{EMAILADDRESS}|%{USER}"),
+        (
+            "COMMONAPACHELOG",
+            r#"%{IPORHOST:clientip} %{HTTPDUSER:ident} %{HTTPDUSER:auth} \[%{HTTPDATE:timestamp}\] "(?:%{WORD:verb} %{NOTSPACE:request}(?: HTTP/%{NUMBER:httpversion})?|%{DATA:rawrequest})" %{NUMBER:response} (?:%{NUMBER:bytes}|-)"#,
+        ),
+        (
+            "COMBINEDAPACHELOG",
+            r"%{COMMONAPACHELOG} %{QS:referrer} %{QS:agent}",
+        ),
+    ])
+});
+
+/// Parameters of a parser node: the field holding the text, the format of the
+/// text and where to write the parsed fields
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParserParams {
+    /// Field holding the text to parse
+    pub field: String,
+    /// Prefix of the names of the parsed fields, they are written as they are
+    /// named by the parser when empty
+    #[serde(default)]
+    pub target_prefix: String,
+    #[serde(default)]
+    pub on_failure: OnFailure,
+    pub parser: ParserFormat,
+}
+
+/// What happens to the records which can't be parsed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnFailure {
+    /// The record continues unchanged
+    #[default]
+    Keep,
+    /// The record is dropped
+    Drop,
+    /// The record continues unchanged and the failure is reported as an error
+    /// of the pipeline
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "format")]
+#[serde(rename_all = "snake_case")]
+pub enum ParserFormat {
+    /// Grok pattern, `%{PATTERN:field}` or `%{PATTERN:field:int|float}`
+    Grok {
+        pattern: String,
+        /// Patterns added to the shipped library, by name
+        #[serde(default)]
+        patterns: HashMap<String, String>,
+    },
+    /// Regex whose named capture groups are the parsed fields
+    Regex { pattern: String },
+    /// Key value pairs, like logfmt `level=info msg="user logged in"`
+    KeyValue {
+        /// Separator of the pairs, any whitespace by default
+        #[serde(default)]
+        field_delimiter: String,
+        #[serde(default = "default_value_delimiter")]
+        value_delimiter: String,
+    },
+    /// Delimited values named by `columns`
+    Csv {
+        columns: Vec<String>,
+        #[serde(default = "default_csv_delimiter")]
+        delimiter: char,
+    },
+    /// JSON object serialized in a string
+    Json,
+}
+
+fn default_value_delimiter() -> String {
+    "=".to_string()
+}
+
+fn default_csv_delimiter() -> char {
+    ','
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldType {
+    String,
+    Int,
+    Float,
+}
+
+/// Parser ready to run, its patterns compiled once for all the records
+#[derive(Debug)]
+pub struct CompiledParser {
+    field: String,
+    target_prefix: String,
+    pub on_failure: OnFailure,
+    format: CompiledFormat,
+}
+
+#[derive(Debug)]
+enum CompiledFormat {
+    /// Regex with the field and type of each of its capture groups
+    Regex(Regex, Vec<Option<(String, FieldType)>>),
+    KeyValue {
+        field_delimiter: String,
+        value_delimiter: String,
+    },
+    Csv {
+        columns: Vec<String>,
+        delimiter: char,
+    },
+    Json,
+}
+
+impl ParserParams {
+    pub fn validate(&self) -> Result<(), String> {
+        self.compile().map(|_| ())
+    }
+
+    pub fn compile(&self) -> Result<CompiledParser, String> {
+        if self.field.is_empty() {
+            return Err("ParserNode field to parse is missing".to_string());
+        }
+        let format = match &self.parser {
+            ParserFormat::Grok { pattern, patterns } => {
+                let mut fields = Vec::new();
+                let expanded = expand_grok(pattern, patterns, &mut fields, 0)?;
+                let regex = build_regex(&expanded)?;
+                // the groups are named by their index in `fields`
+                let groups = regex
+                    .capture_names()
+                    .map(|name| {
+                        name.and_then(|name| name.strip_prefix('g'))
+                            .and_then(|idx| idx.parse::<usize>().ok())
+                            .and_then(|idx| fields.get(idx).cloned())
+                    })
+                    .collect();
+                CompiledFormat::Regex(regex, groups)
+            }
+            ParserFormat::Regex { pattern } => {
+                let regex = build_regex(pattern)?;
+                let groups: Vec<_> = regex
+                    .capture_names()
+                    .map(|name| name.map(|name| (name.to_string(), FieldType::String)))
+                    .collect();
+                if groups.iter().all(Option::is_none) {
+                    return Err("ParserNode regex has no named capture group".to_string());
+                }
+                CompiledFormat::Regex(regex, groups)
+            }
+            ParserFormat::KeyValue {
+                field_delimiter,
+                value_delimiter,
+            } => {
+                if value_delimiter.is_empty() {
+                    return Err("ParserNode key value delimiter is empty".to_string());
+                }
+                CompiledFormat::KeyValue {
+                    field_delimiter: field_delimiter.clone(),
+                    value_delimiter: value_delimiter.clone(),
+                }
+            }
+            ParserFormat::Csv { columns, delimiter } => {
+                if columns.is_empty() {
+                    return Err("ParserNode csv columns are missing".to_string());
+                }
+                if *delimiter == '"' {
+                    return Err("ParserNode csv delimiter can't be a quote".to_string());
+                }
+                CompiledFormat::Csv {
+                    columns: columns.clone(),
+                    delimiter: *delimiter,
+                }
+            }
+            ParserFormat::Json => CompiledFormat::Json,
+        };
+        Ok(CompiledParser {
+            field: self.field.clone(),
+            target_prefix: self.target_prefix.clone(),
+            on_failure: self.on_failure,
+            format,
+        })
+    }
+}
+
+impl CompiledParser {
+    /// Parses the field of the record and writes the parsed fields into it.
+    /// The record is unchanged when the field can't be parsed.
+    pub fn apply(&self, record: &mut Map<String, Value>) -> Result<(), String> {
+        let text = match record.get(&self.field) {
+            Some(Value::String(text)) => text,
+            Some(Value::Null) | None => {
+                return Err(format!("field {} to parse is missing", self.field));
+            }
+            Some(_) => return Err(format!("field {} to parse isn't a string", self.field)),
+        };
+        let parsed = self.parse(text)?;
+        for (name, value) in parsed {
+            record.insert(format!("{}{name}", self.target_prefix), value);
+        }
+        Ok(())
+    }
+
+    fn parse(&self, text: &str) -> Result<Vec<(String, Value)>, String> {
+        match &self.format {
+            CompiledFormat::Regex(regex, groups) => {
+                let caps = regex
+                    .captures(text)
+                    .ok_or_else(|| format!("field {} doesn't match the pattern", self.field))?;
+                let mut parsed: Vec<(String, Value)> = Vec::new();
+                for (idx, group) in groups.iter().enumerate() {
+                    let (Some((name, field_type)), Some(m)) = (group, caps.get(idx)) else {
+                        continue;
+                    };
+                    // alternatives of the pattern may capture the same field
+                    if parsed.iter().any(|(parsed_name, _)| parsed_name == name) {
+                        continue;
+                    }
+                    parsed.push((name.clone(), typed_value(m.as_str(), *field_type)));
+                }
+                Ok(parsed)
+            }
+            CompiledFormat::KeyValue {
+                field_delimiter,
+                value_delimiter,
+            } => {
+                let parsed = parse_key_value(text, field_delimiter, value_delimiter);
+                if parsed.is_empty() {
+                    return Err(format!("field {} has no key value pair", self.field));
+                }
+                Ok(parsed)
+            }
+            CompiledFormat::Csv { columns, delimiter } => {
+                let values = parse_csv_line(text, *delimiter)
+                    .ok_or_else(|| format!("field {} has an unterminated quote", self.field))?;
+                // missing trailing values are skipped, extra values are ignored
+                Ok(columns
+                    .iter()
+                    .zip(values)
+                    .map(|(column, value)| (column.clone(), Value::String(value)))
+                    .collect())
+            }
+            CompiledFormat::Json => match crate::utils::json::from_str::<Value>(text) {
+                Ok(Value::Object(object)) => Ok(object.into_iter().collect()),
+                Ok(_) => Err(format!("field {} isn't a JSON object", self.field)),
+                Err(e) => Err(format!("field {} isn't valid JSON: {e}", self.field)),
+            },
+        }
+    }
+}
+
+fn build_regex(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("ParserNode invalid pattern: {e}"))
+}
+
+/// Replaces the pattern references with their regex, the referenced fields
+/// become capture groups named `g{index in fields}`
+fn expand_grok(
+    pattern: &str,
+    patterns: &HashMap<String, String>,
+    fields: &mut Vec<(String, FieldType)>,
+    depth: usize,
+) -> Result<String, String> {
+    if depth > MAX_GROK_DEPTH {
+        return Err(format!(
+            "ParserNode grok patterns are nested deeper than {MAX_GROK_DEPTH} levels, is a pattern referencing itself?"
+        ));
+    }
+    let mut expanded = String::with_capacity(pattern.len());
+    let mut last = 0;
+    for caps in GROK_REFERENCE.captures_iter(pattern) {
+        let reference = caps.get(0).unwrap();
+        expanded.push_str(&pattern[last..reference.start()]);
+        last = reference.end();
+
+        let name = &caps[1];
+        let definition = patterns
+            .get(name)
+            .map(String::as_str)
+            .or_else(|| GROK_PATTERNS.get(name).copied())
+            .ok_or_else(|| format!("ParserNode unknown grok pattern {name}"))?;
+        let inner = expand_grok(definition, patterns, fields, depth + 1)?;
+        match caps.get(2) {
+            Some(field) => {
+                let field_type = match caps.get(3).map(|t| t.as_str()) {
+                    Some("int") => FieldType::Int,
+                    Some("float") => FieldType::Float,
+                    _ => FieldType::String,
+                };
+                expanded.push_str(&format!("(?P<g{}>{inner})", fields.len()));
+                fields.push((field.as_str().to_string(), field_type));
+            }
+            None => expanded.push_str(&format!("(?:{inner})")),
+        }
+    }
+    expanded.push_str(&pattern[last..]);
+    Ok(expanded)
+}
+
+/// Converts the captured text to its grok type, keeping the text when it
+/// isn't a number
+fn typed_value(text: &str, field_type: FieldType) -> Value {
+    let number = match field_type {
+        FieldType::String => None,
+        FieldType::Int => text.parse::<i64>().ok().map(Number::from),
+        FieldType::Float => text.parse::<f64>().ok().and_then(Number::from_f64),
+    };
+    match number {
+        Some(number) => Value::Number(number),
+        None => Value::String(text.to_string()),
+    }
+}
+
+/// Parses `key=value` pairs separated by `field_delimiter`, any whitespace
+/// when empty. Double quoted values may contain the delimiters, a key without
+/// value is `true`.
+fn parse_key_value(
+    text: &str,
+    field_delimiter: &str,
+    value_delimiter: &str,
+) -> Vec<(String, Value)> {
+    let mut pairs = Vec::new();
+    let mut start = 0;
+    let mut in_quote = false;
+    let mut escaped = false;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if in_quote => escaped = true,
+            '"' => in_quote = !in_quote,
+            _ if !in_quote => {
+                let delimiter_len = if field_delimiter.is_empty() {
+                    if c.is_whitespace() { c.len_utf8() } else { 0 }
+                } else if text[i..].starts_with(field_delimiter) {
+                    field_delimiter.len()
+                } else {
+                    0
+                };
+                if delimiter_len > 0 {
+                    pairs.push(&text[start..i]);
+                    start = i + delimiter_len;
+                    // skip the rest of a multi-char delimiter
+                    while chars.peek().is_some_and(|(j, _)| *j < start) {
+                        chars.next();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    pairs.push(&text[start..]);
+
+    pairs
+        .into_iter()
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .filter_map(|pair| match pair.split_once(value_delimiter) {
+            Some((key, value)) => {
+                let key = key.trim();
+                (!key.is_empty()).then(|| (key.to_string(), Value::String(unquote(value.trim()))))
+            }
+            None => Some((pair.to_string(), Value::Bool(true))),
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    if value.len() < 2 || !value.starts_with('"') || !value.ends_with('"') {
+        return value.to_string();
+    }
+    let mut unquoted = String::with_capacity(value.len() - 2);
+    let mut chars = value[1..value.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.extend(chars.next()),
+            c => unquoted.push(c),
+        }
+    }
+    unquoted
+}
+
+/// Splits a CSV line, values may be double quoted with `""` escaping a quote.
+/// Returns none when a quote isn't terminated.
+fn parse_csv_line(text: &str, delimiter: char) -> Option<Vec<String>> {
+    let mut values = Vec::new();
+    let mut value = String::new();
+    let mut in_quote = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quote {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    value.push('"');
+                }
+                '"' => in_quote = false,
+                c => value.push(c),
+            }
+        } else if c == '"' {
+            in_quote = true;
+        } else if c == delimiter {
+            values.push(std::mem::take(&mut value));
+        } else {
+            value.push(c);
+        }
+    }
+    if in_quote {
+        return None;
+    }
+    values.push(value);
+    Some(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::json;
+
+    fn parse(params: json::Value, record: json::Value) -> Result<json::Value, String> {
+        let params: ParserParams = json::from_value(params).unwrap();
+        let parser = params.compile()?;
+        let mut record = record.as_object().unwrap().clone();
+        parser.apply(&mut record)?;
+        Ok(json::Value::Object(record))
+    }
+
+    #[test]
+    fn test_grok_parser() {
+        let log = r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326 "http://www.example.com/start.html" "Mozilla/4.08""#;
+        let record = parse(
+            json::json!({
+                "field": "log",
+                "target_prefix": "http_",
+                "parser": {"format": "grok", "pattern": "%{COMBINEDAPACHELOG}"}
+            }),
+            json::json!({ "log": log }),
+        )
+        .unwrap();
+        assert_eq!(record["http_clientip"], "127.0.0.1");
+        assert_eq!(record["http_auth"], "frank");
+        assert_eq!(record["http_timestamp"], "10/Oct/2000:13:55:36 -0700");
+        assert_eq!(record["http_verb"], "GET");
+        assert_eq!(record["http_request"], "/apache_pb.gif");
+        assert_eq!(record["http_response"], "200");
+        assert_eq!(record["http_agent"], "\"Mozilla/4.08\"");
+
+        let record = parse(
+            json::json!({
+                "field": "msg",
+                "parser": {
+                    "format": "grok",
+                    "pattern": "%{LOGLEVEL:level} took=%{NUMBER:took:float} %{STATUS:status:int}",
+                    "patterns": {"STATUS": "[0-9]{3}"}
+                }
+            }),
+            json::json!({ "msg": "ERROR took=1.5 503" }),
+        )
+        .unwrap();
+        assert_eq!(record["level"], "ERROR");
+        assert_eq!(record["took"], 1.5);
+        assert_eq!(record["status"], 503);
+
+        let err = parse(
+            json::json!({
+                "field": "msg",
+                "parser": {"format": "grok", "pattern": "%{LOOP}", "patterns": {"LOOP": "a%{LOOP}"}}
+            }),
+            json::json!({ "msg": "a" }),
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_regex_parser() {
+        let params = json::json!({
+            "field": "msg",
+            "parser": {"format": "regex", "pattern": r"user (?P<user>\w+) from (?P<ip>[\d.]+)"}
+        });
+        let record = parse(
+            params.clone(),
+            json::json!({ "msg": "login of user bob from 10.0.0.1" }),
+        )
+        .unwrap();
+        assert_eq!(record["user"], "bob");
+        assert_eq!(record["ip"], "10.0.0.1");
+        assert!(parse(params.clone(), json::json!({ "msg": "logout" })).is_err());
+        assert!(parse(params, json::json!({ "other": "x" })).is_err());
+    }
+
+    #[test]
+    fn test_key_value_parser() {
+        let record = parse(
+            json::json!({"field": "msg", "parser": {"format": "key_value"}}),
+            json::json!({ "msg": r#"level=info msg="user \"bob\" logged in"  debug took=12ms"# }),
+        )
+        .unwrap();
+        assert_eq!(record["level"], "info");
+        assert_eq!(record["msg"], "user \"bob\" logged in");
+        assert_eq!(record["debug"], true);
+        assert_eq!(record["took"], "12ms");
+
+        let record = parse(
+            json::json!({
+                "field": "msg",
+                "parser": {"format": "key_value", "field_delimiter": "; ", "value_delimiter": ":"}
+            }),
+            json::json!({ "msg": "a:1; b:two words; c:3" }),
+        )
+        .unwrap();
+        assert_eq!(record["a"], "1");
+        assert_eq!(record["b"], "two words");
+        assert_eq!(record["c"], "3");
+    }
+
+    #[test]
+    fn test_csv_and_json_parsers() {
+        let record = parse(
+            json::json!({
+                "field": "line",
+                "parser": {"format": "csv", "columns": ["id", "name", "note"]}
+            }),
+            json::json!({ "line": r#"1,"Doe, John","said ""hi""""# }),
+        )
+        .unwrap();
+        assert_eq!(record["id"], "1");
+        assert_eq!(record["name"], "Doe, John");
+        assert_eq!(record["note"], "said \"hi\"");
+
+        let params = json::json!({
+            "field": "payload",
+            "target_prefix": "p_",
+            "parser": {"format": "json"}
+        });
+        let record = parse(
+            params.clone(),
+            json::json!({ "payload": r#"{"a": 1, "b": {"c": "d"}}"# }),
+        )
+        .unwrap();
+        assert_eq!(record["p_a"], 1);
+        assert_eq!(record["p_b"]["c"], "d");
+        assert!(parse(params, json::json!({ "payload": "[1, 2]" })).is_err());
+    }
+}
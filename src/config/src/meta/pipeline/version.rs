@@ -0,0 +1,252 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{
+    Pipeline,
+    components::{Edge, Node, NodeData, PipelineSource},
+};
+
+/// Immutable snapshot of a pipeline, saved each time the pipeline changes
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PipelineVersion {
+    pub version: i32,
+    /// User who made the change
+    pub author: String,
+    /// Unix timestamp in microseconds
+    pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub pipeline: Pipeline,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PipelineVersionSummary {
+    pub version: i32,
+    pub author: String,
+    pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl From<&PipelineVersion> for PipelineVersionSummary {
+    fn from(version: &PipelineVersion) -> Self {
+        Self {
+            version: version.version,
+            author: version.author.clone(),
+            created_at: version.created_at,
+            message: version.message.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct PipelineVersionList {
+    pub list: Vec<PipelineVersionSummary>,
+}
+
+/// New version of a realtime pipeline running on a share of the records of its
+/// source stream, until it is promoted or discarded
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct StagedPipeline {
+    /// Version of the pipeline the staged version replaces, the staged version
+    /// is ignored once the pipeline changes
+    pub base_version: i32,
+    /// Percentage of the records processed by the staged version
+    pub percentage: f64,
+    pub author: String,
+    pub created_at: i64,
+    pub pipeline: Pipeline,
+}
+
+#[derive(Clone, Debug, Deserialize, ToSchema)]
+pub struct StagePipelineRequest {
+    pub pipeline: Pipeline,
+    pub percentage: f64,
+}
+
+impl StagePipelineRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.percentage > 0.0 && self.percentage < 100.0) {
+            return Err("Staged percentage must be between 0 and 100".to_string());
+        }
+        if !matches!(self.pipeline.source, PipelineSource::Realtime(_)) {
+            return Err("Only realtime pipelines can be staged".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Changes between two versions of a pipeline. Nodes are matched by id and
+/// compared by their data, moving a node is not a change.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PipelineDiff {
+    pub from_version: i32,
+    pub to_version: i32,
+    /// Names of the changed pipeline fields: name, description, enabled or source
+    pub changed_fields: Vec<String>,
+    pub nodes_added: Vec<Node>,
+    pub nodes_removed: Vec<Node>,
+    pub nodes_changed: Vec<NodeChange>,
+    pub edges_added: Vec<Edge>,
+    pub edges_removed: Vec<Edge>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct NodeChange {
+    pub id: String,
+    pub before: NodeData,
+    pub after: NodeData,
+}
+
+impl PipelineDiff {
+    pub fn new(from: &PipelineVersion, to: &PipelineVersion) -> Self {
+        let (before, after) = (&from.pipeline, &to.pipeline);
+        let mut diff = Self {
+            from_version: from.version,
+            to_version: to.version,
+            ..Default::default()
+        };
+        for (field, changed) in [
+            ("name", before.name != after.name),
+            ("description", before.description != after.description),
+            ("enabled", before.enabled != after.enabled),
+            ("source", before.source != after.source),
+        ] {
+            if changed {
+                diff.changed_fields.push(field.to_string());
+            }
+        }
+
+        let before_nodes: HashMap<&str, &Node> =
+            before.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        let after_nodes: HashMap<&str, &Node> =
+            after.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        for node in &after.nodes {
+            match before_nodes.get(node.id.as_str()) {
+                None => diff.nodes_added.push(node.clone()),
+                Some(prev) if prev.data != node.data => diff.nodes_changed.push(NodeChange {
+                    id: node.id.clone(),
+                    before: prev.data.clone(),
+                    after: node.data.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        diff.nodes_removed = before
+            .nodes
+            .iter()
+            .filter(|n| !after_nodes.contains_key(n.id.as_str()))
+            .cloned()
+            .collect();
+
+        let connects = |edges: &[Edge], edge: &Edge| {
+            edges
+                .iter()
+                .any(|e| e.source == edge.source && e.target == edge.target)
+        };
+        diff.edges_added = after
+            .edges
+            .iter()
+            .filter(|e| !connects(&before.edges, e))
+            .cloned()
+            .collect();
+        diff.edges_removed = before
+            .edges
+            .iter()
+            .filter(|e| !connects(&after.edges, e))
+            .cloned()
+            .collect();
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::json;
+
+    fn version(version: i32, nodes: json::Value, edges: json::Value) -> PipelineVersion {
+        let pipeline = json::from_value(json::json!({
+            "name": "test",
+            "nodes": nodes,
+            "edges": edges
+        }))
+        .unwrap();
+        PipelineVersion {
+            version,
+            author: "root@example.com".to_string(),
+            created_at: 0,
+            message: None,
+            pipeline,
+        }
+    }
+
+    #[test]
+    fn test_pipeline_diff() {
+        let node = |id: &str, stream_name: &str, x: i32| {
+            json::json!({
+                "id": id,
+                "data": {
+                    "node_type": "stream",
+                    "org_id": "default",
+                    "stream_name": stream_name,
+                    "stream_type": "logs"
+                },
+                "position": {"x": x, "y": 0},
+                "io_type": "output"
+            })
+        };
+        let from = version(
+            1,
+            json::json!([
+                node("1", "default", 0),
+                node("2", "a", 0),
+                node("3", "b", 0)
+            ]),
+            json::json!([
+                {"id": "e1-2", "source": "1", "target": "2"},
+                {"id": "e1-3", "source": "1", "target": "3"}
+            ]),
+        );
+        let to = version(
+            2,
+            json::json!([
+                node("1", "default", 50),
+                node("2", "c", 0),
+                node("4", "d", 0)
+            ]),
+            json::json!([
+                {"id": "e1-2", "source": "1", "target": "2"},
+                {"id": "e1-4", "source": "1", "target": "4"}
+            ]),
+        );
+
+        let diff = PipelineDiff::new(&from, &to);
+        assert!(diff.changed_fields.is_empty());
+        assert_eq!(diff.nodes_added.len(), 1);
+        assert_eq!(diff.nodes_added[0].id, "4");
+        assert_eq!(diff.nodes_removed.len(), 1);
+        assert_eq!(diff.nodes_removed[0].id, "3");
+        assert_eq!(diff.nodes_changed.len(), 1);
+        assert_eq!(diff.nodes_changed[0].id, "2");
+        assert_eq!(diff.edges_added[0].target, "4");
+        assert_eq!(diff.edges_removed[0].target, "3");
+    }
+}
@@ -0,0 +1,534 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Detection and redaction of personal data, shared by the redaction node of
+//! the pipelines and the masking policies applied to search results.
+
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::utils::{
+    json::{Map, Value},
+    sql::Projection,
+};
+
+const REGEX_SIZE_LIMIT: usize = 1024 * 1024;
+
+/// Redacts the values of the string fields matched by the detectors
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct RedactionParams {
+    /// Fields to scan, all the string fields when empty
+    #[serde(default)]
+    pub fields: Vec<String>,
+    #[serde(default)]
+    pub detectors: Vec<BuiltinDetector>,
+    #[serde(default)]
+    pub custom_detectors: Vec<CustomDetector>,
+    pub action: RedactionAction,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionAction {
+    /// Replaces every character of the detected value with `*`
+    Mask,
+    /// Replaces the detected value with its sha256 salted by the organization,
+    /// equal values keep equal hashes
+    Hash,
+    /// Removes the field holding the detected value
+    DropField,
+    /// Drops the record holding the detected value
+    DropRecord,
+}
+
+impl std::fmt::Display for RedactionAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedactionAction::Mask => write!(f, "mask"),
+            RedactionAction::Hash => write!(f, "hash"),
+            RedactionAction::DropField => write!(f, "drop_field"),
+            RedactionAction::DropRecord => write!(f, "drop_record"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinDetector {
+    Email,
+    /// Card numbers of 13 to 19 digits passing the Luhn check
+    CreditCard,
+    Ipv4,
+    Ipv6,
+    /// JWTs, bearer tokens, AWS access key ids, GitHub and Slack tokens
+    Token,
+    /// US social security numbers
+    UsSsn,
+    /// International bank account numbers passing the mod 97 check
+    Iban,
+}
+
+impl BuiltinDetector {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuiltinDetector::Email => "email",
+            BuiltinDetector::CreditCard => "credit_card",
+            BuiltinDetector::Ipv4 => "ipv4",
+            BuiltinDetector::Ipv6 => "ipv6",
+            BuiltinDetector::Token => "token",
+            BuiltinDetector::UsSsn => "us_ssn",
+            BuiltinDetector::Iban => "iban",
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            BuiltinDetector::Email => {
+                r"[A-Za-z0-9._%+-]+@[A-Za-z0-9](?:[A-Za-z0-9-]*[A-Za-z0-9])?(?:\.[A-Za-z0-9](?:[A-Za-z0-9-]*[A-Za-z0-9])?)*\.[A-Za-z]{2,}"
+            }
+            BuiltinDetector::CreditCard => r"\b(?:[0-9][ -]?){12,18}[0-9]\b",
+            BuiltinDetector::Ipv4 => {
+                r"\b(?:(?:25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])\.){3}(?:25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])\b"
+            }
+            BuiltinDetector::Ipv6 => {
+                r"(?i)\b(?:[0-9a-f]{1,4}:){7}[0-9a-f]{1,4}\b|\b(?:[0-9a-f]{1,4}:){1,6}:(?:[0-9a-f]{1,4}(?::[0-9a-f]{1,4}){0,5}\b)?"
+            }
+            BuiltinDetector::Token => {
+                r"\beyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+|(?i:\bbearer\s+)[A-Za-z0-9._~+/-]+=*|\b(?:AKIA|ASIA)[0-9A-Z]{16}\b|\bgh[pousr]_[A-Za-z0-9]{36}\b|\bxox[abprs]-[A-Za-z0-9-]{10,}"
+            }
+            BuiltinDetector::UsSsn => r"\b[0-9]{3}-[0-9]{2}-[0-9]{4}\b",
+            BuiltinDetector::Iban => {
+                r"\b[A-Z]{2}[0-9]{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,4})?\b"
+            }
+        }
+    }
+
+    fn validator(&self) -> Validator {
+        match self {
+            BuiltinDetector::CreditCard => Validator::Luhn,
+            BuiltinDetector::UsSsn => Validator::UsSsn,
+            BuiltinDetector::Iban => Validator::Iban,
+            _ => Validator::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct CustomDetector {
+    /// Name of the detector in the redaction counters
+    pub name: String,
+    pub pattern: String,
+    /// Check of the matched values, the values failing it aren't redacted
+    #[serde(default)]
+    pub validator: Validator,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Validator {
+    #[default]
+    None,
+    /// Luhn checksum of the digits, as in card numbers
+    Luhn,
+    /// ISO 7064 mod 97 checksum, as in IBANs
+    Iban,
+    /// Area, group and serial numbers of a US social security number
+    UsSsn,
+}
+
+impl Validator {
+    fn is_valid(&self, value: &str) -> bool {
+        match self {
+            Validator::None => true,
+            Validator::Luhn => luhn_check(value),
+            Validator::Iban => iban_check(value),
+            Validator::UsSsn => us_ssn_check(value),
+        }
+    }
+}
+
+/// Redaction ready to run, its detectors compiled once for all the records
+#[derive(Debug)]
+pub struct CompiledRedaction {
+    fields: Vec<String>,
+    detectors: Vec<CompiledDetector>,
+    pub action: RedactionAction,
+}
+
+#[derive(Debug)]
+struct CompiledDetector {
+    name: String,
+    regex: Regex,
+    validator: Validator,
+}
+
+impl RedactionParams {
+    pub fn validate(&self) -> Result<(), String> {
+        self.compile().map(|_| ())
+    }
+
+    pub fn compile(&self) -> Result<CompiledRedaction, String> {
+        if self.detectors.is_empty() && self.custom_detectors.is_empty() {
+            return Err("RedactionNode needs at least one detector".to_string());
+        }
+        let mut detectors: Vec<CompiledDetector> = Vec::new();
+        for detector in &self.detectors {
+            if detectors.iter().any(|d| d.name == detector.name()) {
+                continue;
+            }
+            detectors.push(CompiledDetector {
+                name: detector.name().to_string(),
+                regex: Regex::new(detector.pattern()).unwrap(),
+                validator: detector.validator(),
+            });
+        }
+        for detector in &self.custom_detectors {
+            if detector.name.is_empty() {
+                return Err("RedactionNode custom detector name is missing".to_string());
+            }
+            if detectors.iter().any(|d| d.name == detector.name) {
+                return Err(format!(
+                    "RedactionNode detector name {} is used more than once",
+                    detector.name
+                ));
+            }
+            let regex = RegexBuilder::new(&detector.pattern)
+                .size_limit(REGEX_SIZE_LIMIT)
+                .build()
+                .map_err(|e| {
+                    format!(
+                        "RedactionNode custom detector {} has an invalid pattern: {e}",
+                        detector.name
+                    )
+                })?;
+            detectors.push(CompiledDetector {
+                name: detector.name.clone(),
+                regex,
+                validator: detector.validator,
+            });
+        }
+        Ok(CompiledRedaction {
+            fields: self.fields.clone(),
+            detectors,
+            action: self.action,
+        })
+    }
+}
+
+impl CompiledRedaction {
+    /// Names of the detectors, in the order of the counts of [Self::apply]
+    pub fn detector_names(&self) -> impl Iterator<Item = &str> {
+        self.detectors.iter().map(|d| d.name.as_str())
+    }
+
+    /// Extends the fields to the columns projected from them by the query,
+    /// or to all the fields when they're projected by unnamed expressions or
+    /// the query can't be resolved
+    pub fn follow_projection(&mut self, projection: Option<&Projection>) {
+        if self.fields.is_empty() {
+            return;
+        }
+        match projection.and_then(|projection| projection.output_names(&self.fields)) {
+            Some(names) => {
+                for name in names {
+                    if !self.fields.contains(&name) {
+                        self.fields.push(name);
+                    }
+                }
+            }
+            None => self.fields.clear(),
+        }
+    }
+
+    /// Redacts the record, adding the number of values found by each detector
+    /// to `counts`. Returns whether any value was found, the record is left
+    /// unchanged by [RedactionAction::DropRecord] which is up to the caller.
+    pub fn apply(&self, record: &mut Map<String, Value>, salt: &str, counts: &mut [u64]) -> bool {
+        let fields: Vec<String> = if self.fields.is_empty() {
+            record
+                .iter()
+                .filter(|(_, value)| value.is_string())
+                .map(|(key, _)| key.clone())
+                .collect()
+        } else {
+            self.fields.clone()
+        };
+
+        let mut found = false;
+        for field in fields {
+            let Some(Value::String(text)) = record.get(&field) else {
+                continue;
+            };
+            let matches = self.find(text);
+            if matches.is_empty() {
+                continue;
+            }
+            found = true;
+            for (idx, _) in &matches {
+                counts[*idx] += 1;
+            }
+            match self.action {
+                RedactionAction::Mask | RedactionAction::Hash => {
+                    let redacted = self.redact(text, &matches, salt);
+                    record.insert(field, Value::String(redacted));
+                }
+                RedactionAction::DropField => {
+                    record.remove(&field);
+                }
+                RedactionAction::DropRecord => {}
+            }
+        }
+        found
+    }
+
+    /// Values found by the detectors, by detector index and position. Of the
+    /// overlapping values the leftmost then longest one is kept.
+    fn find(&self, text: &str) -> Vec<(usize, Range<usize>)> {
+        let mut candidates: Vec<(usize, Range<usize>)> = Vec::new();
+        for (idx, detector) in self.detectors.iter().enumerate() {
+            for m in detector.regex.find_iter(text) {
+                if !m.is_empty() && detector.validator.is_valid(m.as_str()) {
+                    candidates.push((idx, m.range()));
+                }
+            }
+        }
+        candidates.sort_by(|(_, a), (_, b)| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+        let mut matches: Vec<(usize, Range<usize>)> = Vec::with_capacity(candidates.len());
+        for (idx, range) in candidates {
+            if matches
+                .last()
+                .is_none_or(|(_, last)| last.end <= range.start)
+            {
+                matches.push((idx, range));
+            }
+        }
+        matches
+    }
+
+    fn redact(&self, text: &str, matches: &[(usize, Range<usize>)], salt: &str) -> String {
+        let mut redacted = String::with_capacity(text.len());
+        let mut last = 0;
+        for (_, range) in matches {
+            redacted.push_str(&text[last..range.start]);
+            let value = &text[range.clone()];
+            if self.action == RedactionAction::Hash {
+                redacted.push_str(&sha256::digest(format!("{salt}{value}")));
+            } else {
+                redacted.extend(std::iter::repeat_n('*', value.chars().count()));
+            }
+            last = range.end;
+        }
+        redacted.push_str(&text[last..]);
+        redacted
+    }
+}
+
+/// Masks the search results of the users having one of its roles
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct MaskingPolicy {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Roles of the users whose results are masked
+    pub roles: Vec<String>,
+    /// Streams whose results are masked, all the streams when empty
+    #[serde(default)]
+    pub streams: Vec<String>,
+    #[serde(flatten)]
+    pub redaction: RedactionParams,
+}
+
+impl MaskingPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("Masking policy name is missing".to_string());
+        }
+        if self.name.contains('/') {
+            return Err("Masking policy name can't contain '/'".to_string());
+        }
+        if self.roles.is_empty() {
+            return Err("Masking policy needs at least one role".to_string());
+        }
+        if self.redaction.action == RedactionAction::DropRecord {
+            return Err("Masking policy can't drop records".to_string());
+        }
+        self.redaction.validate()
+    }
+
+    /// Whether the policy masks the results of the streams for the role
+    pub fn applies_to(&self, role: &str, streams: &[String]) -> bool {
+        self.roles.iter().any(|r| r == role)
+            && (self.streams.is_empty() || streams.iter().any(|s| self.streams.contains(s)))
+    }
+}
+
+fn luhn_check(value: &str) -> bool {
+    let digits: Vec<u32> = value.chars().filter_map(|c| c.to_digit(10)).collect();
+    if digits.len() < 12 || digits.len() > 19 {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let d = d * 2;
+                if d > 9 { d - 9 } else { d }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+fn iban_check(value: &str) -> bool {
+    let value: Vec<char> = value.chars().filter(|c| !c.is_whitespace()).collect();
+    if value.len() < 15 || value.len() > 34 {
+        return false;
+    }
+    // the country code and check digits are moved to the end, the letters
+    // count as 10 to 35
+    let mut remainder = 0u32;
+    for c in value[4..].iter().chain(&value[..4]) {
+        let Some(n) = c.to_digit(36) else {
+            return false;
+        };
+        remainder = if n < 10 {
+            (remainder * 10 + n) % 97
+        } else {
+            (remainder * 100 + n) % 97
+        };
+    }
+    remainder == 1
+}
+
+fn us_ssn_check(value: &str) -> bool {
+    let mut parts = value.split('-');
+    let (Some(area), Some(group), Some(serial)) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    area != "000" && area != "666" && !area.starts_with('9') && group != "00" && serial != "0000"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::json;
+
+    fn redact(params: json::Value, record: json::Value) -> (json::Value, bool, Vec<u64>) {
+        let params: RedactionParams = json::from_value(params).unwrap();
+        let redaction = params.compile().unwrap();
+        let mut record = record.as_object().unwrap().clone();
+        let mut counts = vec![0; redaction.detector_names().count()];
+        let found = redaction.apply(&mut record, "salt", &mut counts);
+        (json::Value::Object(record), found, counts)
+    }
+
+    #[test]
+    fn test_mask_builtin_detectors() {
+        let (record, found, counts) = redact(
+            json::json!({
+                "detectors": ["email", "credit_card", "ipv4", "token", "us_ssn", "iban"],
+                "action": "mask"
+            }),
+            json::json!({
+                "msg": "user bob@example.com paid with 4111 1111 1111 1111 from 10.1.2.3",
+                "auth": "Bearer abc.def-123",
+                "ids": "ssn 123-45-6789, not 000-12-3456, iban GB82 WEST 1234 5698 7654 32",
+                "order": "order 4111111111111112",
+                "code": 200
+            }),
+        );
+        assert!(found);
+        assert_eq!(
+            record["msg"],
+            "user *************** paid with ******************* from ********"
+        );
+        assert_eq!(record["auth"], "******************");
+        assert_eq!(
+            record["ids"],
+            "ssn ***********, not 000-12-3456, iban ***************************"
+        );
+        // fails the Luhn check
+        assert_eq!(record["order"], "order 4111111111111112");
+        assert_eq!(record["code"], 200);
+        assert_eq!(counts, vec![1, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_hash_and_drop_actions() {
+        let params = json::json!({
+            "fields": ["user"],
+            "custom_detectors": [{"name": "employee_id", "pattern": "EMP-[0-9]{6}"}],
+            "action": "hash"
+        });
+        let (first, _, counts) = redact(
+            params.clone(),
+            json::json!({"user": "EMP-123456", "other": "EMP-654321"}),
+        );
+        let (second, ..) = redact(params, json::json!({"user": "EMP-123456"}));
+        assert_eq!(counts, vec![1]);
+        assert_ne!(first["user"], "EMP-123456");
+        assert_eq!(first["user"], second["user"]);
+        // only the selected fields are scanned
+        assert_eq!(first["other"], "EMP-654321");
+
+        let (record, found, _) = redact(
+            json::json!({"detectors": ["email"], "action": "drop_field"}),
+            json::json!({"to": "a@b.io", "msg": "sent"}),
+        );
+        assert!(found);
+        assert_eq!(record, json::json!({"msg": "sent"}));
+
+        let (record, found, _) = redact(
+            json::json!({"detectors": ["email"], "action": "drop_record"}),
+            json::json!({"msg": "no personal data"}),
+        );
+        assert!(!found);
+        assert_eq!(record["msg"], "no personal data");
+    }
+
+    #[test]
+    fn test_follow_projection() {
+        let params: RedactionParams = json::from_value(json::json!({
+            "fields": ["email"],
+            "detectors": ["email"],
+            "action": "mask"
+        }))
+        .unwrap();
+        let projection =
+            crate::utils::sql::resolve_projection("SELECT email AS contact, msg FROM t").unwrap();
+        let mut redaction = params.compile().unwrap();
+        redaction.follow_projection(Some(&projection));
+        let mut record = json::json!({"contact": "a@b.io", "msg": "c@d.io"});
+        let record = record.as_object_mut().unwrap();
+        redaction.apply(record, "", &mut [0]);
+        assert_eq!(record["contact"], "******");
+        assert_eq!(record["msg"], "c@d.io");
+
+        // unknown projection, all the fields are scanned
+        let mut redaction = params.compile().unwrap();
+        redaction.follow_projection(None);
+        let mut record = json::json!({"msg": "c@d.io"});
+        let record = record.as_object_mut().unwrap();
+        redaction.apply(record, "", &mut [0]);
+        assert_eq!(record["msg"], "******");
+    }
+}
@@ -13,13 +13,15 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashMap;
+
 use proto::cluster_rpc;
 use serde::{Deserialize, Deserializer, Serialize};
 use utoipa::ToSchema;
 
 use crate::{
     meta::sql::OrderBy,
-    utils::{base64, json},
+    utils::{base64, hash::Sum64, json},
 };
 
 pub const PARTIAL_ERROR_RESPONSE_MESSAGE: &str =
@@ -125,6 +127,16 @@ pub struct Query {
     pub streaming_output: bool,
     #[serde(default)]
     pub streaming_id: Option<String>,
+    // cursor (search-after) pagination
+    #[serde(default)]
+    pub use_cursor: bool,
+    #[serde(default)]
+    pub cursor: Option<String>,
+    // values of the sql parameters, `$name` in the sql
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[schema(value_type = Object)]
+    pub params: HashMap<String, json::Value>,
 }
 
 fn default_size() -> i64 {
@@ -148,6 +160,9 @@ impl Default for Query {
             skip_wal: false,
             streaming_output: false,
             streaming_id: None,
+            use_cursor: false,
+            cursor: None,
+            params: HashMap::new(),
         }
     }
 }
@@ -217,6 +232,9 @@ pub struct Response {
     pub work_group: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub order_by: Option<OrderBy>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>, // for the next page, only in cursor mode
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default, ToSchema)]
@@ -274,6 +292,7 @@ impl Response {
             result_cache_ratio: 0,
             work_group: None,
             order_by: None,
+            cursor: None,
         }
     }
 
@@ -378,6 +397,175 @@ impl Response {
     }
 }
 
+/// Max number of hits sharing one timestamp that a cursor can page through
+pub const MAX_CURSOR_TIES: usize = 1000;
+
+/// Position of a cursor (search-after) paginated search.
+///
+/// The cursor is handed to the client as an opaque string in
+/// [`Response::cursor`] and sent back in [`Query::cursor`] for the next page.
+/// The first page takes a snapshot of the time range, the following pages only
+/// scan the time range that is left after the last returned hit. Every page
+/// reads the WAL too, the records flushed from it between two pages are read
+/// from their new files. The hits sharing the timestamp of the last hit are
+/// told apart by their hash, the tie-breaker, whatever order the next query
+/// returns them in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchCursor {
+    /// hash of the sql the cursor was created for
+    #[serde(rename = "q")]
+    pub sql_hash: u64,
+    /// `_timestamp` of the last returned hit
+    #[serde(rename = "t")]
+    pub timestamp: i64,
+    /// hashes of the returned hits sharing `timestamp`, the tie-breaker
+    #[serde(rename = "h")]
+    #[serde(default)]
+    pub seen: Vec<u64>,
+    /// snapshot of the time range taken on the first page
+    #[serde(rename = "b")]
+    pub start_time: i64,
+    #[serde(rename = "e")]
+    pub end_time: i64,
+    #[serde(rename = "o")]
+    pub order_by: OrderBy,
+}
+
+impl SearchCursor {
+    /// Creates the cursor of the first page, which doesn't skip any hit
+    pub fn new(query: &Query, order_by: OrderBy) -> Self {
+        let end_time = if query.end_time > 0 {
+            query.end_time.min(crate::utils::time::now_micros() + 1)
+        } else {
+            crate::utils::time::now_micros() + 1
+        };
+        let timestamp = match order_by {
+            OrderBy::Desc => end_time - 1,
+            OrderBy::Asc => query.start_time,
+        };
+        Self {
+            sql_hash: Self::hash_sql(&query.sql),
+            timestamp,
+            seen: vec![],
+            start_time: query.start_time,
+            end_time,
+            order_by,
+        }
+    }
+
+    pub fn hash_sql(sql: &str) -> u64 {
+        crate::utils::hash::gxhash::new().sum64(sql.trim())
+    }
+
+    fn hash_hit(hit: &json::Value) -> u64 {
+        crate::utils::hash::gxhash::new().sum64(&hit.to_string())
+    }
+
+    pub fn encode(&self) -> String {
+        base64::encode_url(&json::to_string(self).unwrap_or_default())
+    }
+
+    pub fn decode(s: &str) -> Result<Self, std::io::Error> {
+        let v = base64::decode_url(s).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid cursor: {e}"),
+            )
+        })?;
+        json::from_str(&v).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid cursor: {e}"),
+            )
+        })
+    }
+
+    /// Narrows the query to the hits from the cursor on, the hits already
+    /// returned are removed by [`SearchCursor::filter`]
+    pub fn apply(&self, query: &mut Query) {
+        match self.order_by {
+            OrderBy::Desc => {
+                query.start_time = self.start_time;
+                query.end_time = self.timestamp + 1;
+            }
+            OrderBy::Asc => {
+                query.start_time = self.timestamp;
+                query.end_time = self.end_time;
+            }
+        }
+        query.from = 0;
+        if query.size > 0 {
+            query.size += self.seen.len() as i64;
+        }
+    }
+
+    /// Removes the hits returned by the previous pages from the hits of the
+    /// query narrowed by [`SearchCursor::apply`], keeps `size` hits
+    pub fn filter(&self, hits: &mut Vec<json::Value>, size: i64, ts_column: &str) {
+        let mut seen = HashMap::<u64, usize>::with_capacity(self.seen.len());
+        for hash in self.seen.iter() {
+            *seen.entry(*hash).or_default() += 1;
+        }
+        hits.retain(|hit| {
+            if hit.get(ts_column).and_then(|v| v.as_i64()) != Some(self.timestamp) {
+                return true;
+            }
+            match seen.get_mut(&Self::hash_hit(hit)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            }
+        });
+        if size > 0 {
+            hits.truncate(size as usize);
+        }
+    }
+
+    /// Returns the cursor after the given page of hits, `None` if there are no
+    /// more hits. Fails when more than [`MAX_CURSOR_TIES`] hits share the
+    /// timestamp of the last hit, they can't be told apart anymore.
+    pub fn next(
+        &self,
+        hits: &[json::Value],
+        size: i64,
+        ts_column: &str,
+    ) -> Result<Option<Self>, std::io::Error> {
+        if hits.is_empty() || (size > 0 && (hits.len() as i64) < size) {
+            return Ok(None);
+        }
+        let get_ts = |hit: &json::Value| hit.get(ts_column).and_then(|v| v.as_i64());
+        let Some(timestamp) = hits.last().and_then(get_ts) else {
+            return Ok(None);
+        };
+        let page_seen = hits
+            .iter()
+            .rev()
+            .take_while(|hit| get_ts(hit) == Some(timestamp))
+            .map(Self::hash_hit);
+        // the previous pages returned hits sharing the timestamp too
+        let seen: Vec<u64> = if timestamp == self.timestamp {
+            self.seen.iter().copied().chain(page_seen).collect()
+        } else {
+            page_seen.collect()
+        };
+        if seen.len() > MAX_CURSOR_TIES {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "more than {MAX_CURSOR_TIES} hits share the timestamp {timestamp}, they can't be paged with a cursor"
+                ),
+            ));
+        }
+        Ok(Some(Self {
+            timestamp,
+            seen,
+            ..self.clone()
+        }))
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct SearchPartitionRequest {
     pub sql: String,
@@ -393,6 +581,10 @@ pub struct SearchPartitionRequest {
     pub query_fn: Option<String>,
     #[serde(default)]
     pub streaming_output: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[schema(value_type = Object)]
+    pub params: HashMap<String, json::Value>,
 }
 
 impl SearchPartitionRequest {
@@ -425,6 +617,7 @@ impl From<&Request> for SearchPartitionRequest {
             clusters: req.clusters.clone(),
             query_fn: req.query.query_fn.clone(),
             streaming_output: req.query.streaming_output,
+            params: req.query.params.clone(),
         }
     }
 }
@@ -502,6 +695,9 @@ impl SearchHistoryRequest {
                 skip_wal: false,
                 streaming_output: false,
                 streaming_id: None,
+                use_cursor: false,
+                cursor: None,
+                params: HashMap::new(),
             },
             encoding: RequestEncoding::Empty,
             regions: Vec::new(),
@@ -701,6 +897,11 @@ impl From<Query> for cluster_rpc::SearchQuery {
             query_fn: query.query_fn.unwrap_or_default(),
             action_id: query.action_id.unwrap_or_default(),
             skip_wal: query.skip_wal,
+            params: query
+                .params
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
         }
     }
 }
@@ -1034,6 +1235,9 @@ impl MultiStreamRequest {
                     skip_wal: self.skip_wal,
                     streaming_output: false,
                     streaming_id: None,
+                    use_cursor: false,
+                    cursor: None,
+                    params: HashMap::new(),
                 },
                 regions: self.regions.clone(),
                 clusters: self.clusters.clone(),
@@ -1072,6 +1276,77 @@ mod tests {
         assert_eq!(res.total, 11);
     }
 
+    #[test]
+    fn test_search_cursor() {
+        let query = Query {
+            sql: "select * from t".to_string(),
+            size: 3,
+            start_time: 100,
+            end_time: 200,
+            ..Default::default()
+        };
+        let cursor = SearchCursor::new(&query, OrderBy::Desc);
+        assert_eq!((cursor.timestamp, cursor.end_time), (199, 200));
+
+        let hits = vec![
+            json::json!({"_timestamp": 190, "a": 1}),
+            json::json!({"_timestamp": 150, "a": 2}),
+            json::json!({"_timestamp": 150, "a": 3}),
+        ];
+        let next = cursor.next(&hits, 3, "_timestamp").unwrap().unwrap();
+        assert_eq!(next.timestamp, 150);
+        assert_eq!(next.seen.len(), 2);
+
+        let next = SearchCursor::decode(&next.encode()).unwrap();
+        let mut query = query.clone();
+        next.apply(&mut query);
+        assert_eq!(
+            (query.start_time, query.end_time, query.from, query.size),
+            (100, 151, 0, 5)
+        );
+
+        // the hits of the previous page are removed in whatever order they come
+        let mut page = vec![
+            json::json!({"_timestamp": 150, "a": 3}),
+            json::json!({"_timestamp": 150, "a": 4}),
+            json::json!({"_timestamp": 150, "a": 2}),
+            json::json!({"_timestamp": 150, "a": 5}),
+            json::json!({"_timestamp": 120, "a": 6}),
+        ];
+        next.filter(&mut page, 3, "_timestamp");
+        assert_eq!(
+            page.iter()
+                .map(|hit| hit["a"].as_i64().unwrap())
+                .collect::<Vec<_>>(),
+            vec![4, 5, 6]
+        );
+
+        // a page with only the same timestamp keeps the hashes of the previous
+        let hits = vec![
+            json::json!({"_timestamp": 150, "a": 4}),
+            json::json!({"_timestamp": 150, "a": 5}),
+            json::json!({"_timestamp": 150, "a": 7}),
+        ];
+        assert_eq!(
+            next.next(&hits, 3, "_timestamp")
+                .unwrap()
+                .unwrap()
+                .seen
+                .len(),
+            5
+        );
+        // the last page
+        assert!(next.next(&hits[..1], 3, "_timestamp").unwrap().is_none());
+
+        // too many hits sharing one timestamp to tell them apart
+        let ties = SearchCursor {
+            seen: vec![0; MAX_CURSOR_TIES],
+            ..next.clone()
+        };
+        assert!(ties.next(&hits, 3, "_timestamp").is_err());
+        assert!(SearchCursor::decode("invalid").is_err());
+    }
+
     #[test]
     fn test_request_encoding() {
         let req = json::json!(
@@ -18,7 +18,7 @@ use tokio::{
     sync::{mpsc, oneshot},
     time,
 };
-use usage::{TriggerData, UsageData};
+use usage::{PipelineNodeData, TriggerData, UsageData};
 
 pub mod error;
 pub mod usage;
@@ -40,6 +40,7 @@ pub enum ReportingData {
     Usage(Box<UsageData>),
     Trigger(Box<TriggerData>),
     Error(Box<ErrorData>),
+    PipelineNode(Box<PipelineNodeData>),
 }
 
 #[derive(Debug)]
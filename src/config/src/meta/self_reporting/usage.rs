@@ -27,6 +27,7 @@ pub const USAGE_STREAM: &str = "usage";
 pub const STATS_STREAM: &str = "stats";
 pub const TRIGGERS_USAGE_STREAM: &str = "triggers";
 pub const ERROR_STREAM: &str = "errors";
+pub const PIPELINES_USAGE_STREAM: &str = "pipelines";
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TriggerDataStatus {
@@ -38,6 +39,9 @@ pub enum TriggerDataStatus {
     ConditionNotSatisfied,
     #[serde(rename = "skipped")]
     Skipped,
+    /// muted by a silence
+    #[serde(rename = "silenced")]
+    Silenced,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -73,6 +77,43 @@ pub struct TriggerData {
     pub evaluation_took_in_secs: Option<f64>,
     pub source_node: Option<String>,
     pub query_took: Option<i64>,
+    /// names of the silences which muted the trigger
+    pub silenced_by: Option<String>,
+}
+
+/// Records processed by a pipeline node, summed over the batches of a reporting interval
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PipelineNodeData {
+    pub _timestamp: i64,
+    pub org: String,
+    pub pipeline_id: String,
+    pub pipeline_name: String,
+    pub node_id: String,
+    pub node_type: String,
+    pub batches: u64,
+    pub received: u64,
+    /// records sent to the children of the node or to its destination
+    pub emitted: u64,
+    /// records received but not emitted, e.g. not matching a condition
+    pub dropped: u64,
+    pub failed: u64,
+    /// time spent processing the records, e.g. running VRL for function nodes
+    pub took_in_secs: Option<f64>,
+}
+
+impl PipelineNodeData {
+    pub fn merge(&mut self, other: &PipelineNodeData) {
+        self._timestamp = self._timestamp.max(other._timestamp);
+        self.batches += other.batches;
+        self.received += other.received;
+        self.emitted += other.emitted;
+        self.dropped += other.dropped;
+        self.failed += other.failed;
+        self.took_in_secs = match (self.took_in_secs, other.took_in_secs) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -0,0 +1,363 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::utils::json;
+
+/// Max nesting of macros referencing other macros
+const MAX_EXPAND_DEPTH: usize = 8;
+
+/// Org scoped SQL macro, referenced in a query as `$name`.
+///
+/// A macro is either a SQL fragment (`sql`), expanded in parentheses, or a
+/// typed parameter (`param_type`) whose value is given by the query or falls
+/// back to `default`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct SqlMacro {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sql: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub param_type: Option<SqlParamType>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Object)]
+    pub default: Option<json::Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlParamType {
+    String,
+    Number,
+    Boolean,
+    StringList,
+    NumberList,
+}
+
+impl std::fmt::Display for SqlParamType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SqlParamType::String => write!(f, "string"),
+            SqlParamType::Number => write!(f, "number"),
+            SqlParamType::Boolean => write!(f, "boolean"),
+            SqlParamType::StringList => write!(f, "string_list"),
+            SqlParamType::NumberList => write!(f, "number_list"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct SqlMacroList {
+    pub list: Vec<SqlMacro>,
+}
+
+impl SqlMacro {
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_valid_name(&self.name) {
+            return Err(format!(
+                "invalid macro name [{}], only letters, digits and '_' are allowed and it can't start with a digit",
+                self.name
+            ));
+        }
+        match (&self.sql, self.param_type) {
+            (Some(sql), None) => {
+                if sql.trim().is_empty() {
+                    return Err("macro sql can't be empty".to_string());
+                }
+                if self.default.is_some() {
+                    return Err("default value is only allowed for parameters".to_string());
+                }
+                Ok(())
+            }
+            (None, Some(param_type)) => match &self.default {
+                Some(v) => render_value(&self.name, param_type, v).map(|_| ()),
+                None => Ok(()),
+            },
+            _ => Err("macro should have either sql or param_type".to_string()),
+        }
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Expands the `$name` references in the sql with the macros given by
+/// `get_macro` and the parameter values of the query.
+///
+/// References in string literals, quoted identifiers and comments are kept,
+/// as are the positional placeholders like `$1`. A reference which is neither
+/// a macro nor a query parameter is an error.
+pub fn expand(
+    sql: &str,
+    params: &HashMap<String, json::Value>,
+    get_macro: impl Fn(&str) -> Option<SqlMacro>,
+) -> Result<String, String> {
+    if !sql.contains('$') {
+        return Ok(sql.to_string());
+    }
+    let mut stack = Vec::new();
+    expand_inner(sql, params, &get_macro, &mut stack)
+}
+
+fn expand_inner(
+    sql: &str,
+    params: &HashMap<String, json::Value>,
+    get_macro: &impl Fn(&str) -> Option<SqlMacro>,
+    stack: &mut Vec<String>,
+) -> Result<String, String> {
+    let chars = sql.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(sql.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' | '"' => {
+                // copy the quoted text, a doubled quote is an escaped quote
+                out.push(c);
+                i += 1;
+                while i < chars.len() {
+                    out.push(chars[i]);
+                    if chars[i] == c {
+                        if chars.get(i + 1) == Some(&c) {
+                            out.push(c);
+                            i += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                out.push_str("/*");
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+                if i < chars.len() {
+                    out.push_str("*/");
+                    i += 2;
+                }
+            }
+            '$' if chars
+                .get(i + 1)
+                .is_some_and(|c| c.is_ascii_alphabetic() || *c == '_') =>
+            {
+                let start = i + 1;
+                let mut end = start;
+                while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '_')
+                {
+                    end += 1;
+                }
+                let name = chars[start..end].iter().collect::<String>();
+                out.push_str(&expand_reference(&name, params, get_macro, stack)?);
+                i = end;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn expand_reference(
+    name: &str,
+    params: &HashMap<String, json::Value>,
+    get_macro: &impl Fn(&str) -> Option<SqlMacro>,
+    stack: &mut Vec<String>,
+) -> Result<String, String> {
+    let Some(m) = get_macro(name) else {
+        // parameter given by the query only, typed by its json value
+        return match params.get(name) {
+            Some(value) => render_value(name, infer_type(value), value),
+            None => Err(format!("unknown macro or parameter ${name}")),
+        };
+    };
+    if let Some(sql) = m.sql.as_ref() {
+        if stack.iter().any(|v| v == name) {
+            return Err(format!("macro ${name} references itself"));
+        }
+        if stack.len() >= MAX_EXPAND_DEPTH {
+            return Err(format!(
+                "macro ${name} is nested more than {MAX_EXPAND_DEPTH} levels"
+            ));
+        }
+        stack.push(name.to_string());
+        let expanded = expand_inner(sql, params, get_macro, stack)?;
+        stack.pop();
+        return Ok(format!("({expanded})"));
+    }
+    let param_type = m.param_type.unwrap_or(SqlParamType::String);
+    match params.get(name).or(m.default.as_ref()) {
+        Some(value) => render_value(name, param_type, value),
+        None => Err(format!("missing value of parameter ${name}")),
+    }
+}
+
+fn infer_type(value: &json::Value) -> SqlParamType {
+    match value {
+        json::Value::Number(_) => SqlParamType::Number,
+        json::Value::Bool(_) => SqlParamType::Boolean,
+        json::Value::Array(v) if v.iter().all(|v| v.is_number()) && !v.is_empty() => {
+            SqlParamType::NumberList
+        }
+        json::Value::Array(_) => SqlParamType::StringList,
+        _ => SqlParamType::String,
+    }
+}
+
+fn render_value(
+    name: &str,
+    param_type: SqlParamType,
+    value: &json::Value,
+) -> Result<String, String> {
+    let invalid = || format!("invalid value of parameter ${name}, expected {param_type}");
+    match param_type {
+        SqlParamType::String => value.as_str().map(quote_str).ok_or_else(invalid),
+        SqlParamType::Number => value.as_number().map(|v| v.to_string()).ok_or_else(invalid),
+        SqlParamType::Boolean => value.as_bool().map(|v| v.to_string()).ok_or_else(invalid),
+        SqlParamType::StringList | SqlParamType::NumberList => {
+            let item_type = if param_type == SqlParamType::StringList {
+                SqlParamType::String
+            } else {
+                SqlParamType::Number
+            };
+            let items = value
+                .as_array()
+                .ok_or_else(invalid)?
+                .iter()
+                .map(|v| render_value(name, item_type, v).map_err(|_| invalid()))
+                .collect::<Result<Vec<_>, _>>()?;
+            // an empty list matches nothing in `IN (...)`
+            if items.is_empty() {
+                Ok("NULL".to_string())
+            } else {
+                Ok(items.join(", "))
+            }
+        }
+    }
+}
+
+fn quote_str(v: &str) -> String {
+    format!("'{}'", v.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_macro(name: &str) -> Option<SqlMacro> {
+        match name {
+            "env" => Some(SqlMacro {
+                name: name.to_string(),
+                param_type: Some(SqlParamType::String),
+                default: Some(json::json!("prod")),
+                ..Default::default()
+            }),
+            "service" => Some(SqlMacro {
+                name: name.to_string(),
+                param_type: Some(SqlParamType::StringList),
+                ..Default::default()
+            }),
+            "prod_filter" => Some(SqlMacro {
+                name: name.to_string(),
+                sql: Some("env = $env AND level != 'debug'".to_string()),
+                ..Default::default()
+            }),
+            "loop" => Some(SqlMacro {
+                name: name.to_string(),
+                sql: Some("a = 1 OR $loop".to_string()),
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand() {
+        let mut params = HashMap::new();
+        params.insert("service".to_string(), json::json!(["api", "o'neil"]));
+
+        let sql = "SELECT * FROM t WHERE $prod_filter AND service IN ($service)";
+        assert_eq!(
+            expand(sql, &params, get_macro).unwrap(),
+            "SELECT * FROM t WHERE (env = 'prod' AND level != 'debug') AND service IN ('api', 'o''neil')"
+        );
+
+        params.insert("env".to_string(), json::json!("dev"));
+        params.insert("min".to_string(), json::json!(10));
+        let sql = "SELECT * FROM t WHERE env = $env AND code > $min AND msg = '$env' -- $env";
+        assert_eq!(
+            expand(sql, &params, get_macro).unwrap(),
+            "SELECT * FROM t WHERE env = 'dev' AND code > 10 AND msg = '$env' -- $env"
+        );
+
+        // positional placeholders are kept
+        let sql = "SELECT * FROM t WHERE a = $1";
+        assert_eq!(expand(sql, &params, get_macro).unwrap(), sql);
+    }
+
+    #[test]
+    fn test_expand_errors() {
+        let params = HashMap::new();
+        let sql = "SELECT * FROM t WHERE $unknown";
+        assert!(expand(sql, &params, get_macro).is_err());
+        let sql = "SELECT * FROM t WHERE $loop";
+        assert!(expand(sql, &params, get_macro).is_err());
+        // no value and no default
+        let sql = "SELECT * FROM t WHERE service IN ($service)";
+        assert!(expand(sql, &params, get_macro).is_err());
+
+        let mut params = HashMap::new();
+        params.insert("env".to_string(), json::json!(1));
+        let sql = "SELECT * FROM t WHERE env = $env";
+        assert!(expand(sql, &params, get_macro).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut m = get_macro("env").unwrap();
+        assert!(m.validate().is_ok());
+        m.default = Some(json::json!(["a"]));
+        assert!(m.validate().is_err());
+        m.sql = Some("a = 1".to_string());
+        assert!(m.validate().is_err());
+        assert!(get_macro("prod_filter").unwrap().validate().is_ok());
+        m.name = "1env".to_string();
+        assert!(m.validate().is_err());
+    }
+}
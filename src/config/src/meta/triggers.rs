@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::meta::alerts::state::AlertStates;
+
 #[derive(Debug, Clone, sqlx::Type, PartialEq, Serialize, Deserialize, Default)]
 #[repr(i32)]
 pub enum TriggerStatus {
@@ -64,6 +66,10 @@ pub struct ScheduledTriggerData {
     pub tolerance: i64,
     #[serde(default)]
     pub last_satisfied_at: Option<i64>,
+    /// States of the alert groups, only used by stateful alerts
+    #[serde(default)]
+    #[serde(skip_serializing_if = "AlertStates::is_empty")]
+    pub states: AlertStates,
 }
 
 impl ScheduledTriggerData {
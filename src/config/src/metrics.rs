@@ -634,6 +634,18 @@ pub static QUERY_CANCELED_NUMS: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .expect("Metric created")
 });
+pub static QUERY_QUOTA_REJECTED_NUMS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "query_quota_rejected_nums",
+            "Query numbers rejected by quota",
+        )
+        .namespace(NAMESPACE)
+        .const_labels(create_const_labels()),
+        &["organization", "quota"],
+    )
+    .expect("Metric created")
+});
 
 // This corresponds to mysql or pgsql queries, not sqlite as that is local and can be ignored
 pub static DB_QUERY_NUMS: Lazy<IntCounterVec> = Lazy::new(|| {
@@ -749,6 +761,61 @@ pub static NODE_TCP_CONN_RESETS: Lazy<IntGaugeVec> = Lazy::new(|| {
     .expect("Metric created")
 });
 
+// pipeline stats
+pub static PIPELINE_NODE_RECORDS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "pipeline_node_records",
+            "Records received, emitted, dropped or failed by pipeline nodes.".to_owned()
+                + HELP_SUFFIX,
+        )
+        .namespace(NAMESPACE)
+        .const_labels(create_const_labels()),
+        &["organization", "pipeline", "node_id", "node_type", "status"],
+    )
+    .expect("Metric created")
+});
+pub static PIPELINE_NODE_TIME: Lazy<HistogramVec> = Lazy::new(|| {
+    HistogramVec::new(
+        HistogramOpts::new(
+            "pipeline_node_time",
+            "Time spent by pipeline nodes processing a batch, in seconds.".to_owned() + HELP_SUFFIX,
+        )
+        .namespace(NAMESPACE)
+        .const_labels(create_const_labels()),
+        &["organization", "pipeline", "node_id", "node_type"],
+    )
+    .expect("Metric created")
+});
+
+pub static PIPELINE_REMOTE_DROPPED_RECORDS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "pipeline_remote_dropped_records",
+            "Records of pipelines dropped by their remote destinations.".to_owned() + HELP_SUFFIX,
+        )
+        .namespace(NAMESPACE)
+        .const_labels(create_const_labels()),
+        &["organization", "pipeline", "destination", "reason"],
+    )
+    .expect("Metric created")
+});
+
+// redaction stats
+pub static REDACTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "redactions",
+            "Values redacted by pipeline redaction nodes and search masking policies.".to_owned()
+                + HELP_SUFFIX,
+        )
+        .namespace(NAMESPACE)
+        .const_labels(create_const_labels()),
+        &["organization", "source", "name", "detector", "action"],
+    )
+    .expect("Metric created")
+});
+
 fn register_metrics(registry: &Registry) {
     // http latency
     registry
@@ -846,6 +913,9 @@ fn register_metrics(registry: &Registry) {
     registry
         .register(Box::new(QUERY_CANCELED_NUMS.clone()))
         .expect("Metric registered");
+    registry
+        .register(Box::new(QUERY_QUOTA_REJECTED_NUMS.clone()))
+        .expect("Metric registered");
 
     // compactor stats
     registry
@@ -959,6 +1029,22 @@ fn register_metrics(registry: &Registry) {
     registry
         .register(Box::new(NODE_TCP_CONN_RESETS.clone()))
         .expect("Metric registered");
+
+    // pipeline stats
+    registry
+        .register(Box::new(PIPELINE_NODE_RECORDS.clone()))
+        .expect("Metric registered");
+    registry
+        .register(Box::new(PIPELINE_NODE_TIME.clone()))
+        .expect("Metric registered");
+    registry
+        .register(Box::new(PIPELINE_REMOTE_DROPPED_RECORDS.clone()))
+        .expect("Metric registered");
+
+    // redaction stats
+    registry
+        .register(Box::new(REDACTIONS.clone()))
+        .expect("Metric registered");
 }
 
 fn create_const_labels() -> HashMap<String, String> {
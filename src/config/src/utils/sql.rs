@@ -13,10 +13,15 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::ops::ControlFlow;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::ControlFlow,
+};
 
 use sqlparser::{
-    ast::{Expr, Function, GroupByExpr, Query, SelectItem, SetExpr, Statement, Visit, Visitor},
+    ast::{
+        Expr, Function, GroupByExpr, Query, Select, SelectItem, SetExpr, Statement, Visit, Visitor,
+    },
     dialect::GenericDialect,
     parser::Parser,
 };
@@ -33,6 +38,37 @@ pub const AGGREGATE_UDF_LIST: [&str; 9] = [
     "percentile_cont",
 ];
 
+/// Modes of a `EXPLAIN` query in `_search`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExplainMode {
+    /// only plan the query
+    Plan,
+    /// execute the query and report the metrics of the plan
+    Analyze,
+}
+
+/// Splits a leading `EXPLAIN [ANALYZE]` from the query, returns the explain mode
+/// and the query to explain
+pub fn parse_explain(query: &str) -> (Option<ExplainMode>, &str) {
+    let Some(rest) = strip_keyword(query.trim_start(), "explain") else {
+        return (None, query);
+    };
+    match strip_keyword(rest, "analyze") {
+        Some(rest) => (Some(ExplainMode::Analyze), rest),
+        None => (Some(ExplainMode::Plan), rest),
+    }
+}
+
+fn strip_keyword<'a>(query: &'a str, keyword: &str) -> Option<&'a str> {
+    let prefix = query.get(..keyword.len())?;
+    let rest = &query[keyword.len()..];
+    if prefix.eq_ignore_ascii_case(keyword) && rest.starts_with(char::is_whitespace) {
+        Some(rest.trim_start())
+    } else {
+        None
+    }
+}
+
 pub fn is_aggregate_query(query: &str) -> Result<bool, sqlparser::parser::ParserError> {
     let ast = Parser::parse_sql(&GenericDialect {}, query)?;
     for statement in ast.iter() {
@@ -194,3 +230,167 @@ impl Visitor for SubqueryVisitor {
         ControlFlow::Continue(())
     }
 }
+
+/// Columns referenced by the projected expressions of a query, of its
+/// subqueries included
+#[derive(Debug, Default, PartialEq)]
+pub struct Projection {
+    /// Columns referenced by the aliased expressions, by alias
+    pub aliases: HashMap<String, HashSet<String>>,
+    /// Columns referenced by the expressions without an alias, whose output
+    /// names are picked by the engine
+    pub unnamed: HashSet<String>,
+}
+
+impl Projection {
+    /// Output names of the projections of `columns`, following the aliases of
+    /// aliases. `None` when one of them is projected by an unnamed expression.
+    pub fn output_names(&self, columns: &[String]) -> Option<HashSet<String>> {
+        let mut names: HashSet<String> = columns.iter().cloned().collect();
+        loop {
+            let added: Vec<&String> = self
+                .aliases
+                .iter()
+                .filter(|(alias, sources)| !names.contains(*alias) && !sources.is_disjoint(&names))
+                .map(|(alias, _)| alias)
+                .collect();
+            if added.is_empty() {
+                break;
+            }
+            names.extend(added.into_iter().cloned());
+        }
+        if self.unnamed.is_disjoint(&names) {
+            Some(names)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn resolve_projection(query: &str) -> Result<Projection, sqlparser::parser::ParserError> {
+    let ast = Parser::parse_sql(&GenericDialect {}, query)?;
+    let mut visitor = ProjectionVisitor::default();
+    for statement in ast.iter() {
+        let _ = statement.visit(&mut visitor);
+    }
+    Ok(visitor.projection)
+}
+
+#[derive(Default)]
+struct ProjectionVisitor {
+    projection: Projection,
+}
+
+impl ProjectionVisitor {
+    fn add_set_expr(&mut self, body: &SetExpr) {
+        match body {
+            SetExpr::Select(select) => self.add_select(select),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.add_set_expr(left);
+                self.add_set_expr(right);
+            }
+            // nested queries are visited on their own
+            _ => {}
+        }
+    }
+
+    fn add_select(&mut self, select: &Select) {
+        for item in select.projection.iter() {
+            match item {
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    self.projection
+                        .aliases
+                        .entry(alias.value.clone())
+                        .or_default()
+                        .extend(referenced_columns(expr));
+                }
+                SelectItem::UnnamedExpr(Expr::Identifier(_) | Expr::CompoundIdentifier(_)) => {}
+                SelectItem::UnnamedExpr(expr) => {
+                    self.projection.unnamed.extend(referenced_columns(expr));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Visitor for ProjectionVisitor {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<Self::Break> {
+        self.add_set_expr(&query.body);
+        ControlFlow::Continue(())
+    }
+}
+
+fn referenced_columns(expr: &Expr) -> HashSet<String> {
+    let mut visitor = ColumnVisitor::default();
+    let _ = expr.visit(&mut visitor);
+    visitor.columns
+}
+
+#[derive(Default)]
+struct ColumnVisitor {
+    columns: HashSet<String>,
+}
+
+impl Visitor for ColumnVisitor {
+    type Break = ();
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        match expr {
+            Expr::Identifier(ident) => {
+                self.columns.insert(ident.value.clone());
+            }
+            Expr::CompoundIdentifier(idents) => {
+                if let Some(ident) = idents.last() {
+                    self.columns.insert(ident.value.clone());
+                }
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_explain() {
+        assert_eq!(parse_explain("select * from t"), (None, "select * from t"));
+        assert_eq!(
+            parse_explain("EXPLAIN select * from t"),
+            (Some(ExplainMode::Plan), "select * from t")
+        );
+        assert_eq!(
+            parse_explain(" explain  analyze\nselect * from t"),
+            (Some(ExplainMode::Analyze), "select * from t")
+        );
+        assert_eq!(
+            parse_explain("select explain from t"),
+            (None, "select explain from t")
+        );
+        assert_eq!(parse_explain("explained"), (None, "explained"));
+    }
+
+    #[test]
+    fn test_resolve_projection() {
+        let projection = resolve_projection(
+            "SELECT a2 AS a3, lower(name) FROM (SELECT email AS a2, name FROM t) WHERE code = 1",
+        )
+        .unwrap();
+        let fields = |names: &[&str]| names.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let names = projection.output_names(&fields(&["email"])).unwrap();
+        assert_eq!(
+            names,
+            HashSet::from(["email".to_string(), "a2".to_string(), "a3".to_string()])
+        );
+        assert_eq!(projection.output_names(&fields(&["name"])), None);
+        assert_eq!(
+            projection.output_names(&fields(&["code"])),
+            Some(HashSet::from(["code".to_string()]))
+        );
+    }
+}
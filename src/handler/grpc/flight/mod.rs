@@ -47,7 +47,8 @@ use crate::service::search::SEARCH_SERVER;
 use crate::{
     handler::grpc::MetadataMap,
     service::search::{
-        grpc::flight as grpcFlight, request::FlightSearchRequest, utils::AsyncDefer,
+        datafusion::explain::NodePlanMetrics, grpc::flight as grpcFlight,
+        request::FlightSearchRequest, utils::AsyncDefer,
     },
 };
 
@@ -90,6 +91,7 @@ impl FlightService for FlightServiceImpl {
             req.query_identifier.trace_id, req.query_identifier.job_id
         );
         let is_super_cluster = req.super_cluster_info.is_super_cluster;
+        let is_analyze = req.search_info.is_analyze;
         let timeout = req.search_info.timeout as u64;
         log::info!(
             "[trace_id {}] flight->search: do_get, timeout: {}s",
@@ -149,6 +151,7 @@ impl FlightService for FlightServiceImpl {
         }
 
         schema = add_scan_stats_to_schema(schema, scan_stats);
+        let analyze_plan = is_analyze.then(|| physical_plan.clone());
 
         let start = std::time::Instant::now();
         let write_options: IpcWriteOptions = IpcWriteOptions::default()
@@ -173,9 +176,19 @@ impl FlightService for FlightServiceImpl {
             ))
             .map_err(|err| Status::from_error(Box::new(err)));
 
-        Ok(Response::new(
-            Box::pin(flight_data_stream) as Self::DoGetStream
-        ))
+        // send the plan with metrics after the last batch for EXPLAIN ANALYZE
+        let flight_data_stream = match analyze_plan {
+            Some(plan) => flight_data_stream
+                .chain(futures::stream::once(async move {
+                    let metrics = NodePlanMetrics::new(scan_stats, plan.as_ref());
+                    let metrics = serde_json::to_vec(&metrics).unwrap_or_default();
+                    Ok(FlightData::new().with_app_metadata(metrics))
+                }))
+                .boxed(),
+            None => flight_data_stream.boxed(),
+        };
+
+        Ok(Response::new(flight_data_stream as Self::DoGetStream))
     }
 
     async fn handshake(
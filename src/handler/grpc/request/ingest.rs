@@ -15,7 +15,9 @@
 
 use actix_web::http::StatusCode;
 use config::{
-    meta::{otlp::OtlpRequestType, stream::StreamType},
+    meta::{
+        otlp::OtlpRequestType, pipeline::backfill::BACKFILL_INGEST_METADATA_KEY, stream::StreamType,
+    },
     metrics,
     utils::json,
 };
@@ -43,6 +45,24 @@ impl Ingest for Ingester {
         let in_data = req.data.unwrap_or_default();
 
         let resp = match stream_type {
+            StreamType::Logs
+                if req
+                    .metadata
+                    .as_ref()
+                    .is_some_and(|m| m.data.contains_key(BACKFILL_INGEST_METADATA_KEY)) =>
+            {
+                match json::from_slice::<Vec<json::Value>>(&in_data.data) {
+                    Err(e) => Err(e.into()),
+                    Ok(records) => crate::service::logs::ingest::ingest_backfill(
+                        0,
+                        &org_id,
+                        &stream_name,
+                        records,
+                    )
+                    .await
+                    .map(|_| ()),
+                }
+            }
             StreamType::Logs => {
                 let log_ingestion_type = req.ingestion_type.unwrap_or_default();
                 let data = bytes::Bytes::from(in_data.data);
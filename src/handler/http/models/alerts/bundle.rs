@@ -0,0 +1,211 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Versioned YAML bundle of alerts, destinations and templates
+
+use config::{meta::folder::DEFAULT_FOLDER, utils::json};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::Alert;
+use crate::{
+    handler::http::models::destinations::{Destination, Template},
+    service::alerts::bundle::{self, BUNDLE_VERSION, BundleError, Change},
+};
+
+/// Alert fields set by the server, left out of the exported bundles
+const SERVER_ALERT_FIELDS: [&str; 7] = [
+    "id",
+    "org_id",
+    "owner",
+    "last_triggered_at",
+    "last_satisfied_at",
+    "updated_at",
+    "last_edited_by",
+];
+
+/// Bundle of alerts, destinations and templates managed as code.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct AlertsBundle {
+    /// Version of the bundle format.
+    pub version: u32,
+    #[serde(default)]
+    pub templates: Vec<Template>,
+    #[serde(default)]
+    pub destinations: Vec<Destination>,
+    #[serde(default)]
+    pub alerts: Vec<BundleAlert>,
+}
+
+/// Alert of a bundle with the name of its folder.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct BundleAlert {
+    /// Name of the folder of the alert, created if it doesn't exist.
+    #[serde(default = "default_folder")]
+    pub folder: String,
+    #[serde(flatten)]
+    pub alert: Alert,
+}
+
+fn default_folder() -> String {
+    DEFAULT_FOLDER.to_string()
+}
+
+/// HTTP response body for `ImportAlerts` endpoints.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ImportAlertsResponseBody {
+    /// Whether the changes were only computed.
+    pub dry_run: bool,
+    /// Changes of the import, in the order they are applied.
+    pub changes: Vec<BundleChangeItem>,
+    /// Prometheus rules which were not imported, with the reason.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct BundleChangeItem {
+    /// template, destination or alert.
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    pub name: String,
+    /// create, update or unchanged.
+    pub action: String,
+    /// Dotted paths of the changed fields of an update.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+}
+
+impl AlertsBundle {
+    pub fn from_yaml(body: &[u8]) -> Result<Self, BundleError> {
+        let bundle: Self =
+            serde_yaml_ng::from_slice(body).map_err(|e| BundleError::Invalid(e.to_string()))?;
+        if bundle.version != BUNDLE_VERSION {
+            return Err(BundleError::Invalid(format!(
+                "unsupported version {}, expected {BUNDLE_VERSION}",
+                bundle.version
+            )));
+        }
+        Ok(bundle)
+    }
+
+    pub fn to_yaml(&self) -> Result<String, BundleError> {
+        let mut value = json::to_value(self).map_err(|e| BundleError::Read(e.to_string()))?;
+        if let Some(alerts) = value.get_mut("alerts").and_then(|v| v.as_array_mut()) {
+            for alert in alerts.iter_mut().filter_map(|v| v.as_object_mut()) {
+                for field in SERVER_ALERT_FIELDS {
+                    alert.remove(field);
+                }
+            }
+        }
+        serde_yaml_ng::to_string(&value).map_err(|e| BundleError::Read(e.to_string()))
+    }
+
+    pub fn into(self, org_id: &str) -> Result<bundle::Bundle, BundleError> {
+        let destinations = self
+            .destinations
+            .into_iter()
+            .map(|d| {
+                let name = d.name.clone();
+                d.into(org_id.to_string())
+                    .map_err(|e| BundleError::Invalid(format!("destination {name}: {e}")))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(bundle::Bundle {
+            templates: self.templates.into_iter().map(|t| t.into(org_id)).collect(),
+            destinations,
+            alerts: self
+                .alerts
+                .into_iter()
+                .map(|a| {
+                    let mut alert: config::meta::alerts::alert::Alert = a.alert.into();
+                    alert.org_id = org_id.to_string();
+                    (a.folder, alert)
+                })
+                .collect(),
+        })
+    }
+}
+
+impl From<bundle::Bundle> for AlertsBundle {
+    fn from(value: bundle::Bundle) -> Self {
+        Self {
+            version: BUNDLE_VERSION,
+            templates: value.templates.into_iter().map(Template::from).collect(),
+            destinations: value
+                .destinations
+                .into_iter()
+                .map(Destination::from)
+                .collect(),
+            alerts: value
+                .alerts
+                .into_iter()
+                .map(|(folder, alert)| BundleAlert {
+                    folder,
+                    alert: alert.into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Change> for BundleChangeItem {
+    fn from(value: Change) -> Self {
+        Self {
+            kind: value.kind.to_string(),
+            folder: value.folder,
+            name: value.name,
+            action: value.action.to_string(),
+            fields: value.fields,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_yaml() {
+        let yaml = r#"
+version: 1
+alerts:
+  - name: high_latency
+    stream_type: logs
+    stream_name: api
+    destinations: [slack]
+    query_condition:
+      type: sql
+      sql: SELECT count(*) AS cnt FROM api WHERE took > 1000
+    trigger_condition:
+      period: 5
+      threshold: 1
+      frequency: 60
+      silence: 10
+"#;
+        let bundle = AlertsBundle::from_yaml(yaml.as_bytes()).unwrap();
+        assert_eq!(bundle.alerts[0].folder, DEFAULT_FOLDER);
+        let meta = bundle.clone().into("default").unwrap();
+        assert_eq!(meta.alerts[0].1.org_id, "default");
+
+        let exported = AlertsBundle::from(meta).to_yaml().unwrap();
+        assert!(!exported.contains("org_id"));
+        let reimported = AlertsBundle::from_yaml(exported.as_bytes()).unwrap();
+        assert_eq!(reimported.alerts[0].alert.name, "high_latency");
+
+        assert!(AlertsBundle::from_yaml(b"version: 2").is_err());
+    }
+}
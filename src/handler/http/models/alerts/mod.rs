@@ -13,6 +13,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+pub mod bundle;
 pub mod requests;
 pub mod responses;
 
@@ -126,6 +127,15 @@ pub struct TriggerCondition {
     #[serde(rename = "tolerance_in_secs")]
     #[serde(default)]
     pub tolerance_seconds: Option<i64>,
+
+    /// How long the condition has to hold before the alert fires.
+    #[serde(rename = "for_duration")]
+    #[serde(default)]
+    pub for_duration_seconds: i64,
+
+    /// Notify the destinations when a firing alert is resolved.
+    #[serde(default)]
+    pub notify_on_resolve: bool,
 }
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize, ToSchema, PartialEq)]
@@ -160,6 +170,12 @@ pub struct QueryCondition {
     pub search_event_type: Option<SearchEventType>,
     #[serde(default)]
     pub multi_time_range: Option<Vec<CompareHistoricData>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anomaly_detection: Option<meta_alerts::anomaly::AnomalyDetection>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub composite: Option<meta_alerts::composite::CompositeCondition>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema, PartialEq)]
@@ -204,6 +220,8 @@ pub enum QueryType {
     SQL,
     #[serde(rename = "promql")]
     PromQL,
+    #[serde(rename = "composite")]
+    Composite,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
@@ -315,6 +333,8 @@ impl From<meta_alerts::TriggerCondition> for TriggerCondition {
             silence_minutes: value.silence,
             timezone: value.timezone,
             tolerance_seconds: value.tolerance_in_secs,
+            for_duration_seconds: value.for_duration,
+            notify_on_resolve: value.notify_on_resolve,
         }
     }
 }
@@ -352,6 +372,8 @@ impl From<meta_alerts::QueryCondition> for QueryCondition {
             multi_time_range: value
                 .multi_time_range
                 .map(|cs| cs.into_iter().map(|c| c.into()).collect()),
+            anomaly_detection: value.anomaly_detection,
+            composite: value.composite,
         }
     }
 }
@@ -390,6 +412,7 @@ impl From<meta_alerts::QueryType> for QueryType {
             meta_alerts::QueryType::Custom => Self::Custom,
             meta_alerts::QueryType::SQL => Self::SQL,
             meta_alerts::QueryType::PromQL => Self::PromQL,
+            meta_alerts::QueryType::Composite => Self::Composite,
         }
     }
 }
@@ -488,6 +511,8 @@ impl From<TriggerCondition> for meta_alerts::TriggerCondition {
             silence: value.silence_minutes,
             timezone: value.timezone,
             tolerance_in_secs: value.tolerance_seconds,
+            for_duration: value.for_duration_seconds,
+            notify_on_resolve: value.notify_on_resolve,
         }
     }
 }
@@ -525,6 +550,8 @@ impl From<QueryCondition> for meta_alerts::QueryCondition {
             multi_time_range: value
                 .multi_time_range
                 .map(|cs| cs.into_iter().map(|c| c.into()).collect()),
+            anomaly_detection: value.anomaly_detection,
+            composite: value.composite,
         }
     }
 }
@@ -563,6 +590,7 @@ impl From<QueryType> for meta_alerts::QueryType {
             QueryType::Custom => Self::Custom,
             QueryType::SQL => Self::SQL,
             QueryType::PromQL => Self::PromQL,
+            QueryType::Composite => Self::Composite,
         }
     }
 }
@@ -13,7 +13,10 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use config::meta::alerts::alert as meta_alerts;
+use config::meta::alerts::{
+    alert as meta_alerts,
+    delivery::{Delivery, DeliveryStatus},
+};
 use serde::Deserialize;
 use svix_ksuid::Ksuid;
 use utoipa::ToSchema;
@@ -35,6 +38,20 @@ pub struct CreateAlertRequestBody {
 #[derive(Clone, Debug, Deserialize, ToSchema)]
 pub struct UpdateAlertRequestBody(pub Alert);
 
+/// HTTP request body for `BacktestAlert` endpoint.
+#[derive(Clone, Debug, Deserialize, ToSchema)]
+pub struct BacktestAlertRequestBody {
+    /// (microseconds) start of the time range to replay.
+    pub start_time: i64,
+
+    /// (microseconds) end of the time range to replay, in the past.
+    pub end_time: i64,
+
+    /// Alert to backtest, it doesn't need to be saved.
+    #[serde(flatten)]
+    pub alert: Alert,
+}
+
 /// HTTP request body for `MoveAlerts` endpoint.
 #[derive(Clone, Debug, Deserialize, ToSchema)]
 pub struct MoveAlertsRequestBody {
@@ -90,12 +107,67 @@ pub struct EnableAlertQuery {
     pub value: bool,
 }
 
+/// HTTP URL query component that contains parameters for listing the
+/// notification deliveries.
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(style = Form, parameter_in = Query)]
+#[serde(rename_all = "snake_case")]
+#[into_params(rename_all = "snake_case")]
+pub struct ListDeliveriesQuery {
+    /// Optional status filter parameter, `pending`, `delivered` or `dead`.
+    pub status: Option<String>,
+
+    /// Optional destination name filter parameter.
+    pub destination: Option<String>,
+
+    /// Optional alert name filter parameter.
+    pub alert_name: Option<String>,
+
+    /// Optional start of the creation time filter, in microseconds.
+    pub start_time: Option<i64>,
+
+    /// Optional end of the creation time filter, in microseconds.
+    pub end_time: Option<i64>,
+
+    /// The optional number of deliveries to retrieve, the newest first, 100
+    /// by default and at most 1000.
+    pub size: Option<usize>,
+
+    /// Optional `after` of the previous page, the deliveries older than the
+    /// delivery with this ID are retrieved.
+    pub after: Option<String>,
+}
+
+impl ListDeliveriesQuery {
+    pub fn matches(&self, delivery: &Delivery) -> bool {
+        self.status
+            .as_deref()
+            .is_none_or(|v| DeliveryStatus::from(v) == delivery.status)
+            && self
+                .destination
+                .as_ref()
+                .is_none_or(|v| *v == delivery.destination)
+            && self
+                .alert_name
+                .as_ref()
+                .is_none_or(|v| *v == delivery.alert_name)
+            && self.start_time.is_none_or(|v| delivery.created_at >= v)
+            && self.end_time.is_none_or(|v| delivery.created_at < v)
+    }
+}
+
 impl From<CreateAlertRequestBody> for meta_alerts::Alert {
     fn from(value: CreateAlertRequestBody) -> Self {
         value.alert.into()
     }
 }
 
+impl From<BacktestAlertRequestBody> for meta_alerts::Alert {
+    fn from(value: BacktestAlertRequestBody) -> Self {
+        value.alert.into()
+    }
+}
+
 impl From<UpdateAlertRequestBody> for meta_alerts::Alert {
     fn from(value: UpdateAlertRequestBody) -> Self {
         value.0.into()
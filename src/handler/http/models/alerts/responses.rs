@@ -13,12 +13,23 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use config::meta::{alerts::alert as meta_alerts, folder as meta_folders, triggers::Trigger};
+use config::{
+    meta::{
+        alerts::{
+            alert as meta_alerts,
+            state::{AlertState, AlertStateTransition},
+        },
+        folder as meta_folders,
+        triggers::{ScheduledTriggerData, Trigger},
+    },
+    utils::json,
+};
 use serde::Serialize;
 use svix_ksuid::Ksuid;
 use utoipa::ToSchema;
 
 use super::{Alert, QueryCondition};
+use crate::service::alerts::backtest::Backtest;
 
 /// HTTP response body for `GetAlert` endpoint.
 #[derive(Clone, Debug, Serialize, ToSchema)]
@@ -51,6 +62,51 @@ pub struct EnableAlertResponseBody {
     pub enabled: bool,
 }
 
+/// HTTP response body for `BacktestAlert` endpoint.
+#[derive(Clone, Debug, Default, Serialize, ToSchema)]
+pub struct BacktestAlertResponseBody {
+    /// Number of evaluations the scheduler would have run in the time range.
+    pub evaluations: usize,
+    /// Notifications the alert would have sent, the oldest first.
+    pub notifications: Vec<BacktestNotificationItem>,
+}
+
+/// A notification in the `BacktestAlert` response.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct BacktestNotificationItem {
+    /// (microseconds) when the scheduler would have evaluated the alert.
+    pub timestamp: i64,
+    /// (microseconds) time range queried by the evaluation.
+    pub start_time: i64,
+    pub end_time: i64,
+    pub status: AlertState,
+    /// Rows of the notification, the group by values of each group for a
+    /// resolved notification.
+    #[schema(value_type = Vec<Object>)]
+    pub rows: Vec<json::Map<String, json::Value>>,
+}
+
+/// HTTP response body for `GetAlertState` endpoint.
+#[derive(Clone, Debug, Default, Serialize, ToSchema)]
+pub struct GetAlertStateResponseBody {
+    /// Groups of the alert which are pending or firing, the other groups are
+    /// resolved.
+    pub groups: Vec<AlertGroupStateItem>,
+    /// Recent state transitions, the oldest first.
+    pub history: Vec<AlertStateTransition>,
+}
+
+/// State of one group of an alert in the `GetAlertState` response.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct AlertGroupStateItem {
+    pub group_key: String,
+    #[schema(value_type = Object)]
+    pub labels: json::Map<String, json::Value>,
+    pub state: AlertState,
+    pub since: i64,
+    pub last_notified_at: Option<i64>,
+}
+
 impl From<(meta_alerts::Alert, Option<Trigger>)> for GetAlertResponseBody {
     fn from(value: (meta_alerts::Alert, Option<Trigger>)) -> Self {
         Self(value.into())
@@ -102,3 +158,49 @@ impl TryFrom<(meta_folders::Folder, meta_alerts::Alert, Option<Trigger>)>
         })
     }
 }
+
+impl From<Option<Trigger>> for GetAlertStateResponseBody {
+    fn from(value: Option<Trigger>) -> Self {
+        let Some(trigger_data) =
+            value.and_then(|trigger| json::from_str::<ScheduledTriggerData>(&trigger.data).ok())
+        else {
+            return Self::default();
+        };
+        let mut groups = trigger_data
+            .states
+            .groups
+            .into_iter()
+            .map(|(group_key, group)| AlertGroupStateItem {
+                group_key,
+                labels: group.labels,
+                state: group.state,
+                since: group.since,
+                last_notified_at: group.last_notified_at,
+            })
+            .collect::<Vec<_>>();
+        groups.sort_by(|a, b| a.group_key.cmp(&b.group_key));
+        Self {
+            groups,
+            history: trigger_data.states.history,
+        }
+    }
+}
+
+impl From<Backtest> for BacktestAlertResponseBody {
+    fn from(value: Backtest) -> Self {
+        Self {
+            evaluations: value.evaluations,
+            notifications: value
+                .notifications
+                .into_iter()
+                .map(|n| BacktestNotificationItem {
+                    timestamp: n.timestamp,
+                    start_time: n.start_time,
+                    end_time: n.end_time,
+                    status: n.status,
+                    rows: n.rows,
+                })
+                .collect(),
+        }
+    }
+}
@@ -18,7 +18,14 @@
 
 use std::fmt;
 
-use config::meta::destinations as meta_dest;
+use config::{
+    meta::{
+        alerts::{alert::Alert, state::AlertState},
+        destinations as meta_dest,
+        stream::StreamType,
+    },
+    utils::json::{Map, Value},
+};
 use hashbrown::HashMap;
 #[cfg(feature = "enterprise")]
 use o2_enterprise::enterprise::actions::action_manager::ActionEndpoint;
@@ -27,6 +34,7 @@ use utoipa::ToSchema;
 
 use crate::service::db::alerts::destinations::DestinationError;
 
+/// The secrets of the destination are redacted
 impl From<meta_dest::Destination> for Destination {
     fn from(value: meta_dest::Destination) -> Self {
         match value.module {
@@ -46,7 +54,10 @@ impl From<meta_dest::Destination> for Destination {
                     url: endpoint.url,
                     method: endpoint.method,
                     skip_tls_verify: endpoint.skip_tls_verify,
-                    headers: endpoint.headers,
+                    headers: redact_headers(endpoint.headers),
+                    signing_secret: endpoint
+                        .signing_secret
+                        .map(|_| meta_dest::REDACTED_SECRET.to_string()),
                     destination_type: DestinationType::Http,
                     template: Some(template),
                     ..Default::default()
@@ -59,13 +70,47 @@ impl From<meta_dest::Destination> for Destination {
                     destination_type: DestinationType::Sns,
                     ..Default::default()
                 },
+                meta_dest::DestinationType::Slack(slack) => Self {
+                    name: value.name,
+                    template: Some(template),
+                    url: slack.webhook_url,
+                    channel: slack.channel,
+                    destination_type: DestinationType::Slack,
+                    ..Default::default()
+                },
+                meta_dest::DestinationType::PagerDuty(pagerduty) => Self {
+                    name: value.name,
+                    template: Some(template),
+                    url: pagerduty.events_url.unwrap_or_default(),
+                    routing_key: Some(meta_dest::REDACTED_SECRET.to_string()),
+                    severity: Some(pagerduty.severity),
+                    destination_type: DestinationType::PagerDuty,
+                    ..Default::default()
+                },
+                meta_dest::DestinationType::Opsgenie(opsgenie) => Self {
+                    name: value.name,
+                    template: Some(template),
+                    url: opsgenie.api_url.unwrap_or_default(),
+                    api_key: Some(meta_dest::REDACTED_SECRET.to_string()),
+                    priority: Some(opsgenie.priority),
+                    destination_type: DestinationType::Opsgenie,
+                    ..Default::default()
+                },
+                meta_dest::DestinationType::Teams(teams) => Self {
+                    name: value.name,
+                    template: Some(template),
+                    url: teams.webhook_url,
+                    destination_type: DestinationType::Teams,
+                    ..Default::default()
+                },
             },
             meta_dest::Module::Pipeline { endpoint } => Self {
                 name: value.name,
                 url: endpoint.url,
                 method: endpoint.method,
                 skip_tls_verify: endpoint.skip_tls_verify,
-                headers: endpoint.headers,
+                headers: redact_headers(endpoint.headers),
+                output_format: Some(endpoint.output_format),
                 destination_type: DestinationType::Http,
                 ..Default::default()
             },
@@ -73,6 +118,22 @@ impl From<meta_dest::Destination> for Destination {
     }
 }
 
+/// Replaces the values of the credential headers by the redacted placeholder
+fn redact_headers(headers: Option<HashMap<String, String>>) -> Option<HashMap<String, String>> {
+    headers.map(|headers| {
+        headers
+            .into_iter()
+            .map(|(name, value)| {
+                if meta_dest::is_secret_header(&name) {
+                    (name, meta_dest::REDACTED_SECRET.to_string())
+                } else {
+                    (name, value)
+                }
+            })
+            .collect()
+    })
+}
+
 impl Destination {
     pub fn into(self, org_id: String) -> Result<meta_dest::Destination, DestinationError> {
         match self.template {
@@ -87,12 +148,37 @@ impl Destination {
                             method: self.method,
                             skip_tls_verify: self.skip_tls_verify,
                             headers: self.headers,
+                            signing_secret: self.signing_secret.filter(|v| !v.is_empty()),
+                            output_format: Default::default(),
                         })
                     }
                     DestinationType::Sns => meta_dest::DestinationType::Sns(meta_dest::AwsSns {
                         sns_topic_arn: self.sns_topic_arn.ok_or(DestinationError::InvalidSns)?,
                         aws_region: self.aws_region.ok_or(DestinationError::InvalidSns)?,
                     }),
+                    DestinationType::Slack => meta_dest::DestinationType::Slack(meta_dest::Slack {
+                        webhook_url: self.url,
+                        channel: self.channel.filter(|v| !v.is_empty()),
+                    }),
+                    DestinationType::PagerDuty => {
+                        meta_dest::DestinationType::PagerDuty(meta_dest::PagerDuty {
+                            routing_key: self
+                                .routing_key
+                                .ok_or(DestinationError::InvalidPagerDuty)?,
+                            severity: self.severity.unwrap_or_else(|| "critical".to_string()),
+                            events_url: Some(self.url).filter(|v| !v.is_empty()),
+                        })
+                    }
+                    DestinationType::Opsgenie => {
+                        meta_dest::DestinationType::Opsgenie(meta_dest::Opsgenie {
+                            api_key: self.api_key.ok_or(DestinationError::InvalidOpsgenie)?,
+                            api_url: Some(self.url).filter(|v| !v.is_empty()),
+                            priority: self.priority.unwrap_or_else(|| "P3".to_string()),
+                        })
+                    }
+                    DestinationType::Teams => meta_dest::DestinationType::Teams(meta_dest::Teams {
+                        webhook_url: self.url,
+                    }),
                     #[cfg(feature = "enterprise")]
                     DestinationType::Action => {
                         let action_endpoint = ActionEndpoint::new(&org_id, &self.action_id)
@@ -106,6 +192,8 @@ impl Destination {
                             },
                             skip_tls_verify: action_endpoint.skip_tls,
                             headers: None,
+                            signing_secret: None,
+                            output_format: Default::default(),
                         })
                     }
                 };
@@ -125,6 +213,8 @@ impl Destination {
                     method: self.method,
                     skip_tls_verify: self.skip_tls_verify,
                     headers: self.headers,
+                    signing_secret: None,
+                    output_format: self.output_format.unwrap_or_default(),
                 };
                 Ok(meta_dest::Destination {
                     id: None,
@@ -160,7 +250,11 @@ impl Template {
         let template_type = match self.template_type {
             DestinationType::Email => meta_dest::TemplateType::Email { title: self.title },
             DestinationType::Sns => meta_dest::TemplateType::Sns,
-            DestinationType::Http => meta_dest::TemplateType::Http,
+            DestinationType::Http
+            | DestinationType::Slack
+            | DestinationType::PagerDuty
+            | DestinationType::Opsgenie
+            | DestinationType::Teams => meta_dest::TemplateType::Http,
             #[cfg(feature = "enterprise")]
             DestinationType::Action => meta_dest::TemplateType::Http,
         };
@@ -179,7 +273,8 @@ impl Template {
 pub struct Destination {
     #[serde(default)]
     pub name: String,
-    /// Required for `Http` destination_type
+    /// Required for `Http` destination_type, the webhook url for `Slack` and
+    /// `Teams`, and overrides the api endpoint of `PagerDuty` and `Opsgenie`
     #[serde(default)]
     pub url: String,
     /// Required for `Http` destination_type
@@ -199,6 +294,29 @@ pub struct Destination {
     pub sns_topic_arn: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aws_region: Option<String>,
+    /// Signs the notifications of `Http` alert destinations. Secrets are
+    /// returned as `<redacted>`, which keeps the saved secret on update
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_secret: Option<String>,
+    /// Overrides the channel of the `Slack` webhook
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Required when `destination_type` is `PagerDuty`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routing_key: Option<String>,
+    /// `PagerDuty` event severity: critical (default), error, warning or info
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    /// Required when `destination_type` is `Opsgenie`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// `Opsgenie` alert priority: P1 to P5, P3 by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    /// Format of the records sent to a pipeline destination: json (default),
+    /// otlp or openobserve
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<meta_dest::OutputFormat>,
     #[serde(rename = "type")]
     #[serde(default)]
     pub destination_type: DestinationType,
@@ -215,6 +333,11 @@ pub enum DestinationType {
     Http,
     Email,
     Sns,
+    Slack,
+    #[serde(rename = "pagerduty")]
+    PagerDuty,
+    Opsgenie,
+    Teams,
     #[cfg(feature = "enterprise")]
     Action,
 }
@@ -224,6 +347,10 @@ impl From<&str> for DestinationType {
         match value.to_lowercase().as_str() {
             "email" => DestinationType::Email,
             "sns" => DestinationType::Sns,
+            "slack" => DestinationType::Slack,
+            "pagerduty" => DestinationType::PagerDuty,
+            "opsgenie" => DestinationType::Opsgenie,
+            "teams" => DestinationType::Teams,
             #[cfg(feature = "enterprise")]
             "action" => DestinationType::Action,
             _ => DestinationType::Http,
@@ -237,6 +364,10 @@ impl fmt::Display for DestinationType {
             DestinationType::Email => write!(f, "email"),
             DestinationType::Http => write!(f, "http"),
             DestinationType::Sns => write!(f, "sns"),
+            DestinationType::Slack => write!(f, "slack"),
+            DestinationType::PagerDuty => write!(f, "pagerduty"),
+            DestinationType::Opsgenie => write!(f, "opsgenie"),
+            DestinationType::Teams => write!(f, "teams"),
             #[cfg(feature = "enterprise")]
             DestinationType::Action => write!(f, "action"),
         }
@@ -261,3 +392,53 @@ pub struct Template {
     #[serde(default)]
     pub title: String,
 }
+
+/// Renders a template for sample rows of an example alert, without saving or
+/// sending it
+#[derive(Clone, Debug, Deserialize, ToSchema)]
+pub struct TemplatePreview {
+    pub template: Template,
+    /// Sample rows, like the rows returned by the alert query
+    #[serde(default)]
+    #[schema(value_type = Vec<Object>)]
+    pub rows: Vec<Map<String, Value>>,
+    #[serde(default = "default_preview_alert_name")]
+    pub alert_name: String,
+    #[serde(default)]
+    pub stream_type: StreamType,
+    #[serde(default)]
+    pub stream_name: String,
+    /// Row template of the alert, rendered for each row
+    #[serde(default)]
+    pub row_template: String,
+    /// `firing` by default
+    #[serde(default = "default_preview_alert_status")]
+    pub alert_status: AlertState,
+}
+
+fn default_preview_alert_name() -> String {
+    "sample_alert".to_string()
+}
+
+fn default_preview_alert_status() -> AlertState {
+    AlertState::Firing
+}
+
+impl TemplatePreview {
+    pub fn into_alert(&self, org_id: &str) -> Alert {
+        Alert {
+            org_id: org_id.to_string(),
+            name: self.alert_name.clone(),
+            stream_type: self.stream_type,
+            stream_name: self.stream_name.clone(),
+            row_template: self.row_template.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct TemplatePreviewResponse {
+    pub title: String,
+    pub body: String,
+}
@@ -0,0 +1,209 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use actix_web::{HttpRequest, HttpResponse, get, post, web};
+use hashbrown::HashMap;
+
+use crate::{
+    common::{meta::http::HttpResponse as MetaHttpResponse, utils::auth::UserEmail},
+    handler::http::models::alerts::bundle::{AlertsBundle, ImportAlertsResponseBody},
+    service::alerts::{
+        bundle::{self, Bundle, BundleError},
+        prometheus_rules::{self, RuleFile},
+    },
+};
+
+impl From<BundleError> for HttpResponse {
+    fn from(value: BundleError) -> Self {
+        match &value {
+            BundleError::Invalid(_) => MetaHttpResponse::bad_request(value),
+            BundleError::Apply { .. } => MetaHttpResponse::bad_request(value),
+            BundleError::Read(_) => MetaHttpResponse::internal_error(value),
+        }
+    }
+}
+
+/// ExportAlerts
+///
+/// Exports the templates, the alert destinations and the alerts of the
+/// organization as a YAML bundle which can be imported into an organization.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "ExportAlerts",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("folder" = Option<String>, Query, description = "Name of the folder to export, with the destinations and templates its alerts use"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/yaml", body = AlertsBundle),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/v2/{org_id}/alerts/export")]
+pub async fn export_alerts(path: web::Path<String>, req: HttpRequest) -> HttpResponse {
+    let org_id = path.into_inner();
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string()).unwrap();
+    let folder = query.get("folder").map(|v| v.as_str());
+    let bundle = match bundle::export(&org_id, folder).await {
+        Ok(v) => AlertsBundle::from(v),
+        Err(BundleError::Invalid(e)) => return MetaHttpResponse::not_found(e),
+        Err(e) => return e.into(),
+    };
+    match bundle.to_yaml() {
+        Ok(v) => HttpResponse::Ok().content_type("application/yaml").body(v),
+        Err(e) => e.into(),
+    }
+}
+
+/// ImportAlerts
+///
+/// Reconciles the organization with a YAML bundle: the templates, destinations
+/// and alerts of the bundle are created or updated, keyed on their folder and
+/// name. Importing the same bundle again changes nothing. With `dry_run` the
+/// changes are only returned.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "ImportAlerts",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("dry_run" = Option<bool>, Query, description = "Only compute the changes"),
+    ),
+    request_body(content = AlertsBundle, description = "YAML bundle", content_type = "application/yaml"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = ImportAlertsResponseBody),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/v2/{org_id}/alerts/import")]
+pub async fn import_alerts(
+    path: web::Path<String>,
+    body: web::Bytes,
+    user_email: UserEmail,
+    req: HttpRequest,
+) -> HttpResponse {
+    let org_id = path.into_inner();
+    let dry_run = is_dry_run(&req);
+    let bundle = match AlertsBundle::from_yaml(&body).and_then(|b| b.into(&org_id)) {
+        Ok(v) => v,
+        Err(e) => return e.into(),
+    };
+    import(&org_id, &user_email.user_id, bundle, dry_run, vec![]).await
+}
+
+/// ImportPrometheusRules
+///
+/// Imports the alerting rules of a Prometheus or Thanos rule file as PromQL
+/// alerts. The alerts go to the folder named after their group unless a
+/// folder is given and notify the given destinations. Recording rules and the
+/// rules which can't be translated are reported as skipped.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "ImportPrometheusRules",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("destinations" = String, Query, description = "Comma separated names of the destinations of the alerts"),
+        ("folder" = Option<String>, Query, description = "Name of the folder of the alerts"),
+        ("dry_run" = Option<bool>, Query, description = "Only compute the changes"),
+    ),
+    request_body(content = String, description = "Prometheus rule file", content_type = "application/yaml"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = ImportAlertsResponseBody),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/v2/{org_id}/alerts/import/prometheus")]
+pub async fn import_prometheus_rules(
+    path: web::Path<String>,
+    body: web::Bytes,
+    user_email: UserEmail,
+    req: HttpRequest,
+) -> HttpResponse {
+    let org_id = path.into_inner();
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string()).unwrap();
+    let destinations = query
+        .get("destinations")
+        .map(|v| {
+            v.split(',')
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if destinations.is_empty() {
+        return MetaHttpResponse::bad_request("Alerts need at least one destination");
+    }
+    let folder = query.get("folder").map(|v| v.as_str());
+    let file: RuleFile = match serde_yaml_ng::from_slice(&body) {
+        Ok(v) => v,
+        Err(e) => return MetaHttpResponse::bad_request(format!("Invalid rule file: {e}")),
+    };
+    let translation = prometheus_rules::translate(file, folder, &destinations);
+    let bundle = Bundle {
+        alerts: translation
+            .alerts
+            .into_iter()
+            .map(|(folder, mut alert)| {
+                alert.org_id = org_id.clone();
+                (folder, alert)
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let dry_run = is_dry_run(&req);
+    import(
+        &org_id,
+        &user_email.user_id,
+        bundle,
+        dry_run,
+        translation.skipped,
+    )
+    .await
+}
+
+async fn import(
+    org_id: &str,
+    user_id: &str,
+    bundle: Bundle,
+    dry_run: bool,
+    skipped: Vec<String>,
+) -> HttpResponse {
+    match bundle::apply(org_id, user_id, bundle, dry_run).await {
+        Ok(changes) => MetaHttpResponse::json(ImportAlertsResponseBody {
+            dry_run,
+            changes: changes.into_iter().map(Into::into).collect(),
+            skipped,
+        }),
+        Err(e) => e.into(),
+    }
+}
+
+fn is_dry_run(req: &HttpRequest) -> bool {
+    web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .ok()
+        .and_then(|q| q.get("dry_run").map(|v| v == "true"))
+        .unwrap_or_default()
+}
@@ -0,0 +1,155 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use actix_web::{HttpRequest, HttpResponse, delete, get, post, web};
+use config::meta::alerts::delivery::{Delivery, DeliveryList};
+
+use crate::{
+    common::meta::http::HttpResponse as MetaHttpResponse,
+    handler::http::models::alerts::requests::ListDeliveriesQuery,
+    service::alerts::deliveries::{self, DeliveryError},
+};
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 1000;
+
+impl From<DeliveryError> for HttpResponse {
+    fn from(value: DeliveryError) -> Self {
+        match &value {
+            DeliveryError::NotFound(_) => MetaHttpResponse::not_found(value),
+            DeliveryError::Pending(_) => MetaHttpResponse::bad_request(value),
+            DeliveryError::Db(_) => MetaHttpResponse::internal_error(value),
+        }
+    }
+}
+
+/// ListDeliveries
+///
+/// Delivery log of the alert notifications, every attempt of a delivery with
+/// its HTTP status and latency. Dead deliveries are the dead-letter list. The
+/// log is paged, the `after` of the response gets the next page.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "ListDeliveries",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ListDeliveriesQuery,
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = DeliveryList),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/alerts/deliveries")]
+pub async fn list_deliveries(path: web::Path<String>, req: HttpRequest) -> HttpResponse {
+    let org_id = path.into_inner();
+    let Ok(query) = web::Query::<ListDeliveriesQuery>::from_query(req.query_string()) else {
+        return MetaHttpResponse::bad_request("Error parsing query parameters");
+    };
+    let size = query
+        .size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    match deliveries::list(&org_id, query.after.as_deref(), size, |d| query.matches(d)).await {
+        Ok((list, after)) => MetaHttpResponse::json(DeliveryList { list, after }),
+        Err(e) => e.into(),
+    }
+}
+
+/// GetDelivery
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "GetDelivery",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("delivery_id" = String, Path, description = "Delivery ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = Delivery),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/alerts/deliveries/{delivery_id}")]
+pub async fn get_delivery(path: web::Path<(String, String)>) -> HttpResponse {
+    let (org_id, delivery_id) = path.into_inner();
+    match deliveries::get(&org_id, &delivery_id).await {
+        Ok(v) => MetaHttpResponse::json(v),
+        Err(e) => e.into(),
+    }
+}
+
+/// ResendDelivery
+///
+/// Sends a dead or delivered notification again, it is retried like a new
+/// notification when the attempt fails.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "ResendDelivery",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("delivery_id" = String, Path, description = "Delivery ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = Delivery),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/alerts/deliveries/{delivery_id}/resend")]
+pub async fn resend_delivery(path: web::Path<(String, String)>) -> HttpResponse {
+    let (org_id, delivery_id) = path.into_inner();
+    match deliveries::resend(&org_id, &delivery_id).await {
+        Ok(v) => MetaHttpResponse::json(v),
+        Err(e) => e.into(),
+    }
+}
+
+/// DeleteDelivery
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "DeleteDelivery",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("delivery_id" = String, Path, description = "Delivery ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[delete("/{org_id}/alerts/deliveries/{delivery_id}")]
+pub async fn delete_delivery(path: web::Path<(String, String)>) -> HttpResponse {
+    let (org_id, delivery_id) = path.into_inner();
+    match deliveries::delete(&org_id, &delivery_id).await {
+        Ok(()) => MetaHttpResponse::ok("Delivery deleted"),
+        Err(e) => e.into(),
+    }
+}
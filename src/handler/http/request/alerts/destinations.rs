@@ -28,6 +28,7 @@ impl From<DestinationError> for HttpResponse {
         match &value {
             DestinationError::UsedByAlert(_) => MetaHttpResponse::conflict(value),
             DestinationError::UsedByPipeline(_) => MetaHttpResponse::conflict(value),
+            DestinationError::UsedByNotificationPolicy => MetaHttpResponse::conflict(value),
             DestinationError::InfraError(err) => MetaHttpResponse::internal_error(err),
             DestinationError::NotFound => MetaHttpResponse::not_found(value),
             other_err => MetaHttpResponse::bad_request(other_err),
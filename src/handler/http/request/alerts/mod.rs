@@ -27,20 +27,30 @@ use crate::{
     common::{meta::http::HttpResponse as MetaHttpResponse, utils::auth::UserEmail},
     handler::http::models::alerts::{
         requests::{
-            CreateAlertRequestBody, EnableAlertQuery, ListAlertsQuery, MoveAlertsRequestBody,
-            UpdateAlertRequestBody,
+            BacktestAlertRequestBody, CreateAlertRequestBody, EnableAlertQuery, ListAlertsQuery,
+            MoveAlertsRequestBody, UpdateAlertRequestBody,
+        },
+        responses::{
+            BacktestAlertResponseBody, EnableAlertResponseBody, GetAlertResponseBody,
+            GetAlertStateResponseBody, ListAlertsResponseBody,
         },
-        responses::{EnableAlertResponseBody, GetAlertResponseBody, ListAlertsResponseBody},
     },
     service::{
-        alerts::alert::{self, AlertError},
+        alerts::{
+            alert::{self, AlertError},
+            backtest,
+        },
         db::scheduler,
     },
 };
 
+pub mod bundle;
+pub mod deliveries;
 #[allow(deprecated)]
 pub mod deprecated;
 pub mod destinations;
+pub mod notification_policy;
+pub mod silences;
 pub mod templates;
 
 impl From<AlertError> for HttpResponse {
@@ -61,6 +71,13 @@ impl From<AlertError> for HttpResponse {
             AlertError::DecodeVrl(err) => MetaHttpResponse::bad_request(err),
             AlertError::ParseCron(err) => MetaHttpResponse::bad_request(err),
             AlertError::RealtimeMissingCustomQuery => MetaHttpResponse::bad_request(value),
+            AlertError::RealtimeStateful => MetaHttpResponse::bad_request(value),
+            AlertError::AnomalyDetectionInvalid(_) => MetaHttpResponse::bad_request(value),
+            AlertError::CompositeInvalid(_) => MetaHttpResponse::bad_request(value),
+            AlertError::CompositeReferenced(_) => MetaHttpResponse::conflict(value),
+            AlertError::BacktestInvalid(_) => MetaHttpResponse::bad_request(value),
+            AlertError::BacktestEvaluation(_) => MetaHttpResponse::internal_error(value),
+            AlertError::NegativeForDuration => MetaHttpResponse::bad_request(value),
             AlertError::SqlMissingQuery => MetaHttpResponse::bad_request(value),
             AlertError::SqlContainsSelectStar => MetaHttpResponse::bad_request(value),
             AlertError::PromqlMissingQuery => MetaHttpResponse::bad_request(value),
@@ -70,6 +87,8 @@ impl From<AlertError> for HttpResponse {
             }
             AlertError::PeriodExceedsMaxQueryRange { .. } => MetaHttpResponse::bad_request(value),
             AlertError::ResolveStreamNameError(_) => MetaHttpResponse::internal_error(value),
+            AlertError::SqlMacroInvalid(_) => MetaHttpResponse::bad_request(value),
+            AlertError::RowTemplateInvalid(_) => MetaHttpResponse::bad_request(value),
             AlertError::PermittedAlertsMissingUser => MetaHttpResponse::forbidden(""),
             AlertError::PermittedAlertsValidator(err) => MetaHttpResponse::forbidden(err),
             AlertError::NotSupportedAlertDestinationType(err) => MetaHttpResponse::forbidden(err),
@@ -120,6 +139,44 @@ pub async fn create_alert(
     }
 }
 
+/// BacktestAlert
+///
+/// Replays the scheduled evaluations of an alert over a past time range and
+/// returns the notifications it would have sent. The alert doesn't need to be
+/// saved, no notification is sent.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "BacktestAlert",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+      ),
+    request_body(content = BacktestAlertRequestBody, description = "Alert data and time range", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = BacktestAlertResponseBody),
+        (status = 400, description = "Error",   content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/v2/{org_id}/alerts/backtest")]
+pub async fn backtest_alert(
+    path: web::Path<String>,
+    req_body: web::Json<BacktestAlertRequestBody>,
+) -> HttpResponse {
+    let org_id = path.into_inner();
+    let req_body = req_body.into_inner();
+    let (start_time, end_time) = (req_body.start_time, req_body.end_time);
+    let mut alert: MetaAlert = req_body.into();
+    alert.org_id = org_id;
+
+    match backtest::run(alert, start_time, end_time).await {
+        Ok(backtest) => MetaHttpResponse::json(BacktestAlertResponseBody::from(backtest)),
+        Err(e) => e.into(),
+    }
+}
+
 /// GetAlert
 #[utoipa::path(
     context_path = "/api",
@@ -155,6 +212,44 @@ async fn get_alert(path: web::Path<(String, Ksuid)>) -> HttpResponse {
     }
 }
 
+/// GetAlertState
+///
+/// Returns the pending and firing groups of the alert, and the recent state
+/// transitions.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "GetAlertState",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("alert_id" = Ksuid, Path, description = "Alert ID"),
+      ),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = GetAlertStateResponseBody),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/v2/{org_id}/alerts/{alert_id}/state")]
+async fn get_alert_state(path: web::Path<(String, Ksuid)>) -> HttpResponse {
+    let (org_id, alert_id) = path.into_inner();
+
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    match alert::get_by_id(client, &org_id, alert_id).await {
+        Ok(alert) => {
+            let key = alert.get_unique_key();
+            let scheduled_job = scheduler::get(&org_id, TriggerModule::Alert, &key)
+                .await
+                .ok();
+            let resp_body: GetAlertStateResponseBody = scheduled_job.into();
+            MetaHttpResponse::json(resp_body)
+        }
+        Err(e) => e.into(),
+    }
+}
+
 /// UpdateAlert
 #[utoipa::path(
     context_path = "/api",
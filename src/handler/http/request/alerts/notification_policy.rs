@@ -0,0 +1,118 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::Error;
+
+use actix_web::{HttpResponse, delete, get, put, web};
+use config::meta::alerts::routing::NotificationPolicy;
+
+use crate::{
+    common::meta::http::HttpResponse as MetaHttpResponse,
+    service::alerts::notification_policies::{self, NotificationPolicyError},
+};
+
+impl From<NotificationPolicyError> for HttpResponse {
+    fn from(value: NotificationPolicyError) -> Self {
+        match &value {
+            NotificationPolicyError::Invalid(_) => MetaHttpResponse::bad_request(value),
+            NotificationPolicyError::DestinationNotFound(_) => MetaHttpResponse::bad_request(value),
+            NotificationPolicyError::NotFound => MetaHttpResponse::not_found(value),
+            NotificationPolicyError::Db(err) => MetaHttpResponse::internal_error(err),
+        }
+    }
+}
+
+/// GetNotificationPolicy
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "GetNotificationPolicy",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+      ),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = NotificationPolicy),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/alerts/notification_policy")]
+async fn get_notification_policy(path: web::Path<String>) -> Result<HttpResponse, Error> {
+    let org_id = path.into_inner();
+    match notification_policies::get(&org_id).await {
+        Ok(policy) => Ok(MetaHttpResponse::json(policy)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// SetNotificationPolicy
+///
+/// Replaces the notification policy of the organization. Once set, the
+/// notifications of the scheduled alerts are grouped, routed and inhibited
+/// by the policy.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "SetNotificationPolicy",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+      ),
+    request_body(content = NotificationPolicy, description = "Notification policy", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = NotificationPolicy),
+        (status = 400, description = "Error",   content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[put("/{org_id}/alerts/notification_policy")]
+async fn set_notification_policy(
+    path: web::Path<String>,
+    policy: web::Json<NotificationPolicy>,
+) -> Result<HttpResponse, Error> {
+    let org_id = path.into_inner();
+    match notification_policies::set(&org_id, policy.into_inner()).await {
+        Ok(policy) => Ok(MetaHttpResponse::json(policy)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// DeleteNotificationPolicy
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "DeleteNotificationPolicy",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+      ),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[delete("/{org_id}/alerts/notification_policy")]
+async fn delete_notification_policy(path: web::Path<String>) -> Result<HttpResponse, Error> {
+    let org_id = path.into_inner();
+    match notification_policies::delete(&org_id).await {
+        Ok(_) => Ok(MetaHttpResponse::ok("Notification policy deleted")),
+        Err(e) => Ok(e.into()),
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use actix_web::{HttpResponse, delete, get, post, put, web};
+use config::meta::alerts::silence::{Silence, SilenceList};
+
+use crate::{
+    common::{meta::http::HttpResponse as MetaHttpResponse, utils::auth::UserEmail},
+    service::alerts::silences::{self, SilenceError},
+};
+
+impl From<SilenceError> for HttpResponse {
+    fn from(value: SilenceError) -> Self {
+        match &value {
+            SilenceError::Invalid(_) => MetaHttpResponse::bad_request(value),
+            SilenceError::NotFound(_) => MetaHttpResponse::not_found(value),
+            SilenceError::Db(_) => MetaHttpResponse::internal_error(value),
+        }
+    }
+}
+
+/// CreateSilence
+///
+/// Mutes the alerts, reports and derived streams matching the silence while
+/// it is active, either once between the start and the end time or in the
+/// windows of its recurring schedule.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "CreateSilence",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    request_body(content = Silence, description = "Silence data", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = Silence),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/alerts/silences")]
+pub async fn create_silence(
+    path: web::Path<String>,
+    body: web::Json<Silence>,
+    user_email: UserEmail,
+) -> HttpResponse {
+    let org_id = path.into_inner();
+    match silences::create(&org_id, &user_email.user_id, body.into_inner()).await {
+        Ok(v) => MetaHttpResponse::json(v),
+        Err(e) => e.into(),
+    }
+}
+
+/// UpdateSilence
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "UpdateSilence",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("silence_id" = String, Path, description = "Silence ID"),
+    ),
+    request_body(content = Silence, description = "Silence data", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = Silence),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[put("/{org_id}/alerts/silences/{silence_id}")]
+pub async fn update_silence(
+    path: web::Path<(String, String)>,
+    body: web::Json<Silence>,
+) -> HttpResponse {
+    let (org_id, silence_id) = path.into_inner();
+    match silences::update(&org_id, &silence_id, body.into_inner()).await {
+        Ok(v) => MetaHttpResponse::json(v),
+        Err(e) => e.into(),
+    }
+}
+
+/// GetSilence
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "GetSilence",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("silence_id" = String, Path, description = "Silence ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = Silence),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/alerts/silences/{silence_id}")]
+pub async fn get_silence(path: web::Path<(String, String)>) -> HttpResponse {
+    let (org_id, silence_id) = path.into_inner();
+    match silences::get(&org_id, &silence_id).await {
+        Ok(v) => MetaHttpResponse::json(v),
+        Err(e) => e.into(),
+    }
+}
+
+/// ListSilences
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "ListSilences",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = SilenceList),
+    )
+)]
+#[get("/{org_id}/alerts/silences")]
+pub async fn list_silences(path: web::Path<String>) -> HttpResponse {
+    let org_id = path.into_inner();
+    match silences::list(&org_id).await {
+        Ok(list) => MetaHttpResponse::json(SilenceList { list }),
+        Err(e) => e.into(),
+    }
+}
+
+/// DeleteSilence
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "DeleteSilence",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("silence_id" = String, Path, description = "Silence ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[delete("/{org_id}/alerts/silences/{silence_id}")]
+pub async fn delete_silence(path: web::Path<(String, String)>) -> HttpResponse {
+    let (org_id, silence_id) = path.into_inner();
+    match silences::delete(&org_id, &silence_id).await {
+        Ok(()) => MetaHttpResponse::ok("Silence deleted"),
+        Err(e) => e.into(),
+    }
+}
@@ -19,7 +19,7 @@ use actix_web::{HttpRequest, HttpResponse, delete, get, post, put, web};
 
 use crate::{
     common::meta::http::HttpResponse as MetaHttpResponse,
-    handler::http::models::destinations::Template,
+    handler::http::models::destinations::{Template, TemplatePreview, TemplatePreviewResponse},
     service::{alerts::templates, db::alerts::templates::TemplateError},
 };
 
@@ -68,6 +68,44 @@ pub async fn save_template(
     }
 }
 
+/// PreviewTemplate
+///
+/// Renders the template for sample rows of an example alert, like the
+/// notification would be, without saving or sending it.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Templates",
+    operation_id = "PreviewTemplate",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+      ),
+    request_body(content = TemplatePreview, description = "Template and sample rows", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = TemplatePreviewResponse),
+        (status = 400, description = "Error",   content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/alerts/templates/preview")]
+pub async fn preview_template(
+    path: web::Path<String>,
+    req: web::Json<TemplatePreview>,
+) -> Result<HttpResponse, Error> {
+    let org_id = path.into_inner();
+    let req = req.into_inner();
+    let alert = req.into_alert(&org_id);
+    let tmpl = req.template.into(&org_id);
+    match templates::preview(&tmpl, &alert, &req.rows, req.alert_status).await {
+        Ok((title, body)) => Ok(MetaHttpResponse::json(TemplatePreviewResponse {
+            title,
+            body,
+        })),
+        Err(e) => Ok(e.into()),
+    }
+}
+
 /// UpdateTemplate
 #[utoipa::path(
     context_path = "/api",
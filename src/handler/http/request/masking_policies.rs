@@ -0,0 +1,175 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::Error;
+
+use actix_web::{HttpResponse, delete, get, post, put, web};
+use config::meta::redaction::MaskingPolicy;
+
+use crate::{
+    common::meta::http::HttpResponse as MetaHttpResponse,
+    service::redaction::{self, MaskingPolicyError},
+};
+
+impl From<MaskingPolicyError> for HttpResponse {
+    fn from(value: MaskingPolicyError) -> Self {
+        match &value {
+            MaskingPolicyError::Invalid(_) => MetaHttpResponse::bad_request(value),
+            MaskingPolicyError::AlreadyExists(_) => MetaHttpResponse::conflict(value),
+            MaskingPolicyError::NotFound(_) => MetaHttpResponse::not_found(value),
+            MaskingPolicyError::Db(err) => MetaHttpResponse::internal_error(err),
+        }
+    }
+}
+
+/// CreateMaskingPolicy
+///
+/// Creates a policy masking the personal data in the search results of the
+/// users having one of its roles.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Masking Policies",
+    operation_id = "CreateMaskingPolicy",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+      ),
+    request_body(content = MaskingPolicy, description = "Masking policy", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = MaskingPolicy),
+        (status = 400, description = "Error",   content_type = "application/json", body = HttpResponse),
+        (status = 409, description = "Conflict", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/masking_policies")]
+async fn create_masking_policy(
+    path: web::Path<String>,
+    policy: web::Json<MaskingPolicy>,
+) -> Result<HttpResponse, Error> {
+    let org_id = path.into_inner();
+    match redaction::create(&org_id, policy.into_inner()).await {
+        Ok(policy) => Ok(MetaHttpResponse::json(policy)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// ListMaskingPolicies
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Masking Policies",
+    operation_id = "ListMaskingPolicies",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+      ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = Vec<MaskingPolicy>),
+    )
+)]
+#[get("/{org_id}/masking_policies")]
+async fn list_masking_policies(path: web::Path<String>) -> Result<HttpResponse, Error> {
+    let org_id = path.into_inner();
+    match redaction::list(&org_id).await {
+        Ok(list) => Ok(MetaHttpResponse::json(list)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// GetMaskingPolicy
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Masking Policies",
+    operation_id = "GetMaskingPolicy",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("name" = String, Path, description = "Masking policy name"),
+      ),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = MaskingPolicy),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/masking_policies/{name}")]
+async fn get_masking_policy(path: web::Path<(String, String)>) -> Result<HttpResponse, Error> {
+    let (org_id, name) = path.into_inner();
+    match redaction::get(&org_id, &name).await {
+        Ok(policy) => Ok(MetaHttpResponse::json(policy)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// UpdateMaskingPolicy
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Masking Policies",
+    operation_id = "UpdateMaskingPolicy",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("name" = String, Path, description = "Masking policy name"),
+      ),
+    request_body(content = MaskingPolicy, description = "Masking policy", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = MaskingPolicy),
+        (status = 400, description = "Error",    content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[put("/{org_id}/masking_policies/{name}")]
+async fn update_masking_policy(
+    path: web::Path<(String, String)>,
+    policy: web::Json<MaskingPolicy>,
+) -> Result<HttpResponse, Error> {
+    let (org_id, name) = path.into_inner();
+    match redaction::update(&org_id, &name, policy.into_inner()).await {
+        Ok(policy) => Ok(MetaHttpResponse::json(policy)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// DeleteMaskingPolicy
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Masking Policies",
+    operation_id = "DeleteMaskingPolicy",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("name" = String, Path, description = "Masking policy name"),
+      ),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[delete("/{org_id}/masking_policies/{name}")]
+async fn delete_masking_policy(path: web::Path<(String, String)>) -> Result<HttpResponse, Error> {
+    let (org_id, name) = path.into_inner();
+    match redaction::delete(&org_id, &name).await {
+        Ok(_) => Ok(MetaHttpResponse::ok("Masking policy deleted")),
+        Err(e) => Ok(e.into()),
+    }
+}
@@ -27,6 +27,7 @@ pub mod functions;
 pub mod keys;
 pub mod kv;
 pub mod logs;
+pub mod masking_policies;
 pub mod metrics;
 pub mod organization;
 pub mod pipeline;
@@ -37,6 +38,7 @@ pub mod script_server;
 pub mod search;
 pub mod service_accounts;
 pub mod short_url;
+pub mod sql_macros;
 pub mod status;
 pub mod stream;
 pub mod syslog;
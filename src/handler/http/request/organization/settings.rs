@@ -101,6 +101,28 @@ async fn create(
         }
     }
 
+    if let Some(limit) = settings.query_max_concurrent_per_org {
+        field_found = true;
+        data.query_max_concurrent_per_org = Some(limit);
+    }
+    if let Some(limit) = settings.query_max_concurrent_per_user {
+        field_found = true;
+        data.query_max_concurrent_per_user = Some(limit);
+    }
+    if let Some(limit) = settings.query_max_scan_size {
+        field_found = true;
+        data.query_max_scan_size = Some(limit);
+    }
+    if let Some(limit) = settings.query_max_time_range {
+        if limit < 0 {
+            return Ok(MetaHttpResponse::bad_request(
+                "query_max_time_range should not be negative",
+            ));
+        }
+        field_found = true;
+        data.query_max_time_range = Some(limit);
+    }
+
     if !field_found {
         return Ok(MetaHttpResponse::bad_request("No valid field found"));
     }
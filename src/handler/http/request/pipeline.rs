@@ -17,24 +17,54 @@ use std::io::Error;
 
 use actix_web::{HttpRequest, HttpResponse, delete, get, http, post, put, web};
 use ahash::HashMap;
-use config::{ider, meta::pipeline::Pipeline};
+use config::{
+    ider,
+    meta::pipeline::{
+        Pipeline,
+        backfill::{BackfillJob, BackfillJobList, BackfillRequest},
+        version::{
+            PipelineDiff, PipelineVersion, PipelineVersionList, StagePipelineRequest,
+            StagedPipeline,
+        },
+    },
+};
+use serde::Deserialize;
 
 use crate::{
     common::meta::http::HttpResponse as MetaHttpResponse,
-    service::{db::pipeline::PipelineError, pipeline},
+    service::{
+        db::pipeline::PipelineError,
+        pipeline::{
+            self, backfill,
+            dry_run::{DryRunRequest, PipelineTrace},
+            versions,
+        },
+    },
 };
 
 impl From<PipelineError> for HttpResponse {
     fn from(value: PipelineError) -> Self {
         match value {
             PipelineError::InfraError(err) => MetaHttpResponse::internal_error(err),
-            PipelineError::NotFound(_) => MetaHttpResponse::not_found(value),
+            PipelineError::NotFound(_)
+            | PipelineError::VersionNotFound(..)
+            | PipelineError::StagedNotFound(_)
+            | PipelineError::BackfillNotFound(_) => MetaHttpResponse::not_found(value),
             PipelineError::Modified(_) => MetaHttpResponse::conflict(value),
             error => MetaHttpResponse::bad_request(error),
         }
     }
 }
 
+/// User making the change, recorded in the version history of the pipeline
+fn get_author(req: &HttpRequest) -> String {
+    req.headers()
+        .get("user_id")
+        .and_then(|user_id| user_id.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
 /// CreatePipeline
 #[utoipa::path(
     context_path = "/api",
@@ -56,14 +86,14 @@ impl From<PipelineError> for HttpResponse {
 pub async fn save_pipeline(
     path: web::Path<String>,
     pipeline: web::Json<Pipeline>,
-    _req: HttpRequest,
+    req: HttpRequest,
 ) -> Result<HttpResponse, Error> {
     let org_id = path.into_inner();
     let mut pipeline = pipeline.into_inner();
     pipeline.name = pipeline.name.trim().to_lowercase();
     pipeline.org = org_id;
     pipeline.id = ider::generate();
-    match pipeline::save_pipeline(pipeline).await {
+    match pipeline::save_pipeline(pipeline, &get_author(&req)).await {
         Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
             http::StatusCode::OK.into(),
             "Pipeline created successfully".to_string(),
@@ -72,6 +102,37 @@ pub async fn save_pipeline(
     }
 }
 
+/// DryRunPipeline
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "dryRunPipeline",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    request_body(content = DryRunRequest, description = "Pipeline, or saved pipeline id, and sample records", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = PipelineTrace),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/pipelines/dry_run")]
+pub async fn dry_run_pipeline(
+    path: web::Path<String>,
+    req_body: web::Json<DryRunRequest>,
+    _req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let org_id = path.into_inner();
+    match pipeline::dry_run::dry_run(&org_id, req_body.into_inner()).await {
+        Ok(trace) => Ok(HttpResponse::Ok().json(trace)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
 /// ListPipelines
 #[utoipa::path(
     context_path = "/api",
@@ -82,6 +143,7 @@ pub async fn save_pipeline(
     ),
     params(
         ("org_id" = String, Path, description = "Organization name"),
+        ("with_metrics" = Option<bool>, Query, description = "Include the metrics of the nodes of the pipelines over the last hour"),
     ),
     responses(
         (status = 200, description = "Success", content_type = "application/json", body = PipelineList),
@@ -92,6 +154,10 @@ async fn list_pipelines(
     org_id: web::Path<String>,
     _req: HttpRequest,
 ) -> Result<HttpResponse, Error> {
+    let query = web::Query::<HashMap<String, String>>::from_query(_req.query_string()).unwrap();
+    let with_metrics = query
+        .get("with_metrics")
+        .is_some_and(|v| v.parse::<bool>().unwrap_or_default());
     let mut _permitted = None;
     // Get List of allowed objects
     #[cfg(feature = "enterprise")]
@@ -121,7 +187,7 @@ async fn list_pipelines(
         // Get List of allowed objects ends
     }
 
-    match pipeline::list_pipelines(org_id.into_inner(), _permitted).await {
+    match pipeline::list_pipelines(org_id.into_inner(), _permitted, with_metrics).await {
         Ok(pipeline_list) => Ok(HttpResponse::Ok().json(pipeline_list)),
         Err(e) => Ok(e.into()),
     }
@@ -206,10 +272,10 @@ async fn delete_pipeline(
 #[put("/{org_id}/pipelines")]
 pub async fn update_pipeline(
     pipeline: web::Json<Pipeline>,
-    _req: HttpRequest,
+    req: HttpRequest,
 ) -> Result<HttpResponse, Error> {
     let pipeline = pipeline.into_inner();
-    match pipeline::update_pipeline(pipeline).await {
+    match pipeline::update_pipeline(pipeline, &get_author(&req)).await {
         Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
             http::StatusCode::OK.into(),
             "Pipeline updated successfully".to_string(),
@@ -258,3 +324,469 @@ pub async fn enable_pipeline(
         Err(e) => Ok(e.into()),
     }
 }
+
+/// ListPipelineVersions
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "listPipelineVersions",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = PipelineVersionList),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/pipelines/{pipeline_id}/versions")]
+pub async fn list_pipeline_versions(
+    path: web::Path<(String, String)>,
+    _req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    match versions::list_versions(&org_id, &pipeline_id).await {
+        Ok(list) => Ok(HttpResponse::Ok().json(list)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    from: i32,
+    to: i32,
+}
+
+/// DiffPipelineVersions
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "diffPipelineVersions",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+        ("from" = i32, Query, description = "Version to compare from"),
+        ("to" = i32, Query, description = "Version to compare to"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = PipelineDiff),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/pipelines/{pipeline_id}/versions/diff")]
+pub async fn diff_pipeline_versions(
+    path: web::Path<(String, String)>,
+    query: web::Query<DiffQuery>,
+    _req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    match versions::diff_versions(&org_id, &pipeline_id, query.from, query.to).await {
+        Ok(diff) => Ok(HttpResponse::Ok().json(diff)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// GetPipelineVersion
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "getPipelineVersion",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+        ("version" = i32, Path, description = "Pipeline version"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = PipelineVersion),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/pipelines/{pipeline_id}/versions/{version}")]
+pub async fn get_pipeline_version(
+    path: web::Path<(String, String, i32)>,
+    _req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id, version) = path.into_inner();
+    match versions::get_version(&org_id, &pipeline_id, version).await {
+        Ok(version) => Ok(HttpResponse::Ok().json(version)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// RollbackPipeline
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "rollbackPipeline",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+        ("version" = i32, Path, description = "Pipeline version to restore"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+        (status = 409, description = "Conflict", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/pipelines/{pipeline_id}/versions/{version}/rollback")]
+pub async fn rollback_pipeline(
+    path: web::Path<(String, String, i32)>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id, version) = path.into_inner();
+    match versions::rollback(&org_id, &pipeline_id, version, &get_author(&req)).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
+            http::StatusCode::OK.into(),
+            format!("Pipeline rolled back to version {version}"),
+        ))),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// GetStagedPipeline
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "getStagedPipeline",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = StagedPipeline),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/pipelines/{pipeline_id}/staged")]
+pub async fn get_staged_pipeline(
+    path: web::Path<(String, String)>,
+    _req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    match versions::get_staged(&org_id, &pipeline_id).await {
+        Ok(staged) => Ok(HttpResponse::Ok().json(staged)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// StagePipeline
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "stagePipeline",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+    ),
+    request_body(content = StagePipelineRequest, description = "New version of the pipeline and the percentage of records it processes", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/pipelines/{pipeline_id}/staged")]
+pub async fn stage_pipeline(
+    path: web::Path<(String, String)>,
+    req_body: web::Json<StagePipelineRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    match versions::stage(
+        &org_id,
+        &pipeline_id,
+        req_body.into_inner(),
+        &get_author(&req),
+    )
+    .await
+    {
+        Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
+            http::StatusCode::OK.into(),
+            "Pipeline version staged".to_string(),
+        ))),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// PromoteStagedPipeline
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "promoteStagedPipeline",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+        (status = 409, description = "Conflict", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/pipelines/{pipeline_id}/staged/promote")]
+pub async fn promote_staged_pipeline(
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    match versions::promote_staged(&org_id, &pipeline_id, &get_author(&req)).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
+            http::StatusCode::OK.into(),
+            "Staged pipeline version promoted".to_string(),
+        ))),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// DiscardStagedPipeline
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "discardStagedPipeline",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[delete("/{org_id}/pipelines/{pipeline_id}/staged")]
+pub async fn discard_staged_pipeline(
+    path: web::Path<(String, String)>,
+    _req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    match versions::discard_staged(&org_id, &pipeline_id).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
+            http::StatusCode::OK.into(),
+            "Staged pipeline version discarded".to_string(),
+        ))),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// CreatePipelineBackfill
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "createPipelineBackfill",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+    ),
+    request_body(content = BackfillRequest, description = "Time range to replay and version of the pipeline", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = BackfillJob),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/pipelines/{pipeline_id}/backfill")]
+pub async fn create_pipeline_backfill(
+    path: web::Path<(String, String)>,
+    req_body: web::Json<BackfillRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    match backfill::create(
+        &org_id,
+        &pipeline_id,
+        req_body.into_inner(),
+        &get_author(&req),
+    )
+    .await
+    {
+        Ok(job) => Ok(HttpResponse::Ok().json(job)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// ListPipelineBackfills
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "listPipelineBackfills",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = BackfillJobList),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/pipelines/{pipeline_id}/backfill")]
+pub async fn list_pipeline_backfills(
+    path: web::Path<(String, String)>,
+    _req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    match backfill::list(&org_id, &pipeline_id).await {
+        Ok(list) => Ok(HttpResponse::Ok().json(list)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// GetPipelineBackfill
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "getPipelineBackfill",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+        ("job_id" = String, Path, description = "Backfill job ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = BackfillJob),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/pipelines/{pipeline_id}/backfill/{job_id}")]
+pub async fn get_pipeline_backfill(
+    path: web::Path<(String, String, String)>,
+    _req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id, job_id) = path.into_inner();
+    match backfill::get(&org_id, &pipeline_id, &job_id).await {
+        Ok(job) => Ok(HttpResponse::Ok().json(job)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// CancelPipelineBackfill
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "cancelPipelineBackfill",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+        ("job_id" = String, Path, description = "Backfill job ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/pipelines/{pipeline_id}/backfill/{job_id}/cancel")]
+pub async fn cancel_pipeline_backfill(
+    path: web::Path<(String, String, String)>,
+    _req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id, job_id) = path.into_inner();
+    match backfill::cancel(&org_id, &pipeline_id, &job_id).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
+            http::StatusCode::OK.into(),
+            "Backfill job cancelled".to_string(),
+        ))),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// RetryPipelineBackfill
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "retryPipelineBackfill",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+        ("job_id" = String, Path, description = "Backfill job ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/pipelines/{pipeline_id}/backfill/{job_id}/retry")]
+pub async fn retry_pipeline_backfill(
+    path: web::Path<(String, String, String)>,
+    _req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id, job_id) = path.into_inner();
+    match backfill::retry(&org_id, &pipeline_id, &job_id).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
+            http::StatusCode::OK.into(),
+            "Backfill job queued to run again".to_string(),
+        ))),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// DeletePipelineBackfill
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "deletePipelineBackfill",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+        ("job_id" = String, Path, description = "Backfill job ID"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[delete("/{org_id}/pipelines/{pipeline_id}/backfill/{job_id}")]
+pub async fn delete_pipeline_backfill(
+    path: web::Path<(String, String, String)>,
+    _req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id, job_id) = path.into_inner();
+    match backfill::delete(&org_id, &pipeline_id, &job_id).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
+            http::StatusCode::OK.into(),
+            "Backfill job deleted".to_string(),
+        ))),
+        Err(e) => Ok(e.into()),
+    }
+}
@@ -177,6 +177,9 @@ pub(crate) async fn around(
             skip_wal: false,
             streaming_output: false,
             streaming_id: None,
+            use_cursor: false,
+            cursor: None,
+            params: Default::default(),
         },
         encoding: config::meta::search::RequestEncoding::Empty,
         regions: regions.clone(),
@@ -210,6 +213,9 @@ pub(crate) async fn around(
             skip_wal: false,
             streaming_output: false,
             streaming_id: None,
+            use_cursor: false,
+            cursor: None,
+            params: Default::default(),
         },
         encoding: config::meta::search::RequestEncoding::Empty,
         regions,
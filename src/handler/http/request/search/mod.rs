@@ -217,6 +217,10 @@ pub async fn search(
     if let Err(e) = req.decode() {
         return Ok(MetaHttpResponse::bad_request(e));
     }
+    // expand the sql macros, the streams they read are checked below
+    if let Err(e) = crate::service::sql_macros::expand_query(&org_id, &mut req.query) {
+        return Ok(MetaHttpResponse::bad_request(e));
+    }
     req.use_cache = Some(use_cache);
 
     // set search event type
@@ -351,7 +355,8 @@ pub async fn search(
             log::error!("[trace_id {trace_id}] search error: {}", err);
             Ok(match err {
                 errors::Error::ErrorCode(code) => match code {
-                    errors::ErrorCodes::SearchCancelQuery(_) => HttpResponse::TooManyRequests()
+                    errors::ErrorCodes::SearchCancelQuery(_)
+                    | errors::ErrorCodes::SearchQuotaExceeded(_) => HttpResponse::TooManyRequests()
                         .json(meta::http::HttpResponse::error_code_with_trace_id(
                             code,
                             Some(trace_id),
@@ -460,7 +465,8 @@ pub async fn around_v1(
             log::error!("search around error: {:?}", err);
             Ok(match err {
                 errors::Error::ErrorCode(code) => match code {
-                    errors::ErrorCodes::SearchCancelQuery(_) => HttpResponse::TooManyRequests()
+                    errors::ErrorCodes::SearchCancelQuery(_)
+                    | errors::ErrorCodes::SearchQuotaExceeded(_) => HttpResponse::TooManyRequests()
                         .json(meta::http::HttpResponse::error_code_with_trace_id(
                             code,
                             Some(trace_id),
@@ -579,7 +585,8 @@ pub async fn around_v2(
             log::error!("search around error: {:?}", err);
             Ok(match err {
                 errors::Error::ErrorCode(code) => match code {
-                    errors::ErrorCodes::SearchCancelQuery(_) => HttpResponse::TooManyRequests()
+                    errors::ErrorCodes::SearchCancelQuery(_)
+                    | errors::ErrorCodes::SearchQuotaExceeded(_) => HttpResponse::TooManyRequests()
                         .json(meta::http::HttpResponse::error_code_with_trace_id(
                             code,
                             Some(trace_id),
@@ -738,7 +745,22 @@ async fn values_v1(
     };
 
     if let Some(v) = query.get("sql") {
-        if let Ok(sql) = base64::decode_url(v) {
+        if let Ok(mut sql) = base64::decode_url(v) {
+            // expand the sql macros, the where clause is copied to the values queries
+            if let Err(e) =
+                crate::service::sql_macros::expand_sql(org_id, &mut sql, &mut Default::default())
+            {
+                return Ok(MetaHttpResponse::bad_request(e));
+            }
+            // the streams read by the where clause, e.g. in subqueries
+            #[cfg(feature = "enterprise")]
+            for name in resolve_stream_names(&sql).unwrap_or_default() {
+                if let Some(res) =
+                    check_stream_permissions(&name, org_id, user_id, &stream_type).await
+                {
+                    return Ok(res);
+                }
+            }
             uses_fn = functions::get_all_transform_keys(org_id)
                 .await
                 .iter()
@@ -916,11 +938,15 @@ async fn values_v1(
                 log::error!("search values error: {:?}", err);
                 return Ok(match err {
                     errors::Error::ErrorCode(code) => match code {
-                        errors::ErrorCodes::SearchCancelQuery(_) => HttpResponse::TooManyRequests()
-                            .json(meta::http::HttpResponse::error_code_with_trace_id(
-                                code,
-                                Some(trace_id),
-                            )),
+                        errors::ErrorCodes::SearchCancelQuery(_)
+                        | errors::ErrorCodes::SearchQuotaExceeded(_) => {
+                            HttpResponse::TooManyRequests().json(
+                                meta::http::HttpResponse::error_code_with_trace_id(
+                                    code,
+                                    Some(trace_id),
+                                ),
+                            )
+                        }
                         _ => HttpResponse::InternalServerError().json(
                             meta::http::HttpResponse::error_code_with_trace_id(
                                 code,
@@ -1090,6 +1116,9 @@ pub async fn search_partition(
     if let Err(e) = req.decode() {
         return Ok(MetaHttpResponse::bad_request(e));
     }
+    if let Err(e) = crate::service::sql_macros::expand_sql(&org_id, &mut req.sql, &mut req.params) {
+        return Ok(MetaHttpResponse::bad_request(e));
+    }
 
     let search_res = SearchService::search_partition(
         &trace_id,
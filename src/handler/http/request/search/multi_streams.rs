@@ -179,6 +179,10 @@ pub async fn search_multi(
         if let Err(e) = req.decode() {
             return Ok(MetaHttpResponse::bad_request(e));
         }
+        // expand the sql macros, the streams they read are checked below
+        if let Err(e) = crate::service::sql_macros::expand_query(&org_id, &mut req.query) {
+            return Ok(MetaHttpResponse::bad_request(e));
+        }
     }
     let queries_len = queries.len();
     let mut vrl_stream_name = "".to_string();
@@ -232,33 +236,17 @@ pub async fn search_multi(
                 utils::auth::{AuthExtractor, is_root_user},
             };
 
-            if !is_root_user(user_id) {
-                let user: meta::user::User =
-                    USERS.get(&format!("{org_id}/{user_id}")).unwrap().clone();
-                let stream_type_str = stream_type.as_str();
-
-                if !crate::handler::http::auth::validator::check_permissions(
+            // every stream of the query, including the ones read by its macros
+            for stream_name in resolve_stream_names(&req.query.sql).unwrap_or_default() {
+                if let Some(res) = super::utils::check_stream_permissions(
+                    &stream_name,
+                    &org_id,
                     user_id,
-                    AuthExtractor {
-                        auth: "".to_string(),
-                        method: "GET".to_string(),
-                        o2_type: format!(
-                            "{}:{}",
-                            OFGA_MODELS
-                                .get(stream_type_str)
-                                .map_or(stream_type_str, |model| model.key),
-                            stream_name
-                        ),
-                        org_id: org_id.clone(),
-                        bypass_check: false,
-                        parent_id: "".to_string(),
-                    },
-                    user.role,
-                    user.is_external,
+                    &stream_type,
                 )
                 .await
                 {
-                    return Ok(MetaHttpResponse::forbidden("Unauthorized Access"));
+                    return Ok(res);
                 }
             }
 
@@ -902,11 +890,15 @@ pub async fn around_multi(
                 log::error!("multi search around error: {:?}", err);
                 return Ok(match err {
                     errors::Error::ErrorCode(code) => match code {
-                        errors::ErrorCodes::SearchCancelQuery(_) => HttpResponse::TooManyRequests()
-                            .json(meta::http::HttpResponse::error_code_with_trace_id(
-                                code,
-                                Some(trace_id),
-                            )),
+                        errors::ErrorCodes::SearchCancelQuery(_)
+                        | errors::ErrorCodes::SearchQuotaExceeded(_) => {
+                            HttpResponse::TooManyRequests().json(
+                                meta::http::HttpResponse::error_code_with_trace_id(
+                                    code,
+                                    Some(trace_id),
+                                ),
+                            )
+                        }
                         _ => HttpResponse::InternalServerError().json(
                             meta::http::HttpResponse::error_code_with_trace_id(
                                 code,
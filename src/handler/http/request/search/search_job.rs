@@ -82,6 +82,10 @@ pub async fn submit_job(
     if let Err(e) = req.decode() {
         return Ok(MetaHttpResponse::bad_request(e));
     }
+    // expand the sql macros, the streams they read are checked below
+    if let Err(e) = crate::service::sql_macros::expand_query(&org_id, &mut req.query) {
+        return Ok(MetaHttpResponse::bad_request(e));
+    }
     req.use_cache = Some(use_cache);
 
     // update timeout
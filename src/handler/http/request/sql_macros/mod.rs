@@ -0,0 +1,167 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use actix_web::{HttpResponse, delete, get, post, put, web};
+use config::meta::sql_macro::{SqlMacro, SqlMacroList};
+
+use crate::{
+    common::meta::http::HttpResponse as MetaHttpResponse,
+    service::sql_macros::{self, SqlMacroError},
+};
+
+impl From<SqlMacroError> for HttpResponse {
+    fn from(value: SqlMacroError) -> Self {
+        match &value {
+            SqlMacroError::Invalid(_) => MetaHttpResponse::bad_request(value),
+            SqlMacroError::AlreadyExists(_) => MetaHttpResponse::conflict(value),
+            SqlMacroError::NotFound(_) => MetaHttpResponse::not_found(value),
+            SqlMacroError::Db(_) => MetaHttpResponse::internal_error(value),
+        }
+    }
+}
+
+/// CreateSqlMacro
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Search",
+    operation_id = "CreateSqlMacro",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    request_body(content = SqlMacro, description = "SQL macro, either a sql fragment or a typed parameter", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = SqlMacro),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 409, description = "Already exists", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/sql_macros")]
+pub async fn create_sql_macro(path: web::Path<String>, body: web::Json<SqlMacro>) -> HttpResponse {
+    let org_id = path.into_inner();
+    match sql_macros::create(&org_id, body.into_inner()).await {
+        Ok(v) => MetaHttpResponse::json(v),
+        Err(e) => e.into(),
+    }
+}
+
+/// UpdateSqlMacro
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Search",
+    operation_id = "UpdateSqlMacro",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("name" = String, Path, description = "SQL macro name"),
+    ),
+    request_body(content = SqlMacro, description = "SQL macro, either a sql fragment or a typed parameter", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = SqlMacro),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[put("/{org_id}/sql_macros/{name}")]
+pub async fn update_sql_macro(
+    path: web::Path<(String, String)>,
+    body: web::Json<SqlMacro>,
+) -> HttpResponse {
+    let (org_id, name) = path.into_inner();
+    match sql_macros::update(&org_id, name.trim(), body.into_inner()).await {
+        Ok(v) => MetaHttpResponse::json(v),
+        Err(e) => e.into(),
+    }
+}
+
+/// GetSqlMacro
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Search",
+    operation_id = "GetSqlMacro",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("name" = String, Path, description = "SQL macro name"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = SqlMacro),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/sql_macros/{name}")]
+pub async fn get_sql_macro(path: web::Path<(String, String)>) -> HttpResponse {
+    let (org_id, name) = path.into_inner();
+    match sql_macros::get(&org_id, &name).await {
+        Ok(v) => MetaHttpResponse::json(v),
+        Err(e) => e.into(),
+    }
+}
+
+/// ListSqlMacros
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Search",
+    operation_id = "ListSqlMacros",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = SqlMacroList),
+    )
+)]
+#[get("/{org_id}/sql_macros")]
+pub async fn list_sql_macros(path: web::Path<String>) -> HttpResponse {
+    let org_id = path.into_inner();
+    match sql_macros::list(&org_id).await {
+        Ok(list) => MetaHttpResponse::json(SqlMacroList { list }),
+        Err(e) => e.into(),
+    }
+}
+
+/// DeleteSqlMacro
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Search",
+    operation_id = "DeleteSqlMacro",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("name" = String, Path, description = "SQL macro name"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[delete("/{org_id}/sql_macros/{name}")]
+pub async fn delete_sql_macro(path: web::Path<(String, String)>) -> HttpResponse {
+    let (org_id, name) = path.into_inner();
+    match sql_macros::delete(&org_id, &name).await {
+        Ok(()) => MetaHttpResponse::ok("SQL macro deleted"),
+        Err(e) => e.into(),
+    }
+}
@@ -289,6 +289,9 @@ pub async fn get_latest_traces(
             skip_wal: false,
             streaming_output: false,
             streaming_id: None,
+            use_cursor: false,
+            cursor: None,
+            params: Default::default(),
         },
         encoding: config::meta::search::RequestEncoding::Empty,
         regions: vec![],
@@ -335,7 +338,8 @@ pub async fn get_latest_traces(
             log::error!("get traces latest data error: {:?}", err);
             return Ok(match err {
                 errors::Error::ErrorCode(code) => match code {
-                    errors::ErrorCodes::SearchCancelQuery(_) => HttpResponse::TooManyRequests()
+                    errors::ErrorCodes::SearchCancelQuery(_)
+                    | errors::ErrorCodes::SearchQuotaExceeded(_) => HttpResponse::TooManyRequests()
                         .json(meta::http::HttpResponse::error_code(code)),
                     _ => HttpResponse::InternalServerError()
                         .json(meta::http::HttpResponse::error_code(code)),
@@ -424,8 +428,11 @@ pub async fn get_latest_traces(
                 log::error!("get traces latest data error: {:?}", err);
                 return Ok(match err {
                     errors::Error::ErrorCode(code) => match code {
-                        errors::ErrorCodes::SearchCancelQuery(_) => HttpResponse::TooManyRequests()
-                            .json(meta::http::HttpResponse::error_code(code)),
+                        errors::ErrorCodes::SearchCancelQuery(_)
+                        | errors::ErrorCodes::SearchQuotaExceeded(_) => {
+                            HttpResponse::TooManyRequests()
+                                .json(meta::http::HttpResponse::error_code(code))
+                        }
                         _ => HttpResponse::InternalServerError()
                             .json(meta::http::HttpResponse::error_code(code)),
                     },
@@ -102,6 +102,24 @@ pub async fn handle_search_request(
         req.payload.search_type = Some(req.search_type);
     }
 
+    // the partitions of a streaming search can't continue a cursor
+    if req.payload.query.use_cursor || req.payload.query.cursor.is_some() {
+        let err_res = WsServerEvents::error_response(
+            Error::Message("cursor pagination is not supported by streaming search".to_string()),
+            Some(req_id.to_string()),
+            Some(trace_id),
+        );
+        send_message(req_id, err_res.to_json().to_string()).await?;
+        return Ok(());
+    }
+
+    // expand the sql macros, the streams they read are checked below
+    if let Err(e) = crate::service::sql_macros::expand_query(org_id, &mut req.payload.query) {
+        let err_res = WsServerEvents::error_response(e, Some(req_id.to_string()), Some(trace_id));
+        send_message(req_id, err_res.to_json().to_string()).await?;
+        return Ok(());
+    }
+
     // get stream name
     let stream_names = match resolve_stream_names(&req.payload.query.sql) {
         Ok(v) => v.clone(),
@@ -699,6 +717,7 @@ async fn get_partitions(
         // vrl is not required for _search_partition
         query_fn: Default::default(),
         streaming_output: true,
+        params: search_payload.query.params.clone(),
     };
 
     let res = SearchService::search_partition(
@@ -413,6 +413,11 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(search::saved_view::get_view)
         .service(search::saved_view::get_views)
         .service(search::saved_view::delete_view)
+        .service(sql_macros::create_sql_macro)
+        .service(sql_macros::update_sql_macro)
+        .service(sql_macros::get_sql_macro)
+        .service(sql_macros::list_sql_macros)
+        .service(sql_macros::delete_sql_macro)
         .service(functions::save_function)
         .service(functions::list_functions)
         .service(functions::test_function)
@@ -451,7 +456,12 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(folders::deprecated::get_folder_by_name)
         .service(folders::deprecated::delete_folder)
         .service(alerts::create_alert)
+        .service(alerts::backtest_alert)
+        .service(alerts::bundle::export_alerts)
+        .service(alerts::bundle::import_alerts)
+        .service(alerts::bundle::import_prometheus_rules)
         .service(alerts::get_alert)
+        .service(alerts::get_alert_state)
         .service(alerts::update_alert)
         .service(alerts::delete_alert)
         .service(alerts::list_alerts)
@@ -467,6 +477,7 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(alerts::deprecated::enable_alert)
         .service(alerts::deprecated::trigger_alert)
         .service(alerts::templates::save_template)
+        .service(alerts::templates::preview_template)
         .service(alerts::templates::update_template)
         .service(alerts::templates::get_template)
         .service(alerts::templates::delete_template)
@@ -476,6 +487,18 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(alerts::destinations::get_destination)
         .service(alerts::destinations::list_destinations)
         .service(alerts::destinations::delete_destination)
+        .service(alerts::notification_policy::get_notification_policy)
+        .service(alerts::notification_policy::set_notification_policy)
+        .service(alerts::notification_policy::delete_notification_policy)
+        .service(alerts::silences::create_silence)
+        .service(alerts::silences::update_silence)
+        .service(alerts::silences::get_silence)
+        .service(alerts::silences::list_silences)
+        .service(alerts::silences::delete_silence)
+        .service(alerts::deliveries::list_deliveries)
+        .service(alerts::deliveries::get_delivery)
+        .service(alerts::deliveries::resend_delivery)
+        .service(alerts::deliveries::delete_delivery)
         .service(kv::get)
         .service(kv::set)
         .service(kv::delete)
@@ -511,11 +534,31 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(users::list_roles)
         .service(clusters::list_clusters)
         .service(pipeline::save_pipeline)
+        .service(pipeline::dry_run_pipeline)
         .service(pipeline::update_pipeline)
         .service(pipeline::list_pipelines)
         .service(pipeline::list_streams_with_pipeline)
         .service(pipeline::delete_pipeline)
         .service(pipeline::enable_pipeline)
+        .service(pipeline::list_pipeline_versions)
+        .service(pipeline::diff_pipeline_versions)
+        .service(pipeline::get_pipeline_version)
+        .service(pipeline::rollback_pipeline)
+        .service(pipeline::get_staged_pipeline)
+        .service(pipeline::stage_pipeline)
+        .service(pipeline::promote_staged_pipeline)
+        .service(pipeline::discard_staged_pipeline)
+        .service(pipeline::create_pipeline_backfill)
+        .service(pipeline::list_pipeline_backfills)
+        .service(pipeline::get_pipeline_backfill)
+        .service(pipeline::cancel_pipeline_backfill)
+        .service(pipeline::retry_pipeline_backfill)
+        .service(pipeline::delete_pipeline_backfill)
+        .service(masking_policies::create_masking_policy)
+        .service(masking_policies::list_masking_policies)
+        .service(masking_policies::get_masking_policy)
+        .service(masking_policies::update_masking_policy)
+        .service(masking_policies::delete_masking_policy)
         .service(search::multi_streams::search_multi)
         .service(search::multi_streams::_search_partition_multi)
         .service(search::multi_streams::around_multi)
@@ -83,6 +83,11 @@ use crate::{common::meta, handler::http::request};
         request::folders::deprecated::get_folder,
         request::folders::deprecated::get_folder_by_name,
         request::folders::deprecated::update_folder,
+        request::sql_macros::create_sql_macro,
+        request::sql_macros::update_sql_macro,
+        request::sql_macros::get_sql_macro,
+        request::sql_macros::list_sql_macros,
+        request::sql_macros::delete_sql_macro,
         request::functions::list_functions,
         request::functions::update_function,
         request::functions::save_function,
@@ -110,7 +115,12 @@ use crate::{common::meta, handler::http::request};
         request::alerts::deprecated::enable_alert,
         request::alerts::deprecated::trigger_alert,
         request::alerts::create_alert,
+        request::alerts::backtest_alert,
+        request::alerts::bundle::export_alerts,
+        request::alerts::bundle::import_alerts,
+        request::alerts::bundle::import_prometheus_rules,
         request::alerts::get_alert,
+        request::alerts::get_alert_state,
         request::alerts::update_alert,
         request::alerts::delete_alert,
         request::alerts::list_alerts,
@@ -120,6 +130,7 @@ use crate::{common::meta, handler::http::request};
         request::alerts::templates::list_templates,
         request::alerts::templates::get_template,
         request::alerts::templates::save_template,
+        request::alerts::templates::preview_template,
         request::alerts::templates::update_template,
         request::alerts::templates::delete_template,
         request::alerts::destinations::list_destinations,
@@ -127,6 +138,18 @@ use crate::{common::meta, handler::http::request};
         request::alerts::destinations::save_destination,
         request::alerts::destinations::update_destination,
         request::alerts::destinations::delete_destination,
+        request::alerts::notification_policy::get_notification_policy,
+        request::alerts::notification_policy::set_notification_policy,
+        request::alerts::notification_policy::delete_notification_policy,
+        request::alerts::silences::create_silence,
+        request::alerts::silences::update_silence,
+        request::alerts::silences::get_silence,
+        request::alerts::silences::list_silences,
+        request::alerts::silences::delete_silence,
+        request::alerts::deliveries::list_deliveries,
+        request::alerts::deliveries::get_delivery,
+        request::alerts::deliveries::resend_delivery,
+        request::alerts::deliveries::delete_delivery,
         request::kv::get,
         request::kv::set,
         request::kv::delete,
@@ -135,6 +158,11 @@ use crate::{common::meta, handler::http::request};
         request::syslog::update_route,
         request::syslog::list_routes,
         request::syslog::delete_route,
+        request::masking_policies::create_masking_policy,
+        request::masking_policies::list_masking_policies,
+        request::masking_policies::get_masking_policy,
+        request::masking_policies::update_masking_policy,
+        request::masking_policies::delete_masking_policy,
         request::clusters::list_clusters,
         request::short_url::shorten,
         request::short_url::retrieve,
@@ -175,8 +203,34 @@ use crate::{common::meta, handler::http::request};
             config::meta::alerts::Operator,
             config::meta::alerts::QueryType,
             config::meta::alerts::QueryCondition,
+            config::meta::alerts::anomaly::AnomalyDetection,
+            config::meta::alerts::anomaly::AnomalyMethod,
+            config::meta::alerts::anomaly::Seasonality,
+            config::meta::alerts::anomaly::AnomalyDirection,
+            config::meta::alerts::composite::CompositeCondition,
+            config::meta::alerts::composite::CompositeExpr,
+            config::meta::alerts::composite::CompositeState,
             config::meta::alerts::TriggerCondition,
+            config::meta::alerts::routing::NotificationPolicy,
+            config::meta::alerts::routing::Route,
+            config::meta::alerts::routing::LabelMatcher,
+            config::meta::alerts::routing::MatchOperator,
+            config::meta::alerts::routing::InhibitRule,
+            config::meta::alerts::silence::Silence,
+            config::meta::alerts::silence::SilenceSchedule,
+            config::meta::alerts::silence::SilenceList,
+            config::meta::alerts::delivery::DeliveryStatus,
+            config::meta::alerts::delivery::DeliveryAttempt,
+            config::meta::alerts::delivery::Delivery,
+            config::meta::alerts::delivery::DeliveryList,
+            config::meta::redaction::MaskingPolicy,
+            config::meta::redaction::RedactionParams,
+            config::meta::redaction::RedactionAction,
+            config::meta::redaction::BuiltinDetector,
+            config::meta::redaction::CustomDetector,
+            config::meta::redaction::Validator,
             config::meta::destinations::HTTPType,
+            config::meta::destinations::OutputFormat,
             config::meta::timed_annotations::TimedAnnotation,
             config::meta::timed_annotations::TimedAnnotationReq,
             config::meta::timed_annotations::TimedAnnotationDelete,
@@ -195,14 +249,27 @@ use crate::{common::meta, handler::http::request};
             crate::handler::http::models::destinations::Destination,
             crate::handler::http::models::destinations::DestinationType,
             crate::handler::http::models::destinations::Template,
+            crate::handler::http::models::destinations::TemplatePreview,
+            crate::handler::http::models::destinations::TemplatePreviewResponse,
             // Alerts
             crate::handler::http::models::alerts::requests::CreateAlertRequestBody,
             crate::handler::http::models::alerts::requests::UpdateAlertRequestBody,
             crate::handler::http::models::alerts::requests::MoveAlertsRequestBody,
+            crate::handler::http::models::alerts::requests::BacktestAlertRequestBody,
             crate::handler::http::models::alerts::responses::GetAlertResponseBody,
             crate::handler::http::models::alerts::responses::ListAlertsResponseBody,
             crate::handler::http::models::alerts::responses::ListAlertsResponseBodyItem,
             crate::handler::http::models::alerts::responses::EnableAlertResponseBody,
+            crate::handler::http::models::alerts::responses::GetAlertStateResponseBody,
+            crate::handler::http::models::alerts::responses::AlertGroupStateItem,
+            crate::handler::http::models::alerts::responses::BacktestAlertResponseBody,
+            crate::handler::http::models::alerts::responses::BacktestNotificationItem,
+            crate::handler::http::models::alerts::bundle::AlertsBundle,
+            crate::handler::http::models::alerts::bundle::BundleAlert,
+            crate::handler::http::models::alerts::bundle::ImportAlertsResponseBody,
+            crate::handler::http::models::alerts::bundle::BundleChangeItem,
+            config::meta::alerts::state::AlertState,
+            config::meta::alerts::state::AlertStateTransition,
             crate::handler::http::models::alerts::Alert,
             crate::handler::http::models::alerts::TriggerCondition,
             crate::handler::http::models::alerts::CompareHistoricData,
@@ -221,6 +288,9 @@ use crate::{common::meta, handler::http::request};
             crate::handler::http::models::folders::UpdateFolderRequestBody,
             crate::handler::http::models::folders::FolderType,
             config::meta::function::Transform,
+            config::meta::sql_macro::SqlMacro,
+            config::meta::sql_macro::SqlMacroList,
+            config::meta::sql_macro::SqlParamType,
             config::meta::function::FunctionList,
             config::meta::function::StreamOrder,
             config::meta::function::TestVRLRequest,
@@ -303,6 +373,7 @@ use crate::{common::meta, handler::http::request};
         (name = "Metrics", description = "Metrics data ingestion operations"),
         (name = "Traces", description = "Traces data ingestion operations"),
         (name = "Syslog Routes", description = "Syslog Routes retrieval & management operations"),
+        (name = "Masking Policies", description = "Masking of personal data in search results by role"),
         (name = "Clusters", description = "Super cluster operations"),
         (name = "Short Url", description = "Short Url Service"),
     ),
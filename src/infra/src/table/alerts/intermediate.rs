@@ -205,12 +205,14 @@ pub enum QueryType {
     Custom,
     Sql,
     Promql,
+    Composite,
 }
 
 impl QueryType {
     const CUSTOM: i16 = 0;
     const SQL: i16 = 1;
     const PROMQL: i16 = 2;
+    const COMPOSITE: i16 = 3;
 }
 
 impl From<QueryType> for i16 {
@@ -219,6 +221,7 @@ impl From<QueryType> for i16 {
             QueryType::Custom => QueryType::CUSTOM,
             QueryType::Sql => QueryType::SQL,
             QueryType::Promql => QueryType::PROMQL,
+            QueryType::Composite => QueryType::COMPOSITE,
         }
     }
 }
@@ -231,6 +234,7 @@ impl TryFrom<i16> for QueryType {
             Self::CUSTOM => Ok(QueryType::Custom),
             Self::SQL => Ok(QueryType::Sql),
             Self::PROMQL => Ok(QueryType::Promql),
+            Self::COMPOSITE => Ok(QueryType::Composite),
             _ => Err(FromI16Error {
                 value,
                 ty: "QueryType".to_string(),
@@ -245,6 +249,7 @@ impl From<MetaQueryType> for QueryType {
             MetaQueryType::Custom => QueryType::Custom,
             MetaQueryType::SQL => QueryType::Sql,
             MetaQueryType::PromQL => QueryType::Promql,
+            MetaQueryType::Composite => QueryType::Composite,
         }
     }
 }
@@ -255,6 +260,7 @@ impl From<QueryType> for MetaQueryType {
             QueryType::Custom => MetaQueryType::Custom,
             QueryType::Sql => MetaQueryType::SQL,
             QueryType::Promql => MetaQueryType::PromQL,
+            QueryType::Composite => MetaQueryType::Composite,
         }
     }
 }
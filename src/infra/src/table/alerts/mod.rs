@@ -124,6 +124,14 @@ impl TryFrom<alerts::Model> for MetaAlert {
             search_event_type: query_search_event_type.map(|t| t.into()),
             multi_time_range: query_multi_time_range
                 .map(|ds| ds.into_iter().map(|d| d.into()).collect()),
+            anomaly_detection: value
+                .query_anomaly_detection
+                .map(serde_json::from_value)
+                .transpose()?,
+            composite: value
+                .query_composite
+                .map(serde_json::from_value)
+                .transpose()?,
         };
         alert.trigger_condition = MetaTriggerCondition {
             // DB model stores period in seconds, but service layer stores
@@ -137,6 +145,8 @@ impl TryFrom<alerts::Model> for MetaAlert {
             silence: value.trigger_silence_seconds / 60,
             timezone: value.trigger_frequency_cron_timezone,
             tolerance_in_secs: value.trigger_tolerance_seconds,
+            for_duration: value.trigger_for_seconds,
+            notify_on_resolve: value.trigger_notify_on_resolve,
         };
         alert.set_last_satisfied_at(value.last_satisfied_at);
         alert.set_last_triggered_at(value.last_triggered_at);
@@ -629,6 +639,16 @@ fn update_mutable_fields(
         })
         .map(serde_json::to_value)
         .transpose()?;
+    let query_anomaly_detection = alert
+        .query_condition
+        .anomaly_detection
+        .map(serde_json::to_value)
+        .transpose()?;
+    let query_composite = alert
+        .query_condition
+        .composite
+        .map(serde_json::to_value)
+        .transpose()?;
     let trigger_threshold_operator: String =
         intermediate::TriggerThresholdOperator::try_from(alert.trigger_condition.operator)
             .map_err(|_| {
@@ -647,6 +667,8 @@ fn update_mutable_fields(
         alert.trigger_condition.timezone.filter(|s| !s.is_empty());
     let trigger_silence_seconds = alert.trigger_condition.silence * 60;
     let trigger_tolerance_seconds = alert.trigger_condition.tolerance_in_secs;
+    let trigger_for_seconds = alert.trigger_condition.for_duration;
+    let trigger_notify_on_resolve = alert.trigger_condition.notify_on_resolve;
     let owner = alert.owner.filter(|s| !s.is_empty());
     let last_edited_by = alert.last_edited_by.filter(|s| !s.is_empty());
     let updated_at: i64 = chrono::Utc::now().timestamp();
@@ -669,6 +691,8 @@ fn update_mutable_fields(
     alert_am.query_vrl_function = Set(query_vrl_function);
     alert_am.query_search_event_type = Set(query_search_event_type);
     alert_am.query_multi_time_range = Set(query_multi_time_range);
+    alert_am.query_anomaly_detection = Set(query_anomaly_detection);
+    alert_am.query_composite = Set(query_composite);
     alert_am.trigger_threshold_operator = Set(trigger_threshold_operator);
     alert_am.trigger_period_seconds = Set(trigger_period_seconds);
     alert_am.trigger_threshold_count = Set(trigger_threshold_count);
@@ -678,6 +702,8 @@ fn update_mutable_fields(
     alert_am.trigger_frequency_cron_timezone = Set(trigger_frequency_cron_timezone);
     alert_am.trigger_silence_seconds = Set(trigger_silence_seconds);
     alert_am.trigger_tolerance_seconds = Set(trigger_tolerance_seconds);
+    alert_am.trigger_for_seconds = Set(trigger_for_seconds);
+    alert_am.trigger_notify_on_resolve = Set(trigger_notify_on_resolve);
     alert_am.owner = Set(owner);
     alert_am.last_edited_by = Set(last_edited_by);
     alert_am.updated_at = Set(Some(updated_at));
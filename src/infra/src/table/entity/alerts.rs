@@ -30,6 +30,8 @@ pub struct Model {
     pub query_vrl_function: Option<String>,
     pub query_search_event_type: Option<i16>,
     pub query_multi_time_range: Option<Json>,
+    pub query_anomaly_detection: Option<Json>,
+    pub query_composite: Option<Json>,
     pub trigger_threshold_operator: String,
     pub trigger_period_seconds: i64,
     pub trigger_threshold_count: i64,
@@ -39,6 +41,8 @@ pub struct Model {
     pub trigger_frequency_cron_timezone: Option<String>,
     pub trigger_silence_seconds: i64,
     pub trigger_tolerance_seconds: Option<i64>,
+    pub trigger_for_seconds: i64,
+    pub trigger_notify_on_resolve: bool,
     pub owner: Option<String>,
     pub last_edited_by: Option<String>,
     pub updated_at: Option<i64>,
@@ -44,6 +44,9 @@ mod m20250125_133700_populate_destinations_table;
 mod m20250125_153005_delete_metas_destinations;
 mod m20250125_172300_delete_metas_templates;
 mod m20250213_000001_add_dashboard_updated_at;
+mod m20250301_000001_add_alert_lifecycle_columns;
+mod m20250315_000001_add_alert_anomaly_detection_column;
+mod m20250320_000001_add_alert_composite_column;
 
 pub struct Migrator;
 
@@ -79,6 +82,9 @@ impl MigratorTrait for Migrator {
             Box::new(m20250125_133700_populate_destinations_table::Migration),
             Box::new(m20250125_153005_delete_metas_destinations::Migration),
             Box::new(m20250213_000001_add_dashboard_updated_at::Migration),
+            Box::new(m20250301_000001_add_alert_lifecycle_columns::Migration),
+            Box::new(m20250315_000001_add_alert_anomaly_detection_column::Migration),
+            Box::new(m20250320_000001_add_alert_composite_column::Migration),
         ]
     }
 }
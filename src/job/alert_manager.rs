@@ -64,6 +64,9 @@ pub async fn run() -> Result<(), anyhow::Error> {
     tokio::task::spawn(async move { run_schedule_jobs().await });
     tokio::task::spawn(async move { clean_complete_jobs().await });
     tokio::task::spawn(async move { watch_timeout_jobs().await });
+    tokio::task::spawn(async move { run_notification_groups().await });
+    tokio::task::spawn(async move { run_notification_deliveries().await });
+    tokio::task::spawn(async move { run_pipeline_backfill().await });
     for i in 0..cfg.limit.search_job_workers {
         tokio::task::spawn(async move { run_search_jobs(i).await });
     }
@@ -109,6 +112,41 @@ async fn watch_timeout_jobs() -> Result<(), anyhow::Error> {
     }
 }
 
+/// Sends the due notifications of the notification policy groups
+async fn run_notification_groups() -> Result<(), anyhow::Error> {
+    let mut interval = time::interval(time::Duration::from_secs(1));
+    interval.tick().await; // trigger the first run
+    loop {
+        interval.tick().await;
+        service::alerts::notifier::flush(config::utils::time::now_micros()).await;
+    }
+}
+
+async fn run_notification_deliveries() -> Result<(), anyhow::Error> {
+    let mut interval = time::interval(time::Duration::from_secs(10));
+    interval.tick().await; // trigger the first run
+    loop {
+        interval.tick().await;
+        let now = config::utils::time::now_micros();
+        if let Err(e) = service::alerts::deliveries::process_queue(now).await {
+            log::error!("[ALERT MANAGER] retry notification deliveries error: {}", e);
+        }
+    }
+}
+
+/// Runs the pipeline backfill jobs, one at a time
+async fn run_pipeline_backfill() -> Result<(), anyhow::Error> {
+    let interval = get_config().pipeline.backfill_interval.max(1);
+    let mut interval = time::interval(time::Duration::from_secs(interval));
+    interval.tick().await; // trigger the first run
+    loop {
+        interval.tick().await;
+        if let Err(e) = service::pipeline::backfill::run().await {
+            log::error!("[PIPELINE BACKFILL] run backfill jobs error: {}", e);
+        }
+    }
+}
+
 #[cfg(feature = "enterprise")]
 async fn run_search_jobs(id: i64) -> Result<(), anyhow::Error> {
     let interval = get_config().limit.search_job_scheduler_interval;
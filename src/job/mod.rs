@@ -117,10 +117,14 @@ pub async fn init() -> Result<(), anyhow::Error> {
     // initialize metadata watcher
     tokio::task::spawn(async move { db::schema::watch().await });
     tokio::task::spawn(async move { db::functions::watch().await });
+    tokio::task::spawn(async move { db::sql_macros::watch().await });
+    tokio::task::spawn(async move { db::redaction::watch().await });
     tokio::task::spawn(async move { db::compact::retention::watch().await });
     tokio::task::spawn(async move { db::metrics::watch_prom_cluster_leader().await });
     tokio::task::spawn(async move { db::alerts::templates::watch().await });
     tokio::task::spawn(async move { db::alerts::destinations::watch().await });
+    tokio::task::spawn(async move { db::alerts::notification_policies::watch().await });
+    tokio::task::spawn(async move { db::alerts::silences::watch().await });
     tokio::task::spawn(async move { db::alerts::realtime_triggers::watch().await });
     tokio::task::spawn(async move { db::alerts::alert::watch().await });
     tokio::task::spawn(async move { db::dashboards::reports::watch().await });
@@ -144,6 +148,12 @@ pub async fn init() -> Result<(), anyhow::Error> {
     db::functions::cache()
         .await
         .expect("functions cache failed");
+    db::sql_macros::cache()
+        .await
+        .expect("sql macros cache failed");
+    db::redaction::cache()
+        .await
+        .expect("masking policies cache failed");
     db::compact::retention::cache()
         .await
         .expect("compact delete cache failed");
@@ -158,6 +168,12 @@ pub async fn init() -> Result<(), anyhow::Error> {
     db::alerts::destinations::cache()
         .await
         .expect("alerts destinations cache failed");
+    db::alerts::notification_policies::cache()
+        .await
+        .expect("alerts notification policies cache failed");
+    db::alerts::silences::cache()
+        .await
+        .expect("alerts silences cache failed");
     db::alerts::realtime_triggers::cache()
         .await
         .expect("alerts realtime triggers cache failed");
@@ -213,6 +229,11 @@ pub async fn init() -> Result<(), anyhow::Error> {
     tokio::task::spawn(
         async move { o2_enterprise::enterprise::pipeline::pipeline_job::run().await },
     );
+    // close the aggregation windows of pipelines left open by the lack of records
+    tokio::task::spawn(async move { crate::service::pipeline::aggregation::run().await });
+    // resume exporting to the remote destinations of pipelines
+    #[cfg(not(feature = "enterprise"))]
+    tokio::task::spawn(async move { crate::service::pipeline::remote_destination::run().await });
 
     #[cfg(feature = "enterprise")]
     o2_openfga::authorizer::authz::init_open_fga().await;
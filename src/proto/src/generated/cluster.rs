@@ -893,6 +893,12 @@ pub struct SearchQuery {
     pub skip_wal: bool,
     #[prost(string, tag = "15")]
     pub action_id: ::prost::alloc::string::String,
+    /// json encoded values of the sql parameters
+    #[prost(map = "string, string", tag = "16")]
+    pub params: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2618,6 +2624,20 @@ pub struct NewEmptyExecNode {
     #[prost(message, optional, tag = "7")]
     pub full_schema: ::core::option::Option<::datafusion_proto::protobuf::Schema>,
 }
+/// field numbers don't overlap with NewEmptyExecNode, so the composed codec
+/// never decodes one as the other
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnrichmentExecNode {
+    #[prost(string, tag = "11")]
+    pub org_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "12")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "13")]
+    pub schema: ::core::option::Option<::datafusion_proto::protobuf::Schema>,
+    #[prost(int64, tag = "14")]
+    pub version: i64,
+}
 /// Search request
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2662,6 +2682,9 @@ pub struct SearchInfo {
     pub end_time: i64,
     #[prost(int64, tag = "6")]
     pub timeout: i64,
+    /// for EXPLAIN ANALYZE
+    #[prost(bool, tag = "7")]
+    pub is_analyze: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -26,6 +26,7 @@ use config::{
         alerts::{
             FrequencyType, Operator, QueryType, TriggerEvalResults,
             alert::{Alert, AlertListFilter, ListAlertsParams},
+            state::AlertState,
         },
         destinations::{
             AwsSns, DestinationType, Email, Endpoint, HTTPType, Module, Template, TemplateType,
@@ -53,7 +54,11 @@ use crate::{
         utils::auth::{is_ofga_unsupported, remove_ownership, set_ownership},
     },
     service::{
-        alerts::{QueryConditionExt, build_sql, destinations},
+        alerts::{
+            QueryConditionExt, build_sql, composite,
+            deliveries::{self, Sent, StatusError},
+            destinations, integrations, templating,
+        },
         db, folders,
         search::sql::RE_ONLY_SELECT,
         short_url,
@@ -114,6 +119,27 @@ pub enum AlertError {
     #[error("Realtime alert should use Custom query type")]
     RealtimeMissingCustomQuery,
 
+    #[error("Realtime alert does not support for duration and resolved notifications")]
+    RealtimeStateful,
+
+    #[error("Invalid anomaly detection: {0}")]
+    AnomalyDetectionInvalid(String),
+
+    #[error("Invalid composite alert: {0}")]
+    CompositeInvalid(String),
+
+    #[error("Alert is used by the composite alerts: {0}")]
+    CompositeReferenced(String),
+
+    #[error("Invalid backtest: {0}")]
+    BacktestInvalid(String),
+
+    #[error("Backtest evaluation failed {0}")]
+    BacktestEvaluation(String),
+
+    #[error("Alert for duration can not be negative")]
+    NegativeForDuration,
+
     #[error("Alert with SQL mode should have a query")]
     SqlMissingQuery,
 
@@ -140,6 +166,12 @@ pub enum AlertError {
     #[error("Error resolving stream names in SQL query: {0}")]
     ResolveStreamNameError(#[source] anyhow::Error),
 
+    #[error("Invalid SQL macro in query: {0}")]
+    SqlMacroInvalid(String),
+
+    #[error("Invalid row template: {0}")]
+    RowTemplateInvalid(String),
+
     /// An error occured trying to get the list of permitted alerts in
     /// enterprise mode because no user_id was provided.
     #[error("user_id required to get permitted alerts in enterprise mode")]
@@ -262,6 +294,7 @@ async fn prepare_alert(
     if alert.name.contains('/') {
         return Err(AlertError::AlertNameContainsForwardSlash);
     }
+    templating::validate(&alert.row_template).map_err(AlertError::RowTemplateInvalid)?;
 
     if let Some(vrl) = alert.query_condition.vrl_function.as_ref() {
         match base64::decode_url(vrl) {
@@ -313,32 +346,52 @@ async fn prepare_alert(
         alert.context_attributes = Some(new_attrs);
     }
 
-    // before saving alert check column type to decide numeric condition
-    let schema = infra::schema::get(org_id, stream_name, stream_type).await?;
-    if stream_name.is_empty() || schema.fields().is_empty() {
-        return Err(AlertError::StreamNotFound {
-            stream_name: stream_name.to_owned(),
-        });
-    }
-
-    // Alerts must follow the max_query_range of the stream as set in the schema
-    if let Some(settings) = unwrap_stream_settings(&schema) {
-        let max_query_range = settings.max_query_range;
-        if max_query_range > 0
-            && !alert.is_real_time
-            && alert.trigger_condition.period > max_query_range * 60
-        {
-            return Err(AlertError::PeriodExceedsMaxQueryRange {
-                max_query_range_hours: max_query_range,
+    // Composite alerts don't query their stream, it only namespaces them
+    if alert.query_condition.query_type != QueryType::Composite {
+        // before saving alert check column type to decide numeric condition
+        let schema = infra::schema::get(org_id, stream_name, stream_type).await?;
+        if stream_name.is_empty() || schema.fields().is_empty() {
+            return Err(AlertError::StreamNotFound {
                 stream_name: stream_name.to_owned(),
             });
         }
+
+        // Alerts must follow the max_query_range of the stream as set in the schema
+        if let Some(settings) = unwrap_stream_settings(&schema) {
+            let max_query_range = settings.max_query_range;
+            if max_query_range > 0
+                && !alert.is_real_time
+                && alert.trigger_condition.period > max_query_range * 60
+            {
+                return Err(AlertError::PeriodExceedsMaxQueryRange {
+                    max_query_range_hours: max_query_range,
+                    stream_name: stream_name.to_owned(),
+                });
+            }
+        }
     }
 
     if alert.is_real_time && alert.query_condition.query_type != QueryType::Custom {
         return Err(AlertError::RealtimeMissingCustomQuery);
     }
 
+    if alert.trigger_condition.for_duration < 0 {
+        return Err(AlertError::NegativeForDuration);
+    }
+    if alert.is_real_time && alert.trigger_condition.is_stateful() {
+        return Err(AlertError::RealtimeStateful);
+    }
+    if let Some(detection) = alert.query_condition.anomaly_detection.as_ref() {
+        if alert.is_real_time {
+            return Err(AlertError::AnomalyDetectionInvalid(
+                "realtime alerts don't support anomaly detection".to_string(),
+            ));
+        }
+        detection
+            .validate(&alert.query_condition)
+            .map_err(AlertError::AnomalyDetectionInvalid)?;
+    }
+
     match alert.query_condition.query_type {
         QueryType::Custom => {
             if alert.query_condition.aggregation.is_some() {
@@ -360,6 +413,8 @@ async fn prepare_alert(
             }
 
             let sql = alert.query_condition.sql.as_ref().unwrap();
+            crate::service::sql_macros::validate(org_id, sql)
+                .map_err(AlertError::SqlMacroInvalid)?;
             let stream_names = match resolve_stream_names(sql) {
                 Ok(stream_names) => stream_names,
                 Err(e) => {
@@ -389,13 +444,15 @@ async fn prepare_alert(
             }
         }
         QueryType::PromQL => {
+            // without condition every series returned by the query matches, as
+            // for the rules of Prometheus
             if alert.query_condition.promql.is_none()
                 || alert.query_condition.promql.as_ref().unwrap().is_empty()
-                || alert.query_condition.promql_condition.is_none()
             {
                 return Err(AlertError::PromqlMissingQuery);
             }
         }
+        QueryType::Composite => composite::validate(alert).await?,
     }
 
     // Commented intentionally - in case the alert period is big and there
@@ -468,6 +525,9 @@ pub async fn update<C: ConnectionTrait + TransactionTrait>(
         }
     }
 
+    if !alert.enabled {
+        composite::ensure_not_referenced(org_id, alert.id).await?;
+    }
     let alert_name = alert.name.clone();
     let stream_name = alert.stream_name.clone();
     prepare_alert(org_id, &stream_name, &alert_name, &mut alert, false).await?;
@@ -576,6 +636,7 @@ pub async fn delete_by_id<C: ConnectionTrait>(
     let Some((_, alert)) = db::alerts::alert::get_by_id(conn, org_id, alert_id).await? else {
         return Ok(());
     };
+    composite::ensure_not_referenced(org_id, alert.id).await?;
 
     match db::alerts::alert::delete_by_id(conn, org_id, alert_id).await {
         Ok(_) => {
@@ -592,11 +653,12 @@ pub async fn delete_by_name(
     stream_name: &str,
     name: &str,
 ) -> Result<(), AlertError> {
-    if db::alerts::alert::get_by_name(org_id, stream_type, stream_name, name)
-        .await
-        .is_err()
-    {
-        return Err(AlertError::AlertNotFound);
+    let alert = match db::alerts::alert::get_by_name(org_id, stream_type, stream_name, name).await {
+        Ok(alert) => alert,
+        Err(_) => return Err(AlertError::AlertNotFound),
+    };
+    if let Some(alert) = alert {
+        composite::ensure_not_referenced(org_id, alert.id).await?;
     }
     match db::alerts::alert::delete_by_name(org_id, stream_type, stream_name, name).await {
         Ok(_) => {
@@ -636,6 +698,9 @@ pub async fn enable_by_name(
                 return Err(AlertError::AlertNotFound);
             }
         };
+    if !value {
+        composite::ensure_not_referenced(org_id, alert.id).await?;
+    }
     alert.enabled = value;
     db::alerts::alert::set(org_id, stream_type, stream_name, alert, false).await?;
     Ok(())
@@ -691,6 +756,14 @@ pub trait AlertExt: Sync + Send + 'static {
         start_time: Option<i64>,
         evaluation_timestamp: i64,
     ) -> Result<(String, String), AlertError>;
+
+    /// Notifies the destinations that the groups of a stateful alert are
+    /// resolved, `rows` holds the group by values of each resolved group.
+    async fn send_resolved_notification(
+        &self,
+        rows: &[Map<String, Value>],
+        evaluation_timestamp: i64,
+    ) -> Result<(String, String), AlertError>;
 }
 
 #[async_trait]
@@ -700,7 +773,9 @@ impl AlertExt for Alert {
         row: Option<&Map<String, Value>>,
         (start_time, end_time): (Option<i64>, i64),
     ) -> Result<TriggerEvalResults, anyhow::Error> {
-        if self.is_real_time {
+        if self.query_condition.query_type == QueryType::Composite {
+            composite::evaluate(self, end_time).await
+        } else if self.is_real_time {
             self.query_condition.evaluate_realtime(row).await
         } else {
             let search_event_ctx = SearchEventContext::with_alert(Some(format!(
@@ -728,117 +803,222 @@ impl AlertExt for Alert {
         start_time: Option<i64>,
         evaluation_timestamp: i64,
     ) -> Result<(String, String), AlertError> {
-        let mut err_message = "".to_string();
-        let mut success_message = "".to_string();
-        let mut no_of_error = 0;
-        for dest in self.destinations.iter() {
-            let (dest, template) = destinations::get_with_template(&self.org_id, dest).await?;
-            let Module::Alert {
-                destination_type, ..
-            } = dest.module
-            else {
-                return Err(AlertError::GetDestinationWithTemplateError(
-                    db::alerts::destinations::DestinationError::UnsupportedType,
-                ));
-            };
-            match send_notification(
-                self,
-                &destination_type,
-                &template,
-                rows,
-                rows_end_time,
-                start_time,
-                evaluation_timestamp,
-            )
-            .await
-            {
-                Ok(resp) => {
-                    success_message =
-                        format!("{success_message} destination {} {resp};", dest.name);
-                }
-                Err(e) => {
-                    log::error!(
-                        "Error sending notification for {}/{}/{}/{} for destination {} err: {}",
-                        self.org_id,
-                        self.stream_type,
-                        self.stream_name,
-                        self.name,
-                        dest.name,
-                        e
-                    );
-                    no_of_error += 1;
-                    err_message = format!(
-                        "{err_message} Error sending notification for destination {} err: {e};",
-                        dest.name
-                    );
-                }
+        notify_destinations(
+            self,
+            &self.destinations,
+            rows,
+            rows_end_time,
+            start_time,
+            evaluation_timestamp,
+            AlertState::Firing,
+        )
+        .await
+    }
+
+    async fn send_resolved_notification(
+        &self,
+        rows: &[Map<String, Value>],
+        evaluation_timestamp: i64,
+    ) -> Result<(String, String), AlertError> {
+        notify_destinations(
+            self,
+            &self.destinations,
+            rows,
+            evaluation_timestamp,
+            None,
+            evaluation_timestamp,
+            AlertState::Resolved,
+        )
+        .await
+    }
+}
+
+/// Sends the notification of the alert to the destinations, returns the
+/// responses and the errors of the destinations, or an error when none of
+/// them succeeded
+pub(super) async fn notify_destinations(
+    alert: &Alert,
+    dest_names: &[String],
+    rows: &[Map<String, Value>],
+    rows_end_time: i64,
+    start_time: Option<i64>,
+    evaluation_timestamp: i64,
+    alert_status: AlertState,
+) -> Result<(String, String), AlertError> {
+    let mut err_message = "".to_string();
+    let mut success_message = "".to_string();
+    let mut no_of_error = 0;
+    for dest in dest_names.iter() {
+        let (dest, template) = destinations::get_with_template(&alert.org_id, dest).await?;
+        let Module::Alert {
+            destination_type, ..
+        } = dest.module
+        else {
+            return Err(AlertError::GetDestinationWithTemplateError(
+                db::alerts::destinations::DestinationError::UnsupportedType,
+            ));
+        };
+        match send_notification(
+            alert,
+            &dest.name,
+            &destination_type,
+            &template,
+            rows,
+            rows_end_time,
+            start_time,
+            evaluation_timestamp,
+            alert_status,
+        )
+        .await
+        {
+            Ok(resp) => {
+                success_message = format!("{success_message} destination {} {resp};", dest.name);
+            }
+            Err(e) => {
+                log::error!(
+                    "Error sending notification for {}/{}/{}/{} for destination {} err: {}",
+                    alert.org_id,
+                    alert.stream_type,
+                    alert.stream_name,
+                    alert.name,
+                    dest.name,
+                    e
+                );
+                no_of_error += 1;
+                err_message = format!(
+                    "{err_message} Error sending notification for destination {} err: {e};",
+                    dest.name
+                );
             }
-        }
-        if no_of_error == self.destinations.len() {
-            Err(AlertError::SendNotificationError {
-                error_message: err_message,
-            })
-        } else {
-            Ok((success_message, err_message))
         }
     }
+    if no_of_error == dest_names.len() {
+        Err(AlertError::SendNotificationError {
+            error_message: err_message,
+        })
+    } else {
+        Ok((success_message, err_message))
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn send_notification(
     alert: &Alert,
+    dest_name: &str,
     dest_type: &DestinationType,
     template: &Template,
     rows: &[Map<String, Value>],
     rows_end_time: i64,
     start_time: Option<i64>,
     evaluation_timestamp: i64,
+    alert_status: AlertState,
 ) -> Result<String, anyhow::Error> {
-    let rows_tpl_val = if alert.row_template.is_empty() {
-        vec!["".to_string()]
-    } else {
-        process_row_template(&alert.row_template, alert, rows)
-    };
     let is_email = matches!(dest_type, DestinationType::Email(_));
-    let msg: String = process_dest_template(
-        &template.body,
+    let (email_subject, msg) = render_template(
         alert,
+        template,
         rows,
-        &rows_tpl_val,
         ProcessTemplateOptions {
             rows_end_time,
             start_time,
             evaluation_timestamp,
             is_email,
+            alert_status,
+            shorten_url: true,
         },
     )
-    .await;
-
-    let email_subject = if let TemplateType::Email { title } = &template.template_type {
-        process_dest_template(
-            title,
-            alert,
-            rows,
-            &rows_tpl_val,
-            ProcessTemplateOptions {
-                rows_end_time,
-                start_time,
-                evaluation_timestamp,
-                is_email,
-            },
-        )
-        .await
-    } else {
-        template.name.clone()
-    };
+    .await?;
+    deliveries::deliver(
+        alert,
+        dest_name,
+        dest_type,
+        rows,
+        email_subject,
+        msg,
+        alert_status,
+    )
+    .await
+}
 
+/// Sends the rendered notification to the destination, a single attempt
+/// without any wait: a failed attempt is retried by the delivery queue, after
+/// its backoff or the `Retry-After` of the destination
+pub(super) async fn dispatch(
+    alert: &Alert,
+    dest_type: &DestinationType,
+    rows: &[Map<String, Value>],
+    email_subject: &str,
+    msg: String,
+    alert_status: AlertState,
+) -> Result<Sent, anyhow::Error> {
     match dest_type {
         DestinationType::Http(endpoint) => send_http_notification(endpoint, msg).await,
-        DestinationType::Email(email) => send_email_notification(&email_subject, email, msg).await,
+        DestinationType::Email(email) => send_email_notification(email_subject, email, msg).await,
         DestinationType::Sns(aws_sns) => send_sns_notification(&alert.name, aws_sns, msg).await,
+        DestinationType::Slack(slack) => {
+            integrations::send_slack(slack, alert, &msg, alert_status).await
+        }
+        DestinationType::Teams(teams) => {
+            integrations::send_teams(teams, alert, &msg, alert_status).await
+        }
+        DestinationType::PagerDuty(pagerduty) => {
+            integrations::send_pagerduty(pagerduty, alert, rows, &msg, alert_status).await
+        }
+        DestinationType::Opsgenie(opsgenie) => {
+            integrations::send_opsgenie(opsgenie, alert, rows, &msg, alert_status).await
+        }
     }
 }
 
-async fn send_http_notification(endpoint: &Endpoint, msg: String) -> Result<String, anyhow::Error> {
+/// Renders the title and the body of the notification, the title is the name
+/// of the template unless it is an email template
+async fn render_template(
+    alert: &Alert,
+    template: &Template,
+    rows: &[Map<String, Value>],
+    options: ProcessTemplateOptions,
+) -> Result<(String, String), anyhow::Error> {
+    let rows_tpl_val = if alert.row_template.is_empty() {
+        vec!["".to_string()]
+    } else {
+        process_row_template(&alert.row_template, alert, rows, options.alert_status)?
+    };
+    let msg = process_dest_template(&template.body, alert, rows, &rows_tpl_val, options).await?;
+    let title = if let TemplateType::Email { title } = &template.template_type {
+        process_dest_template(title, alert, rows, &rows_tpl_val, options).await?
+    } else {
+        template.name.clone()
+    };
+    Ok((title, msg))
+}
+
+/// Renders the template for the sample rows of the alert like a notification,
+/// returns the title and the body
+pub async fn preview_template(
+    alert: &Alert,
+    template: &Template,
+    rows: &[Map<String, Value>],
+    alert_status: AlertState,
+) -> Result<(String, String), anyhow::Error> {
+    let now = Utc::now().timestamp_micros();
+    render_template(
+        alert,
+        template,
+        rows,
+        ProcessTemplateOptions {
+            rows_end_time: now,
+            start_time: None,
+            evaluation_timestamp: now,
+            is_email: matches!(template.template_type, TemplateType::Email { .. }),
+            alert_status,
+            // previews don't need a short url
+            shorten_url: false,
+        },
+    )
+    .await
+}
+
+async fn send_http_notification(endpoint: &Endpoint, msg: String) -> Result<Sent, anyhow::Error> {
     let client = if endpoint.skip_tls_verify {
         reqwest::Client::builder()
             .danger_accept_invalid_certs(true)
@@ -869,38 +1049,45 @@ async fn send_http_notification(endpoint: &Endpoint, msg: String) -> Result<Stri
     if !has_context_type {
         req = req.header("Content-type", "application/json");
     }
+    if let Some(secret) = endpoint.signing_secret.as_deref() {
+        let timestamp = Utc::now().timestamp();
+        req = req.header(
+            integrations::SIGNATURE_HEADER,
+            integrations::signature(secret, timestamp, &msg),
+        );
+    }
 
     let resp = req.body(msg.clone()).send().await?;
     let resp_status = resp.status();
-    let resp_body = resp.text().await?;
-    log::debug!(
-        "Alert sent to destination {} with status: {}, body: {:?}",
-        endpoint.url,
-        resp_status,
-        resp_body,
-    );
     if !resp_status.is_success() {
+        let err = StatusError::from_response(resp).await;
         log::error!(
             "Alert http notification failed with status: {}, body: {}, payload: {}",
             resp_status,
-            resp_body,
+            err.body,
             msg
         );
-        return Err(anyhow::anyhow!(
-            "sent error status: {}, err: {}",
-            resp_status,
-            resp_body
-        ));
+        return Err(err.into());
     }
+    let resp_body = resp.text().await?;
+    log::debug!(
+        "Alert sent to destination {} with status: {}, body: {:?}",
+        endpoint.url,
+        resp_status,
+        resp_body,
+    );
 
-    Ok(format!("sent status: {}, body: {}", resp_status, resp_body))
+    Ok(Sent {
+        status_code: Some(resp_status.as_u16()),
+        response: format!("sent status: {}, body: {}", resp_status, resp_body),
+    })
 }
 
 async fn send_email_notification(
     email_subject: &str,
     email: &Email,
     msg: String,
-) -> Result<String, anyhow::Error> {
+) -> Result<Sent, anyhow::Error> {
     let cfg = get_config();
     if !cfg.smtp.smtp_enabled {
         return Err(anyhow::anyhow!("SMTP configuration not enabled"));
@@ -925,7 +1112,10 @@ async fn send_email_notification(
 
     // Send the email
     match SMTP_CLIENT.as_ref().unwrap().send(email).await {
-        Ok(resp) => Ok(format!("sent email response code: {}", resp.code())),
+        Ok(resp) => Ok(Sent {
+            status_code: None,
+            response: format!("sent email response code: {}", resp.code()),
+        }),
         Err(e) => Err(anyhow::anyhow!("Error sending email: {e}")),
     }
 }
@@ -934,7 +1124,7 @@ async fn send_sns_notification(
     alert_name: &str,
     aws_sns: &AwsSns,
     msg: String,
-) -> Result<String, anyhow::Error> {
+) -> Result<Sent, anyhow::Error> {
     let mut message_attributes = HashMap::new();
     message_attributes.insert(
         "AlertName".to_string(),
@@ -953,16 +1143,35 @@ async fn send_sns_notification(
         .send()
         .await;
     match ret {
-        Ok(resp) => Ok(format!(
-            "sent SNS response message_id: {:?}, sequence_number: {:?}",
-            resp.message_id(),
-            resp.sequence_number()
-        )),
+        Ok(resp) => Ok(Sent {
+            status_code: None,
+            response: format!(
+                "sent SNS response message_id: {:?}, sequence_number: {:?}",
+                resp.message_id(),
+                resp.sequence_number()
+            ),
+        }),
         Err(e) => Err(anyhow::anyhow!("Error sending SNS notification: {e}")),
     }
 }
 
-fn process_row_template(tpl: &String, alert: &Alert, rows: &[Map<String, Value>]) -> Vec<String> {
+fn process_row_template(
+    tpl: &String,
+    alert: &Alert,
+    rows: &[Map<String, Value>],
+    alert_status: AlertState,
+) -> Result<Vec<String>, anyhow::Error> {
+    if templating::is_template(tpl) {
+        return rows
+            .iter()
+            .map(|row| {
+                let mut ctx = alert_context(alert, alert_status, rows.len());
+                ctx.extend(row.clone());
+                ctx.insert("row".to_string(), Value::Object(row.clone()));
+                templating::render(tpl, &ctx)
+            })
+            .collect();
+    }
     let alert_type = if alert.is_real_time {
         "realtime"
     } else {
@@ -1043,6 +1252,7 @@ fn process_row_template(tpl: &String, alert: &Alert, rows: &[Map<String, Value>]
                 &alert.trigger_condition.threshold.to_string(),
             )
             .replace("{alert_count}", &alert_count.to_string())
+            .replace("{alert_status}", &alert_status.to_string())
             .replace("{alert_start_time}", &alert_start_time_str)
             .replace("{alert_end_time}", &alert_end_time_str);
 
@@ -1061,14 +1271,63 @@ fn process_row_template(tpl: &String, alert: &Alert, rows: &[Map<String, Value>]
         rows_tpl.push(resp);
     }
 
-    rows_tpl
+    Ok(rows_tpl)
+}
+
+/// Variables of the alert in the templates using the template language
+fn alert_context(
+    alert: &Alert,
+    alert_status: AlertState,
+    alert_count: usize,
+) -> Map<String, Value> {
+    let mut ctx = Map::new();
+    if let Some(attrs) = &alert.context_attributes {
+        for (key, value) in attrs.iter() {
+            ctx.insert(key.to_string(), Value::String(value.to_string()));
+        }
+    }
+    let alert_type = if alert.is_real_time {
+        "realtime"
+    } else {
+        "scheduled"
+    };
+    ctx.insert("org_name".to_string(), alert.org_id.clone().into());
+    ctx.insert("stream_type".to_string(), alert.stream_type.as_str().into());
+    ctx.insert("stream_name".to_string(), alert.stream_name.clone().into());
+    ctx.insert("alert_name".to_string(), alert.name.clone().into());
+    ctx.insert("alert_type".to_string(), alert_type.into());
+    ctx.insert(
+        "alert_period".to_string(),
+        alert.trigger_condition.period.into(),
+    );
+    ctx.insert(
+        "alert_operator".to_string(),
+        alert.trigger_condition.operator.to_string().into(),
+    );
+    ctx.insert(
+        "alert_threshold".to_string(),
+        alert.trigger_condition.threshold.into(),
+    );
+    ctx.insert("alert_count".to_string(), alert_count.into());
+    ctx.insert("alert_status".to_string(), alert_status.to_string().into());
+    if let Some(condition) = &alert.query_condition.promql_condition {
+        ctx.insert(
+            "alert_promql_operator".to_string(),
+            condition.operator.to_string().into(),
+        );
+        ctx.insert("alert_promql_value".to_string(), condition.value.clone());
+    }
+    ctx
 }
 
+#[derive(Clone, Copy)]
 struct ProcessTemplateOptions {
     pub rows_end_time: i64,
     pub start_time: Option<i64>,
     pub evaluation_timestamp: i64,
     pub is_email: bool,
+    pub alert_status: AlertState,
+    pub shorten_url: bool,
 }
 
 async fn process_dest_template(
@@ -1077,13 +1336,15 @@ async fn process_dest_template(
     rows: &[Map<String, Value>],
     rows_tpl_val: &[String],
     options: ProcessTemplateOptions,
-) -> String {
+) -> Result<String, anyhow::Error> {
     let cfg = get_config();
     let ProcessTemplateOptions {
         rows_end_time,
         start_time,
         evaluation_timestamp,
         is_email,
+        alert_status,
+        shorten_url,
     } = options;
     // format values
     let alert_count = rows.len();
@@ -1166,16 +1427,18 @@ async fn process_dest_template(
     };
     let alert_url = if alert.query_condition.query_type == QueryType::PromQL {
         if let Some(promql) = &alert.query_condition.promql {
-            let condition = alert.query_condition.promql_condition.as_ref().unwrap();
-            alert_query = format!(
-                "({}) {} {}",
-                promql,
-                match condition.operator {
-                    Operator::EqualTo => "==".to_string(),
-                    _ => condition.operator.to_string(),
-                },
-                to_float(&condition.value)
-            );
+            alert_query = match alert.query_condition.promql_condition.as_ref() {
+                Some(condition) => format!(
+                    "({}) {} {}",
+                    promql,
+                    match condition.operator {
+                        Operator::EqualTo => "==".to_string(),
+                        _ => condition.operator.to_string(),
+                    },
+                    to_float(&condition.value)
+                ),
+                None => promql.clone(),
+            };
         }
         // http://localhost:5080/web/metrics?stream=zo_http_response_time_bucket&from=1705248000000000&to=1705334340000000&query=em9faHR0cF9yZXNwb25zZV90aW1lX2J1Y2tldHt9&org_identifier=default
         format!(
@@ -1214,7 +1477,10 @@ async fn process_dest_template(
                     }
                 }
             }
-            _ => unreachable!(),
+            // composite alerts don't query a stream, the link only opens
+            // the stream the alert is saved under
+            QueryType::Composite => {}
+            QueryType::PromQL => unreachable!(),
         };
         // http://localhost:5080/web/logs?stream_type=logs&stream=test&from=1708416534519324&to=1708416597898186&sql_mode=true&query=U0VMRUNUICogRlJPTSAidGVzdCIgd2hlcmUgbGV2ZWwgPSAnaW5mbyc=&org_identifier=default
         format!(
@@ -1234,14 +1500,49 @@ async fn process_dest_template(
     };
 
     // Shorten the alert url
-    let alert_url = match short_url::shorten(&alert.org_id, &alert_url).await {
-        Ok(short_url) => short_url,
-        Err(e) => {
-            log::error!("Error shortening alert url: {e}");
-            alert_url
+    let alert_url = if !shorten_url {
+        alert_url
+    } else {
+        match short_url::shorten(&alert.org_id, &alert_url).await {
+            Ok(short_url) => short_url,
+            Err(e) => {
+                log::error!("Error shortening alert url: {e}");
+                alert_url
+            }
         }
     };
 
+    if templating::is_template(tpl) {
+        let mut ctx = Map::new();
+        // like the `{column}` placeholders, the columns hold the distinct values of the rows
+        for (key, value) in vars.iter() {
+            ctx.insert(key.to_string(), Value::String(value.iter().join(", ")));
+        }
+        ctx.extend(alert_context(alert, alert_status, alert_count));
+        ctx.insert("alert_start_time".to_string(), alert_start_time_str.into());
+        ctx.insert("alert_end_time".to_string(), alert_end_time_str.into());
+        ctx.insert("alert_start_timestamp".to_string(), alert_start_time.into());
+        ctx.insert("alert_end_timestamp".to_string(), alert_end_time.into());
+        ctx.insert("alert_url".to_string(), alert_url.into());
+        ctx.insert(
+            "alert_trigger_time".to_string(),
+            evaluation_timestamp.into(),
+        );
+        ctx.insert(
+            "alert_trigger_time_str".to_string(),
+            evaluation_timestamp_str.into(),
+        );
+        ctx.insert(
+            "rows".to_string(),
+            Value::Array(rows.iter().cloned().map(Value::Object).collect()),
+        );
+        ctx.insert(
+            "row_texts".to_string(),
+            Value::Array(rows_tpl_val.iter().cloned().map(Value::String).collect()),
+        );
+        return templating::render(tpl, &ctx);
+    }
+
     let mut resp = tpl
         .replace("{org_name}", &alert.org_id)
         .replace("{stream_type}", alert.stream_type.as_str())
@@ -1261,6 +1562,7 @@ async fn process_dest_template(
             &alert.trigger_condition.threshold.to_string(),
         )
         .replace("{alert_count}", &alert_count.to_string())
+        .replace("{alert_status}", &alert_status.to_string())
         .replace("{alert_start_time}", &alert_start_time_str)
         .replace("{alert_end_time}", &alert_end_time_str)
         .replace("{alert_url}", &alert_url)
@@ -1286,7 +1588,7 @@ async fn process_dest_template(
         }
     }
 
-    resp
+    Ok(resp)
 }
 
 fn process_variable_replace(tpl: &mut String, var_name: &str, var_val: &VarValue, is_email: bool) {
@@ -0,0 +1,359 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Anomaly detection of the scheduled alerts.
+//!
+//! Every group of an anomaly alert has a baseline per season, the hour of
+//! the day or the hour of the week of the evaluation. The value of the group
+//! is compared with the baseline of the current season, then learnt: the
+//! values of the evaluations of an occurrence of the season, one hour, are
+//! reduced to their median once the hour is over, so an alert evaluated every
+//! minute keeps a single sample per occurrence. The model is saved apart from
+//! the trigger and updated incrementally by every evaluation.
+
+use chrono::{DateTime, Datelike, FixedOffset, Timelike};
+use config::{
+    meta::alerts::{
+        alert::Alert,
+        anomaly::{
+            AnomalyDetection, AnomalyDirection, AnomalyMethod, AnomalyModel, GroupBaseline,
+            Occurrence, Seasonality,
+        },
+        state::group_of,
+    },
+    utils::{
+        json::{Map, Value},
+        time::{day_micros, hour_micros},
+    },
+};
+
+use crate::service::promql::{functions::holt_winters_calculation, value::Sample};
+
+/// Scale of the median absolute deviation to the standard deviation of a
+/// normal distribution
+const MAD_SCALE: f64 = 1.4826;
+/// Lower bound of the sigma, relative to the baseline, a constant season
+/// would turn every change into an anomaly otherwise
+const MIN_RELATIVE_SIGMA: f64 = 0.01;
+const HW_SMOOTHING_FACTOR: f64 = 0.3;
+const HW_TREND_FACTOR: f64 = 0.1;
+/// Groups not seen for this many days are forgotten
+const GROUP_TTL_DAYS: i64 = 14;
+/// Upper bound of the groups of an alert, the least recently seen are
+/// forgotten first
+const MAX_GROUPS: usize = 1000;
+/// Upper bound of the values kept for the current occurrence, every other
+/// value is dropped beyond it
+const MAX_OCCURRENCE_VALUES: usize = 16;
+
+/// Applies the anomaly detection of the alert to the rows of an evaluation at
+/// `now`, returns the anomalies, or the rows as they are when the alert has no
+/// anomaly detection
+pub fn apply(
+    alert: &Alert,
+    model: &mut AnomalyModel,
+    rows: Option<Vec<Map<String, Value>>>,
+    now: i64,
+) -> Option<Vec<Map<String, Value>>> {
+    let Some(detection) = alert.query_condition.anomaly_detection.as_ref() else {
+        return rows;
+    };
+    let rows = rows.unwrap_or_default();
+    let group_by = alert.get_group_by_fields(rows.first());
+    let anomalies = detect(
+        detection,
+        model,
+        detection.value_column(&alert.query_condition.query_type),
+        rows,
+        &group_by,
+        now,
+        alert.tz_offset,
+    );
+    Some(anomalies).filter(|rows| !rows.is_empty())
+}
+
+/// Scores the value of every row against the baseline of its group and
+/// learns it, returns the rows which are anomalies, with their score and the
+/// bounds of the baseline.
+pub fn detect(
+    detection: &AnomalyDetection,
+    model: &mut AnomalyModel,
+    value_column: &str,
+    rows: Vec<Map<String, Value>>,
+    group_by: &[String],
+    now: i64,
+    tz_offset: i32,
+) -> Vec<Map<String, Value>> {
+    let season = season_of(detection.seasonality, now, tz_offset);
+    let hour = now.div_euclid(hour_micros(1));
+    let mut anomalies = Vec::new();
+    for mut row in rows {
+        let Some(value) = row.get(value_column).and_then(to_f64) else {
+            continue;
+        };
+        let (key, _) = group_of(&row, group_by);
+        let group = model.groups.entry(key).or_default();
+        group.last_seen = now;
+        if group.current.as_ref().is_some_and(|c| c.hour != hour) {
+            close_occurrence(group, detection.max_samples);
+        }
+        let samples = group
+            .seasons
+            .get(&season)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        if samples.len() >= detection.min_samples {
+            let (baseline, sigma) = estimate(detection.method, samples);
+            let score = (value - baseline) / sigma;
+            let is_anomaly = match detection.direction {
+                AnomalyDirection::Both => score.abs() > detection.sensitivity,
+                AnomalyDirection::Above => score > detection.sensitivity,
+                AnomalyDirection::Below => score < -detection.sensitivity,
+            };
+            if is_anomaly {
+                let band = detection.sensitivity * sigma;
+                row.insert("_anomaly_score".to_string(), round(score).into());
+                row.insert("_anomaly_baseline".to_string(), round(baseline).into());
+                row.insert("_anomaly_lower".to_string(), round(baseline - band).into());
+                row.insert("_anomaly_upper".to_string(), round(baseline + band).into());
+                anomalies.push(row);
+            }
+        }
+        let current = group.current.get_or_insert_with(|| Occurrence {
+            season,
+            hour,
+            values: vec![],
+        });
+        if current.values.len() >= MAX_OCCURRENCE_VALUES {
+            current.values = current.values.iter().step_by(2).copied().collect();
+        }
+        current.values.push(value as f32);
+    }
+    forget_groups(model, now);
+    anomalies
+}
+
+/// The median of the values of the current occurrence becomes a sample of its
+/// season
+fn close_occurrence(group: &mut GroupBaseline, max_samples: usize) {
+    let Some(occurrence) = group.current.take() else {
+        return;
+    };
+    let values = occurrence.values.iter().map(|v| *v as f64).collect();
+    let samples = group.seasons.entry(occurrence.season).or_default();
+    samples.push(median(values) as f32);
+    if samples.len() > max_samples {
+        let excess = samples.len() - max_samples;
+        samples.drain(..excess);
+    }
+}
+
+/// Season of the evaluation at `now` (microseconds), in the timezone of the
+/// alert (minutes)
+fn season_of(seasonality: Seasonality, now: i64, tz_offset: i32) -> u32 {
+    let Some(time) = FixedOffset::east_opt(tz_offset * 60)
+        .and_then(|tz| DateTime::from_timestamp_micros(now).map(|t| t.with_timezone(&tz)))
+    else {
+        return 0;
+    };
+    match seasonality {
+        Seasonality::None => 0,
+        Seasonality::HourOfDay => time.hour(),
+        Seasonality::HourOfWeek => time.weekday().num_days_from_sunday() * 24 + time.hour(),
+    }
+}
+
+/// Returns the baseline of the season and its sigma
+fn estimate(method: AnomalyMethod, samples: &[f32]) -> (f64, f64) {
+    let samples = samples.iter().map(|v| *v as f64).collect::<Vec<_>>();
+    let center = median(samples.clone());
+    let mad = median(samples.iter().map(|v| (v - center).abs()).collect());
+    let baseline = match method {
+        AnomalyMethod::MedianMad => center,
+        AnomalyMethod::HoltWinters => {
+            let samples = samples
+                .iter()
+                .enumerate()
+                .map(|(i, v)| Sample::new(i as i64, *v))
+                .collect::<Vec<_>>();
+            holt_winters_calculation(&samples, HW_SMOOTHING_FACTOR, HW_TREND_FACTOR)
+                .unwrap_or(center)
+        }
+    };
+    let sigma = (MAD_SCALE * mad)
+        .max(baseline.abs() * MIN_RELATIVE_SIGMA)
+        .max(f64::EPSILON);
+    (baseline, sigma)
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+fn forget_groups(model: &mut AnomalyModel, now: i64) {
+    model
+        .groups
+        .retain(|_, group| now - group.last_seen < day_micros(GROUP_TTL_DAYS));
+    if model.groups.len() > MAX_GROUPS {
+        let mut last_seen = model
+            .groups
+            .values()
+            .map(|group| group.last_seen)
+            .collect::<Vec<_>>();
+        last_seen.sort_unstable_by(|a, b| b.cmp(a));
+        let oldest_kept = last_seen[MAX_GROUPS - 1];
+        model
+            .groups
+            .retain(|_, group| group.last_seen >= oldest_kept);
+    }
+}
+
+fn to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(v) => v.as_f64(),
+        Value::String(v) => v.trim().parse().ok(),
+        _ => None,
+    }
+    .filter(|v: &f64| v.is_finite())
+}
+
+fn round(v: f64) -> f64 {
+    (v * 1000.0).round() / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use config::utils::json;
+
+    use super::*;
+
+    fn row(host: &str, value: f64) -> Map<String, Value> {
+        json::from_str(&format!(
+            r#"{{"host": "{host}", "alert_agg_value": {value}}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_season_of() {
+        // 2025-03-02 is a Sunday
+        let now = DateTime::parse_from_rfc3339("2025-03-02T23:30:00Z")
+            .unwrap()
+            .timestamp_micros();
+        assert_eq!(season_of(Seasonality::None, now, 0), 0);
+        assert_eq!(season_of(Seasonality::HourOfDay, now, 0), 23);
+        assert_eq!(season_of(Seasonality::HourOfWeek, now, 0), 23);
+        // Monday 01:30 at UTC+2
+        assert_eq!(season_of(Seasonality::HourOfWeek, now, 120), 25);
+    }
+
+    #[test]
+    fn test_estimate() {
+        let samples = [10.0, 11.0, 9.0, 10.0, 100.0];
+        let (baseline, sigma) = estimate(AnomalyMethod::MedianMad, &samples);
+        assert_eq!(baseline, 10.0);
+        assert!((sigma - MAD_SCALE).abs() < 1e-9);
+        let (baseline, _) = estimate(AnomalyMethod::MedianMad, &[0.0, 0.0, 0.0]);
+        assert_eq!(baseline, 0.0);
+        let (baseline, _) = estimate(AnomalyMethod::HoltWinters, &[1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert!(baseline > 3.0);
+    }
+
+    #[test]
+    fn test_detect() {
+        let detection = AnomalyDetection {
+            seasonality: Seasonality::None,
+            min_samples: 3,
+            max_samples: 4,
+            ..Default::default()
+        };
+        let group_by = vec!["host".to_string()];
+        let mut model = AnomalyModel::default();
+        let mut evaluate = |detection: &AnomalyDetection, rows, now| {
+            detect(
+                detection,
+                &mut model,
+                "alert_agg_value",
+                rows,
+                &group_by,
+                now,
+                0,
+            )
+        };
+        // learning, the evaluations of an hour are a single sample
+        for (hour, v) in [10.0, 12.0, 11.0].into_iter().enumerate() {
+            for minute in 0..3 {
+                let now = hour_micros(hour as i64) + minute * 60_000_000;
+                let rows = vec![row("a", v + minute as f64), row("b", v * 10.0)];
+                assert!(evaluate(&detection, rows, now).is_empty());
+            }
+        }
+        let rows = vec![row("a", 50.0), row("b", 115.0)];
+        let anomalies = evaluate(&detection, rows, hour_micros(3));
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0]["host"], "a");
+        assert!(anomalies[0]["_anomaly_score"].as_f64().unwrap() > 3.0);
+
+        // below the baseline is ignored when only values above are anomalies
+        let detection = AnomalyDetection {
+            direction: AnomalyDirection::Above,
+            ..detection
+        };
+        let rows = vec![row("a", -50.0)];
+        assert!(evaluate(&detection, rows, hour_micros(4)).is_empty());
+        evaluate(&detection, vec![row("a", 1.0)], hour_micros(5));
+        assert_eq!(model.groups.len(), 2);
+        assert_eq!(
+            model.groups["host=a"].seasons[&0],
+            vec![13.0, 12.0, 50.0, -50.0]
+        );
+
+        // groups which are not seen anymore are forgotten
+        detect(
+            &detection,
+            &mut model,
+            "alert_agg_value",
+            vec![row("a", 1.0)],
+            &group_by,
+            day_micros(GROUP_TTL_DAYS) + hour_micros(3),
+            0,
+        );
+        assert_eq!(model.groups.len(), 1);
+    }
+
+    #[test]
+    fn test_occurrence_values() {
+        let mut group = GroupBaseline {
+            current: Some(Occurrence {
+                season: 1,
+                hour: 0,
+                values: vec![1.0, 5.0, 3.0],
+            }),
+            ..Default::default()
+        };
+        close_occurrence(&mut group, 2);
+        assert_eq!(group.seasons[&1], vec![3.0]);
+        assert!(group.current.is_none());
+    }
+}
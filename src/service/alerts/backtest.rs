@@ -0,0 +1,301 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Backtesting of the scheduled alerts: replays the evaluations the scheduler
+//! would have run over a past time range and returns the notifications the
+//! alert would have sent.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset};
+use config::{
+    get_config,
+    meta::alerts::{
+        FrequencyType, QueryType, TriggerCondition,
+        alert::Alert,
+        anomaly::AnomalyModel,
+        state::{AlertState, AlertStates, group_of},
+    },
+    utils::{
+        json::{Map, Value},
+        time::{now_micros, second_micros},
+    },
+};
+use cron::Schedule;
+
+use super::{
+    alert::{AlertError, AlertExt},
+    anomaly,
+};
+
+/// Upper bound of the evaluations of a backtest
+const MAX_EVALUATIONS: usize = 1000;
+
+#[derive(Debug, Default)]
+pub struct Backtest {
+    pub evaluations: usize,
+    pub notifications: Vec<BacktestNotification>,
+}
+
+/// A notification the alert would have sent
+#[derive(Debug)]
+pub struct BacktestNotification {
+    /// (microseconds) when the scheduler would have evaluated the alert
+    pub timestamp: i64,
+    /// (microseconds) time range of the evaluation
+    pub start_time: i64,
+    pub end_time: i64,
+    pub status: AlertState,
+    pub rows: Vec<Map<String, Value>>,
+}
+
+/// Replays the evaluations of the alert between `start_time` and `end_time`
+/// (microseconds). The evaluations follow the frequency or the cron
+/// expression of the alert, skip the silence period after a notification and
+/// are delayed by the whole tolerance, the upper bound of the random delay of
+/// the scheduler. Stateful and anomaly alerts start from a clean state.
+pub async fn run(mut alert: Alert, start_time: i64, end_time: i64) -> Result<Backtest, AlertError> {
+    if alert.is_real_time {
+        return Err(AlertError::BacktestInvalid(
+            "realtime alerts can't be backtested".to_string(),
+        ));
+    }
+    if alert.query_condition.query_type == QueryType::Composite {
+        return Err(AlertError::BacktestInvalid(
+            "composite alerts depend on the live states of their alerts".to_string(),
+        ));
+    }
+    if let Some(detection) = alert.query_condition.anomaly_detection.as_ref() {
+        detection
+            .validate(&alert.query_condition)
+            .map_err(AlertError::AnomalyDetectionInvalid)?;
+    }
+    // same defaults as a saved alert
+    if alert.trigger_condition.frequency_type == FrequencyType::Cron {
+        if let Some(rest) = alert.trigger_condition.cron.strip_prefix("* ") {
+            alert.trigger_condition.cron = format!("0 {rest}");
+        }
+    } else if alert.trigger_condition.frequency == 0 {
+        alert.trigger_condition.frequency =
+            std::cmp::max(60, get_config().limit.alert_schedule_interval);
+    }
+    let alert = &alert;
+    if start_time >= end_time || end_time > now_micros() {
+        return Err(AlertError::BacktestInvalid(
+            "the time range should be in the past and end after its start".to_string(),
+        ));
+    }
+    let schedule = Scheduler::new(&alert.trigger_condition, alert.tz_offset)?;
+    let trigger = &alert.trigger_condition;
+    let is_stateful = trigger.is_stateful();
+    let period = second_micros(trigger.period * 60);
+    let tolerance = schedule.tolerance;
+
+    let mut backtest = Backtest::default();
+    let mut states = AlertStates::default();
+    let mut model = AnomalyModel::default();
+    let mut next_run_at = schedule.first_run(start_time);
+    while next_run_at <= end_time {
+        if backtest.evaluations == MAX_EVALUATIONS {
+            return Err(AlertError::BacktestInvalid(format!(
+                "the time range needs more than {MAX_EVALUATIONS} evaluations, shorten it"
+            )));
+        }
+        backtest.evaluations += 1;
+        let now = next_run_at;
+        let window_start = now - period - tolerance;
+        let results = alert
+            .evaluate(None, (Some(window_start), now))
+            .await
+            .map_err(|e| AlertError::BacktestEvaluation(format!("at {now}: {e}")))?;
+        let data = anomaly::apply(alert, &mut model, results.data, now);
+
+        let rows = data.unwrap_or_default();
+        let group_by = alert.get_group_by_fields(rows.first());
+        let groups = rows
+            .iter()
+            .map(|row| group_of(row, &group_by))
+            .collect::<Vec<_>>();
+        let changes = states.apply(
+            groups.clone(),
+            now,
+            second_micros(trigger.for_duration),
+            second_micros(trigger.silence * 60),
+        );
+        let firing = if is_stateful {
+            rows.into_iter()
+                .zip(groups)
+                .filter_map(|(row, (key, _))| changes.firing.contains(&key).then_some(row))
+                .collect()
+        } else {
+            rows
+        };
+        let fired = !firing.is_empty();
+        if fired {
+            backtest.notifications.push(BacktestNotification {
+                timestamp: now,
+                start_time: window_start,
+                end_time: now,
+                status: AlertState::Firing,
+                rows: firing,
+            });
+        }
+        if trigger.notify_on_resolve && !changes.resolved.is_empty() {
+            backtest.notifications.push(BacktestNotification {
+                timestamp: now,
+                start_time: window_start,
+                end_time: now,
+                status: AlertState::Resolved,
+                rows: changes.resolved.into_iter().map(|g| g.labels).collect(),
+            });
+        }
+        next_run_at = schedule.next_run(now, !is_stateful && fired);
+    }
+    Ok(backtest)
+}
+
+/// Run times of the scheduler for an alert
+struct Scheduler<'a> {
+    trigger: &'a TriggerCondition,
+    cron: Option<(Schedule, FixedOffset)>,
+    /// (microseconds)
+    tolerance: i64,
+}
+
+impl<'a> Scheduler<'a> {
+    fn new(trigger: &'a TriggerCondition, tz_offset: i32) -> Result<Self, AlertError> {
+        let cron = if trigger.frequency_type == FrequencyType::Cron {
+            let schedule = Schedule::from_str(&trigger.cron)?;
+            let tz = FixedOffset::east_opt(tz_offset * 60).ok_or_else(|| {
+                AlertError::BacktestInvalid(format!("invalid timezone offset {tz_offset}"))
+            })?;
+            Some((schedule, tz))
+        } else if trigger.frequency <= 0 {
+            return Err(AlertError::BacktestInvalid(
+                "the alert frequency should be greater than 0".to_string(),
+            ));
+        } else {
+            None
+        };
+        Ok(Self {
+            trigger,
+            cron,
+            tolerance: second_micros(trigger.tolerance_in_secs.unwrap_or_default().max(0)),
+        })
+    }
+
+    /// First run of an alert created at `start_time`
+    fn first_run(&self, start_time: i64) -> i64 {
+        match &self.cron {
+            Some(_) => self.cron_after(start_time),
+            None => start_time,
+        }
+    }
+
+    /// Run following the one at `now`, after the silence period when the
+    /// alert notified and is silenced
+    fn next_run(&self, now: i64, silenced: bool) -> i64 {
+        let silence = second_micros(self.trigger.silence * 60);
+        let next = match (&self.cron, silenced && silence > 0) {
+            (Some(_), true) => self.cron_after(now + silence),
+            (Some(_), false) => self.cron_after(now),
+            (None, true) => {
+                now + second_micros(std::cmp::max(
+                    self.trigger.silence * 60,
+                    self.trigger.frequency,
+                ))
+            }
+            (None, false) => now + second_micros(self.trigger.frequency),
+        };
+        next + self.tolerance
+    }
+
+    fn cron_after(&self, time: i64) -> i64 {
+        let Some((schedule, tz)) = &self.cron else {
+            return i64::MAX;
+        };
+        DateTime::from_timestamp_micros(time)
+            .map(|t| t.with_timezone(tz))
+            .and_then(|t| schedule.after(&t).next())
+            .map(|t| t.timestamp_micros())
+            // a cron expression without upcoming run
+            .unwrap_or(i64::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(v: &str) -> i64 {
+        DateTime::parse_from_rfc3339(v).unwrap().timestamp_micros()
+    }
+
+    #[test]
+    fn test_minutes_schedule() {
+        let trigger = TriggerCondition {
+            period: 10,
+            frequency: 300,
+            silence: 30,
+            tolerance_in_secs: Some(20),
+            ..Default::default()
+        };
+        let scheduler = Scheduler::new(&trigger, 0).unwrap();
+        let start = micros("2025-03-01T00:00:00Z");
+        assert_eq!(scheduler.first_run(start), start);
+        assert_eq!(
+            scheduler.next_run(start, false),
+            micros("2025-03-01T00:05:20Z")
+        );
+        assert_eq!(
+            scheduler.next_run(start, true),
+            micros("2025-03-01T00:30:20Z")
+        );
+        let trigger = TriggerCondition {
+            frequency: 0,
+            ..trigger
+        };
+        assert!(Scheduler::new(&trigger, 0).is_err());
+    }
+
+    #[test]
+    fn test_cron_schedule() {
+        // every hour at minute 0, in UTC+1
+        let trigger = TriggerCondition {
+            period: 60,
+            frequency_type: FrequencyType::Cron,
+            cron: "0 0 * * * *".to_string(),
+            silence: 90,
+            ..Default::default()
+        };
+        let scheduler = Scheduler::new(&trigger, 60).unwrap();
+        let start = micros("2025-03-01T00:10:00Z");
+        let first = scheduler.first_run(start);
+        assert_eq!(first, micros("2025-03-01T01:00:00Z"));
+        assert_eq!(
+            scheduler.next_run(first, false),
+            micros("2025-03-01T02:00:00Z")
+        );
+        assert_eq!(
+            scheduler.next_run(first, true),
+            micros("2025-03-01T03:00:00Z")
+        );
+        let trigger = TriggerCondition {
+            cron: "not a cron".to_string(),
+            ..trigger
+        };
+        assert!(Scheduler::new(&trigger, 0).is_err());
+    }
+}
@@ -0,0 +1,523 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Alerts, destinations and templates of an organization managed as code: the
+//! resources are exported as a bundle and a bundle is reconciled against the
+//! organization, creating and updating its resources keyed on their folder
+//! and name. Resources missing from the bundle are left as they are.
+
+use std::collections::{HashMap, HashSet};
+
+use config::{
+    meta::{
+        alerts::alert::{Alert, ListAlertsParams},
+        destinations::{Destination, Module, Template},
+        folder::{DEFAULT_FOLDER, Folder, FolderType},
+    },
+    utils::json::{self, Map, Value},
+};
+use infra::db::{ORM_CLIENT, connect_to_orm};
+
+use super::{alert, destinations, templates};
+use crate::service::folders;
+
+/// Version of the bundle format
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("Invalid bundle: {0}")]
+    Invalid(String),
+    #[error("Error applying {kind} {name}: {error}")]
+    Apply {
+        kind: ResourceKind,
+        name: String,
+        error: String,
+    },
+    #[error("Error reading the resources of the organization: {0}")]
+    Read(String),
+}
+
+#[derive(Debug, Default)]
+pub struct Bundle {
+    pub templates: Vec<Template>,
+    pub destinations: Vec<Destination>,
+    /// Alerts with the name of their folder
+    pub alerts: Vec<(String, Alert)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ResourceKind {
+    Template,
+    Destination,
+    Alert,
+}
+
+impl std::fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResourceKind::Template => write!(f, "template"),
+            ResourceKind::Destination => write!(f, "destination"),
+            ResourceKind::Alert => write!(f, "alert"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeAction {
+    Create,
+    Update,
+    Unchanged,
+}
+
+impl std::fmt::Display for ChangeAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeAction::Create => write!(f, "create"),
+            ChangeAction::Update => write!(f, "update"),
+            ChangeAction::Unchanged => write!(f, "unchanged"),
+        }
+    }
+}
+
+/// Change a bundle makes to a resource of the organization
+#[derive(Clone, Debug)]
+pub struct Change {
+    pub kind: ResourceKind,
+    /// Folder of an alert
+    pub folder: Option<String>,
+    pub name: String,
+    pub action: ChangeAction,
+    /// Paths of the fields an update changes, e.g. `trigger_condition.period`
+    pub fields: Vec<String>,
+}
+
+/// Exports the templates, the alert destinations and the alerts of the
+/// organization. With a `folder`, only the alerts of the folder are exported
+/// with the destinations and templates they use. The YAML bundle carries the
+/// secrets of the destinations as placeholders, see [plan].
+pub async fn export(org_id: &str, folder: Option<&str>) -> Result<Bundle, BundleError> {
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    let mut params = ListAlertsParams::new(org_id);
+    if let Some(folder) = folder {
+        let folder = folders::get_folder_by_name(org_id, folder, FolderType::Alerts)
+            .await
+            .map_err(|_| BundleError::Invalid(format!("folder {folder} not found")))?;
+        params = params.in_folder(&folder.folder_id);
+    }
+    let mut alerts = alert::list_v2(client, None, params)
+        .await
+        .map_err(|e| BundleError::Read(e.to_string()))?
+        .into_iter()
+        .map(|(folder, alert)| (folder.name, alert))
+        .collect::<Vec<_>>();
+    alerts.sort_by(|a, b| (&a.0, &a.1.name).cmp(&(&b.0, &b.1.name)));
+
+    let mut destinations = destinations::list(org_id, Some("alert"), None)
+        .await
+        .map_err(|e| BundleError::Read(e.to_string()))?;
+    let mut templates = templates::list(org_id, None)
+        .await
+        .map_err(|e| BundleError::Read(e.to_string()))?;
+    if folder.is_some() {
+        let used = alerts
+            .iter()
+            .flat_map(|(_, alert)| alert.destinations.iter())
+            .collect::<HashSet<_>>();
+        destinations.retain(|d| used.contains(&d.name));
+        let used = destinations
+            .iter()
+            .filter_map(|d| match &d.module {
+                Module::Alert { template, .. } => Some(template),
+                Module::Pipeline { .. } => None,
+            })
+            .collect::<HashSet<_>>();
+        templates.retain(|t| used.contains(&t.name));
+    }
+    destinations.sort_by(|a, b| a.name.cmp(&b.name));
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Bundle {
+        templates,
+        destinations,
+        alerts,
+    })
+}
+
+/// Compares the bundle with the organization and returns the changes applying
+/// it makes, in the order they are applied. A redacted secret of a
+/// destination keeps the stored value, so it is not a change.
+pub async fn plan(org_id: &str, bundle: &Bundle) -> Result<Vec<Change>, BundleError> {
+    validate(bundle)?;
+    let mut changes = Vec::new();
+    for template in bundle.templates.iter() {
+        let existing = templates::get(org_id, &template.name).await.ok();
+        changes.push(change_of(
+            ResourceKind::Template,
+            None,
+            &template.name,
+            existing.map(|t| normalize_template(&t)),
+            normalize_template(template),
+        ));
+    }
+    for destination in bundle.destinations.iter() {
+        // the exported secrets are placeholders, which keep the stored values
+        let mut destination = destination.clone();
+        let existing = destinations::get(org_id, &destination.name).await.ok();
+        match existing.as_ref() {
+            Some(existing) => destination.restore_secrets(existing),
+            None if destination.has_redacted_secrets() => {
+                return Err(BundleError::Invalid(format!(
+                    "destination {} is new and has redacted secrets, set their values",
+                    destination.name
+                )));
+            }
+            None => {}
+        }
+        changes.push(change_of(
+            ResourceKind::Destination,
+            None,
+            &destination.name,
+            existing.map(|d| normalize_destination(&d)),
+            normalize_destination(&destination),
+        ));
+    }
+    let existing = existing_alerts(org_id).await?;
+    for (folder, alert) in bundle.alerts.iter() {
+        let old = existing.get(&(folder.clone(), alert.name.clone()));
+        changes.push(change_of(
+            ResourceKind::Alert,
+            Some(folder),
+            &alert.name,
+            old.map(normalize_alert),
+            normalize_alert(alert),
+        ));
+    }
+    Ok(changes)
+}
+
+/// Applies the bundle: creates the missing resources, and the missing folders
+/// of the alerts, and updates the changed ones. Applying a bundle twice
+/// changes nothing the second time. Returns the changes, without applying
+/// them on a `dry_run`.
+pub async fn apply(
+    org_id: &str,
+    user_id: &str,
+    bundle: Bundle,
+    dry_run: bool,
+) -> Result<Vec<Change>, BundleError> {
+    let changes = plan(org_id, &bundle).await?;
+    if dry_run {
+        return Ok(changes);
+    }
+    let mut changes_iter = changes.iter();
+
+    for mut template in bundle.templates {
+        let change = changes_iter.next().unwrap();
+        if change.action == ChangeAction::Unchanged {
+            continue;
+        }
+        template.org_id = org_id.to_string();
+        let create = change.action == ChangeAction::Create;
+        let name = if create { "" } else { change.name.as_str() };
+        templates::save(name, template, create)
+            .await
+            .map_err(|e| apply_error(change, e))?;
+    }
+    for mut destination in bundle.destinations {
+        let change = changes_iter.next().unwrap();
+        if change.action == ChangeAction::Unchanged {
+            continue;
+        }
+        destination.org_id = org_id.to_string();
+        let create = change.action == ChangeAction::Create;
+        let name = if create { "" } else { change.name.as_str() };
+        destinations::save(name, destination, create)
+            .await
+            .map_err(|e| apply_error(change, e))?;
+    }
+
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    let existing = existing_alerts(org_id).await?;
+    let mut folder_ids = HashMap::new();
+    for (folder, mut alert) in bundle.alerts {
+        let change = changes_iter.next().unwrap();
+        if change.action == ChangeAction::Unchanged {
+            continue;
+        }
+        alert.org_id = org_id.to_string();
+        alert.last_edited_by = Some(user_id.to_string());
+        if let Some(old) = existing.get(&(folder.clone(), alert.name.clone())) {
+            alert.id = old.id;
+            alert::update(client, org_id, None, alert)
+                .await
+                .map_err(|e| apply_error(change, e))?;
+            continue;
+        }
+        if !folder_ids.contains_key(&folder) {
+            let folder_id = folder_id_of(org_id, &folder)
+                .await
+                .map_err(|e| apply_error(change, e))?;
+            folder_ids.insert(folder.clone(), folder_id);
+        }
+        alert.id = None;
+        alert.owner = Some(user_id.to_string());
+        alert::create(client, org_id, &folder_ids[&folder], alert)
+            .await
+            .map_err(|e| apply_error(change, e))?;
+    }
+    Ok(changes)
+}
+
+fn validate(bundle: &Bundle) -> Result<(), BundleError> {
+    let mut names = HashSet::new();
+    for template in bundle.templates.iter() {
+        if template.name.trim().is_empty() {
+            return Err(BundleError::Invalid("a template has no name".to_string()));
+        }
+        if !names.insert(template.name.as_str()) {
+            return Err(BundleError::Invalid(format!(
+                "template {} is defined twice",
+                template.name
+            )));
+        }
+    }
+    let mut names = HashSet::new();
+    for destination in bundle.destinations.iter() {
+        if destination.name.trim().is_empty() {
+            return Err(BundleError::Invalid(
+                "a destination has no name".to_string(),
+            ));
+        }
+        if !destination.is_alert_destinations() {
+            return Err(BundleError::Invalid(format!(
+                "destination {} is not an alert destination",
+                destination.name
+            )));
+        }
+        if !names.insert(destination.name.as_str()) {
+            return Err(BundleError::Invalid(format!(
+                "destination {} is defined twice",
+                destination.name
+            )));
+        }
+    }
+    let mut names = HashSet::new();
+    for (folder, alert) in bundle.alerts.iter() {
+        if folder.trim().is_empty() || alert.name.trim().is_empty() {
+            return Err(BundleError::Invalid(
+                "an alert has no folder or no name".to_string(),
+            ));
+        }
+        if !names.insert((folder.as_str(), alert.name.as_str())) {
+            return Err(BundleError::Invalid(format!(
+                "alert {} is defined twice in folder {folder}",
+                alert.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Alerts of the organization by folder name and alert name
+async fn existing_alerts(org_id: &str) -> Result<HashMap<(String, String), Alert>, BundleError> {
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    Ok(alert::list_v2(client, None, ListAlertsParams::new(org_id))
+        .await
+        .map_err(|e| BundleError::Read(e.to_string()))?
+        .into_iter()
+        .map(|(folder, alert)| ((folder.name, alert.name.clone()), alert))
+        .collect())
+}
+
+/// ID of the alert folder with the name, the folder is created when missing
+async fn folder_id_of(org_id: &str, name: &str) -> Result<String, folders::FolderError> {
+    if name == DEFAULT_FOLDER {
+        return Ok(DEFAULT_FOLDER.to_string());
+    }
+    if let Ok(folder) = folders::get_folder_by_name(org_id, name, FolderType::Alerts).await {
+        return Ok(folder.folder_id);
+    }
+    let folder = Folder {
+        folder_id: String::new(),
+        name: name.to_string(),
+        description: String::new(),
+    };
+    let folder = folders::save_folder(org_id, folder, FolderType::Alerts, false).await?;
+    Ok(folder.folder_id)
+}
+
+fn apply_error(change: &Change, error: impl std::fmt::Display) -> BundleError {
+    BundleError::Apply {
+        kind: change.kind,
+        name: match change.folder.as_ref() {
+            Some(folder) => format!("{folder}/{}", change.name),
+            None => change.name.clone(),
+        },
+        error: error.to_string(),
+    }
+}
+
+fn change_of(
+    kind: ResourceKind,
+    folder: Option<&String>,
+    name: &str,
+    old: Option<Value>,
+    new: Value,
+) -> Change {
+    let (action, fields) = match old {
+        None => (ChangeAction::Create, vec![]),
+        Some(old) => {
+            let mut fields = vec![];
+            diff("", &old, &new, &mut fields);
+            if fields.is_empty() {
+                (ChangeAction::Unchanged, fields)
+            } else {
+                (ChangeAction::Update, fields)
+            }
+        }
+    };
+    Change {
+        kind,
+        folder: folder.cloned(),
+        name: name.to_string(),
+        action,
+        fields,
+    }
+}
+
+/// Collects the paths of the fields which differ, a missing field is null
+fn diff(path: &str, old: &Value, new: &Value, fields: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys = old.keys().chain(new.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{path}.{key}")
+                };
+                diff(
+                    &path,
+                    old.get(key).unwrap_or(&Value::Null),
+                    new.get(key).unwrap_or(&Value::Null),
+                    fields,
+                );
+            }
+        }
+        (old, new) if old != new => fields.push(path.to_string()),
+        _ => {}
+    }
+}
+
+/// Fields of the alert which are compared, without the fields set by the
+/// server
+fn normalize_alert(alert: &Alert) -> Value {
+    let mut alert = alert.clone();
+    alert.id = None;
+    alert.org_id = String::new();
+    alert.owner = None;
+    alert.updated_at = None;
+    alert.last_edited_by = None;
+    alert.set_last_triggered_at(None);
+    alert.set_last_satisfied_at(None);
+    without_nulls(json::to_value(alert).unwrap_or_default())
+}
+
+fn normalize_destination(destination: &Destination) -> Value {
+    let mut destination = destination.clone();
+    destination.id = None;
+    destination.org_id = String::new();
+    without_nulls(json::to_value(destination).unwrap_or_default())
+}
+
+fn normalize_template(template: &Template) -> Value {
+    let mut template = template.clone();
+    template.id = None;
+    template.org_id = String::new();
+    // set by the server from the organization
+    template.is_default = false;
+    without_nulls(json::to_value(template).unwrap_or_default())
+}
+
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, without_nulls(v)))
+                .collect::<Map<_, _>>(),
+        ),
+        v => v,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let old = json::json!({
+            "name": "a",
+            "trigger_condition": {"period": 10, "frequency": 60},
+            "context_attributes": null,
+        });
+        let new = json::json!({
+            "name": "a",
+            "trigger_condition": {"period": 5, "frequency": 60},
+            "destinations": ["slack"],
+        });
+        let mut fields = vec![];
+        diff(
+            "",
+            &without_nulls(old.clone()),
+            &without_nulls(new),
+            &mut fields,
+        );
+        assert_eq!(fields, vec!["destinations", "trigger_condition.period"]);
+
+        let mut fields = vec![];
+        diff("", &old, &old, &mut fields);
+        assert!(fields.is_empty());
+    }
+
+    #[test]
+    fn test_change_of_alert() {
+        let mut alert = Alert {
+            name: "errors".to_string(),
+            ..Default::default()
+        };
+        let new = normalize_alert(&alert);
+        let change = change_of(ResourceKind::Alert, None, "errors", None, new.clone());
+        assert_eq!(change.action, ChangeAction::Create);
+
+        // the fields set by the server are not compared
+        alert.id = Some(svix_ksuid::Ksuid::new(None, None));
+        alert.org_id = "default".to_string();
+        alert.owner = Some("root@example.com".to_string());
+        alert.set_last_triggered_at(Some(1));
+        let old = normalize_alert(&alert);
+        let change = change_of(ResourceKind::Alert, None, "errors", Some(old), new.clone());
+        assert_eq!(change.action, ChangeAction::Unchanged);
+
+        alert.enabled = true;
+        let old = normalize_alert(&alert);
+        let change = change_of(ResourceKind::Alert, None, "errors", Some(old), new);
+        assert_eq!(change.action, ChangeAction::Update);
+        assert_eq!(change.fields, vec!["enabled"]);
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Composite alerts, evaluated on the current states of the scheduled alerts
+//! they refer to instead of querying a stream.
+
+use std::{collections::HashMap, str::FromStr};
+
+use config::{
+    meta::{
+        alerts::{QueryType, TriggerEvalResults, alert::Alert, composite::CompositeCondition},
+        triggers::{ScheduledTriggerData, TriggerModule},
+    },
+    utils::{json, time::second_micros},
+};
+use infra::db::{ORM_CLIENT, connect_to_orm};
+use svix_ksuid::Ksuid;
+
+use super::alert::{self, AlertError};
+use crate::{common::infra::config::STREAM_ALERTS, service::db::scheduler};
+
+/// (seconds) how long a composite alert waits for one of its alerts due for
+/// an evaluation, an alert late by more is stuck and not waited for
+const MAX_WAIT_SECS: i64 = 60;
+
+/// (seconds) delay of a composite alert waiting for its alerts
+pub const WAIT_INTERVAL_SECS: i64 = 5;
+
+/// Checks the composite condition of the alert and the alerts it refers to,
+/// which should be scheduled alerts of the same organization.
+pub async fn validate(alert: &Alert) -> Result<(), AlertError> {
+    let condition = condition_of(alert).map_err(AlertError::CompositeInvalid)?;
+    condition.validate().map_err(AlertError::CompositeInvalid)?;
+    for alert_id in condition.alert_ids() {
+        if alert.id.is_some_and(|id| id.to_string() == alert_id) {
+            return Err(AlertError::CompositeInvalid(
+                "a composite alert can't refer to itself".to_string(),
+            ));
+        }
+        let constituent = get_alert(&alert.org_id, alert_id).await?;
+        if !constituent.enabled {
+            return Err(AlertError::CompositeInvalid(format!(
+                "alert {} is disabled",
+                constituent.name
+            )));
+        }
+        if constituent.is_real_time {
+            return Err(AlertError::CompositeInvalid(format!(
+                "alert {} is a realtime alert, it has no state",
+                constituent.name
+            )));
+        }
+        if constituent.query_condition.query_type == QueryType::Composite {
+            return Err(AlertError::CompositeInvalid(format!(
+                "alert {} is a composite alert",
+                constituent.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Evaluates the composite condition at `now` on the pending and firing
+/// groups of the alerts. Alerts referred to can't be deleted or disabled, a
+/// missing alert fails the evaluation and a disabled one has no group.
+pub async fn evaluate(alert: &Alert, now: i64) -> Result<TriggerEvalResults, anyhow::Error> {
+    let condition = condition_of(alert).map_err(|e| anyhow::anyhow!(e))?;
+    let mut groups = HashMap::new();
+    for alert_id in condition.alert_ids() {
+        let constituent = get_alert(&alert.org_id, alert_id).await?;
+        let states = if constituent.enabled {
+            scheduler::get(
+                &alert.org_id,
+                TriggerModule::Alert,
+                &constituent.get_unique_key(),
+            )
+            .await
+            .ok()
+            .and_then(|trigger| json::from_str::<ScheduledTriggerData>(&trigger.data).ok())
+            .map(|data| data.states.groups.into_values().collect())
+            .unwrap_or_default()
+        } else {
+            vec![]
+        };
+        groups.insert(alert_id.to_string(), states);
+    }
+    let rows = condition.evaluate(&groups, now);
+    Ok(TriggerEvalResults {
+        data: Some(rows).filter(|rows| !rows.is_empty()),
+        end_time: now,
+        query_took: None,
+    })
+}
+
+/// Whether one of the alerts of the composite alert is due for an evaluation
+/// at `now`, the composite alert is evaluated after its alerts.
+pub async fn has_due_alerts(alert: &Alert, now: i64) -> bool {
+    let Ok(condition) = condition_of(alert) else {
+        return false;
+    };
+    for alert_id in condition.alert_ids() {
+        let Ok(constituent) = get_alert(&alert.org_id, alert_id).await else {
+            continue;
+        };
+        if !constituent.enabled {
+            continue;
+        }
+        let Ok(trigger) = scheduler::get(
+            &alert.org_id,
+            TriggerModule::Alert,
+            &constituent.get_unique_key(),
+        )
+        .await
+        else {
+            continue;
+        };
+        let late = now - trigger.next_run_at;
+        if (0..second_micros(MAX_WAIT_SECS)).contains(&late) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Names of the composite alerts of the organization referring to the alert
+pub async fn referred_by(org_id: &str, alert_id: &str) -> Vec<String> {
+    let mut names = STREAM_ALERTS
+        .read()
+        .await
+        .values()
+        .flatten()
+        .filter(|a| {
+            a.org_id == org_id
+                && a.query_condition.query_type == QueryType::Composite
+                && condition_of(a).is_ok_and(|c| c.alert_ids().contains(&alert_id))
+        })
+        .map(|a| a.name.clone())
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Whether a composite alert refers to the alert, reading its states
+pub async fn is_referenced(alert: &Alert) -> bool {
+    match alert.id {
+        Some(id) => !referred_by(&alert.org_id, &id.to_string()).await.is_empty(),
+        None => false,
+    }
+}
+
+/// Fails when composite alerts refer to the alert, which then can't be
+/// deleted or disabled without breaking them
+pub async fn ensure_not_referenced(
+    org_id: &str,
+    alert_id: Option<Ksuid>,
+) -> Result<(), AlertError> {
+    let Some(alert_id) = alert_id else {
+        return Ok(());
+    };
+    let names = referred_by(org_id, &alert_id.to_string()).await;
+    if names.is_empty() {
+        Ok(())
+    } else {
+        Err(AlertError::CompositeReferenced(names.join(", ")))
+    }
+}
+
+fn condition_of(alert: &Alert) -> Result<&CompositeCondition, String> {
+    alert
+        .query_condition
+        .composite
+        .as_ref()
+        .ok_or_else(|| "composite alerts need a composite condition".to_string())
+}
+
+async fn get_alert(org_id: &str, alert_id: &str) -> Result<Alert, AlertError> {
+    let Ok(id) = Ksuid::from_str(alert_id) else {
+        return Err(AlertError::CompositeInvalid(format!(
+            "invalid alert ID {alert_id}"
+        )));
+    };
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    match alert::get_by_id(client, org_id, id).await {
+        Err(AlertError::AlertNotFound) => Err(AlertError::CompositeInvalid(format!(
+            "alert {alert_id} not found"
+        ))),
+        res => res,
+    }
+}
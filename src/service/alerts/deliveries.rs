@@ -0,0 +1,498 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Durable delivery of the alert notifications.
+//!
+//! Every notification sent to a destination is a [Delivery], saved with each
+//! of its attempts in the delivery log. A failed attempt which can be retried,
+//! an unreachable destination, a timeout, 429 or 5xx, keeps the delivery in
+//! the queue and [process_queue] retries it with an exponential backoff.
+//! Deliveries which failed permanently or ran out of retries are dead, the
+//! dead-letter list, until they are re-sent from the API. Destinations
+//! over their rate limit get their notifications queued instead of sent.
+//!
+//! A delivery is saved once, in the log, with the rendered notification and
+//! the ID of its alert, which retries load again. The queue only keeps the
+//! next attempt of the pending deliveries.
+
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::atomic::{AtomicI64, Ordering},
+    time::Instant,
+};
+
+use config::{
+    get_config, ider,
+    meta::{
+        alerts::{
+            alert::Alert,
+            delivery::{Delivery, DeliveryAttempt, DeliveryStatus},
+            state::AlertState,
+        },
+        destinations::{DestinationType, Module},
+    },
+    utils::{
+        json::{Map, Value},
+        time::{day_micros, hour_micros, now_micros, second_micros},
+    },
+};
+use infra::{
+    db::{ORM_CLIENT, connect_to_orm},
+    dist_lock,
+};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use svix_ksuid::Ksuid;
+
+use super::{alert::dispatch, destinations, integrations};
+use crate::service::db;
+
+const QUEUE_LOCK_KEY: &str = "/alert_delivery/lock";
+/// Window of the rate limit of the destinations
+const RATE_LIMIT_WINDOW_SECS: i64 = 60;
+
+static RATE_LIMITER: Lazy<Mutex<RateLimiter>> = Lazy::new(Default::default);
+static LAST_CLEANUP: AtomicI64 = AtomicI64::new(0);
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeliveryError {
+    #[error("Delivery {0} not found")]
+    NotFound(String),
+    #[error("Delivery {0} is pending, it can't be re-sent")]
+    Pending(String),
+    #[error("Error saving delivery: {0}")]
+    Db(#[from] anyhow::Error),
+}
+
+/// Response of a destination which received the notification
+#[derive(Debug, Default)]
+pub struct Sent {
+    pub status_code: Option<u16>,
+    pub response: String,
+}
+
+/// Non successful HTTP response of a destination
+#[derive(Debug, thiserror::Error)]
+#[error("sent error status: {status_code}, err: {body}")]
+pub struct StatusError {
+    pub status_code: u16,
+    pub body: String,
+    /// (seconds) `Retry-After` header of the response
+    pub retry_after: Option<i64>,
+}
+
+impl StatusError {
+    /// Error of the failed response, with its body
+    pub async fn from_response(resp: reqwest::Response) -> Self {
+        let status_code = resp.status().as_u16();
+        let retry_after = resp
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| *v > 0);
+        let body = resp.text().await.unwrap_or_default();
+        Self {
+            status_code,
+            body,
+            retry_after,
+        }
+    }
+
+    /// Timeouts, rate limits and server errors may succeed later
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.status_code, 408 | 429) || self.status_code >= 500
+    }
+}
+
+/// Fixed window counter of the notifications sent to each destination
+#[derive(Default)]
+struct RateLimiter {
+    windows: HashMap<String, (i64, u32)>,
+}
+
+impl RateLimiter {
+    /// Counts a notification to the destination, returns the end of the
+    /// window when the destination already got `limit` notifications in it
+    fn acquire(&mut self, key: &str, now: i64, limit: u32) -> Result<(), i64> {
+        if limit == 0 {
+            return Ok(());
+        }
+        let window = second_micros(RATE_LIMIT_WINDOW_SECS);
+        let start = now - now % window;
+        let (window_start, count) = self.windows.entry(key.to_string()).or_insert((start, 0));
+        if *window_start != start {
+            *window_start = start;
+            *count = 0;
+        }
+        if *count >= limit {
+            return Err(start + window);
+        }
+        *count += 1;
+        Ok(())
+    }
+
+    fn clean(&mut self, now: i64) {
+        let window = second_micros(RATE_LIMIT_WINDOW_SECS);
+        self.windows.retain(|_, (start, _)| now - *start < window);
+    }
+}
+
+/// Sends the rendered notification to the destination and saves the delivery,
+/// returns the response of the destination. The notification is queued when
+/// the destination is rate limited or the attempt failed but can be retried,
+/// it is an error when the delivery is dead or couldn't be saved.
+#[allow(clippy::too_many_arguments)]
+pub async fn deliver(
+    alert: &Alert,
+    dest_name: &str,
+    dest_type: &DestinationType,
+    rows: &[Map<String, Value>],
+    title: String,
+    message: String,
+    alert_status: AlertState,
+) -> Result<String, anyhow::Error> {
+    let now = now_micros();
+    let mut delivery = Delivery {
+        id: ider::generate(),
+        org_id: alert.org_id.clone(),
+        alert_name: alert.name.clone(),
+        destination: dest_name.to_string(),
+        destination_type: dest_type.name().to_string(),
+        alert_status,
+        status: DeliveryStatus::Pending,
+        created_at: now,
+        next_attempt_at: now,
+        retries: 0,
+        attempts: vec![],
+        title,
+        message,
+        alert_id: alert.id.map(|id| id.to_string()).unwrap_or_default(),
+        rows: stored_rows(alert, dest_type, rows),
+    };
+    attempt(&mut delivery, alert, dest_type, rows, now).await;
+    // a pending delivery which isn't saved would never be retried
+    db::alerts::deliveries::set(&delivery).await.map_err(|e| {
+        anyhow::anyhow!(
+            "Error saving delivery {} of alert {}/{}: {e}",
+            delivery.id,
+            delivery.org_id,
+            delivery.alert_name
+        )
+    })?;
+    outcome(&delivery)
+}
+
+/// Response of the last attempt of the delivery
+fn outcome(delivery: &Delivery) -> Result<String, anyhow::Error> {
+    let response = delivery
+        .attempts
+        .last()
+        .map(|a| a.response.as_str())
+        .unwrap_or_default();
+    match delivery.status {
+        DeliveryStatus::Delivered => Ok(response.to_string()),
+        DeliveryStatus::Pending if delivery.attempts.is_empty() => Ok(format!(
+            "queued as delivery {}, destination is rate limited",
+            delivery.id
+        )),
+        DeliveryStatus::Pending => Ok(format!(
+            "queued as delivery {} for retry {}, {response}",
+            delivery.id, delivery.retries
+        )),
+        DeliveryStatus::Dead => Err(anyhow::anyhow!(
+            "{response}, delivery {} moved to the dead-letter list",
+            delivery.id
+        )),
+    }
+}
+
+/// Rows kept with the delivery for its retries, only the destinations which
+/// send an event per group use them
+fn stored_rows(
+    alert: &Alert,
+    dest_type: &DestinationType,
+    rows: &[Map<String, Value>],
+) -> Vec<Map<String, Value>> {
+    match dest_type {
+        DestinationType::PagerDuty(_) | DestinationType::Opsgenie(_) => {
+            integrations::group_rows(alert, rows)
+        }
+        _ => vec![],
+    }
+}
+
+/// Attempts the delivery unless its destination is rate limited, then the
+/// delivery waits for the next window
+async fn attempt(
+    delivery: &mut Delivery,
+    alert: &Alert,
+    dest_type: &DestinationType,
+    rows: &[Map<String, Value>],
+    now: i64,
+) {
+    let cfg = get_config();
+    let key = format!("{}/{}", delivery.org_id, delivery.destination);
+    let limited = RATE_LIMITER
+        .lock()
+        .acquire(&key, now, cfg.limit.alert_notification_rate_limit);
+    if let Err(window_end) = limited {
+        log::warn!(
+            "Destination {key} is rate limited, delivery {} queued",
+            delivery.id
+        );
+        delivery.status = DeliveryStatus::Pending;
+        delivery.next_attempt_at = window_end;
+        return;
+    }
+
+    let start = Instant::now();
+    let ret = dispatch(
+        alert,
+        dest_type,
+        rows,
+        &delivery.title,
+        delivery.message.clone(),
+        delivery.alert_status,
+    )
+    .await;
+    let latency_ms = start.elapsed().as_millis() as i64;
+    let (attempt, retryable) = match ret {
+        Ok(sent) => (
+            DeliveryAttempt {
+                timestamp: now,
+                latency_ms,
+                status_code: sent.status_code,
+                success: true,
+                response: sent.response,
+                retry_after: None,
+            },
+            false,
+        ),
+        Err(e) => {
+            let (status_code, retryable, retry_after) = match e.downcast_ref::<StatusError>() {
+                Some(status) => (
+                    Some(status.status_code),
+                    status.is_retryable(),
+                    status.retry_after,
+                ),
+                // the destination couldn't be reached
+                None => (None, true, None),
+            };
+            log::error!(
+                "Delivery {} of alert {}/{} to destination {} failed: {e}",
+                delivery.id,
+                delivery.org_id,
+                delivery.alert_name,
+                delivery.destination
+            );
+            (
+                DeliveryAttempt {
+                    timestamp: now,
+                    latency_ms,
+                    status_code,
+                    success: false,
+                    response: e.to_string(),
+                    retry_after,
+                },
+                retryable,
+            )
+        }
+    };
+    delivery.record(
+        attempt,
+        retryable,
+        cfg.limit.alert_notification_max_retries,
+        cfg.limit.alert_notification_retry_backoff,
+    );
+}
+
+/// Retries the queued deliveries which are due, and removes the deliveries
+/// older than the retention of the delivery log
+pub async fn process_queue(now: i64) -> Result<(), anyhow::Error> {
+    let locker = dist_lock::lock(QUEUE_LOCK_KEY, 0).await?;
+    let ret = retry_due(now).await;
+    if now - LAST_CLEANUP.load(Ordering::Relaxed) >= hour_micros(1) {
+        LAST_CLEANUP.store(now, Ordering::Relaxed);
+        RATE_LIMITER.lock().clean(now);
+        if let Err(e) = clean_log(now).await {
+            log::error!("Error cleaning the alert delivery log: {e}");
+        }
+    }
+    dist_lock::unlock(&locker).await?;
+    ret
+}
+
+async fn retry_due(now: i64) -> Result<(), anyhow::Error> {
+    for queued in db::alerts::deliveries::list_queue().await? {
+        if queued.next_attempt_at > now {
+            // the queue is sorted by the next attempt
+            break;
+        }
+        let Ok(mut delivery) = db::alerts::deliveries::get(&queued.org_id, &queued.id).await else {
+            // removed from the log, drop it from the queue
+            db::alerts::deliveries::delete(&queued.org_id, &queued.id).await?;
+            continue;
+        };
+        retry(&mut delivery, now).await;
+        db::alerts::deliveries::set(&delivery).await?;
+    }
+    Ok(())
+}
+
+/// Attempts the delivery again, the alert and the destination are loaded
+/// again as their settings may have been fixed since the previous attempt
+async fn retry(delivery: &mut Delivery, now: i64) {
+    let dest_type = match destinations::get(&delivery.org_id, &delivery.destination).await {
+        Ok(dest) => match dest.module {
+            Module::Alert {
+                destination_type, ..
+            } => Some(destination_type),
+            _ => None,
+        },
+        Err(_) => None,
+    };
+    let Some(dest_type) = dest_type else {
+        return kill(
+            delivery,
+            now,
+            format!("destination {} not found", delivery.destination),
+        );
+    };
+    let Some(alert) = get_alert(&delivery.org_id, &delivery.alert_id).await else {
+        return kill(
+            delivery,
+            now,
+            format!("alert {} not found", delivery.alert_name),
+        );
+    };
+    let rows = std::mem::take(&mut delivery.rows);
+    attempt(delivery, &alert, &dest_type, &rows, now).await;
+    delivery.rows = rows;
+}
+
+async fn get_alert(org_id: &str, alert_id: &str) -> Option<Alert> {
+    let id = Ksuid::from_str(alert_id).ok()?;
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    match db::alerts::alert::get_by_id(client, org_id, id).await {
+        Ok(alert) => alert.map(|(_, alert)| alert),
+        Err(e) => {
+            log::error!("Error getting alert {org_id}/{alert_id}: {e}");
+            None
+        }
+    }
+}
+
+/// The delivery can't be attempted anymore
+fn kill(delivery: &mut Delivery, now: i64, response: String) {
+    delivery.status = DeliveryStatus::Dead;
+    delivery.attempts.push(DeliveryAttempt {
+        timestamp: now,
+        success: false,
+        response,
+        ..Default::default()
+    });
+}
+
+async fn clean_log(now: i64) -> Result<(), anyhow::Error> {
+    let retention = get_config().limit.alert_delivery_log_retention;
+    if retention <= 0 {
+        return Ok(());
+    }
+    let expired_at = now - day_micros(retention);
+    for delivery in db::alerts::deliveries::list_all().await? {
+        if delivery.status != DeliveryStatus::Pending && delivery.created_at < expired_at {
+            db::alerts::deliveries::delete(&delivery.org_id, &delivery.id).await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn get(org_id: &str, id: &str) -> Result<Delivery, DeliveryError> {
+    db::alerts::deliveries::get(org_id, id)
+        .await
+        .map_err(|_| DeliveryError::NotFound(id.to_string()))
+}
+
+/// Page of the deliveries of the org matching the `filter`, newest first,
+/// with the `after` of the next page
+pub async fn list(
+    org_id: &str,
+    after: Option<&str>,
+    size: usize,
+    filter: impl Fn(&Delivery) -> bool,
+) -> Result<(Vec<Delivery>, Option<String>), DeliveryError> {
+    Ok(db::alerts::deliveries::list(org_id, after, size, filter).await?)
+}
+
+pub async fn delete(org_id: &str, id: &str) -> Result<(), DeliveryError> {
+    get(org_id, id).await?;
+    db::alerts::deliveries::delete(org_id, id).await?;
+    Ok(())
+}
+
+/// Sends a dead or delivered notification again, with a fresh set of retries
+pub async fn resend(org_id: &str, id: &str) -> Result<Delivery, DeliveryError> {
+    let mut delivery = get(org_id, id).await?;
+    if delivery.status == DeliveryStatus::Pending {
+        return Err(DeliveryError::Pending(id.to_string()));
+    }
+    let now = now_micros();
+    delivery.status = DeliveryStatus::Pending;
+    delivery.retries = 0;
+    delivery.next_attempt_at = now;
+    retry(&mut delivery, now).await;
+    db::alerts::deliveries::set(&delivery).await?;
+    Ok(delivery)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::default();
+        let now = second_micros(120);
+        assert!(limiter.acquire("org/slack", now, 0).is_ok());
+        assert!(limiter.acquire("org/slack", now, 2).is_ok());
+        assert!(limiter.acquire("org/slack", now + 1, 2).is_ok());
+        assert_eq!(
+            limiter.acquire("org/slack", now + 2, 2),
+            Err(second_micros(180))
+        );
+        assert!(limiter.acquire("org/pagerduty", now, 2).is_ok());
+        assert!(limiter.acquire("org/slack", second_micros(180), 2).is_ok());
+        limiter.clean(second_micros(300));
+        assert!(limiter.windows.is_empty());
+    }
+
+    #[test]
+    fn test_status_error() {
+        let retryable = |status_code| {
+            StatusError {
+                status_code,
+                body: String::new(),
+                retry_after: None,
+            }
+            .is_retryable()
+        };
+        assert!(retryable(429));
+        assert!(retryable(503));
+        assert!(!retryable(400));
+        assert!(!retryable(404));
+    }
+}
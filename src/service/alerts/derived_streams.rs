@@ -46,6 +46,10 @@ pub async fn save(
                     ));
                 }
 
+                if let Err(e) = crate::service::sql_macros::validate(&derived_stream.org_id, sql) {
+                    return Err(anyhow::anyhow!("Invalid SQL macro in query: {e}"));
+                }
+
                 // Check the max_query_range of streams in the sql query
                 let stream_names = match resolve_stream_names(sql) {
                     Ok(stream_names) => stream_names,
@@ -89,6 +93,11 @@ pub async fn save(
                 ));
             }
         }
+        QueryType::Composite => {
+            return Err(anyhow::anyhow!(
+                "DerivedStreams don't support composite query conditions"
+            ));
+        }
         _ => {}
     };
     // End input validation
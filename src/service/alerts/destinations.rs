@@ -13,11 +13,11 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use config::meta::destinations::{Destination, DestinationType, Module, Template};
+use config::meta::destinations::{Destination, DestinationType, Module, Slack, Teams, Template};
 
 use crate::{
     common::{
-        infra::config::STREAM_ALERTS,
+        infra::config::{NOTIFICATION_POLICIES, STREAM_ALERTS},
         meta::authz::Authz,
         utils::auth::{is_ofga_unsupported, remove_ownership, set_ownership},
     },
@@ -63,6 +63,27 @@ pub async fn save(
                     return Err(DestinationError::InvalidSns);
                 }
             }
+            DestinationType::Slack(Slack { webhook_url, .. })
+            | DestinationType::Teams(Teams { webhook_url }) => {
+                if url::Url::parse(webhook_url).is_err() {
+                    return Err(DestinationError::InvalidWebhookUrl);
+                }
+            }
+            DestinationType::PagerDuty(pagerduty) => {
+                if pagerduty.routing_key.trim().is_empty()
+                    || !["critical", "error", "warning", "info"]
+                        .contains(&pagerduty.severity.as_str())
+                {
+                    return Err(DestinationError::InvalidPagerDuty);
+                }
+            }
+            DestinationType::Opsgenie(opsgenie) => {
+                if opsgenie.api_key.trim().is_empty()
+                    || !["P1", "P2", "P3", "P4", "P5"].contains(&opsgenie.priority.as_str())
+                {
+                    return Err(DestinationError::InvalidOpsgenie);
+                }
+            }
         },
         Module::Pipeline { endpoint, .. } => {
             if endpoint.url.is_empty() {
@@ -83,10 +104,12 @@ pub async fn save(
     }
 
     match db::alerts::destinations::get(&destination.org_id, &destination.name).await {
-        Ok(_) => {
+        Ok(stored) => {
             if create {
                 return Err(DestinationError::AlreadyExists);
             }
+            // the API returns the secrets redacted, they are sent back as is
+            destination.restore_secrets(&stored);
         }
         Err(_) => {
             if !create {
@@ -154,6 +177,12 @@ pub async fn delete(org_id: &str, name: &str) -> Result<(), DestinationError> {
     }
     drop(cacher);
 
+    if let Some(policy) = NOTIFICATION_POLICIES.get(org_id) {
+        if policy.destinations().contains(&name) {
+            return Err(DestinationError::UsedByNotificationPolicy);
+        }
+    }
+
     if let Ok(pls) = db::pipeline::list_by_org(org_id).await {
         for pl in pls {
             if pl.contains_remote_destination(name) {
@@ -0,0 +1,438 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Native notifications of the Slack, Microsoft Teams, PagerDuty and Opsgenie
+//! destinations, and the signature of the generic webhooks.
+//!
+//! Each notification is sent once, a failed one is retried by the delivery
+//! queue rather than by waiting here.
+
+use std::collections::HashSet;
+
+use config::{
+    meta::{
+        alerts::{
+            alert::Alert,
+            state::{AlertState, group_of},
+        },
+        destinations::{Opsgenie, PagerDuty, Slack, Teams},
+    },
+    utils::{
+        json::{self, Map, Value},
+        md5,
+    },
+};
+use hmac::{Hmac, Mac};
+use reqwest::{
+    RequestBuilder,
+    header::{AUTHORIZATION, CONTENT_TYPE},
+};
+use sha2::Sha256;
+
+use super::deliveries::{Sent, StatusError};
+
+/// Header of the signature of the webhook notifications
+pub const SIGNATURE_HEADER: &str = "X-OpenObserve-Signature";
+
+const PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+const OPSGENIE_API_URL: &str = "https://api.opsgenie.com";
+const SOURCE: &str = "OpenObserve";
+
+// Provider limits
+const PAGERDUTY_SUMMARY_LEN: usize = 1024;
+const OPSGENIE_MESSAGE_LEN: usize = 130;
+const OPSGENIE_DESCRIPTION_LEN: usize = 15000;
+
+/// Returns the signature of the webhook body sent at `timestamp` (seconds),
+/// `t={timestamp},v1={hex}` where `hex` is the HMAC-SHA256 of
+/// `{timestamp}.{body}` keyed with the secret of the destination
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any size");
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body.as_bytes());
+    format!(
+        "t={timestamp},v1={}",
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+pub async fn send_slack(
+    slack: &Slack,
+    alert: &Alert,
+    msg: &str,
+    status: AlertState,
+) -> Result<Sent, anyhow::Error> {
+    let payload = slack_payload(slack.channel.as_deref(), &title(alert, status), msg, status);
+    let req = reqwest::Client::new().post(&slack.webhook_url);
+    send(req, &payload).await
+}
+
+pub async fn send_teams(
+    teams: &Teams,
+    alert: &Alert,
+    msg: &str,
+    status: AlertState,
+) -> Result<Sent, anyhow::Error> {
+    let payload = teams_payload(&title(alert, status), msg, status);
+    let req = reqwest::Client::new().post(&teams.webhook_url);
+    send(req, &payload).await
+}
+
+/// Sends one event per firing or resolved group, the dedup key of the group
+/// lets PagerDuty keep a single incident per group and resolve it
+pub async fn send_pagerduty(
+    pagerduty: &PagerDuty,
+    alert: &Alert,
+    rows: &[Map<String, Value>],
+    msg: &str,
+    status: AlertState,
+) -> Result<Sent, anyhow::Error> {
+    let url = pagerduty
+        .events_url
+        .as_deref()
+        .unwrap_or(PAGERDUTY_EVENTS_URL);
+    let client = reqwest::Client::new();
+    let summary = summary(alert, msg, status);
+    let mut sent = Vec::new();
+    for (group_key, labels) in groups(alert, rows) {
+        let event = pagerduty_event(
+            pagerduty,
+            &dedup_key(alert, &group_key),
+            &summary,
+            &alert.get_unique_key(),
+            msg,
+            labels,
+            status,
+        );
+        sent.push(send(client.post(url), &event).await?);
+    }
+    Ok(joined(sent))
+}
+
+/// Creates one Opsgenie alert per firing group and closes it once the group
+/// is resolved, the alias of the group deduplicates the alerts
+pub async fn send_opsgenie(
+    opsgenie: &Opsgenie,
+    alert: &Alert,
+    rows: &[Map<String, Value>],
+    msg: &str,
+    status: AlertState,
+) -> Result<Sent, anyhow::Error> {
+    let api_url = opsgenie
+        .api_url
+        .as_deref()
+        .unwrap_or(OPSGENIE_API_URL)
+        .trim_end_matches('/');
+    let client = reqwest::Client::new();
+    let summary = summary(alert, msg, status);
+    let mut sent = Vec::new();
+    for (group_key, labels) in groups(alert, rows) {
+        let alias = dedup_key(alert, &group_key);
+        let (req, payload) = if status == AlertState::Resolved {
+            (
+                client.post(format!(
+                    "{api_url}/v2/alerts/{alias}/close?identifierType=alias"
+                )),
+                json::json!({ "source": SOURCE }),
+            )
+        } else {
+            (
+                client.post(format!("{api_url}/v2/alerts")),
+                opsgenie_alert(opsgenie, &alias, &summary, msg, labels),
+            )
+        };
+        let req = req.header(AUTHORIZATION, format!("GenieKey {}", opsgenie.api_key));
+        sent.push(send(req, &payload).await?);
+    }
+    Ok(joined(sent))
+}
+
+async fn send(req: RequestBuilder, payload: &Value) -> Result<Sent, anyhow::Error> {
+    let req = req
+        .header(CONTENT_TYPE, "application/json")
+        .body(json::to_string(payload)?);
+    let resp = req.send().await?;
+    let resp_status = resp.status();
+    if !resp_status.is_success() {
+        return Err(StatusError::from_response(resp).await.into());
+    }
+    let resp_body = resp.text().await?;
+    Ok(Sent {
+        status_code: Some(resp_status.as_u16()),
+        response: format!("sent status: {}, body: {}", resp_status, resp_body),
+    })
+}
+
+/// Merges the responses of the events sent for the groups, the status is the
+/// status of the last event
+fn joined(sent: Vec<Sent>) -> Sent {
+    Sent {
+        status_code: sent.last().and_then(|s| s.status_code),
+        response: sent
+            .into_iter()
+            .map(|s| s.response)
+            .collect::<Vec<_>>()
+            .join("; "),
+    }
+}
+
+/// Splits the rows into the groups of the alert, there is a single group
+/// when the alert has no group by fields or no rows
+/// First row of each group of the rows, enough to send the same events again
+pub(super) fn group_rows(alert: &Alert, rows: &[Map<String, Value>]) -> Vec<Map<String, Value>> {
+    let group_by = alert.get_group_by_fields(rows.first());
+    let mut keys = HashSet::new();
+    rows.iter()
+        .filter(|row| keys.insert(group_of(row, &group_by).0))
+        .cloned()
+        .collect()
+}
+
+fn groups(alert: &Alert, rows: &[Map<String, Value>]) -> Vec<(String, Map<String, Value>)> {
+    let group_by = alert.get_group_by_fields(rows.first());
+    let mut groups: Vec<(String, Map<String, Value>)> = Vec::new();
+    for row in rows {
+        let (key, labels) = group_of(row, &group_by);
+        if !groups.iter().any(|(k, _)| *k == key) {
+            groups.push((key, labels));
+        }
+    }
+    if groups.is_empty() {
+        groups.push((String::new(), Map::new()));
+    }
+    groups
+}
+
+/// Stable key of a group of the alert, used as PagerDuty dedup key and
+/// Opsgenie alias
+fn dedup_key(alert: &Alert, group_key: &str) -> String {
+    md5::hash(&format!(
+        "{}/{}/{}",
+        alert.org_id,
+        alert.get_unique_key(),
+        group_key
+    ))
+}
+
+fn title(alert: &Alert, status: AlertState) -> String {
+    format!("[{}] {}", status.to_string().to_uppercase(), alert.name)
+}
+
+/// Title followed by the first line of the rendered template
+fn summary(alert: &Alert, msg: &str, status: AlertState) -> String {
+    let title = title(alert, status);
+    match msg.lines().map(str::trim).find(|l| !l.is_empty()) {
+        Some(line) => format!("{title}: {line}"),
+        None => title,
+    }
+}
+
+fn truncate(v: &str, len: usize) -> String {
+    v.chars().take(len).collect()
+}
+
+fn color(status: AlertState) -> &'static str {
+    match status {
+        AlertState::Resolved => "#2eb886",
+        _ => "#d00000",
+    }
+}
+
+fn slack_payload(channel: Option<&str>, title: &str, msg: &str, status: AlertState) -> Value {
+    let mut payload = json::json!({
+        "text": title,
+        "attachments": [{
+            "color": color(status),
+            "text": msg,
+            "mrkdwn_in": ["text"],
+        }],
+    });
+    if let Some(channel) = channel {
+        payload["channel"] = Value::String(channel.to_string());
+    }
+    payload
+}
+
+fn teams_payload(title: &str, msg: &str, status: AlertState) -> Value {
+    json::json!({
+        "type": "message",
+        "attachments": [{
+            "contentType": "application/vnd.microsoft.card.adaptive",
+            "content": {
+                "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                "type": "AdaptiveCard",
+                "version": "1.4",
+                "body": [
+                    {
+                        "type": "TextBlock",
+                        "size": "Medium",
+                        "weight": "Bolder",
+                        "color": if status == AlertState::Resolved { "Good" } else { "Attention" },
+                        "text": title,
+                    },
+                    {
+                        "type": "TextBlock",
+                        "wrap": true,
+                        "text": msg,
+                    },
+                ],
+            },
+        }],
+    })
+}
+
+fn pagerduty_event(
+    pagerduty: &PagerDuty,
+    dedup_key: &str,
+    summary: &str,
+    source: &str,
+    msg: &str,
+    mut details: Map<String, Value>,
+    status: AlertState,
+) -> Value {
+    if status == AlertState::Resolved {
+        return json::json!({
+            "routing_key": pagerduty.routing_key,
+            "event_action": "resolve",
+            "dedup_key": dedup_key,
+        });
+    }
+    details.insert("message".to_string(), Value::String(msg.to_string()));
+    json::json!({
+        "routing_key": pagerduty.routing_key,
+        "event_action": "trigger",
+        "dedup_key": dedup_key,
+        "payload": {
+            "summary": truncate(summary, PAGERDUTY_SUMMARY_LEN),
+            "source": source,
+            "severity": pagerduty.severity,
+            "custom_details": details,
+        },
+    })
+}
+
+fn opsgenie_alert(
+    opsgenie: &Opsgenie,
+    alias: &str,
+    summary: &str,
+    msg: &str,
+    labels: Map<String, Value>,
+) -> Value {
+    // the details of an opsgenie alert are strings
+    let details = labels
+        .into_iter()
+        .map(|(k, v)| {
+            let v = match v {
+                Value::String(v) => v,
+                v => v.to_string(),
+            };
+            (k, Value::String(v))
+        })
+        .collect::<Map<_, _>>();
+    json::json!({
+        "message": truncate(summary, OPSGENIE_MESSAGE_LEN),
+        "alias": alias,
+        "description": truncate(msg, OPSGENIE_DESCRIPTION_LEN),
+        "priority": opsgenie.priority,
+        "source": SOURCE,
+        "details": details,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        // echo -n '1700000000.{"a":1}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            signature("secret", 1700000000, r#"{"a":1}"#),
+            "t=1700000000,v1=49f24e537407743fa4a0242bb63b94b9a47ee99cbbe071ccd8a22550ae411686"
+        );
+    }
+
+    #[test]
+    fn test_pagerduty_event() {
+        let pagerduty = PagerDuty {
+            routing_key: "key".to_string(),
+            severity: "warning".to_string(),
+            events_url: None,
+        };
+        let mut labels = Map::new();
+        labels.insert("host".to_string(), Value::String("web-1".to_string()));
+        let event = pagerduty_event(
+            &pagerduty,
+            "abc",
+            &"x".repeat(2000),
+            "logs/k8s/errors",
+            "msg",
+            labels.clone(),
+            AlertState::Firing,
+        );
+        assert_eq!(event["event_action"], "trigger");
+        assert_eq!(event["dedup_key"], "abc");
+        assert_eq!(event["payload"]["severity"], "warning");
+        assert_eq!(event["payload"]["custom_details"]["host"], "web-1");
+        assert_eq!(
+            event["payload"]["summary"].as_str().unwrap().len(),
+            PAGERDUTY_SUMMARY_LEN
+        );
+
+        let event = pagerduty_event(
+            &pagerduty,
+            "abc",
+            "summary",
+            "logs/k8s/errors",
+            "msg",
+            labels,
+            AlertState::Resolved,
+        );
+        assert_eq!(event["event_action"], "resolve");
+        assert_eq!(event["dedup_key"], "abc");
+        assert!(event.get("payload").is_none());
+    }
+
+    #[test]
+    fn test_opsgenie_alert() {
+        let opsgenie = Opsgenie {
+            api_key: "key".to_string(),
+            api_url: None,
+            priority: "P2".to_string(),
+        };
+        let mut labels = Map::new();
+        labels.insert("code".to_string(), json::json!(500));
+        let payload = opsgenie_alert(&opsgenie, "abc", "summary", "msg", labels);
+        assert_eq!(payload["alias"], "abc");
+        assert_eq!(payload["priority"], "P2");
+        assert_eq!(payload["details"]["code"], "500");
+    }
+
+    #[test]
+    fn test_slack_payload() {
+        let payload = slack_payload(
+            Some("#ops"),
+            "[RESOLVED] errors",
+            "msg",
+            AlertState::Resolved,
+        );
+        assert_eq!(payload["channel"], "#ops");
+        assert_eq!(payload["attachments"][0]["color"], "#2eb886");
+        let payload = slack_payload(None, "[FIRING] errors", "msg", AlertState::Firing);
+        assert!(payload.get("channel").is_none());
+        assert_eq!(payload["attachments"][0]["color"], "#d00000");
+    }
+}
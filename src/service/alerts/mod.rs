@@ -38,10 +38,21 @@ use super::promql;
 use crate::service::search as SearchService;
 
 pub mod alert;
+pub mod anomaly;
+pub mod backtest;
+pub mod bundle;
+pub mod composite;
+pub mod deliveries;
 pub mod derived_streams;
 pub mod destinations;
+pub mod integrations;
+pub mod notification_policies;
+pub mod notifier;
+pub mod prometheus_rules;
 pub mod scheduler;
+pub mod silences;
 pub mod templates;
+pub mod templating;
 
 #[async_trait]
 pub trait QueryConditionExt: Sync + Send + 'static {
@@ -146,9 +157,9 @@ impl QueryConditionExt for QueryCondition {
                             .unwrap()
                 };
                 let end = end_time;
-                let condition = self.promql_condition.as_ref().unwrap();
-                let req = promql::MetricsQueryRequest {
-                    query: format!(
+                let query = match self.promql_condition.as_ref() {
+                    // anomaly alerts need the value of every series
+                    Some(condition) if self.anomaly_detection.is_none() => format!(
                         "({}) {} {}",
                         v,
                         match &condition.operator {
@@ -157,6 +168,10 @@ impl QueryConditionExt for QueryCondition {
                         },
                         to_float(&condition.value)
                     ),
+                    _ => v.to_string(),
+                };
+                let req = promql::MetricsQueryRequest {
+                    query,
                     start,
                     end,
                     step: std::cmp::max(
@@ -183,7 +198,11 @@ impl QueryConditionExt for QueryCondition {
                 // TODO calculate the sample in a row, suddenly a sample can be ignored
                 let value = value
                     .into_iter()
-                    .filter(|f| f.samples.len() >= trigger_condition.threshold as usize)
+                    .filter(|f| {
+                        !f.samples.is_empty()
+                            && (self.anomaly_detection.is_some()
+                                || f.samples.len() >= trigger_condition.threshold as usize)
+                    })
                     .collect::<Vec<_>>();
                 if !value.is_empty() {
                     eval_results.data = Some(
@@ -207,6 +226,11 @@ impl QueryConditionExt for QueryCondition {
                 }
                 return Ok(eval_results);
             }
+            QueryType::Composite => {
+                return Err(anyhow::anyhow!(
+                    "Composite query condition is evaluated on the states of its alerts"
+                ));
+            }
         };
 
         let stream_names = match resolve_stream_names(&sql) {
@@ -369,6 +393,9 @@ impl QueryConditionExt for QueryCondition {
                     skip_wal: false,
                     streaming_output: false,
                     streaming_id: None,
+                    use_cursor: false,
+                    cursor: None,
+                    params: Default::default(),
                 },
                 encoding: config::meta::search::RequestEncoding::Empty,
                 regions: vec![],
@@ -420,7 +447,8 @@ impl QueryConditionExt for QueryCondition {
         });
         log::debug!("alert resp hits len:{:#?}", records.len());
         eval_results.query_took = Some(resp.took as i64);
-        eval_results.data = if self.search_event_type.is_none() {
+        eval_results.data = if self.search_event_type.is_none() && self.anomaly_detection.is_none()
+        {
             let threshold = trigger_condition.threshold as usize;
             match trigger_condition.operator {
                 Operator::EqualTo => (records.len() == threshold).then_some(records),
@@ -557,7 +585,16 @@ async fn build_sql(
                 ));
             }
         };
-        build_expr(&agg.having, "alert_agg_value", data_type)?
+        // anomaly alerts need the value of every group, not only the ones
+        // over the threshold
+        if query_condition.anomaly_detection.is_some() {
+            String::new()
+        } else {
+            format!(
+                "HAVING {}",
+                build_expr(&agg.having, "alert_agg_value", data_type)?
+            )
+        }
     };
 
     let func_expr = match agg.function {
@@ -577,7 +614,7 @@ async fn build_sql(
     if let Some(group) = agg.group_by.as_ref() {
         if !group.is_empty() {
             sql = format!(
-                "SELECT {}, {} AS alert_agg_value, MIN({}) as zo_sql_min_time, MAX({}) AS zo_sql_max_time FROM \"{}\" {} GROUP BY {} HAVING {}",
+                "SELECT {}, {} AS alert_agg_value, MIN({}) as zo_sql_min_time, MAX({}) AS zo_sql_max_time FROM \"{}\" {} GROUP BY {} {}",
                 group.join(", "),
                 func_expr,
                 TIMESTAMP_COL_NAME,
@@ -591,7 +628,7 @@ async fn build_sql(
     }
     if sql.is_empty() {
         sql = format!(
-            "SELECT {} AS alert_agg_value, MIN({}) as zo_sql_min_time, MAX({}) AS zo_sql_max_time FROM \"{}\" {} HAVING {}",
+            "SELECT {} AS alert_agg_value, MIN({}) as zo_sql_min_time, MAX({}) AS zo_sql_max_time FROM \"{}\" {} {}",
             func_expr, TIMESTAMP_COL_NAME, TIMESTAMP_COL_NAME, stream_name, where_sql, having_expr
         );
     }
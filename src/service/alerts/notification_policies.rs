@@ -0,0 +1,70 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use config::meta::alerts::routing::NotificationPolicy;
+
+use crate::service::db;
+
+#[derive(Debug, thiserror::Error)]
+pub enum NotificationPolicyError {
+    #[error("Invalid notification policy: {0}")]
+    Invalid(String),
+    #[error("Notification policy destination {0} not found")]
+    DestinationNotFound(String),
+    #[error("Notification policy not found")]
+    NotFound,
+    #[error("Error saving notification policy: {0}")]
+    Db(#[from] anyhow::Error),
+}
+
+pub async fn set(
+    org_id: &str,
+    policy: NotificationPolicy,
+) -> Result<NotificationPolicy, NotificationPolicyError> {
+    policy
+        .validate()
+        .map_err(NotificationPolicyError::Invalid)?;
+    for dest in policy.destinations() {
+        match db::alerts::destinations::get(org_id, dest).await {
+            Ok(d) if d.is_alert_destinations() => {}
+            _ => {
+                return Err(NotificationPolicyError::DestinationNotFound(
+                    dest.to_string(),
+                ));
+            }
+        }
+    }
+    db::alerts::notification_policies::set(org_id, &policy).await?;
+    Ok(policy)
+}
+
+pub async fn get(org_id: &str) -> Result<NotificationPolicy, NotificationPolicyError> {
+    db::alerts::notification_policies::get(org_id)
+        .await
+        .map_err(|_| NotificationPolicyError::NotFound)
+}
+
+pub async fn delete(org_id: &str) -> Result<(), NotificationPolicyError> {
+    if db::alerts::notification_policies::get(org_id)
+        .await
+        .is_err()
+    {
+        return Err(NotificationPolicyError::NotFound);
+    }
+    db::alerts::notification_policies::delete(org_id).await?;
+    // the groups are only flushed for the orgs with a policy
+    db::alerts::notifier::delete_org(org_id).await?;
+    Ok(())
+}
@@ -0,0 +1,487 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Notification layer of the orgs with a notification policy, between the
+//! evaluation of a scheduled alert and its destinations.
+//!
+//! Every firing group of an alert becomes an alert instance with labels: the
+//! alert name, the stream, the context attributes and the group by values.
+//! The routes of the policy matching the labels put the instance into
+//! aggregation groups, which are notified after `group_wait`, then every
+//! `group_interval` when instances were added or resolved, and every
+//! `repeat_interval` otherwise. The same instance firing again is only
+//! notified again by the repeat. Instances muted by an inhibition rule are
+//! dropped.
+//!
+//! The groups and the firing instances are saved in the meta store, so they
+//! survive restarts and are shared by the alert manager nodes, and changed
+//! under a distributed lock of the org. [`flush`] claims the due groups of an
+//! org under the lock and hands their notifications to the delivery queue
+//! after releasing it, a group is only marked as sent once they are accepted,
+//! otherwise it is notified again by the next flush. The groups of an org are
+//! dropped with its notification policy.
+
+use std::collections::{HashMap, HashSet, hash_map::Entry};
+
+use config::{
+    meta::alerts::{
+        alert::Alert,
+        routing::{
+            AggrGroup, AlertInstance, FiringInstance, Labels, NotificationPolicy, RouteOpts,
+        },
+        state::{AlertState, group_of},
+    },
+    utils::{
+        json::{Map, Value},
+        time::second_micros,
+    },
+};
+use infra::dist_lock;
+use once_cell::sync::Lazy;
+use tokio::sync::Mutex;
+
+use super::alert::notify_destinations;
+use crate::{common::infra::config::NOTIFICATION_POLICIES, service::db};
+
+const NOTIFIER_LOCK_KEY: &str = "/alert_notifier/lock";
+
+/// Serializes the changes of the tasks of this node, the distributed lock is
+/// not taken in local mode
+static LOCAL_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Runs `f` under the lock of the org, the groups and the firing instances of
+/// an org are only changed under it
+async fn with_lock<T>(
+    org_id: &str,
+    f: impl Future<Output = Result<T, anyhow::Error>>,
+) -> Result<T, anyhow::Error> {
+    let _guard = LOCAL_LOCK.lock().await;
+    let locker = dist_lock::lock(&format!("{NOTIFIER_LOCK_KEY}/{org_id}"), 0).await?;
+    let ret = f.await;
+    dist_lock::unlock(&locker).await?;
+    ret
+}
+
+/// Whether the notifications of the org go through its notification policy
+pub fn has_policy(org_id: &str) -> bool {
+    NOTIFICATION_POLICIES.contains_key(org_id)
+}
+
+/// Puts the firing groups of the alert into the aggregation groups of the
+/// matching routes. Returns a summary for the triggers usage stream, the
+/// notifications are sent later by [`flush`].
+pub async fn dispatch(
+    alert: &Alert,
+    rows: &[Map<String, Value>],
+    group_by: &[String],
+    now: i64,
+) -> Result<String, anyhow::Error> {
+    let Some(policy) = NOTIFICATION_POLICIES
+        .get(&alert.org_id)
+        .map(|v| v.value().clone())
+    else {
+        return Ok(String::new());
+    };
+    with_lock(
+        &alert.org_id,
+        dispatch_instances(&policy, alert, rows, group_by, now),
+    )
+    .await
+}
+
+async fn dispatch_instances(
+    policy: &NotificationPolicy,
+    alert: &Alert,
+    rows: &[Map<String, Value>],
+    group_by: &[String],
+    now: i64,
+) -> Result<String, anyhow::Error> {
+    let expires_at = now + instance_ttl(alert);
+
+    // rows of the same group form one instance
+    let mut instances: HashMap<String, (Labels, Vec<Map<String, Value>>)> = HashMap::new();
+    for row in rows {
+        let (_, group_labels) = group_of(row, group_by);
+        let labels = labels_of(alert, &group_labels);
+        let fp = fingerprint(alert, &labels);
+        instances
+            .entry(fp)
+            .or_insert_with(|| (labels, Vec::new()))
+            .1
+            .push(row.clone());
+    }
+
+    let mut firing = db::alerts::notifier::get_firing(&alert.org_id).await?;
+    firing.retain(|_, instance| instance.expires_at > now);
+    for (fp, (labels, _)) in instances.iter() {
+        firing.insert(
+            fp.to_string(),
+            FiringInstance {
+                labels: labels.clone(),
+                expires_at,
+            },
+        );
+    }
+    db::alerts::notifier::set_firing(&alert.org_id, &firing).await?;
+
+    let mut groups: HashMap<String, AggrGroup> = HashMap::new();
+    let (mut routed, mut inhibited) = (0, 0);
+    for (fp, (labels, rows)) in instances {
+        if is_inhibited(policy, &firing, &fp, &labels) {
+            log::info!(
+                "[NOTIFIER] alert {}/{} instance {fp} is inhibited",
+                alert.org_id,
+                alert.name
+            );
+            inhibited += 1;
+            continue;
+        }
+        for route in policy.route.find(&labels) {
+            let group = match groups.entry(group_key(&alert.org_id, &route, &labels)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let group = db::alerts::notifier::get_group(&alert.org_id, entry.key())
+                        .await?
+                        .unwrap_or_else(|| AggrGroup {
+                            org_id: alert.org_id.clone(),
+                            next_flush_at: now + second_micros(route.group_wait),
+                            route,
+                            instances: HashMap::new(),
+                            last_sent_at: None,
+                            has_changes: false,
+                        });
+                    entry.insert(group)
+                }
+            };
+            let instance = AlertInstance {
+                stream_type: alert.stream_type,
+                stream_name: alert.stream_name.clone(),
+                alert_name: alert.name.clone(),
+                labels: labels.clone(),
+                rows: rows.clone(),
+                resolved: false,
+                expires_at,
+            };
+            match group.instances.insert(fp.clone(), instance) {
+                Some(prev) if !prev.resolved => {}
+                _ => group.has_changes = true,
+            }
+        }
+        routed += 1;
+    }
+    for (key, group) in groups.iter() {
+        db::alerts::notifier::set_group(key, group).await?;
+    }
+    Ok(format!(
+        "queued {routed} alerts for the notification policy, {inhibited} inhibited"
+    ))
+}
+
+/// Marks the resolved groups of the alert, `rows` holds the group by values
+/// of each resolved group.
+pub async fn resolve(alert: &Alert, rows: &[Map<String, Value>]) -> Result<(), anyhow::Error> {
+    with_lock(&alert.org_id, resolve_instances(alert, rows)).await
+}
+
+async fn resolve_instances(
+    alert: &Alert,
+    rows: &[Map<String, Value>],
+) -> Result<(), anyhow::Error> {
+    let fps: HashSet<String> = rows
+        .iter()
+        .map(|row| fingerprint(alert, &labels_of(alert, row)))
+        .collect();
+
+    let mut firing = db::alerts::notifier::get_firing(&alert.org_id).await?;
+    let num_firing = firing.len();
+    firing.retain(|fp, _| !fps.contains(fp));
+    if firing.len() != num_firing {
+        db::alerts::notifier::set_firing(&alert.org_id, &firing).await?;
+    }
+
+    for (db_key, mut group) in db::alerts::notifier::list_groups(&alert.org_id).await? {
+        let mut changed = false;
+        for fp in fps.iter() {
+            if alert.trigger_condition.notify_on_resolve {
+                if let Some(instance) = group.instances.get_mut(fp) {
+                    instance.resolved = true;
+                    group.has_changes = true;
+                    changed = true;
+                }
+            } else if group.instances.remove(fp).is_some() {
+                changed = true;
+            }
+        }
+        if !changed {
+            continue;
+        }
+        if group.instances.is_empty() {
+            db::alerts::notifier::delete_group(&db_key).await?;
+        } else {
+            db::alerts::notifier::update_group(&db_key, &group).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Sends the notifications of the groups which are due, org by org
+pub async fn flush(now: i64) {
+    let orgs = NOTIFICATION_POLICIES
+        .iter()
+        .map(|v| v.key().to_string())
+        .collect::<Vec<_>>();
+    for org_id in orgs {
+        if let Err(e) = flush_org(&org_id, now).await {
+            log::error!("[NOTIFIER] Error flushing the aggregation groups of org {org_id}: {e}");
+        }
+    }
+}
+
+async fn flush_org(org_id: &str, now: i64) -> Result<(), anyhow::Error> {
+    let due = with_lock(org_id, take_due_groups(org_id, now)).await?;
+    // the lock isn't held while sending, the groups were claimed by moving
+    // their next flush
+    for (db_key, group) in due {
+        let sent = notify(&group, now).await;
+        with_lock(org_id, finish_group(&db_key, &group, sent, now)).await?;
+    }
+    Ok(())
+}
+
+/// Claims the due groups of the org, returns a copy of the claimed groups
+/// holding the instances to send
+async fn take_due_groups(
+    org_id: &str,
+    now: i64,
+) -> Result<Vec<(String, AggrGroup)>, anyhow::Error> {
+    let policy = NOTIFICATION_POLICIES.get(org_id).map(|v| v.value().clone());
+    let mut firing = None;
+    let mut due = Vec::new();
+    for (db_key, mut group) in db::alerts::notifier::list_groups(org_id).await? {
+        if group.next_flush_at > now {
+            continue;
+        }
+        group
+            .instances
+            .retain(|_, instance| instance.resolved || instance.expires_at > now);
+        let repeat_due = group
+            .last_sent_at
+            .is_none_or(|t| now - t >= second_micros(group.route.repeat_interval));
+        let mut claimed = false;
+        if group.has_changes || repeat_due {
+            // the source of an inhibition may have started firing after
+            // the instance joined the group
+            let firing = match firing.as_mut() {
+                Some(firing) => firing,
+                None => firing.insert(db::alerts::notifier::get_firing(org_id).await?),
+            };
+            let instances = group
+                .instances
+                .iter()
+                .filter(|(fp, instance)| {
+                    instance.resolved
+                        || !policy.as_ref().is_some_and(|policy| {
+                            is_inhibited(policy, firing, fp, &instance.labels)
+                        })
+                })
+                .map(|(fp, instance)| (fp.to_string(), instance.clone()))
+                .collect::<HashMap<_, _>>();
+            if instances.is_empty() {
+                group.last_sent_at = Some(now);
+            } else {
+                due.push((
+                    db_key.clone(),
+                    AggrGroup {
+                        instances,
+                        ..group.clone()
+                    },
+                ));
+                claimed = true;
+            }
+        }
+        // the resolved instances of a claimed group are removed once sent
+        if !claimed {
+            group.instances.retain(|_, instance| !instance.resolved);
+        }
+        group.has_changes = false;
+        group.next_flush_at = now + second_micros(group.route.group_interval);
+        if group.instances.is_empty() {
+            db::alerts::notifier::delete_group(&db_key).await?;
+        } else {
+            db::alerts::notifier::update_group(&db_key, &group).await?;
+        }
+    }
+    Ok(due)
+}
+
+/// Records the outcome of sending a claimed group, the group may have
+/// changed while it was sent
+async fn finish_group(
+    db_key: &str,
+    sent_group: &AggrGroup,
+    sent: bool,
+    now: i64,
+) -> Result<(), anyhow::Error> {
+    let Some(mut group) = db::alerts::notifier::load_group(db_key).await? else {
+        return Ok(());
+    };
+    if sent {
+        group.last_sent_at = Some(now);
+        group
+            .instances
+            .retain(|fp, instance| !instance.resolved || !sent_group.instances.contains_key(fp));
+    } else {
+        // the changes are notified again by the next flush
+        group.has_changes = true;
+    }
+    if group.instances.is_empty() {
+        db::alerts::notifier::delete_group(db_key).await?;
+    } else {
+        db::alerts::notifier::update_group(db_key, &group).await?;
+    }
+    Ok(())
+}
+
+/// Sends one notification per alert and status with the rows of all the
+/// instances of the group, returns whether all of them were delivered or
+/// queued for retry
+async fn notify(group: &AggrGroup, now: i64) -> bool {
+    let mut notifications: HashMap<(String, bool), Vec<&AlertInstance>> = HashMap::new();
+    for instance in group.instances.values() {
+        let key = format!(
+            "{}/{}/{}",
+            instance.stream_type, instance.stream_name, instance.alert_name
+        );
+        notifications
+            .entry((key, instance.resolved))
+            .or_default()
+            .push(instance);
+    }
+
+    let mut sent = true;
+    for ((_, resolved), instances) in notifications {
+        let first = instances[0];
+        let alert = match db::alerts::alert::get_by_name(
+            &group.org_id,
+            first.stream_type,
+            &first.stream_name,
+            &first.alert_name,
+        )
+        .await
+        {
+            Ok(Some(alert)) => alert,
+            // the alert was deleted since it fired
+            Ok(None) => continue,
+            Err(e) => {
+                log::error!(
+                    "[NOTIFIER] Error getting alert {}/{}: {e}",
+                    group.org_id,
+                    first.alert_name
+                );
+                sent = false;
+                continue;
+            }
+        };
+        let status = if resolved {
+            AlertState::Resolved
+        } else {
+            AlertState::Firing
+        };
+        let rows = instances
+            .iter()
+            .flat_map(|instance| instance.rows.iter().cloned())
+            .collect::<Vec<_>>();
+        let destinations = if group.route.destinations.is_empty() {
+            &alert.destinations
+        } else {
+            &group.route.destinations
+        };
+        if let Err(e) =
+            notify_destinations(&alert, destinations, &rows, now, None, now, status).await
+        {
+            log::error!(
+                "[NOTIFIER] Error sending {status} notification of alert {}/{}: {e}",
+                alert.org_id,
+                alert.name
+            );
+            sent = false;
+        }
+    }
+    sent
+}
+
+/// Labels of an alert instance, the group by values override the context
+/// attributes of the same name
+pub fn labels_of(alert: &Alert, group_labels: &Map<String, Value>) -> Labels {
+    let mut labels = Labels::new();
+    labels.insert("alertname".to_string(), alert.name.clone());
+    labels.insert("stream_type".to_string(), alert.stream_type.to_string());
+    labels.insert("stream_name".to_string(), alert.stream_name.clone());
+    if let Some(attrs) = alert.context_attributes.as_ref() {
+        for (k, v) in attrs.iter() {
+            labels.insert(k.to_string(), v.to_string());
+        }
+    }
+    for (k, v) in group_labels.iter() {
+        let v = match v {
+            Value::String(v) => v.to_string(),
+            v => v.to_string(),
+        };
+        labels.insert(k.to_string(), v);
+    }
+    labels
+}
+
+fn fingerprint(alert: &Alert, labels: &Labels) -> String {
+    let labels = labels
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>();
+    format!("{}/{}", alert.get_unique_key(), labels.join(","))
+}
+
+fn group_key(org_id: &str, route: &RouteOpts, labels: &Labels) -> String {
+    let values = route
+        .group_by
+        .iter()
+        .map(|name| format!("{name}={}", labels.get(name).cloned().unwrap_or_default()))
+        .collect::<Vec<_>>();
+    format!("{org_id}/{}/{}", route.id, values.join(","))
+}
+
+fn is_inhibited(
+    policy: &NotificationPolicy,
+    firing: &HashMap<String, FiringInstance>,
+    fp: &str,
+    labels: &Labels,
+) -> bool {
+    firing
+        .iter()
+        .any(|(source_fp, source)| source_fp != fp && policy.is_inhibited(&source.labels, labels))
+}
+
+/// A firing instance expires when the alert missed two evaluations
+fn instance_ttl(alert: &Alert) -> i64 {
+    let next_run = [
+        alert.trigger_condition.frequency,
+        alert.trigger_condition.period * 60,
+        alert.trigger_condition.silence * 60,
+        60,
+    ]
+    .into_iter()
+    .max()
+    .unwrap_or_default();
+    second_micros(next_run * 2)
+}
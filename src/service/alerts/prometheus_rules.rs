@@ -0,0 +1,304 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Translation of Prometheus and Thanos alerting rule files into PromQL alerts
+
+use std::collections::HashMap;
+
+use config::{
+    meta::{
+        alerts::{Condition, Operator, QueryCondition, QueryType, TriggerCondition, alert::Alert},
+        stream::StreamType,
+    },
+    utils::{json, time::parse_milliseconds},
+};
+use promql_parser::{
+    parser::{self, Expr, NumberLiteral, VectorSelector, token},
+    util::{ExprVisitor, walk_expr},
+};
+use serde::Deserialize;
+
+/// Evaluation interval of a group without interval, as in Prometheus
+const DEFAULT_INTERVAL_SECS: i64 = 60;
+
+/// (minutes) repeat interval of the notifications of a firing alert, the
+/// default of Alertmanager
+const REPEAT_INTERVAL: i64 = 240;
+
+#[derive(Debug, Deserialize)]
+pub struct RuleFile {
+    #[serde(default)]
+    pub groups: Vec<RuleGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RuleGroup {
+    pub name: String,
+    #[serde(default)]
+    pub interval: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+/// Alerting or recording rule, the fields specific to Thanos are ignored
+#[derive(Debug, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    pub alert: Option<String>,
+    #[serde(default)]
+    pub record: Option<String>,
+    pub expr: String,
+    #[serde(rename = "for")]
+    #[serde(default)]
+    pub for_duration: Option<String>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    #[serde(default)]
+    pub annotations: HashMap<String, String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Translation {
+    /// Alerts with the name of their folder
+    pub alerts: Vec<(String, Alert)>,
+    /// Rules which are not translated, with the reason
+    pub skipped: Vec<String>,
+}
+
+/// Translates the alerting rules of the file. An alert goes to the folder
+/// named after its group unless a `folder` is given, and notifies the
+/// `destinations`. The labels of a rule become the context attributes of the
+/// alert, its summary and description annotations the description.
+pub fn translate(file: RuleFile, folder: Option<&str>, destinations: &[String]) -> Translation {
+    let mut translation = Translation::default();
+    for group in file.groups {
+        let frequency = match group.interval.as_deref().map(parse_seconds) {
+            None => DEFAULT_INTERVAL_SECS,
+            Some(Ok(v)) if v > 0 => v,
+            Some(_) => {
+                translation.skipped.push(format!(
+                    "group {}: invalid interval {}",
+                    group.name,
+                    group.interval.unwrap_or_default()
+                ));
+                continue;
+            }
+        };
+        let folder = folder.unwrap_or(&group.name);
+        for rule in group.rules {
+            let Some(name) = rule.alert.clone() else {
+                translation.skipped.push(format!(
+                    "group {}: recording rule {} is not an alert",
+                    group.name,
+                    rule.record.unwrap_or_default()
+                ));
+                continue;
+            };
+            match translate_rule(rule, frequency, destinations) {
+                Ok(alert) => translation.alerts.push((folder.to_string(), alert)),
+                Err(e) => translation
+                    .skipped
+                    .push(format!("group {}: alert {name}: {e}", group.name)),
+            }
+        }
+    }
+    translation
+}
+
+fn translate_rule(rule: Rule, frequency: i64, destinations: &[String]) -> Result<Alert, String> {
+    let expr = parser::parse(&rule.expr)?;
+    let mut visitor = FirstMetric::default();
+    // the visitor never fails
+    let _ = walk_expr(&mut visitor, &expr);
+    let Some(stream_name) = visitor.name else {
+        return Err("the expression has no metric".to_string());
+    };
+    let for_duration = match rule.for_duration.as_deref() {
+        Some(v) => parse_seconds(v).map_err(|_| format!("invalid for duration {v}"))?,
+        None => 0,
+    };
+    // Split `expr > 10` into the query and the condition of the alert, the
+    // other expressions fire on every series they return
+    let (promql, promql_condition) = match split_condition(&expr) {
+        Some((query, condition)) => (query, Some(condition)),
+        None => (rule.expr.trim().to_string(), None),
+    };
+    let description = ["summary", "description"]
+        .iter()
+        .filter_map(|k| rule.annotations.get(*k))
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    Ok(Alert {
+        name: rule.alert.unwrap_or_default(),
+        stream_type: StreamType::Metrics,
+        stream_name,
+        query_condition: QueryCondition {
+            query_type: QueryType::PromQL,
+            promql: Some(promql),
+            promql_condition,
+            ..Default::default()
+        },
+        trigger_condition: TriggerCondition {
+            period: std::cmp::max(1, frequency / 60),
+            operator: Operator::GreaterThanEquals,
+            threshold: 1,
+            frequency,
+            silence: REPEAT_INTERVAL,
+            for_duration,
+            notify_on_resolve: true,
+            ..Default::default()
+        },
+        destinations: destinations.to_vec(),
+        context_attributes: Some(rule.labels).filter(|labels| !labels.is_empty()),
+        description,
+        enabled: true,
+        ..Default::default()
+    })
+}
+
+/// Splits a comparison with a number into the compared expression and the
+/// condition of the alert
+fn split_condition(expr: &Expr) -> Option<(String, Condition)> {
+    let Expr::Binary(binary) = expr else {
+        return None;
+    };
+    if binary.return_bool() || !binary.op.is_comparison_operator() {
+        return None;
+    }
+    let Expr::NumberLiteral(NumberLiteral { val }) = binary.rhs.as_ref() else {
+        return None;
+    };
+    let operator = match binary.op.id() {
+        token::T_EQLC => Operator::EqualTo,
+        token::T_NEQ => Operator::NotEqualTo,
+        token::T_GTR => Operator::GreaterThan,
+        token::T_GTE => Operator::GreaterThanEquals,
+        token::T_LSS => Operator::LessThan,
+        token::T_LTE => Operator::LessThanEquals,
+        _ => return None,
+    };
+    let value = json::Number::from_f64(*val)?;
+    Some((
+        binary.lhs.prettify(),
+        Condition {
+            column: "value".to_string(),
+            operator,
+            value: json::Value::Number(value),
+            ignore_case: false,
+        },
+    ))
+}
+
+fn parse_seconds(v: &str) -> Result<i64, anyhow::Error> {
+    Ok((parse_milliseconds(v.trim())? / 1000) as i64)
+}
+
+/// Name of the first metric of an expression
+#[derive(Default)]
+struct FirstMetric {
+    name: Option<String>,
+}
+
+impl FirstMetric {
+    fn visit(&mut self, selector: &VectorSelector) {
+        if self.name.is_none() {
+            self.name = selector.name.clone();
+        }
+    }
+}
+
+impl ExprVisitor for FirstMetric {
+    type Error = &'static str;
+
+    fn pre_visit(&mut self, expr: &Expr) -> Result<bool, Self::Error> {
+        match expr {
+            Expr::VectorSelector(selector) => self.visit(selector),
+            Expr::MatrixSelector(selector) => self.visit(&selector.vs),
+            _ => {}
+        }
+        Ok(self.name.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"
+groups:
+  - name: api
+    interval: 30s
+    partial_response_strategy: warn
+    rules:
+      - record: job:http_errors:rate5m
+        expr: sum by (job) (rate(http_errors_total[5m]))
+      - alert: HighErrorRate
+        expr: sum by (job) (rate(http_errors_total[5m])) > 0.5
+        for: 10m
+        labels:
+          severity: page
+        annotations:
+          summary: High error rate
+          description: The API returns errors
+      - alert: ApiDown
+        expr: absent(up{job="api"})
+      - alert: Broken
+        expr: sum(
+"#;
+
+    #[test]
+    fn test_translate() {
+        let file: RuleFile = serde_yaml_ng::from_str(RULES).unwrap();
+        let translation = translate(file, None, &["slack".to_string()]);
+        assert_eq!(translation.skipped.len(), 2);
+        assert_eq!(translation.alerts.len(), 2);
+
+        let (folder, alert) = &translation.alerts[0];
+        assert_eq!(folder, "api");
+        assert_eq!(alert.name, "HighErrorRate");
+        assert_eq!(alert.stream_name, "http_errors_total");
+        assert_eq!(alert.trigger_condition.frequency, 30);
+        assert_eq!(alert.trigger_condition.period, 1);
+        assert_eq!(alert.trigger_condition.for_duration, 600);
+        let condition = alert.query_condition.promql_condition.as_ref().unwrap();
+        assert_eq!(condition.operator, Operator::GreaterThan);
+        assert_eq!(condition.value, json::json!(0.5));
+        assert!(!alert.query_condition.promql.as_ref().unwrap().contains('>'));
+        assert_eq!(
+            alert.context_attributes.as_ref().unwrap()["severity"],
+            "page"
+        );
+        assert_eq!(
+            alert.description,
+            "High error rate\n\nThe API returns errors"
+        );
+        assert_eq!(alert.destinations, vec!["slack"]);
+
+        let (_, alert) = &translation.alerts[1];
+        assert_eq!(alert.stream_name, "up");
+        assert!(alert.query_condition.promql_condition.is_none());
+        assert_eq!(
+            alert.query_condition.promql.as_deref(),
+            Some(r#"absent(up{job="api"})"#)
+        );
+
+        let file: RuleFile = serde_yaml_ng::from_str(RULES).unwrap();
+        let translation = translate(file, Some("prometheus"), &[]);
+        assert!(translation.alerts.iter().all(|(f, _)| f == "prometheus"));
+    }
+}
@@ -20,8 +20,12 @@ use config::{
     cluster::LOCAL_NODE,
     get_config,
     meta::{
-        alerts::FrequencyType,
+        alerts::{
+            FrequencyType, QueryType,
+            state::{StateChanges, group_of},
+        },
         dashboards::reports::ReportFrequencyType,
+        pipeline::components::DerivedStream,
         self_reporting::{
             error::{ErrorData, ErrorSource, PipelineError},
             usage::{TriggerData, TriggerDataStatus, TriggerDataType},
@@ -41,8 +45,10 @@ use proto::cluster_rpc;
 
 use crate::service::{
     alerts::{
-        alert::{AlertExt, get_alert_start_end_time, get_by_name, get_row_column_map},
+        alert::{AlertError, AlertExt, get_alert_start_end_time, get_by_name, get_row_column_map},
+        anomaly, composite,
         derived_streams::DerivedStreamExt,
+        notifier, silences,
     },
     dashboards::reports::SendReport,
     db::{self, alerts::alert::set_without_updating_trigger},
@@ -51,6 +57,10 @@ use crate::service::{
     self_reporting::publish_triggers_usage,
 };
 
+/// (microseconds) Longest wait of a report sent once for the end of the silence
+/// muting it, so that a silence without end, or removed, is checked again
+const SILENCED_REPORT_RECHECK: i64 = 3600 * 1_000_000;
+
 pub async fn handle_triggers(
     trace_id: &str,
     trigger: db::scheduler::Trigger,
@@ -147,6 +157,20 @@ async fn handle_alert_triggers(
         return Ok(());
     }
 
+    // Composite alerts read the states of their alerts, wait for the alerts
+    // due for an evaluation
+    if alert.query_condition.query_type == QueryType::Composite
+        && composite::has_due_alerts(&alert, now).await
+    {
+        log::debug!(
+            "[SCHEDULER trace_id {trace_id}] composite alert {} waits for its alerts",
+            &new_trigger.module_key
+        );
+        new_trigger.next_run_at += second_micros(composite::WAIT_INTERVAL_SECS);
+        db::scheduler::update_trigger(new_trigger).await?;
+        return Ok(());
+    }
+
     let trigger_data: Result<ScheduledTriggerData, json::Error> = json::from_str(&trigger.data);
     let mut trigger_data = if let Ok(trigger_data) = trigger_data {
         trigger_data
@@ -155,6 +179,7 @@ async fn handle_alert_triggers(
             period_end_time: None,
             tolerance: 0,
             last_satisfied_at: None,
+            states: Default::default(),
         }
     };
 
@@ -224,6 +249,7 @@ async fn handle_alert_triggers(
                 evaluation_took_in_secs: None,
                 source_node: Some(source_node.clone()),
                 query_took: None,
+                silenced_by: None,
             })
             .await;
             log::info!(
@@ -279,6 +305,7 @@ async fn handle_alert_triggers(
         evaluation_took_in_secs: None,
         source_node: Some(source_node),
         query_took: None,
+        silenced_by: None,
     };
 
     let evaluation_took = Instant::now();
@@ -351,8 +378,17 @@ async fn handle_alert_triggers(
         return Err(err);
     }
 
-    let trigger_results = result.unwrap();
+    let mut trigger_results = result.unwrap();
     trigger_data_stream.query_took = trigger_results.query_took;
+    // Anomaly alerts match the groups whose value deviates from their
+    // baseline, the baselines learn from every evaluation
+    let mut prev_anomaly = None;
+    if alert.query_condition.anomaly_detection.is_some() {
+        let mut model = db::alerts::anomaly::get(&new_trigger.org, &new_trigger.module_key).await?;
+        prev_anomaly = Some(model.clone());
+        trigger_results.data = anomaly::apply(&alert, &mut model, trigger_results.data.take(), now);
+        db::alerts::anomaly::set(&new_trigger.org, &new_trigger.module_key, &model).await?;
+    }
     log::debug!(
         "[SCHEDULER trace_id {trace_id}] result of alert {} evaluation matched condition: {}",
         &new_trigger.module_key,
@@ -365,6 +401,65 @@ async fn handle_alert_triggers(
             &new_trigger.module_key
         );
     }
+    // Track the state of every group of the alert. Stateful alerts notify when
+    // a group starts firing or is resolved, the silence period only delays
+    // the repeated notifications of a firing group. The states are only kept
+    // when something reads them.
+    let is_stateful = alert.trigger_condition.is_stateful();
+    let keep_states =
+        is_stateful || notifier::has_policy(&org_id) || composite::is_referenced(&alert).await;
+    let is_satisfied = trigger_results.data.is_some();
+    let prev_states = trigger_data.states.clone();
+    let rows = trigger_results.data.unwrap_or_default();
+    let group_by = alert.get_group_by_fields(rows.first());
+    let groups = rows
+        .iter()
+        .map(|row| group_of(row, &group_by))
+        .collect::<Vec<_>>();
+    let changes = if keep_states {
+        trigger_data.states.apply(
+            groups.clone(),
+            now,
+            second_micros(alert.trigger_condition.for_duration),
+            second_micros(alert.trigger_condition.silence * 60),
+        )
+    } else {
+        trigger_data.states = Default::default();
+        StateChanges::default()
+    };
+    let notify_data = if !is_stateful {
+        Some(rows).filter(|_| is_satisfied)
+    } else {
+        let rows = rows
+            .into_iter()
+            .zip(groups)
+            .filter_map(|(row, (key, _))| changes.firing.contains(&key).then_some(row))
+            .collect::<Vec<_>>();
+        Some(rows).filter(|rows| !rows.is_empty())
+    };
+    // Silences mute the firing groups they match, the states of the groups
+    // move on as usual
+    let notify_data = match notify_data {
+        Some(data) => {
+            let (data, silenced) = silences::filter_alert_rows(&alert, data, &group_by, now).await;
+            if !silenced.is_empty() {
+                log::info!(
+                    "[SCHEDULER trace_id {trace_id}] Alert notification muted by silences, org: {}, module_key: {}, silences: {}",
+                    &new_trigger.org,
+                    &new_trigger.module_key,
+                    silences::names(&silenced)
+                );
+                trigger_data_stream.silenced_by = Some(silences::names(&silenced));
+                if data.is_empty() {
+                    trigger_data_stream.status = TriggerDataStatus::Silenced;
+                }
+            }
+            Some(data).filter(|data| !data.is_empty())
+        }
+        None => None,
+    };
+    let mut notify_resolved = alert.trigger_condition.notify_on_resolve;
+
     let tolerance = match alert.trigger_condition.tolerance_in_secs {
         Some(tolerance) if tolerance > 0 => {
             let tolerance = Duration::seconds(get_rand_num_within(0, tolerance as u64) as i64)
@@ -377,7 +472,7 @@ async fn handle_alert_triggers(
         }
         _ => 0,
     };
-    if trigger_results.data.is_some() && alert.trigger_condition.silence > 0 {
+    if !is_stateful && notify_data.is_some() && alert.trigger_condition.silence > 0 {
         if alert.trigger_condition.frequency_type == FrequencyType::Cron {
             let schedule = Schedule::from_str(&alert.trigger_condition.cron)?;
             let silence =
@@ -429,12 +524,12 @@ async fn handle_alert_triggers(
     }
     trigger_data_stream.next_run_at = new_trigger.next_run_at;
 
-    if trigger_results.data.is_some() {
+    if is_satisfied {
         trigger_data.last_satisfied_at = Some(triggered_at);
     }
 
     // send notification
-    if let Some(data) = trigger_results.data {
+    if let Some(data) = notify_data {
         let vars = get_row_column_map(&data);
         // Multi-time range alerts can have multiple time ranges, hence only
         // use the main start_time (now - period) and end_time (now) for the alert evaluation.
@@ -454,10 +549,21 @@ async fn handle_alert_triggers(
         );
         trigger_data_stream.start_time = alert_start_time;
         trigger_data_stream.end_time = alert_end_time;
-        match alert
-            .send_notification(&data, trigger_results.end_time, start_time, now)
-            .await
-        {
+        // Orgs with a notification policy hand the alert over to the notifier,
+        // which groups the notifications and sends them later
+        let result = if notifier::has_policy(&org_id) {
+            notifier::dispatch(&alert, &data, &group_by, now)
+                .await
+                .map(|summary| (summary, String::new()))
+                .map_err(|e| AlertError::SendNotificationError {
+                    error_message: format!("error queueing notification: {e}"),
+                })
+        } else {
+            alert
+                .send_notification(&data, trigger_results.end_time, start_time, now)
+                .await
+        };
+        match result {
             Ok((success_msg, err_msg)) => {
                 let success_msg = success_msg.trim().to_owned();
                 let err_msg = err_msg.trim().to_owned();
@@ -515,6 +621,14 @@ async fn handle_alert_triggers(
                     trigger_data_stream.next_run_at = new_trigger.next_run_at;
                     db::scheduler::update_trigger(new_trigger).await?;
                 } else {
+                    // The evaluation is retried, the groups have to go through
+                    // the same transitions again
+                    trigger_data.states = prev_states;
+                    if let Some(model) = prev_anomaly.as_ref() {
+                        db::alerts::anomaly::set(&new_trigger.org, &new_trigger.module_key, model)
+                            .await?;
+                    }
+                    notify_resolved = false;
                     let trigger_data = json::to_string(&trigger_data).unwrap();
                     // Otherwise update its status and data only
                     db::scheduler::update_status(
@@ -534,11 +648,19 @@ async fn handle_alert_triggers(
             }
         }
     } else {
-        log::info!(
-            "[SCHEDULER trace_id {trace_id}] Alert conditions not satisfied, org: {}, module_key: {}",
-            &new_trigger.org,
-            &new_trigger.module_key
-        );
+        if is_satisfied {
+            log::info!(
+                "[SCHEDULER trace_id {trace_id}] Alert conditions satisfied but no group to notify, org: {}, module_key: {}",
+                &new_trigger.org,
+                &new_trigger.module_key
+            );
+        } else {
+            log::info!(
+                "[SCHEDULER trace_id {trace_id}] Alert conditions not satisfied, org: {}, module_key: {}",
+                &new_trigger.org,
+                &new_trigger.module_key
+            );
+        }
         // Condition did not match, store the last used end_time in the triggers
         // In the next run, the alert will be checked from the last end_time
         trigger_data.period_end_time = if should_store_last_end_time {
@@ -559,7 +681,68 @@ async fn handle_alert_triggers(
             }
         };
         trigger_data_stream.end_time = trigger_results.end_time;
-        trigger_data_stream.status = TriggerDataStatus::ConditionNotSatisfied;
+        if !is_satisfied {
+            trigger_data_stream.status = TriggerDataStatus::ConditionNotSatisfied;
+        }
+    }
+
+    // Resolved notifications are not retried, the group is already resolved
+    let resolved_rows = changes
+        .resolved
+        .into_iter()
+        .map(|group| group.labels)
+        .collect::<Vec<_>>();
+    if !resolved_rows.is_empty() && notifier::has_policy(&org_id) {
+        if let Err(e) = notifier::resolve(&alert, &resolved_rows).await {
+            log::error!(
+                "[SCHEDULER trace_id {trace_id}] Error resolving alert instances, org: {}, module_key: {}: {e}",
+                &org_id,
+                &trigger_data_stream.key
+            );
+            let prev = trigger_data_stream.error.take().unwrap_or_default();
+            trigger_data_stream.error = Some(format!("{prev} resolved: {e}").trim().to_string());
+        }
+    } else if !resolved_rows.is_empty() && notify_resolved {
+        let (resolved_rows, _) =
+            silences::filter_alert_rows(&alert, resolved_rows, &group_by, now).await;
+        // groups muted by a silence resolve quietly
+        if !resolved_rows.is_empty() {
+            match alert.send_resolved_notification(&resolved_rows, now).await {
+                Ok((success_msg, err_msg)) => {
+                    log::info!(
+                        "[SCHEDULER trace_id {trace_id}] Alert resolved notification sent, org: {}, module_key: {}",
+                        &org_id,
+                        &trigger_data_stream.key
+                    );
+                    let success_msg = success_msg.trim();
+                    let err_msg = err_msg.trim();
+                    if !err_msg.is_empty() {
+                        let prev = trigger_data_stream.error.take().unwrap_or_default();
+                        trigger_data_stream.error =
+                            Some(format!("{prev} resolved: {err_msg}").trim().to_string());
+                    }
+                    let prev = trigger_data_stream
+                        .success_response
+                        .take()
+                        .unwrap_or_default();
+                    trigger_data_stream.success_response =
+                        Some(format!("{prev} resolved: {success_msg}").trim().to_string());
+                }
+                Err(e) => {
+                    log::error!(
+                        "[SCHEDULER trace_id {trace_id}] Error sending alert resolved notification: org: {}, module_key: {}, err: {e}",
+                        &org_id,
+                        &trigger_data_stream.key
+                    );
+                    let prev = trigger_data_stream.error.take().unwrap_or_default();
+                    trigger_data_stream.error = Some(
+                        format!("{prev} error sending resolved notification for alert: {e}")
+                            .trim()
+                            .to_string(),
+                    );
+                }
+            }
+        }
     }
 
     log::debug!(
@@ -682,6 +865,7 @@ async fn handle_report_triggers(
         evaluation_took_in_secs: None,
         source_node: Some(LOCAL_NODE.name.clone()),
         query_took: None,
+        silenced_by: None,
     };
 
     if trigger.retries >= max_retries {
@@ -695,6 +879,24 @@ async fn handle_report_triggers(
         db::scheduler::update_trigger(new_trigger).await?;
         return Ok(());
     }
+    // A silenced report skips this run, a report sent once waits for the end
+    // of the silence
+    let now = Utc::now().timestamp_micros();
+    if let Some((silenced, until)) = silences::find(org_id, &silences::report_labels(&report), now)
+    {
+        let silenced_by = silences::names(&silenced);
+        log::info!("Report {report_name} is silenced by: {silenced_by}");
+        if run_once {
+            new_trigger.next_run_at = until.min(now + SILENCED_REPORT_RECHECK);
+        }
+        trigger_data_stream.next_run_at = new_trigger.next_run_at;
+        trigger_data_stream.end_time = now;
+        trigger_data_stream.status = TriggerDataStatus::Silenced;
+        trigger_data_stream.silenced_by = Some(silenced_by);
+        db::scheduler::update_trigger(new_trigger).await?;
+        publish_triggers_usage(trigger_data_stream).await;
+        return Ok(());
+    }
     match report.send_subscribers().await {
         Ok(_) => {
             log::info!("Report {} sent to destination", report_name);
@@ -853,6 +1055,44 @@ async fn handle_derived_stream_triggers(
         ..trigger.clone()
     };
 
+    // A silenced derived stream skips its runs, the first run after the
+    // silence catches up from the last processed period
+    let labels = silences::derived_stream_labels(&pipeline);
+    if let Some((silenced, _)) = silences::find(org_id, &labels, now) {
+        let silenced_by = silences::names(&silenced);
+        log::info!(
+            "[SCHEDULER trace_id {trace_id}] DerivedStream(org: {}/module_key: {}) is silenced by: {silenced_by}",
+            new_trigger.org,
+            new_trigger.module_key
+        );
+        new_trigger.next_run_at =
+            derived_stream_next_run_at(&derived_stream, new_trigger.next_run_at)?;
+        publish_triggers_usage(TriggerData {
+            _timestamp: now,
+            org: new_trigger.org.clone(),
+            module: TriggerDataType::DerivedStream,
+            key: new_trigger.module_key.to_lowercase(),
+            next_run_at: new_trigger.next_run_at,
+            is_realtime: new_trigger.is_realtime,
+            is_silenced: new_trigger.is_silenced,
+            status: TriggerDataStatus::Silenced,
+            start_time: start.unwrap_or(end - period_num_microseconds),
+            end_time: end,
+            retries: new_trigger.retries,
+            error: None,
+            success_response: None,
+            is_partial: None,
+            delay_in_secs: None,
+            evaluation_took_in_secs: None,
+            source_node: Some(LOCAL_NODE.name.clone()),
+            query_took: None,
+            silenced_by: Some(silenced_by),
+        })
+        .await;
+        db::scheduler::update_trigger(new_trigger).await?;
+        return Ok(());
+    }
+
     while end <= now {
         log::debug!(
             "[SCHEDULER trace_id {trace_id}] DerivedStream: querying for time range: start_time {}, end_time {}. Final end_time is {}",
@@ -884,6 +1124,7 @@ async fn handle_derived_stream_triggers(
             evaluation_took_in_secs: None,
             source_node: Some(LOCAL_NODE.name.clone()),
             query_took: None,
+            silenced_by: None,
         };
 
         // evaluate trigger and configure trigger next run time
@@ -1113,6 +1354,7 @@ async fn handle_derived_stream_triggers(
                     period_end_time: Some(start_time), // updated start_time as end_time
                     tolerance: 0,
                     last_satisfied_at: None,
+                    states: Default::default(),
                 })
                 .unwrap();
             }
@@ -1125,22 +1367,8 @@ async fn handle_derived_stream_triggers(
                 && new_trigger.retries < max_retries)
             {
                 // Go to the next nun at, but use the same trigger start time
-                if derived_stream.trigger_condition.frequency_type == FrequencyType::Cron {
-                    let schedule = Schedule::from_str(&derived_stream.trigger_condition.cron)?;
-                    // tz_offset is in minutes
-                    let tz_offset = FixedOffset::east_opt(derived_stream.tz_offset * 60).unwrap();
-                    new_trigger.next_run_at = schedule
-                        .upcoming(tz_offset)
-                        .next()
-                        .unwrap()
-                        .timestamp_micros();
-                } else {
-                    new_trigger.next_run_at +=
-                        Duration::try_minutes(derived_stream.trigger_condition.frequency)
-                            .unwrap()
-                            .num_microseconds()
-                            .unwrap();
-                }
+                new_trigger.next_run_at =
+                    derived_stream_next_run_at(&derived_stream, new_trigger.next_run_at)?;
 
                 // If the trigger didn't fail, we need to reset the `retries` count.
                 // Only cumulative failures should be used to check with `max_retries`
@@ -1192,3 +1420,26 @@ async fn handle_derived_stream_triggers(
 
     Ok(())
 }
+
+/// Returns the next run of the derived stream after the run at `next_run_at`
+fn derived_stream_next_run_at(
+    derived_stream: &DerivedStream,
+    next_run_at: i64,
+) -> Result<i64, anyhow::Error> {
+    if derived_stream.trigger_condition.frequency_type == FrequencyType::Cron {
+        let schedule = Schedule::from_str(&derived_stream.trigger_condition.cron)?;
+        // tz_offset is in minutes
+        let tz_offset = FixedOffset::east_opt(derived_stream.tz_offset * 60).unwrap();
+        Ok(schedule
+            .upcoming(tz_offset)
+            .next()
+            .unwrap()
+            .timestamp_micros())
+    } else {
+        Ok(next_run_at
+            + Duration::try_minutes(derived_stream.trigger_condition.frequency)
+                .unwrap()
+                .num_microseconds()
+                .unwrap())
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use config::{
+    ider,
+    meta::{
+        alerts::{alert::Alert, routing::Labels, silence::Silence, state::group_of},
+        dashboards::reports::Report,
+        pipeline::Pipeline,
+    },
+    utils::json::{Map, Value},
+};
+use infra::db::{ORM_CLIENT, connect_to_orm};
+
+use super::notifier::labels_of;
+use crate::{common::infra::config::SILENCES, service::db};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SilenceError {
+    #[error("Invalid silence: {0}")]
+    Invalid(String),
+    #[error("Silence {0} not found")]
+    NotFound(String),
+    #[error("Error saving silence: {0}")]
+    Db(#[from] anyhow::Error),
+}
+
+pub async fn create(
+    org_id: &str,
+    user_id: &str,
+    mut silence: Silence,
+) -> Result<Silence, SilenceError> {
+    silence.validate().map_err(SilenceError::Invalid)?;
+    silence.id = ider::generate();
+    silence.created_by = user_id.to_string();
+    db::alerts::silences::set(org_id, &silence).await?;
+    Ok(silence)
+}
+
+pub async fn update(org_id: &str, id: &str, mut silence: Silence) -> Result<Silence, SilenceError> {
+    silence.validate().map_err(SilenceError::Invalid)?;
+    let Ok(old) = db::alerts::silences::get(org_id, id).await else {
+        return Err(SilenceError::NotFound(id.to_string()));
+    };
+    silence.id = old.id;
+    silence.created_by = old.created_by;
+    db::alerts::silences::set(org_id, &silence).await?;
+    Ok(silence)
+}
+
+pub async fn get(org_id: &str, id: &str) -> Result<Silence, SilenceError> {
+    db::alerts::silences::get(org_id, id)
+        .await
+        .map_err(|_| SilenceError::NotFound(id.to_string()))
+}
+
+pub async fn list(org_id: &str) -> Result<Vec<Silence>, SilenceError> {
+    Ok(db::alerts::silences::list(org_id).await?)
+}
+
+pub async fn delete(org_id: &str, id: &str) -> Result<(), SilenceError> {
+    if db::alerts::silences::get(org_id, id).await.is_err() {
+        return Err(SilenceError::NotFound(id.to_string()));
+    }
+    db::alerts::silences::delete(org_id, id).await?;
+    Ok(())
+}
+
+/// Returns the silences of the org which are active at `now`
+fn active(org_id: &str, now: i64) -> Vec<Silence> {
+    let prefix = format!("{org_id}/");
+    SILENCES
+        .iter()
+        .filter(|v| v.key().starts_with(&prefix) && v.value().is_active(now))
+        .map(|v| v.value().clone())
+        .collect()
+}
+
+/// Returns the active silences muting the labels and when the last of them
+/// ends, `None` if the labels are not muted
+pub fn find(org_id: &str, labels: &Labels, now: i64) -> Option<(Vec<Silence>, i64)> {
+    let silences = active(org_id, now)
+        .into_iter()
+        .filter(|s| s.matches(labels))
+        .collect::<Vec<_>>();
+    let until = silences.iter().filter_map(|s| s.active_until(now)).max()?;
+    Some((silences, until))
+}
+
+/// Names of the silences for the triggers usage stream
+pub fn names(silences: &[Silence]) -> String {
+    let mut names = silences.iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    names.join(", ")
+}
+
+/// Drops the rows of the firing groups of the alert which are muted by a
+/// silence. Returns the remaining rows and the silences which muted any.
+pub async fn filter_alert_rows(
+    alert: &Alert,
+    rows: Vec<Map<String, Value>>,
+    group_by: &[String],
+    now: i64,
+) -> (Vec<Map<String, Value>>, Vec<Silence>) {
+    let silences = active(&alert.org_id, now);
+    if silences.is_empty() {
+        return (rows, vec![]);
+    }
+    let mut base = Labels::new();
+    base.insert("module".to_string(), "alert".to_string());
+    base.insert("name".to_string(), alert.name.clone());
+    // the folder is only in the db, look it up when a silence needs it
+    if silences
+        .iter()
+        .any(|s| s.matchers.iter().any(|m| m.name == "folder"))
+    {
+        if let Some(folder) = alert_folder(alert).await {
+            base.insert("folder".to_string(), folder);
+        }
+    }
+
+    let mut kept = Vec::with_capacity(rows.len());
+    let mut muted_by: Vec<Silence> = Vec::new();
+    for row in rows {
+        let (_, group_labels) = group_of(&row, group_by);
+        let mut labels = labels_of(alert, &group_labels);
+        labels.extend(base.clone());
+        let matched = silences
+            .iter()
+            .filter(|s| s.matches(&labels))
+            .collect::<Vec<_>>();
+        if matched.is_empty() {
+            kept.push(row);
+            continue;
+        }
+        for s in matched {
+            if !muted_by.iter().any(|v| v.id == s.id) {
+                muted_by.push(s.clone());
+            }
+        }
+    }
+    (kept, muted_by)
+}
+
+async fn alert_folder(alert: &Alert) -> Option<String> {
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    match db::alerts::alert::get_by_id(client, &alert.org_id, alert.id?).await {
+        Ok(Some((folder, _))) => Some(folder.folder_id),
+        _ => None,
+    }
+}
+
+pub fn report_labels(report: &Report) -> Labels {
+    let mut labels = Labels::new();
+    labels.insert("module".to_string(), "report".to_string());
+    labels.insert("name".to_string(), report.name.clone());
+    if let Some(dashboard) = report.dashboards.first() {
+        labels.insert("folder".to_string(), dashboard.folder.clone());
+    }
+    labels
+}
+
+pub fn derived_stream_labels(pipeline: &Pipeline) -> Labels {
+    let stream = pipeline.get_source_stream_params();
+    let mut labels = Labels::new();
+    labels.insert("module".to_string(), "derived_stream".to_string());
+    labels.insert("name".to_string(), pipeline.name.clone());
+    labels.insert("stream_type".to_string(), stream.stream_type.to_string());
+    labels.insert("stream_name".to_string(), stream.stream_name.to_string());
+    labels
+}
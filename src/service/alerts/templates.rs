@@ -13,14 +13,23 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use config::meta::destinations::{Template, TemplateType};
+use config::{
+    meta::{
+        alerts::{alert::Alert, state::AlertState},
+        destinations::{Template, TemplateType},
+    },
+    utils::json::{Map, Value},
+};
 
 use crate::{
     common::{
         meta::{authz::Authz, organization::DEFAULT_ORG},
         utils::auth::{is_ofga_unsupported, remove_ownership, set_ownership},
     },
-    service::db::{self, alerts::templates::TemplateError},
+    service::{
+        alerts::{alert, templating},
+        db::{self, alerts::templates::TemplateError},
+    },
 };
 
 pub async fn save(name: &str, mut template: Template, create: bool) -> Result<(), TemplateError> {
@@ -42,7 +51,9 @@ pub async fn save(name: &str, mut template: Template, create: bool) -> Result<()
         if title.is_empty() {
             return Err(TemplateError::EmptyTitle);
         }
+        templating::validate(title).map_err(TemplateError::InvalidTemplate)?;
     }
+    templating::validate(&template.body).map_err(TemplateError::InvalidTemplate)?;
 
     match db::alerts::templates::get(&template.org_id, &template.name).await {
         Ok(existing) => {
@@ -67,6 +78,24 @@ pub async fn save(name: &str, mut template: Template, create: bool) -> Result<()
     Ok(())
 }
 
+/// Renders the template for the sample rows of the alert, returns the title
+/// and the body of the notification
+pub async fn preview(
+    template: &Template,
+    alert: &Alert,
+    rows: &[Map<String, Value>],
+    alert_status: AlertState,
+) -> Result<(String, String), TemplateError> {
+    if let TemplateType::Email { title } = &template.template_type {
+        templating::validate(title).map_err(TemplateError::InvalidTemplate)?;
+    }
+    templating::validate(&template.body).map_err(TemplateError::InvalidTemplate)?;
+    templating::validate(&alert.row_template).map_err(TemplateError::InvalidTemplate)?;
+    alert::preview_template(alert, template, rows, alert_status)
+        .await
+        .map_err(|e| TemplateError::InvalidTemplate(e.to_string()))
+}
+
 pub async fn get(org_id: &str, name: &str) -> Result<Template, TemplateError> {
     db::alerts::templates::get(org_id, name).await
 }
@@ -0,0 +1,278 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Template language of the alert and report notifications.
+//!
+//! Templates using `{{ ... }}` or `{% ... %}` are rendered by a Jinja2 style
+//! engine (MiniJinja), which can loop over the rows, branch on conditions and
+//! format values with the helpers below. Other templates are legacy templates
+//! with `{var}` placeholders and are rendered as before.
+//!
+//! Alert templates have the variables of the placeholders, plus `rows`, the
+//! rows of the alert, and `row_texts`, the row template rendered for each row.
+//! Row templates have the fields of the row and `row`.
+//!
+//! Helpers:
+//! - `ts | format_time(fmt, tz_offset=minutes)`, the timestamp is in seconds, milliseconds,
+//!   microseconds or nanoseconds, or an RFC 3339 string
+//! - `value | format_number(precision)`, `1234.5 | format_number` is `1,234.50`
+//! - `value | json_escape` escapes the value to be embedded in a JSON string
+//! - `value | tojson` and `params | urlencode`
+//! - `url(base, params)` appends the url encoded parameters to the base url
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use chrono::{DateTime, FixedOffset, Utc};
+use config::utils::json::{self, Map, Value};
+use minijinja::{
+    Environment, Error, ErrorKind,
+    value::{Kwargs, Value as TplValue, ValueKind},
+};
+use once_cell::sync::Lazy;
+
+const DEFAULT_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+static ENV: Lazy<Environment<'static>> = Lazy::new(|| {
+    let mut env = Environment::new();
+    env.add_filter("format_time", format_time);
+    env.add_filter("format_number", format_number);
+    env.add_filter("json_escape", json_escape);
+    env.add_function("url", url);
+    env
+});
+
+/// Legacy templates only have `{var}` placeholders
+pub fn is_template(tpl: &str) -> bool {
+    tpl.contains("{{") || tpl.contains("{%")
+}
+
+/// Checks the syntax of the template
+pub fn validate(tpl: &str) -> Result<(), String> {
+    if !is_template(tpl) {
+        return Ok(());
+    }
+    ENV.template_from_str(tpl)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+pub fn render(tpl: &str, ctx: &Map<String, Value>) -> Result<String, anyhow::Error> {
+    let ctx = ctx
+        .iter()
+        .map(|(k, v)| (k.to_string(), to_value(v)))
+        .collect::<BTreeMap<_, _>>();
+    ENV.render_str(tpl, ctx)
+        .map_err(|e| anyhow::anyhow!("Error rendering template: {e}"))
+}
+
+/// Converts the json value, the numbers of serde_json with arbitrary precision
+/// don't serialize as numbers
+fn to_value(v: &Value) -> TplValue {
+    match v {
+        Value::Null => TplValue::from(()),
+        Value::Bool(v) => TplValue::from(*v),
+        Value::Number(n) => {
+            if let Some(v) = n.as_i64() {
+                TplValue::from(v)
+            } else if let Some(v) = n.as_u64() {
+                TplValue::from(v)
+            } else {
+                TplValue::from(n.as_f64().unwrap_or_default())
+            }
+        }
+        Value::String(v) => TplValue::from(v.as_str()),
+        Value::Array(v) => v.iter().map(to_value).collect(),
+        Value::Object(v) => TplValue::from(
+            v.iter()
+                .map(|(k, v)| (k.to_string(), to_value(v)))
+                .collect::<BTreeMap<_, _>>(),
+        ),
+    }
+}
+
+fn invalid(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidOperation, msg)
+}
+
+fn format_time(value: TplValue, fmt: Option<String>, kwargs: Kwargs) -> Result<String, Error> {
+    let tz_offset: Option<i32> = kwargs.get("tz_offset")?;
+    kwargs.assert_all_used()?;
+    let tz_offset = tz_offset.unwrap_or_default();
+    let tz = FixedOffset::east_opt(tz_offset * 60)
+        .ok_or_else(|| invalid(format!("invalid tz_offset {tz_offset}")))?;
+    let time = match value.as_str() {
+        Some(v) => match v.trim().parse::<i64>() {
+            Ok(ts) => from_timestamp(ts),
+            Err(_) => DateTime::parse_from_rfc3339(v.trim())
+                .ok()
+                .map(|t| t.with_timezone(&Utc)),
+        },
+        None => f64::try_from(value.clone())
+            .ok()
+            .and_then(|ts| from_timestamp(ts as i64)),
+    };
+    let Some(time) = time else {
+        return Err(invalid(format!("{value} is not a timestamp")));
+    };
+    let mut out = String::new();
+    write!(
+        out,
+        "{}",
+        time.with_timezone(&tz)
+            .format(fmt.as_deref().unwrap_or(DEFAULT_TIME_FORMAT))
+    )
+    .map_err(|_| invalid(format!("invalid time format {}", fmt.unwrap_or_default())))?;
+    Ok(out)
+}
+
+/// Guesses the unit of the timestamp from its magnitude
+fn from_timestamp(ts: i64) -> Option<DateTime<Utc>> {
+    match ts.unsigned_abs() {
+        0..100_000_000_000 => DateTime::from_timestamp(ts, 0),
+        100_000_000_000..100_000_000_000_000 => DateTime::from_timestamp_millis(ts),
+        100_000_000_000_000..100_000_000_000_000_000 => DateTime::from_timestamp_micros(ts),
+        _ => Some(DateTime::from_timestamp_nanos(ts)),
+    }
+}
+
+fn format_number(value: TplValue, precision: Option<usize>) -> Result<String, Error> {
+    let v = match value.as_str() {
+        Some(v) => v.trim().parse::<f64>().ok(),
+        None => f64::try_from(value.clone()).ok(),
+    };
+    let Some(v) = v else {
+        return Err(invalid(format!("{value} is not a number")));
+    };
+    let formatted = format!("{:.*}", precision.unwrap_or(2).min(20), v.abs());
+    let (int, frac) = match formatted.split_once('.') {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (formatted.as_str(), None),
+    };
+    let mut out = String::with_capacity(formatted.len() + int.len() / 3 + 1);
+    if v < 0.0 && formatted.chars().any(|c| ('1'..='9').contains(&c)) {
+        out.push('-');
+    }
+    for (i, c) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    if let Some(frac) = frac {
+        out.push('.');
+        out.push_str(frac);
+    }
+    Ok(out)
+}
+
+fn json_escape(value: TplValue) -> String {
+    let v = match value.as_str() {
+        Some(v) => v.to_string(),
+        None => value.to_string(),
+    };
+    let quoted = json::to_string(&v).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+fn url(base: String, params: Option<TplValue>) -> Result<String, Error> {
+    let mut url =
+        url::Url::parse(&base).map_err(|e| invalid(format!("invalid url {base}: {e}")))?;
+    if let Some(params) = params {
+        if params.kind() != ValueKind::Map {
+            return Err(invalid("url parameters should be a map".to_string()));
+        }
+        let mut query = url.query_pairs_mut();
+        for key in params.try_iter()? {
+            let value = params.get_item(&key)?;
+            if value.is_none() || value.is_undefined() {
+                continue;
+            }
+            query.append_pair(&key.to_string(), &value.to_string());
+        }
+    }
+    Ok(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> Map<String, Value> {
+        json::from_str(
+            r#"{
+                "alert_name": "errors",
+                "alert_count": 2,
+                "rows": [
+                    {"host": "web-1", "count": 1234.567, "_timestamp": 1700000000000000},
+                    {"host": "web-\"2\"", "count": 3, "_timestamp": 1700000060000000}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_is_template() {
+        assert!(!is_template(r#"{"text": "{alert_name} {rows:10}"}"#));
+        assert!(is_template("{{ alert_name }}"));
+        assert!(is_template("{% for row in rows %}{% endfor %}"));
+        assert!(validate("{% for row in rows %}").is_err());
+        assert!(validate("{alert_name}").is_ok());
+    }
+
+    #[test]
+    fn test_render() {
+        let tpl = "{{ alert_name }}:{% for row in rows %} {{ row.host }}={{ row.count | format_number(1) }}{% if loop.last %}.{% else %},{% endif %}{% endfor %}";
+        assert_eq!(
+            render(tpl, &ctx()).unwrap(),
+            "errors: web-1=1,234.6, web-\"2\"=3.0."
+        );
+        let tpl = r#"{"text": "{% for row in rows %}{{ row.host | json_escape }} {% endfor %}"}"#;
+        let out = render(tpl, &ctx()).unwrap();
+        assert!(json::from_str::<Value>(&out).is_ok());
+        assert!(render("{{ alert_name | format_number }}", &ctx()).is_err());
+    }
+
+    #[test]
+    fn test_format_time() {
+        let tpl = "{{ rows[0]._timestamp | format_time }} {{ rows[1]._timestamp | format_time('%H:%M', tz_offset=120) }} {{ 1700000000 | format_time('%Y') }}";
+        assert_eq!(
+            render(tpl, &ctx()).unwrap(),
+            "2023-11-14T22:13:20 00:14 2023"
+        );
+        assert!(render("{{ 1700000000 | format_time('%Q') }}", &ctx()).is_err());
+    }
+
+    #[test]
+    fn test_format_number_and_url() {
+        assert_eq!(
+            format_number(TplValue::from(-1234567.891), None).unwrap(),
+            "-1,234,567.89"
+        );
+        assert_eq!(format_number(TplValue::from(999), Some(0)).unwrap(), "999");
+        assert_eq!(
+            format_number(TplValue::from("-0.001"), None).unwrap(),
+            "0.00"
+        );
+        assert_eq!(
+            render(
+                r#"{{ url("https://example.com/search", {"q": alert_name ~ " now", "n": alert_count}) }}"#,
+                &ctx()
+            )
+            .unwrap(),
+            "https://example.com/search?n=2&q=errors+now"
+        );
+    }
+}
@@ -28,6 +28,10 @@ use config::{
             ReportFrequencyType, ReportListFilters, ReportTimerangeType,
         },
     },
+    utils::{
+        json::{self, Map},
+        time::now_micros,
+    },
 };
 use cron::Schedule;
 use futures::{StreamExt, future::try_join_all};
@@ -43,7 +47,7 @@ use crate::{
         meta::authz::Authz,
         utils::auth::{is_ofga_unsupported, remove_ownership, set_ownership},
     },
-    service::{db, short_url},
+    service::{alerts::templating, db, short_url},
 };
 
 pub async fn save(
@@ -85,6 +89,9 @@ pub async fn save(
     if report.name.contains('/') {
         return Err(anyhow::anyhow!("Report name cannot contain '/'"));
     }
+    for tpl in [&report.title, &report.message] {
+        templating::validate(tpl).map_err(|e| anyhow::anyhow!("Invalid report template: {e}"))?;
+    }
 
     if report.frequency.frequency_type == ReportFrequencyType::Cron {
         let cron_exp = report.frequency.cron.clone();
@@ -300,10 +307,12 @@ impl SendReport for Report {
         if self.dashboards.is_empty() {
             return Err(anyhow::anyhow!("Atleast one dashboard is required"));
         }
+        // the title and the message can use the template language
+        let report = render_message(self)?;
 
         let cfg = get_config();
         let mut recipients = vec![];
-        for recipient in &self.destinations {
+        for recipient in &report.destinations {
             match recipient {
                 ReportDestination::Email(email) => recipients.push(email.clone()),
             }
@@ -311,19 +320,19 @@ impl SendReport for Report {
         let no_of_recipients = recipients.len();
         if !cfg.common.report_server_url.is_empty() {
             let report_data = HttpReportPayload {
-                dashboards: self.dashboards.clone(),
+                dashboards: report.dashboards.clone(),
                 email_details: ReportEmailDetails {
-                    title: self.title.clone(),
+                    title: report.title.clone(),
                     recipients,
-                    name: self.name.clone(),
-                    message: self.message.clone(),
+                    name: report.name.clone(),
+                    message: report.message.clone(),
                     dashb_url: format!("{}{}/web", cfg.common.web_url, cfg.common.base_uri),
                 },
             };
 
             let url = url::Url::parse(&format!(
                 "{}/api/{}/reports/{}/send",
-                &cfg.common.report_server_url, &self.org_id, &self.name
+                &cfg.common.report_server_url, &report.org_id, &report.name
             ))
             .unwrap();
             match Client::builder()
@@ -331,7 +340,7 @@ impl SendReport for Report {
                 .build()
                 .unwrap()
                 .put(url)
-                .query(&[("timezone", &self.timezone)])
+                .query(&[("timezone", &report.timezone)])
                 .header("Content-Type", "application/json")
                 .json(&report_data)
                 .send()
@@ -353,22 +362,48 @@ impl SendReport for Report {
             Ok(())
         } else {
             // Currently only one `ReportDashboard` can be captured and sent
-            let dashboard = &self.dashboards[0];
-            let report = generate_report(
+            let dashboard = &report.dashboards[0];
+            let (pdf_data, dashb_url) = generate_report(
                 dashboard,
-                &self.org_id,
+                &report.org_id,
                 &cfg.common.report_user_name,
                 &cfg.common.report_user_password,
-                &self.timezone,
+                &report.timezone,
                 no_of_recipients,
-                &self.name,
+                &report.name,
             )
             .await?;
-            send_email(self, &report.0, report.1).await
+            send_email(&report, &pdf_data, dashb_url).await
         }
     }
 }
 
+/// Renders the title and the message of the report which use the template
+/// language, see [templating]
+fn render_message(report: &Report) -> Result<Report, anyhow::Error> {
+    let mut ctx = Map::new();
+    ctx.insert("org_name".to_string(), report.org_id.clone().into());
+    ctx.insert("report_name".to_string(), report.name.clone().into());
+    ctx.insert("timezone".to_string(), report.timezone.clone().into());
+    ctx.insert("tz_offset".to_string(), report.tz_offset.into());
+    ctx.insert("report_time".to_string(), now_micros().into());
+    ctx.insert(
+        "dashboards".to_string(),
+        json::to_value(&report.dashboards)?,
+    );
+    let render = |tpl: &str| {
+        if templating::is_template(tpl) {
+            templating::render(tpl, &ctx)
+        } else {
+            Ok(tpl.to_string())
+        }
+    };
+    let mut report = report.clone();
+    report.title = render(&report.title)?;
+    report.message = render(&report.message)?;
+    Ok(report)
+}
+
 /// Sends emails to the [`Report`] recipients. Currently only one pdf data is supported.
 async fn send_email(
     report: &Report,
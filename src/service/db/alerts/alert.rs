@@ -240,6 +240,9 @@ pub async fn delete_by_id<C: ConnectionTrait>(
     {
         log::error!("Failed to delete trigger: {}", e);
     };
+    if let Err(e) = db::alerts::anomaly::delete(org_id, &schedule_key).await {
+        log::error!("Failed to delete anomaly model: {}", e);
+    }
     Ok(())
 }
 
@@ -272,6 +275,9 @@ pub async fn delete_by_name(
     {
         log::error!("Failed to delete trigger: {}", e);
     };
+    if let Err(e) = db::alerts::anomaly::delete(org_id, &schedule_key).await {
+        log::error!("Failed to delete anomaly model: {}", e);
+    }
     Ok(())
 }
 
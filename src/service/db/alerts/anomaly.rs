@@ -0,0 +1,56 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use config::{meta::alerts::anomaly::AnomalyModel, utils::json};
+use infra::errors::{DbError, Error};
+
+use crate::service::db;
+
+/// Models of the anomaly alerts by org and trigger key of the alert
+const ANOMALY_KEY_PREFIX: &str = "/alert_anomaly/";
+
+pub async fn get(org_id: &str, alert_key: &str) -> Result<AnomalyModel, anyhow::Error> {
+    match db::get(&format!("{ANOMALY_KEY_PREFIX}{org_id}/{alert_key}")).await {
+        Ok(val) => Ok(json::from_slice(&val)?),
+        Err(Error::DbError(DbError::KeyNotExists(_))) => Ok(AnomalyModel::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn set(org_id: &str, alert_key: &str, model: &AnomalyModel) -> Result<(), anyhow::Error> {
+    db::put(
+        &format!("{ANOMALY_KEY_PREFIX}{org_id}/{alert_key}"),
+        json::to_vec(model)?.into(),
+        db::NO_NEED_WATCH,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn delete(org_id: &str, alert_key: &str) -> Result<(), anyhow::Error> {
+    db::delete_if_exists(
+        &format!("{ANOMALY_KEY_PREFIX}{org_id}/{alert_key}"),
+        false,
+        db::NO_NEED_WATCH,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn reset() -> Result<(), anyhow::Error> {
+    db::delete(ANOMALY_KEY_PREFIX, true, db::NO_NEED_WATCH, None).await?;
+    Ok(())
+}
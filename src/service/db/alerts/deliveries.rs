@@ -0,0 +1,160 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use config::{
+    meta::alerts::delivery::{Delivery, DeliveryStatus},
+    utils::json,
+};
+
+use crate::service::db;
+
+/// Every delivery, the delivery log
+const DELIVERY_LOG_KEY_PREFIX: &str = "/alert_delivery/log/";
+/// Next attempt of the pending deliveries, scanned by the retry job
+const DELIVERY_QUEUE_KEY_PREFIX: &str = "/alert_delivery/queue/";
+
+/// Pending delivery of the queue
+#[derive(Debug, PartialEq)]
+pub struct QueuedDelivery {
+    pub org_id: String,
+    pub id: String,
+    /// (microseconds)
+    pub next_attempt_at: i64,
+}
+
+/// Saves the delivery to the log, and keeps its next attempt in the queue
+/// while it is pending
+pub async fn set(delivery: &Delivery) -> Result<(), anyhow::Error> {
+    let id = format!("{}/{}", delivery.org_id, delivery.id);
+    db::put(
+        &format!("{DELIVERY_LOG_KEY_PREFIX}{id}"),
+        json::to_vec(delivery)?.into(),
+        db::NO_NEED_WATCH,
+        None,
+    )
+    .await?;
+    let queue_key = format!("{DELIVERY_QUEUE_KEY_PREFIX}{id}");
+    if delivery.status == DeliveryStatus::Pending {
+        db::put(
+            &queue_key,
+            json::to_vec(&delivery.next_attempt_at)?.into(),
+            db::NO_NEED_WATCH,
+            None,
+        )
+        .await?;
+    } else if let Err(e) = db::delete(&queue_key, false, db::NO_NEED_WATCH, None).await {
+        log::debug!("Delivery {id} was not queued: {e}");
+    }
+    Ok(())
+}
+
+pub async fn get(org_id: &str, id: &str) -> Result<Delivery, anyhow::Error> {
+    let val = db::get(&format!("{DELIVERY_LOG_KEY_PREFIX}{org_id}/{id}")).await?;
+    Ok(json::from_slice(&val)?)
+}
+
+pub async fn delete(org_id: &str, id: &str) -> Result<(), anyhow::Error> {
+    let id = format!("{org_id}/{id}");
+    let _ = db::delete(
+        &format!("{DELIVERY_QUEUE_KEY_PREFIX}{id}"),
+        false,
+        db::NO_NEED_WATCH,
+        None,
+    )
+    .await;
+    db::delete(
+        &format!("{DELIVERY_LOG_KEY_PREFIX}{id}"),
+        false,
+        db::NO_NEED_WATCH,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Lists a page of the deliveries of the org matching the `filter`, newest
+/// first, starting after the delivery `after`. Only the keys of the log are
+/// listed, the deliveries are loaded until the page is full. Returns the
+/// `after` of the next page too.
+pub async fn list(
+    org_id: &str,
+    after: Option<&str>,
+    size: usize,
+    filter: impl Fn(&Delivery) -> bool,
+) -> Result<(Vec<Delivery>, Option<String>), anyhow::Error> {
+    let prefix = format!("{DELIVERY_LOG_KEY_PREFIX}{org_id}/");
+    let after = after.and_then(|id| id.parse::<i64>().ok());
+    let mut ids = db::list_keys(&prefix)
+        .await?
+        .into_iter()
+        .filter_map(|key| key.strip_prefix(&prefix)?.parse::<i64>().ok())
+        .filter(|id| after.is_none_or(|after| *id < after))
+        .collect::<Vec<_>>();
+    // the ids are generated in time order
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut list = Vec::new();
+    let mut scanned = None;
+    for id in ids {
+        if list.len() >= size {
+            return Ok((list, scanned.map(|id: i64| id.to_string())));
+        }
+        scanned = Some(id);
+        let delivery = match get(org_id, &id.to_string()).await {
+            Ok(delivery) => delivery,
+            // deleted since the keys were listed
+            Err(_) => continue,
+        };
+        if filter(&delivery) {
+            list.push(delivery);
+        }
+    }
+    Ok((list, None))
+}
+
+/// Lists the deliveries of all the orgs, pending or not
+pub async fn list_all() -> Result<Vec<Delivery>, anyhow::Error> {
+    Ok(db::list(DELIVERY_LOG_KEY_PREFIX)
+        .await?
+        .values()
+        .filter_map(|val| json::from_slice(val).ok())
+        .collect())
+}
+
+/// Lists the pending deliveries of all the orgs by their next attempt
+pub async fn list_queue() -> Result<Vec<QueuedDelivery>, anyhow::Error> {
+    let mut list: Vec<QueuedDelivery> = db::list(DELIVERY_QUEUE_KEY_PREFIX)
+        .await?
+        .into_iter()
+        .filter_map(|(key, val)| {
+            let (org_id, id) = key
+                .strip_prefix(DELIVERY_QUEUE_KEY_PREFIX)?
+                .split_once('/')?;
+            Some(QueuedDelivery {
+                org_id: org_id.to_string(),
+                id: id.to_string(),
+                next_attempt_at: json::from_slice(&val).ok()?,
+            })
+        })
+        .collect();
+    list.sort_by(|a, b| a.next_attempt_at.cmp(&b.next_attempt_at));
+    Ok(list)
+}
+
+pub async fn reset() -> Result<(), anyhow::Error> {
+    db::delete(DELIVERY_QUEUE_KEY_PREFIX, true, db::NO_NEED_WATCH, None).await?;
+    db::delete(DELIVERY_LOG_KEY_PREFIX, true, db::NO_NEED_WATCH, None).await?;
+    Ok(())
+}
@@ -40,6 +40,14 @@ pub enum DestinationError {
     EmptyUrl,
     #[error("SNS destination must have Topic ARN and Region")]
     InvalidSns,
+    #[error("Slack and Teams destinations must have a valid webhook url")]
+    InvalidWebhookUrl,
+    #[error(
+        "PagerDuty destination must have a routing key and a severity of critical, error, warning or info"
+    )]
+    InvalidPagerDuty,
+    #[error("Opsgenie destination must have an api key and a priority from P1 to P5")]
+    InvalidOpsgenie,
     #[error("Email destination must have at least one email recipient")]
     EmptyEmail,
     #[error("Email destination recipients must be part of this org")]
@@ -58,6 +66,8 @@ pub enum DestinationError {
     UsedByAlert(String),
     #[error("Destination is currently used by pipeline: {0}")]
     UsedByPipeline(String),
+    #[error("Destination is currently used by the notification policy")]
+    UsedByNotificationPolicy,
     #[cfg(feature = "enterprise")]
     #[error("Invalid action id: {0}")]
     InvalidActionId(anyhow::Error),
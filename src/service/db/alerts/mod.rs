@@ -14,6 +14,11 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 pub mod alert;
+pub mod anomaly;
+pub mod deliveries;
 pub mod destinations;
+pub mod notification_policies;
+pub mod notifier;
 pub mod realtime_triggers;
+pub mod silences;
 pub mod templates;
@@ -0,0 +1,110 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use config::{meta::alerts::routing::NotificationPolicy, utils::json};
+
+use crate::{common::infra::config::NOTIFICATION_POLICIES, service::db};
+
+const NOTIFICATION_POLICY_KEY_PREFIX: &str = "/notification_policy/";
+
+pub async fn set(org_id: &str, policy: &NotificationPolicy) -> Result<(), anyhow::Error> {
+    let key = format!("{NOTIFICATION_POLICY_KEY_PREFIX}{org_id}");
+    db::put(
+        &key,
+        json::to_vec(policy).unwrap().into(),
+        db::NEED_WATCH,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn get(org_id: &str) -> Result<NotificationPolicy, anyhow::Error> {
+    let val = db::get(&format!("{NOTIFICATION_POLICY_KEY_PREFIX}{org_id}")).await?;
+    Ok(json::from_slice(&val)?)
+}
+
+pub async fn delete(org_id: &str) -> Result<(), anyhow::Error> {
+    let key = format!("{NOTIFICATION_POLICY_KEY_PREFIX}{org_id}");
+    db::delete(&key, false, db::NEED_WATCH, None).await?;
+    Ok(())
+}
+
+pub async fn watch() -> Result<(), anyhow::Error> {
+    let key = NOTIFICATION_POLICY_KEY_PREFIX;
+    let cluster_coordinator = db::get_coordinator().await;
+    let mut events = cluster_coordinator.watch(key).await?;
+    let events = Arc::get_mut(&mut events).unwrap();
+    log::info!("Start watching notification policies");
+    loop {
+        let ev = match events.recv().await {
+            Some(ev) => ev,
+            None => {
+                log::error!("watch_notification_policies: event channel closed");
+                break;
+            }
+        };
+        match ev {
+            db::Event::Put(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                let item_value: NotificationPolicy = match db::get(&ev.key).await {
+                    Ok(val) => match json::from_slice(&val) {
+                        Ok(val) => val,
+                        Err(e) => {
+                            log::error!("Error getting value: {}", e);
+                            continue;
+                        }
+                    },
+                    Err(e) => {
+                        log::error!("Error getting value: {}", e);
+                        continue;
+                    }
+                };
+                NOTIFICATION_POLICIES.insert(item_key.to_owned(), item_value);
+            }
+            db::Event::Delete(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                NOTIFICATION_POLICIES.remove(item_key);
+            }
+            db::Event::Empty => {}
+        }
+    }
+    Ok(())
+}
+
+pub async fn cache() -> Result<(), anyhow::Error> {
+    let key = NOTIFICATION_POLICY_KEY_PREFIX;
+    let ret = db::list(key).await?;
+    for (item_key, item_value) in ret {
+        let item_key = item_key.strip_prefix(key).unwrap();
+        let json_val: NotificationPolicy = json::from_slice(&item_value)?;
+        NOTIFICATION_POLICIES.insert(item_key.to_string(), json_val);
+    }
+    log::info!("Notification policies Cached");
+    Ok(())
+}
+
+pub async fn reset() -> Result<(), anyhow::Error> {
+    db::delete(
+        NOTIFICATION_POLICY_KEY_PREFIX,
+        true,
+        db::NO_NEED_WATCH,
+        None,
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,128 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use config::{
+    meta::alerts::routing::{AggrGroup, FiringInstance},
+    utils::{json, md5},
+};
+use infra::errors::{DbError, Error};
+
+use crate::service::db;
+
+/// Aggregation groups by org and hash of the group key
+const GROUP_KEY_PREFIX: &str = "/alert_notifier/group/";
+/// Firing instances of each org, the sources of the inhibitions
+const FIRING_KEY_PREFIX: &str = "/alert_notifier/firing/";
+
+fn group_db_key(org_id: &str, group_key: &str) -> String {
+    format!("{GROUP_KEY_PREFIX}{org_id}/{}", md5::hash(group_key))
+}
+
+pub async fn get_group(org_id: &str, group_key: &str) -> Result<Option<AggrGroup>, anyhow::Error> {
+    match db::get(&group_db_key(org_id, group_key)).await {
+        Ok(val) => Ok(Some(json::from_slice(&val)?)),
+        Err(Error::DbError(DbError::KeyNotExists(_))) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn set_group(group_key: &str, group: &AggrGroup) -> Result<(), anyhow::Error> {
+    db::put(
+        &group_db_key(&group.org_id, group_key),
+        json::to_vec(group)?.into(),
+        db::NO_NEED_WATCH,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Gets a group by its db key, as returned by [list_groups]
+pub async fn load_group(db_key: &str) -> Result<Option<AggrGroup>, anyhow::Error> {
+    match db::get(db_key).await {
+        Ok(val) => Ok(Some(json::from_slice(&val)?)),
+        Err(Error::DbError(DbError::KeyNotExists(_))) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Deletes a group by its db key, as returned by [list_groups]
+pub async fn delete_group(db_key: &str) -> Result<(), anyhow::Error> {
+    db::delete(db_key, false, db::NO_NEED_WATCH, None).await?;
+    Ok(())
+}
+
+/// Lists the groups of the org by their db key
+pub async fn list_groups(org_id: &str) -> Result<Vec<(String, AggrGroup)>, anyhow::Error> {
+    Ok(db::list(&format!("{GROUP_KEY_PREFIX}{org_id}/"))
+        .await?
+        .into_iter()
+        .filter_map(|(key, val)| json::from_slice(&val).ok().map(|group| (key, group)))
+        .collect())
+}
+
+/// Saves a group listed by [list_groups]
+pub async fn update_group(db_key: &str, group: &AggrGroup) -> Result<(), anyhow::Error> {
+    db::put(db_key, json::to_vec(group)?.into(), db::NO_NEED_WATCH, None).await?;
+    Ok(())
+}
+
+/// Firing instances of the org by fingerprint
+pub async fn get_firing(org_id: &str) -> Result<HashMap<String, FiringInstance>, anyhow::Error> {
+    match db::get(&format!("{FIRING_KEY_PREFIX}{org_id}")).await {
+        Ok(val) => Ok(json::from_slice(&val)?),
+        Err(Error::DbError(DbError::KeyNotExists(_))) => Ok(HashMap::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn set_firing(
+    org_id: &str,
+    firing: &HashMap<String, FiringInstance>,
+) -> Result<(), anyhow::Error> {
+    db::put(
+        &format!("{FIRING_KEY_PREFIX}{org_id}"),
+        json::to_vec(firing)?.into(),
+        db::NO_NEED_WATCH,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Deletes the groups and the firing instances of the org
+pub async fn delete_org(org_id: &str) -> Result<(), anyhow::Error> {
+    db::delete_if_exists(
+        &format!("{GROUP_KEY_PREFIX}{org_id}/"),
+        true,
+        db::NO_NEED_WATCH,
+    )
+    .await?;
+    db::delete_if_exists(
+        &format!("{FIRING_KEY_PREFIX}{org_id}"),
+        false,
+        db::NO_NEED_WATCH,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn reset() -> Result<(), anyhow::Error> {
+    db::delete(GROUP_KEY_PREFIX, true, db::NO_NEED_WATCH, None).await?;
+    db::delete(FIRING_KEY_PREFIX, true, db::NO_NEED_WATCH, None).await?;
+    Ok(())
+}
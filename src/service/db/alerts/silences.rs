@@ -0,0 +1,114 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use config::{meta::alerts::silence::Silence, utils::json};
+
+use crate::{common::infra::config::SILENCES, service::db};
+
+const SILENCES_KEY_PREFIX: &str = "/silence/";
+
+pub async fn set(org_id: &str, silence: &Silence) -> Result<(), anyhow::Error> {
+    let key = format!("{SILENCES_KEY_PREFIX}{org_id}/{}", silence.id);
+    db::put(
+        &key,
+        json::to_vec(silence).unwrap().into(),
+        db::NEED_WATCH,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn get(org_id: &str, id: &str) -> Result<Silence, anyhow::Error> {
+    let val = db::get(&format!("{SILENCES_KEY_PREFIX}{org_id}/{id}")).await?;
+    Ok(json::from_slice(&val)?)
+}
+
+pub async fn delete(org_id: &str, id: &str) -> Result<(), anyhow::Error> {
+    let key = format!("{SILENCES_KEY_PREFIX}{org_id}/{id}");
+    db::delete(&key, false, db::NEED_WATCH, None).await?;
+    Ok(())
+}
+
+pub async fn list(org_id: &str) -> Result<Vec<Silence>, anyhow::Error> {
+    let mut list: Vec<Silence> = db::list(&format!("{SILENCES_KEY_PREFIX}{org_id}/"))
+        .await?
+        .values()
+        .filter_map(|val| json::from_slice(val).ok())
+        .collect();
+    list.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+    Ok(list)
+}
+
+pub async fn watch() -> Result<(), anyhow::Error> {
+    let key = SILENCES_KEY_PREFIX;
+    let cluster_coordinator = db::get_coordinator().await;
+    let mut events = cluster_coordinator.watch(key).await?;
+    let events = Arc::get_mut(&mut events).unwrap();
+    log::info!("Start watching silences");
+    loop {
+        let ev = match events.recv().await {
+            Some(ev) => ev,
+            None => {
+                log::error!("watch_silences: event channel closed");
+                break;
+            }
+        };
+        match ev {
+            db::Event::Put(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                let item_value: Silence = match db::get(&ev.key).await {
+                    Ok(val) => match json::from_slice(&val) {
+                        Ok(val) => val,
+                        Err(e) => {
+                            log::error!("Error getting value: {}", e);
+                            continue;
+                        }
+                    },
+                    Err(e) => {
+                        log::error!("Error getting value: {}", e);
+                        continue;
+                    }
+                };
+                SILENCES.insert(item_key.to_owned(), item_value);
+            }
+            db::Event::Delete(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                SILENCES.remove(item_key);
+            }
+            db::Event::Empty => {}
+        }
+    }
+    Ok(())
+}
+
+pub async fn cache() -> Result<(), anyhow::Error> {
+    let key = SILENCES_KEY_PREFIX;
+    let ret = db::list(key).await?;
+    for (item_key, item_value) in ret {
+        let item_key = item_key.strip_prefix(key).unwrap();
+        let json_val: Silence = json::from_slice(&item_value)?;
+        SILENCES.insert(item_key.to_string(), json_val);
+    }
+    log::info!("Silences Cached");
+    Ok(())
+}
+
+pub async fn reset() -> Result<(), anyhow::Error> {
+    db::delete(SILENCES_KEY_PREFIX, true, db::NO_NEED_WATCH, None).await?;
+    Ok(())
+}
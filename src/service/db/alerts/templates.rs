@@ -44,6 +44,8 @@ pub enum TemplateError {
     EmptyTitle,
     #[error("Template body cannot be empty")]
     EmptyBody,
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
     #[error("Template with the same name already exists")]
     AlreadyExists,
     #[error("Template is in use for destination {0}")]
@@ -18,7 +18,10 @@ use std::sync::Arc;
 use chrono::Utc;
 use config::{
     meta::stream::StreamType,
-    utils::{json, time::BASE_TIME},
+    utils::{
+        json,
+        time::{BASE_TIME, now_micros},
+    },
 };
 use infra::{cache::stats, db};
 use vrl::prelude::NotNan;
@@ -28,6 +31,13 @@ use crate::{
     service::{enrichment::StreamTable, search as SearchService},
 };
 
+fn notify_key(org_id: &str, name: &str) -> String {
+    format!(
+        "/enrichment_table/{org_id}/{}/{name}",
+        StreamType::EnrichmentTables
+    )
+}
+
 pub async fn get(org_id: &str, name: &str) -> Result<Vec<vrl::value::Value>, anyhow::Error> {
     let stats = stats::get_stream_stats(org_id, name, StreamType::EnrichmentTables);
 
@@ -96,24 +106,48 @@ fn convert_to_vrl(value: &json::Value) -> vrl::value::Value {
     }
 }
 
-pub async fn notify_update(org_id: &str, name: &str) -> Result<(), infra::errors::Error> {
+/// Returns the enrichment table with its version, the upload time saved by
+/// [notify_update]. The version is read first, so a table uploaded meanwhile
+/// is only newer than its version and gets reloaded again.
+pub async fn get_table(org_id: &str, name: &str) -> Result<StreamTable, anyhow::Error> {
     let cluster_coordinator = db::get_coordinator().await;
-    let key: String = format!(
-        "/enrichment_table/{org_id}/{}/{}",
-        StreamType::EnrichmentTables,
-        name
+    let updated_at = match cluster_coordinator.get(&notify_key(org_id, name)).await {
+        Ok(val) => String::from_utf8_lossy(&val).parse().unwrap_or_default(),
+        Err(_) => 0,
+    };
+    let data = get(org_id, name).await?;
+    Ok(StreamTable::new(org_id, name, data, updated_at))
+}
+
+/// Reloads the enrichment table cached on this node and drops its arrow
+/// representation built for the lookup joins
+pub async fn reload(org_id: &str, name: &str) -> Result<(), anyhow::Error> {
+    let table = get_table(org_id, name).await?;
+    ENRICHMENT_TABLES.insert(
+        format!("{org_id}/{}/{name}", StreamType::EnrichmentTables),
+        table,
     );
-    cluster_coordinator.put(&key, "".into(), true, None).await
+    SearchService::datafusion::table_provider::enrichment_table::remove(org_id, name);
+    Ok(())
+}
+
+pub async fn notify_update(org_id: &str, name: &str) -> Result<(), infra::errors::Error> {
+    let cluster_coordinator = db::get_coordinator().await;
+    cluster_coordinator
+        .put(
+            &notify_key(org_id, name),
+            now_micros().to_string().into(),
+            true,
+            None,
+        )
+        .await
 }
 
 pub async fn delete(org_id: &str, name: &str) -> Result<(), infra::errors::Error> {
     let cluster_coordinator = db::get_coordinator().await;
-    let key: String = format!(
-        "/enrichment_table/{org_id}/{}/{}",
-        StreamType::EnrichmentTables,
-        name
-    );
-    cluster_coordinator.delete(&key, false, false, None).await
+    cluster_coordinator
+        .delete(&notify_key(org_id, name), false, false, None)
+        .await
 }
 
 pub async fn watch() -> Result<(), anyhow::Error> {
@@ -137,19 +171,21 @@ pub async fn watch() -> Result<(), anyhow::Error> {
                 let org_id = keys[0];
                 let stream_name = keys[2];
 
-                let data = super::enrichment_table::get(org_id, stream_name)
-                    .await
-                    .unwrap();
-                ENRICHMENT_TABLES.insert(
-                    item_key.to_owned(),
-                    StreamTable {
-                        org_id: org_id.to_string(),
-                        stream_name: stream_name.to_string(),
-                        data,
-                    },
+                if let Err(e) = reload(org_id, stream_name).await {
+                    log::error!("Error reloading enrichment table {item_key}: {e}");
+                }
+            }
+            db::Event::Delete(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                let keys = item_key.split('/').collect::<Vec<&str>>();
+                let org_id = keys[0];
+                let stream_name = keys[2];
+                ENRICHMENT_TABLES.remove(item_key);
+                SearchService::datafusion::table_provider::enrichment_table::remove(
+                    org_id,
+                    stream_name,
                 );
             }
-            db::Event::Delete(_) => {}
             db::Event::Empty => {}
         }
     }
@@ -38,12 +38,16 @@ pub mod metrics;
 pub mod ofga;
 pub mod organization;
 pub mod pipeline;
+pub mod pipeline_backfill;
+pub mod pipeline_versions;
+pub mod redaction;
 pub mod saved_view;
 pub mod scheduler;
 pub mod schema;
 pub mod search_job;
 pub mod session;
 pub mod short_url;
+pub mod sql_macros;
 pub mod syslog;
 pub mod user;
 pub mod version;
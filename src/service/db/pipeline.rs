@@ -51,6 +51,16 @@ pub enum PipelineError {
     InvalidDerivedStream(String),
     #[error("Error deleting previous DerivedStream: {0}")]
     DeleteDerivedStream(String),
+    #[error("Pipeline dry run failed: {0}")]
+    DryRun(String),
+    #[error("Version {1} of pipeline with ID {0} not found.")]
+    VersionNotFound(String, i32),
+    #[error("Pipeline with ID {0} has no staged version.")]
+    StagedNotFound(String),
+    #[error("Backfill job {0} not found.")]
+    BackfillNotFound(String),
+    #[error("Invalid backfill: {0}")]
+    InvalidBackfill(String),
 }
 
 /// Stores a new pipeline to database.
@@ -139,6 +149,32 @@ pub async fn delete(pipeline_id: &str) -> Result<(), PipelineError> {
     Ok(())
 }
 
+/// Refreshes the cached ExecutablePipeline of the pipeline on all nodes, used
+/// when its staged version changes.
+pub async fn refresh_cache(pipeline: &Pipeline) {
+    update_cache(PipelineTableEvent::Add(pipeline)).await;
+}
+
+/// Builds the ExecutablePipeline of a realtime pipeline along with its staged
+/// version, if one is staged on the current version of the pipeline.
+async fn new_executable_pipeline(pipeline: &Pipeline) -> anyhow::Result<ExecutablePipeline> {
+    let mut exec_pl = ExecutablePipeline::new(pipeline).await?;
+    let Ok(staged) = super::pipeline_versions::get_staged(&pipeline.id).await else {
+        return Ok(exec_pl);
+    };
+    if staged.base_version == pipeline.version {
+        match ExecutablePipeline::new(&staged.pipeline).await {
+            Ok(staged_pl) => exec_pl.set_staged(staged_pl, staged.percentage),
+            Err(e) => log::error!(
+                "[Pipeline] {}/{}: error initializing the staged version, all records go to the current version: {e}",
+                pipeline.org,
+                pipeline.id
+            ),
+        }
+    }
+    Ok(exec_pl)
+}
+
 /// Preload all enabled pipelines into the cache at startup.
 pub async fn cache() -> Result<(), anyhow::Error> {
     let pipelines = list().await?;
@@ -157,7 +193,7 @@ pub async fn cache() -> Result<(), anyhow::Error> {
     for pipeline in pipelines.into_iter() {
         if pipeline.enabled {
             if let PipelineSource::Realtime(stream_params) = &pipeline.source {
-                match ExecutablePipeline::new(&pipeline).await {
+                match new_executable_pipeline(&pipeline).await {
                     Err(e) => {
                         log::error!(
                             "[Pipeline] error initializing ExecutablePipeline from pipeline {}/{}. {}. Not cached",
@@ -267,7 +303,7 @@ pub async fn watch() -> Result<(), anyhow::Error> {
                     let mut pipeline_stream_mapping_cache = PIPELINE_STREAM_MAPPING.write().await;
                     let mut stream_exec_pl = STREAM_EXECUTABLE_PIPELINES.write().await;
                     if pipeline.enabled {
-                        match ExecutablePipeline::new(&pipeline).await {
+                        match new_executable_pipeline(&pipeline).await {
                             Err(e) => {
                                 log::error!(
                                     "[Pipeline::watch] {}/{}/{}: Error initializing pipeline into ExecutablePipeline when updating cache: {}",
@@ -280,7 +316,18 @@ pub async fn watch() -> Result<(), anyhow::Error> {
                             Ok(exec_pl) => {
                                 pipeline_stream_mapping_cache
                                     .insert(pipeline_id.to_string(), stream_params.clone());
-                                stream_exec_pl.insert(stream_params.clone(), exec_pl);
+                                let state_ids = exec_pl.state_ids();
+                                if let Some(replaced) =
+                                    stream_exec_pl.insert(stream_params.clone(), exec_pl)
+                                {
+                                    // the state of the nodes of the replaced versions
+                                    let stale = replaced
+                                        .state_ids()
+                                        .into_iter()
+                                        .filter(|id| !state_ids.contains(id))
+                                        .collect::<Vec<_>>();
+                                    ExecutablePipeline::remove_state(&stale);
+                                }
                                 log::info!(
                                     "[Pipeline::watch]: pipeline {} added to cache.",
                                     &pipeline.id
@@ -290,7 +337,8 @@ pub async fn watch() -> Result<(), anyhow::Error> {
                     } else {
                         // remove pipeline from cache if the update is to disable
                         if let Some(removed) = pipeline_stream_mapping_cache.remove(pipeline_id) {
-                            if stream_exec_pl.remove(&removed).is_some() {
+                            if let Some(removed) = stream_exec_pl.remove(&removed) {
+                                ExecutablePipeline::remove_state(&removed.state_ids());
                                 log::info!(
                                     "[Pipeline]: pipeline {} disabled and removed from cache.",
                                     pipeline_id
@@ -303,12 +351,10 @@ pub async fn watch() -> Result<(), anyhow::Error> {
             db::Event::Delete(ev) => {
                 let pipeline_id = ev.key.strip_prefix(PIPELINES_WATCH_PREFIX).unwrap();
                 if let Some(removed) = PIPELINE_STREAM_MAPPING.write().await.remove(pipeline_id) {
-                    if STREAM_EXECUTABLE_PIPELINES
-                        .write()
-                        .await
-                        .remove(&removed)
-                        .is_some()
+                    if let Some(removed) =
+                        STREAM_EXECUTABLE_PIPELINES.write().await.remove(&removed)
                     {
+                        ExecutablePipeline::remove_state(&removed.state_ids());
                         log::info!(
                             "[Pipeline]: pipeline {} deleted and removed from cache.",
                             pipeline_id
@@ -0,0 +1,63 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use config::{meta::pipeline::backfill::BackfillJob, utils::json};
+use infra::errors::Result;
+
+use crate::service::db;
+
+const BACKFILL_KEY_PREFIX: &str = "/pipeline_backfill/";
+
+pub async fn set(job: &BackfillJob) -> Result<()> {
+    db::put(
+        &format!("{BACKFILL_KEY_PREFIX}{}/{}", job.org_id, job.id),
+        json::to_vec(job).unwrap().into(),
+        db::NO_NEED_WATCH,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn get(org_id: &str, job_id: &str) -> Result<BackfillJob> {
+    let val = db::get(&format!("{BACKFILL_KEY_PREFIX}{org_id}/{job_id}")).await?;
+    Ok(json::from_slice(&val)?)
+}
+
+/// Lists the jobs of the org, or of all the orgs when it is empty, oldest first
+pub async fn list(org_id: &str) -> Result<Vec<BackfillJob>> {
+    let prefix = if org_id.is_empty() {
+        BACKFILL_KEY_PREFIX.to_string()
+    } else {
+        format!("{BACKFILL_KEY_PREFIX}{org_id}/")
+    };
+    let mut list: Vec<BackfillJob> = db::list(&prefix)
+        .await?
+        .values()
+        .filter_map(|val| json::from_slice(val).ok())
+        .collect();
+    list.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(list)
+}
+
+pub async fn delete(org_id: &str, job_id: &str) -> Result<()> {
+    db::delete_if_exists(
+        &format!("{BACKFILL_KEY_PREFIX}{org_id}/{job_id}"),
+        false,
+        db::NO_NEED_WATCH,
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,112 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use config::{
+    meta::pipeline::version::{PipelineVersion, StagedPipeline},
+    utils::json,
+};
+use infra::errors::Result;
+
+use crate::service::db;
+
+const PIPELINE_VERSIONS_KEY_PREFIX: &str = "/pipeline_version/";
+const STAGED_PIPELINES_KEY_PREFIX: &str = "/pipeline_staged/";
+
+/// Saves the version, keeping the last `max_versions` versions of the pipeline
+/// when it is not 0
+pub async fn set(pipeline_version: &PipelineVersion, max_versions: usize) -> Result<()> {
+    let pipeline_id = &pipeline_version.pipeline.id;
+    // zero padded to list the versions in order
+    let key = format!(
+        "{PIPELINE_VERSIONS_KEY_PREFIX}{pipeline_id}/{:010}",
+        pipeline_version.version
+    );
+    db::put(
+        &key,
+        json::to_vec(pipeline_version).unwrap().into(),
+        db::NO_NEED_WATCH,
+        None,
+    )
+    .await?;
+
+    if max_versions > 0 {
+        let versions = list(pipeline_id).await?;
+        for expired in versions.iter().skip(max_versions) {
+            let key = format!(
+                "{PIPELINE_VERSIONS_KEY_PREFIX}{pipeline_id}/{:010}",
+                expired.version
+            );
+            db::delete(&key, false, db::NO_NEED_WATCH, None).await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn get(pipeline_id: &str, version: i32) -> Result<PipelineVersion> {
+    let val = db::get(&format!(
+        "{PIPELINE_VERSIONS_KEY_PREFIX}{pipeline_id}/{version:010}"
+    ))
+    .await?;
+    Ok(json::from_slice(&val)?)
+}
+
+/// Lists the versions of the pipeline, latest first
+pub async fn list(pipeline_id: &str) -> Result<Vec<PipelineVersion>> {
+    let mut list: Vec<PipelineVersion> =
+        db::list(&format!("{PIPELINE_VERSIONS_KEY_PREFIX}{pipeline_id}/"))
+            .await?
+            .values()
+            .filter_map(|val| json::from_slice(val).ok())
+            .collect();
+    list.sort_by(|a, b| b.version.cmp(&a.version));
+    Ok(list)
+}
+
+/// Deletes all the versions and the staged version of the pipeline
+pub async fn delete(pipeline_id: &str) -> Result<()> {
+    db::delete_if_exists(
+        &format!("{PIPELINE_VERSIONS_KEY_PREFIX}{pipeline_id}/"),
+        true,
+        db::NO_NEED_WATCH,
+    )
+    .await?;
+    delete_staged(pipeline_id).await
+}
+
+pub async fn set_staged(pipeline_id: &str, staged: &StagedPipeline) -> Result<()> {
+    db::put(
+        &format!("{STAGED_PIPELINES_KEY_PREFIX}{pipeline_id}"),
+        json::to_vec(staged).unwrap().into(),
+        db::NO_NEED_WATCH,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn get_staged(pipeline_id: &str) -> Result<StagedPipeline> {
+    let val = db::get(&format!("{STAGED_PIPELINES_KEY_PREFIX}{pipeline_id}")).await?;
+    Ok(json::from_slice(&val)?)
+}
+
+pub async fn delete_staged(pipeline_id: &str) -> Result<()> {
+    db::delete_if_exists(
+        &format!("{STAGED_PIPELINES_KEY_PREFIX}{pipeline_id}"),
+        false,
+        db::NO_NEED_WATCH,
+    )
+    .await?;
+    Ok(())
+}
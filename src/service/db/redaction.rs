@@ -0,0 +1,152 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use config::{
+    meta::redaction::MaskingPolicy,
+    utils::{json, rand::generate_random_string},
+};
+use infra::{
+    dist_lock,
+    errors::{DbError, Error},
+};
+
+use crate::{
+    common::infra::config::{MASKING_POLICIES, REDACTION_SALTS},
+    service::db,
+};
+
+const MASKING_POLICY_KEY_PREFIX: &str = "/masking_policy/";
+
+const REDACTION_SALT_KEY_PREFIX: &str = "/redaction_salt/";
+
+pub async fn set(org_id: &str, policy: &MaskingPolicy) -> Result<(), anyhow::Error> {
+    let key = format!("{MASKING_POLICY_KEY_PREFIX}{org_id}/{}", policy.name);
+    db::put(
+        &key,
+        json::to_vec(policy).unwrap().into(),
+        db::NEED_WATCH,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn get(org_id: &str, name: &str) -> Result<MaskingPolicy, anyhow::Error> {
+    let val = db::get(&format!("{MASKING_POLICY_KEY_PREFIX}{org_id}/{name}")).await?;
+    Ok(json::from_slice(&val)?)
+}
+
+pub async fn list(org_id: &str) -> Result<Vec<MaskingPolicy>, anyhow::Error> {
+    let mut list: Vec<MaskingPolicy> = db::list(&format!("{MASKING_POLICY_KEY_PREFIX}{org_id}/"))
+        .await?
+        .values()
+        .filter_map(|val| json::from_slice(val).ok())
+        .collect();
+    list.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(list)
+}
+
+pub async fn delete(org_id: &str, name: &str) -> Result<(), anyhow::Error> {
+    let key = format!("{MASKING_POLICY_KEY_PREFIX}{org_id}/{name}");
+    db::delete(&key, false, db::NEED_WATCH, None).await?;
+    Ok(())
+}
+
+pub async fn watch() -> Result<(), anyhow::Error> {
+    let key = MASKING_POLICY_KEY_PREFIX;
+    let cluster_coordinator = db::get_coordinator().await;
+    let mut events = cluster_coordinator.watch(key).await?;
+    let events = Arc::get_mut(&mut events).unwrap();
+    log::info!("Start watching masking policies");
+    loop {
+        let ev = match events.recv().await {
+            Some(ev) => ev,
+            None => {
+                log::error!("watch_masking_policies: event channel closed");
+                break;
+            }
+        };
+        match ev {
+            db::Event::Put(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                let item_value: MaskingPolicy = match db::get(&ev.key).await {
+                    Ok(val) => match json::from_slice(&val) {
+                        Ok(val) => val,
+                        Err(e) => {
+                            log::error!("Error getting value: {}", e);
+                            continue;
+                        }
+                    },
+                    Err(e) => {
+                        log::error!("Error getting value: {}", e);
+                        continue;
+                    }
+                };
+                MASKING_POLICIES.insert(item_key.to_owned(), item_value);
+            }
+            db::Event::Delete(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                MASKING_POLICIES.remove(item_key);
+            }
+            db::Event::Empty => {}
+        }
+    }
+    Ok(())
+}
+
+pub async fn cache() -> Result<(), anyhow::Error> {
+    let key = MASKING_POLICY_KEY_PREFIX;
+    let ret = db::list(key).await?;
+    for (item_key, item_value) in ret {
+        let item_key = item_key.strip_prefix(key).unwrap();
+        let json_val: MaskingPolicy = json::from_slice(&item_value)?;
+        MASKING_POLICIES.insert(item_key.to_string(), json_val);
+    }
+    log::info!("Masking policies Cached");
+    Ok(())
+}
+
+/// Returns the salt of the hashes of the redacted values of the org, it is
+/// created on first use and never changes so equal values keep equal hashes
+pub async fn get_salt(org_id: &str) -> Result<String, anyhow::Error> {
+    if let Some(salt) = REDACTION_SALTS.get(org_id) {
+        return Ok(salt.value().clone());
+    }
+    let key = format!("{REDACTION_SALT_KEY_PREFIX}{org_id}");
+    let salt = match db::get(&key).await {
+        Ok(val) => String::from_utf8(val.to_vec())?,
+        Err(Error::DbError(DbError::KeyNotExists(_))) => {
+            // another node may be creating it
+            let locker = dist_lock::lock(&key, 0).await?;
+            let ret = match db::get(&key).await {
+                Ok(val) => String::from_utf8(val.to_vec()).map_err(anyhow::Error::from),
+                Err(_) => {
+                    let salt = generate_random_string(32);
+                    db::put(&key, salt.clone().into(), db::NO_NEED_WATCH, None)
+                        .await
+                        .map(|_| salt)
+                        .map_err(anyhow::Error::from)
+                }
+            };
+            dist_lock::unlock(&locker).await?;
+            ret?
+        }
+        Err(e) => return Err(e.into()),
+    };
+    REDACTION_SALTS.insert(org_id.to_string(), salt.clone());
+    Ok(salt)
+}
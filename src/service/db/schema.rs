@@ -385,17 +385,9 @@ pub async fn watch() -> Result<(), anyhow::Error> {
                 let stream_name = keys[2];
 
                 if stream_type.eq(&StreamType::EnrichmentTables) {
-                    let data = super::enrichment_table::get(org_id, stream_name)
-                        .await
-                        .unwrap();
-                    ENRICHMENT_TABLES.insert(
-                        item_key.to_owned(),
-                        StreamTable {
-                            org_id: org_id.to_string(),
-                            stream_name: stream_name.to_string(),
-                            data,
-                        },
-                    );
+                    if let Err(e) = super::enrichment_table::reload(org_id, stream_name).await {
+                        log::error!("Error reloading enrichment table {item_key}: {e}");
+                    }
                 }
             }
             db::Event::Delete(ev) => {
@@ -537,11 +529,7 @@ pub async fn cache_enrichment_tables() -> Result<(), anyhow::Error> {
         }
         tables.insert(
             schema_key.to_owned(),
-            StreamTable {
-                org_id: org_id.to_string(),
-                stream_name: stream_name.to_string(),
-                data: vec![],
-            },
+            StreamTable::new(org_id, stream_name, vec![], 0),
         );
     }
     drop(r);
@@ -565,15 +553,8 @@ pub async fn cache_enrichment_tables() -> Result<(), anyhow::Error> {
 
     // fill data
     for (key, tbl) in tables {
-        let data = super::enrichment_table::get(&tbl.org_id, &tbl.stream_name).await?;
-        ENRICHMENT_TABLES.insert(
-            key,
-            StreamTable {
-                org_id: tbl.org_id,
-                stream_name: tbl.stream_name,
-                data,
-            },
-        );
+        let table = super::enrichment_table::get_table(&tbl.org_id, &tbl.stream_name).await?;
+        ENRICHMENT_TABLES.insert(key, table);
     }
     log::info!("EnrichmentTables Cached");
     Ok(())
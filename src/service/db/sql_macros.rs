@@ -0,0 +1,114 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use config::{meta::sql_macro::SqlMacro, utils::json};
+
+use crate::{common::infra::config::SQL_MACROS, service::db};
+
+const SQL_MACROS_KEY_PREFIX: &str = "/sql_macro/";
+
+pub async fn set(org_id: &str, sql_macro: &SqlMacro) -> Result<(), anyhow::Error> {
+    let key = format!("{SQL_MACROS_KEY_PREFIX}{org_id}/{}", sql_macro.name);
+    db::put(
+        &key,
+        json::to_vec(sql_macro).unwrap().into(),
+        db::NEED_WATCH,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn get(org_id: &str, name: &str) -> Result<SqlMacro, anyhow::Error> {
+    let val = db::get(&format!("{SQL_MACROS_KEY_PREFIX}{org_id}/{name}")).await?;
+    Ok(json::from_slice(&val)?)
+}
+
+pub async fn delete(org_id: &str, name: &str) -> Result<(), anyhow::Error> {
+    let key = format!("{SQL_MACROS_KEY_PREFIX}{org_id}/{name}");
+    db::delete(&key, false, db::NEED_WATCH, None).await?;
+    Ok(())
+}
+
+pub async fn list(org_id: &str) -> Result<Vec<SqlMacro>, anyhow::Error> {
+    let mut list: Vec<SqlMacro> = db::list(&format!("{SQL_MACROS_KEY_PREFIX}{org_id}/"))
+        .await?
+        .values()
+        .filter_map(|val| json::from_slice(val).ok())
+        .collect();
+    list.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(list)
+}
+
+pub async fn watch() -> Result<(), anyhow::Error> {
+    let key = SQL_MACROS_KEY_PREFIX;
+    let cluster_coordinator = db::get_coordinator().await;
+    let mut events = cluster_coordinator.watch(key).await?;
+    let events = Arc::get_mut(&mut events).unwrap();
+    log::info!("Start watching sql macros");
+    loop {
+        let ev = match events.recv().await {
+            Some(ev) => ev,
+            None => {
+                log::error!("watch_sql_macros: event channel closed");
+                break;
+            }
+        };
+        match ev {
+            db::Event::Put(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                let item_value: SqlMacro = match db::get(&ev.key).await {
+                    Ok(val) => match json::from_slice(&val) {
+                        Ok(val) => val,
+                        Err(e) => {
+                            log::error!("Error getting value: {}", e);
+                            continue;
+                        }
+                    },
+                    Err(e) => {
+                        log::error!("Error getting value: {}", e);
+                        continue;
+                    }
+                };
+                SQL_MACROS.insert(item_key.to_owned(), item_value);
+            }
+            db::Event::Delete(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                SQL_MACROS.remove(item_key);
+            }
+            db::Event::Empty => {}
+        }
+    }
+    Ok(())
+}
+
+pub async fn cache() -> Result<(), anyhow::Error> {
+    let key = SQL_MACROS_KEY_PREFIX;
+    let ret = db::list(key).await?;
+    for (item_key, item_value) in ret {
+        let item_key = item_key.strip_prefix(key).unwrap();
+        let json_val: SqlMacro = json::from_slice(&item_value)?;
+        SQL_MACROS.insert(item_key.to_string(), json_val);
+    }
+    log::info!("SQL macros Cached");
+    Ok(())
+}
+
+pub async fn reset() -> Result<(), anyhow::Error> {
+    db::delete(SQL_MACROS_KEY_PREFIX, true, db::NO_NEED_WATCH, None).await?;
+    Ok(())
+}
@@ -26,8 +26,25 @@ pub struct StreamTable {
     pub org_id: String,
     pub stream_name: String,
     pub data: Vec<vrl::value::Value>,
+    /// time of the last upload, used as the version of the table
+    pub updated_at: i64,
+}
+
+impl StreamTable {
+    pub fn new(
+        org_id: &str,
+        stream_name: &str,
+        data: Vec<vrl::value::Value>,
+        updated_at: i64,
+    ) -> Self {
+        Self {
+            org_id: org_id.to_string(),
+            stream_name: stream_name.to_string(),
+            data,
+            updated_at,
+        }
+    }
 }
-impl StreamTable {}
 
 #[async_trait]
 impl Table for StreamTable {
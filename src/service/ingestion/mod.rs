@@ -52,7 +52,11 @@ use crate::{
         meta::{ingestion::IngestionRequest, stream::SchemaRecords},
         utils::functions::get_vrl_compiler_config,
     },
-    service::{alerts::alert::AlertExt, db, logs::bulk::TRANSFORM_FAILED},
+    service::{
+        alerts::{alert::AlertExt, silences},
+        db,
+        logs::bulk::TRANSFORM_FAILED,
+    },
 };
 
 pub mod grpc;
@@ -253,8 +257,21 @@ pub async fn evaluate_trigger(triggers: TriggerAlertData) {
             evaluation_took_in_secs: None,
             source_node: Some(LOCAL_NODE.name.clone()),
             query_took: None,
+            silenced_by: None,
         };
-        match alert.send_notification(val, now, None, now).await {
+        let group_by = alert.get_group_by_fields(val.first());
+        let (val, silenced) =
+            silences::filter_alert_rows(alert, val.to_vec(), &group_by, now).await;
+        if !silenced.is_empty() {
+            trigger_data_stream.silenced_by = Some(silences::names(&silenced));
+            if val.is_empty() {
+                trigger_data_stream.status = TriggerDataStatus::Silenced;
+                trigger_data_stream.end_time = Utc::now().timestamp_micros();
+                trigger_usage_reports.push(trigger_data_stream);
+                continue;
+            }
+        }
+        match alert.send_notification(&val, now, None, now).await {
             Err(e) => {
                 log::error!("Failed to send notification: {}", e);
                 trigger_data_stream.status = TriggerDataStatus::Failed;
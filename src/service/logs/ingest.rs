@@ -411,6 +411,87 @@ pub async fn ingest(
     ))
 }
 
+/// Writes the records replayed by a pipeline backfill into the WAL of the stream, keyed by
+/// their own timestamp. Unlike [`ingest`], records older than `ZO_INGEST_ALLOWED_UPTO` are
+/// kept, and the pipelines of the stream don't run, as for the records a pipeline writes
+/// into its destinations.
+pub async fn ingest_backfill(
+    thread_id: usize,
+    org_id: &str,
+    stream_name: &str,
+    records: Vec<json::Value>,
+) -> Result<IngestionResponse> {
+    let start = std::time::Instant::now();
+    let started_at: i64 = Utc::now().timestamp_micros();
+    let log_ingestion_errors = ingestion_log_enabled().await;
+    check_ingestion_allowed(org_id, Some(stream_name))?;
+
+    let mut user_defined_schema_map: HashMap<String, HashSet<String>> = HashMap::new();
+    crate::service::ingestion::get_uds_and_original_data_streams(
+        &[StreamParams::new(org_id, stream_name, StreamType::Logs)],
+        &mut user_defined_schema_map,
+        &mut HashSet::new(),
+    )
+    .await;
+
+    let mut stream_status = StreamStatus::new(stream_name);
+    let mut ts_data = Vec::with_capacity(records.len());
+    for mut res in records {
+        // any timestamp is accepted, the records were ingested before
+        let timestamp = match handle_timestamp(&mut res, i64::MIN) {
+            Ok(ts) => ts,
+            Err(e) => {
+                stream_status.status.failed += 1;
+                stream_status.status.error = e.to_string();
+                metrics::INGEST_ERRORS
+                    .with_label_values(&[
+                        org_id,
+                        StreamType::Logs.as_str(),
+                        stream_name,
+                        TS_PARSE_FAILED,
+                    ])
+                    .inc();
+                log_failed_record(log_ingestion_errors, &res, &e.to_string());
+                continue;
+            }
+        };
+        let mut local_val = match res.take() {
+            json::Value::Object(val) => val,
+            _ => unreachable!(),
+        };
+        if let Some(fields) = user_defined_schema_map.get(stream_name) {
+            local_val = crate::service::logs::refactor_map(local_val, fields);
+        }
+        ts_data.push((timestamp, local_val));
+    }
+    if ts_data.is_empty() {
+        return Ok(IngestionResponse::new(
+            http::StatusCode::OK.into(),
+            vec![stream_status],
+        ));
+    }
+
+    let mut status = IngestionStatus::Record(stream_status.status);
+    super::write_logs_by_stream(
+        thread_id,
+        org_id,
+        "",
+        (started_at, &start),
+        UsageType::Json,
+        &mut status,
+        HashMap::from([(stream_name.to_string(), (ts_data, Some(0)))]),
+    )
+    .await?;
+    stream_status.status = match status {
+        IngestionStatus::Record(status) => status,
+        IngestionStatus::Bulk(_) => unreachable!(),
+    };
+    Ok(IngestionResponse::new(
+        http::StatusCode::OK.into(),
+        vec![stream_status],
+    ))
+}
+
 pub fn handle_timestamp(value: &mut json::Value, min_ts: i64) -> Result<i64, anyhow::Error> {
     let local_val = value
         .as_object_mut()
@@ -36,6 +36,7 @@ pub mod node;
 pub mod organization;
 pub mod pipeline;
 pub mod promql;
+pub mod redaction;
 pub mod schema;
 pub mod search;
 #[cfg(feature = "enterprise")]
@@ -43,6 +44,7 @@ pub mod search_jobs;
 pub mod self_reporting;
 pub mod session;
 pub mod short_url;
+pub mod sql_macros;
 pub mod stream;
 pub mod syslogs_route;
 pub mod tls;
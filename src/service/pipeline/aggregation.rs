@@ -0,0 +1,504 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Windowed aggregation of the records of realtime pipelines.
+//!
+//! The open windows of an AggregationNode are kept in memory by each
+//! ingester, which aggregates the records it receives: the windows still open
+//! are lost on restart. The event time of the records drives the windows: a
+//! window closes once a record at least the allowed lateness past its end
+//! arrives, later records of the window are dropped. Without records, the
+//! event time is taken to go on with the processing time and the windows are
+//! closed by [run].
+//!
+//! The percentiles are estimated with a DDSketch, within 1% of the value.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use chrono::Utc;
+use config::{
+    TIMESTAMP_COL_NAME,
+    cluster::LOCAL_NODE,
+    meta::{
+        pipeline::components::{AggregationFunction, AggregationParams},
+        stream::StreamType,
+    },
+    utils::json::{self, Map, Value},
+};
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use sketches_ddsketch::DDSketch;
+
+use crate::common::infra::config::STREAM_EXECUTABLE_PIPELINES;
+
+/// Max number of groups of a window, the records of the other groups are
+/// dropped
+const MAX_GROUPS_PER_WINDOW: usize = 100_000;
+
+/// Interval of the closing of the windows without records
+const FLUSH_INTERVAL_SECS: u64 = 5;
+
+/// Open windows of the AggregationNodes by pipeline id and node id
+static WINDOWS: Lazy<Mutex<HashMap<(String, String), Windows>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Output {
+    /// Records of the closed windows
+    pub records: Vec<Value>,
+    /// Records which arrived after their windows closed
+    pub late: usize,
+    /// Records of groups over the max number of groups of a window
+    pub overflow: usize,
+}
+
+/// Aggregates the records, with their timestamp in microseconds, into the
+/// windows of the node and returns the windows which closed
+pub fn process(
+    pipeline_id: &str,
+    node_id: &str,
+    params: &AggregationParams,
+    records: Vec<(i64, Map<String, Value>)>,
+) -> Output {
+    let now = Utc::now().timestamp_micros();
+    let mut nodes = WINDOWS.lock();
+    let windows = nodes
+        .entry((pipeline_id.to_string(), node_id.to_string()))
+        .or_insert_with(|| Windows::new(params.clone()));
+    // the node changed with the pipeline
+    if &windows.params != params {
+        *windows = Windows::new(params.clone());
+    }
+    let mut output = Output::default();
+    for (timestamp, record) in records {
+        match windows.add(timestamp, &record) {
+            Added::Yes => windows.updated_at = now,
+            Added::Late => output.late += 1,
+            Added::Overflow => output.overflow += 1,
+        }
+    }
+    output.records = windows.close();
+    output
+}
+
+/// Closes the windows of the node which would have closed had the event time
+/// gone on with the processing time since its latest record
+pub fn flush(pipeline_id: &str, node_id: &str, params: &AggregationParams, now: i64) -> Vec<Value> {
+    let mut nodes = WINDOWS.lock();
+    match nodes.get_mut(&(pipeline_id.to_string(), node_id.to_string())) {
+        // the windows of a node which changed are dropped by its next batch
+        Some(windows) if &windows.params == params => windows.close_idle(now),
+        _ => Vec::new(),
+    }
+}
+
+/// Drops the open windows of all the AggregationNodes of the pipeline
+pub fn remove_pipeline(pipeline_id: &str) {
+    WINDOWS.lock().retain(|(id, _), _| id != pipeline_id);
+}
+
+/// Closes the windows left open by the lack of records every few seconds, and
+/// runs their records through the rest of their pipelines
+pub async fn run() {
+    if !LOCAL_NODE.is_ingester() {
+        return;
+    }
+    let mut interval = tokio::time::interval(Duration::from_secs(FLUSH_INTERVAL_SECS));
+    interval.tick().await; // trigger the first run
+    loop {
+        interval.tick().await;
+        flush_pipelines().await;
+    }
+}
+
+async fn flush_pipelines() {
+    let pipelines = STREAM_EXECUTABLE_PIPELINES
+        .read()
+        .await
+        .iter()
+        .map(|(stream_params, pipeline)| (stream_params.org_id.clone(), pipeline.clone()))
+        .collect::<Vec<_>>();
+    for (org_id, pipeline) in pipelines {
+        let pipeline_id = pipeline.get_pipeline_id();
+        let results = match pipeline.flush(&org_id).await {
+            Ok(results) => results,
+            Err(e) => {
+                log::error!(
+                    "[Pipeline] {pipeline_id}: error flushing the aggregation windows: {e}"
+                );
+                continue;
+            }
+        };
+        for (params, records) in results {
+            if params.stream_type != StreamType::Logs {
+                log::warn!(
+                    "[Pipeline] {pipeline_id}: aggregation windows can't be flushed to the {} stream {}",
+                    params.stream_type,
+                    params.stream_name
+                );
+                continue;
+            }
+            let records = records.into_iter().map(|(_, record)| record).collect();
+            if let Err(e) = crate::service::logs::ingest::ingest_backfill(
+                0,
+                &params.org_id,
+                &params.stream_name,
+                records,
+            )
+            .await
+            {
+                log::error!(
+                    "[Pipeline] {pipeline_id}: error writing the aggregation windows to {}: {e}",
+                    params.stream_name
+                );
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Added {
+    Yes,
+    Late,
+    Overflow,
+}
+
+struct Windows {
+    params: AggregationParams,
+    /// Groups of the open windows by window start
+    windows: BTreeMap<i64, HashMap<String, Group>>,
+    /// Latest timestamp of the records
+    watermark: i64,
+    /// Processing time of the latest record
+    updated_at: i64,
+    /// The windows ending the allowed lateness before it are closed
+    closed_before: i64,
+}
+
+struct Group {
+    labels: Map<String, Value>,
+    accumulators: Vec<Accumulator>,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    /// Distribution of the values of percentile aggregations
+    sketch: Option<DDSketch>,
+}
+
+impl Windows {
+    fn new(params: AggregationParams) -> Self {
+        Self {
+            params,
+            windows: BTreeMap::new(),
+            watermark: i64::MIN,
+            updated_at: i64::MIN,
+            closed_before: i64::MIN,
+        }
+    }
+
+    fn size(&self) -> i64 {
+        self.params.window_size * 1_000_000
+    }
+
+    fn lateness(&self) -> i64 {
+        self.params.allowed_lateness * 1_000_000
+    }
+
+    fn is_closed(&self, start: i64) -> bool {
+        start + self.size() + self.lateness() <= self.closed_before
+    }
+
+    /// Adds the record to the open windows containing its timestamp
+    fn add(&mut self, timestamp: i64, record: &Map<String, Value>) -> Added {
+        let (size, slide) = (self.size(), self.params.slide() * 1_000_000);
+        let last_start = timestamp - timestamp.rem_euclid(slide);
+        if self.is_closed(last_start) {
+            return Added::Late;
+        }
+        self.watermark = self.watermark.max(timestamp);
+
+        let labels = self
+            .params
+            .group_by
+            .iter()
+            .map(|field| {
+                let value = record.get(field).cloned().unwrap_or(Value::Null);
+                (field.to_string(), value)
+            })
+            .collect::<Map<_, _>>();
+        let key = json::to_string(&labels).unwrap_or_default();
+        let mut added = Added::Overflow;
+        let mut start = last_start;
+        while start + size > timestamp && !self.is_closed(start) {
+            let groups = self.windows.entry(start).or_default();
+            if groups.len() < MAX_GROUPS_PER_WINDOW || groups.contains_key(&key) {
+                let group = groups.entry(key.clone()).or_insert_with(|| Group {
+                    labels: labels.clone(),
+                    accumulators: self
+                        .params
+                        .aggregations
+                        .iter()
+                        .map(|_| Accumulator::default())
+                        .collect(),
+                });
+                for (aggregation, accumulator) in self
+                    .params
+                    .aggregations
+                    .iter()
+                    .zip(group.accumulators.iter_mut())
+                {
+                    if aggregation.function == AggregationFunction::Count {
+                        if aggregation.field.is_empty()
+                            || record.get(&aggregation.field).is_some_and(|v| !v.is_null())
+                        {
+                            accumulator.count += 1;
+                        }
+                    } else if let Some(value) = record.get(&aggregation.field).and_then(numeric) {
+                        accumulator.add(
+                            value,
+                            aggregation.function == AggregationFunction::Percentile,
+                        );
+                    }
+                }
+                added = Added::Yes;
+            }
+            start -= slide;
+        }
+        added
+    }
+
+    /// Closes the windows ending the allowed lateness before the watermark
+    /// and returns their records, in the order of the windows
+    fn close(&mut self) -> Vec<Value> {
+        self.close_until(self.watermark)
+    }
+
+    /// Closes the windows as if the event time went on with the processing
+    /// time since the latest record
+    fn close_idle(&mut self, now: i64) -> Vec<Value> {
+        if self.windows.is_empty() {
+            return Vec::new();
+        }
+        let idle = now.saturating_sub(self.updated_at).max(0);
+        self.close_until(self.watermark.saturating_add(idle))
+    }
+
+    fn close_until(&mut self, watermark: i64) -> Vec<Value> {
+        let (size, lateness) = (self.size(), self.lateness());
+        self.closed_before = self.closed_before.max(watermark);
+        let mut records = Vec::new();
+        while let Some(entry) = self.windows.first_entry() {
+            let start = *entry.key();
+            if start + size + lateness > self.closed_before {
+                break;
+            }
+            let mut groups = entry.remove().into_iter().collect::<Vec<_>>();
+            groups.sort_by(|a, b| a.0.cmp(&b.0));
+            for (_, group) in groups {
+                records.push(self.result(start, group));
+            }
+        }
+        records
+    }
+
+    fn result(&self, start: i64, group: Group) -> Value {
+        let mut record = group.labels;
+        record.insert(TIMESTAMP_COL_NAME.to_string(), start.into());
+        record.insert("window_start".to_string(), start.into());
+        record.insert("window_end".to_string(), (start + self.size()).into());
+        for (aggregation, accumulator) in self
+            .params
+            .aggregations
+            .iter()
+            .zip(group.accumulators.into_iter())
+        {
+            let value = match aggregation.function {
+                AggregationFunction::Count => Some(accumulator.count as f64),
+                _ if accumulator.count == 0 => None,
+                AggregationFunction::Sum => Some(accumulator.sum),
+                AggregationFunction::Min => Some(accumulator.min),
+                AggregationFunction::Max => Some(accumulator.max),
+                AggregationFunction::Avg => Some(accumulator.sum / accumulator.count as f64),
+                AggregationFunction::Percentile => accumulator.sketch.as_ref().and_then(|sketch| {
+                    let q = aggregation.percentile.unwrap_or_default() / 100.0;
+                    sketch.quantile(q.clamp(0.0, 1.0)).ok().flatten()
+                }),
+            };
+            let value = match value {
+                Some(v) if aggregation.function == AggregationFunction::Count => {
+                    Value::from(v as u64)
+                }
+                Some(v) => json::Number::from_f64(v)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null),
+                None => Value::Null,
+            };
+            record.insert(aggregation.alias.to_string(), value);
+        }
+        Value::Object(record)
+    }
+}
+
+impl Accumulator {
+    fn add(&mut self, value: f64, keep: bool) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+        self.sum += value;
+        if keep {
+            self.sketch.get_or_insert_with(DDSketch::default).add(value);
+        }
+    }
+}
+
+fn numeric(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(v) => v.as_f64(),
+        Value::String(v) => v.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use config::meta::pipeline::components::{Aggregation, WindowType};
+
+    use super::*;
+
+    const SEC: i64 = 1_000_000;
+
+    fn params(window_type: WindowType, allowed_lateness: i64) -> AggregationParams {
+        let aggregation = |function, field: &str, alias: &str| Aggregation {
+            function,
+            field: field.to_string(),
+            percentile: (function == AggregationFunction::Percentile).then_some(50.0),
+            alias: alias.to_string(),
+        };
+        AggregationParams {
+            group_by: vec!["status".to_string()],
+            window_type,
+            window_size: 60,
+            slide: 30,
+            allowed_lateness,
+            aggregations: vec![
+                aggregation(AggregationFunction::Count, "", "requests"),
+                aggregation(AggregationFunction::Sum, "took", "took_sum"),
+                aggregation(AggregationFunction::Max, "took", "took_max"),
+                aggregation(AggregationFunction::Percentile, "took", "took_p50"),
+            ],
+        }
+    }
+
+    fn record(status: i64, took: i64) -> Map<String, Value> {
+        json::json!({"status": status, "took": took})
+            .as_object()
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn test_tumbling_windows() {
+        let mut windows = Windows::new(params(WindowType::Tumbling, 10));
+        assert_eq!(windows.add(5 * SEC, &record(200, 10)), Added::Yes);
+        assert_eq!(windows.add(20 * SEC, &record(200, 30)), Added::Yes);
+        assert_eq!(windows.add(30 * SEC, &record(500, 5)), Added::Yes);
+        assert_eq!(windows.add(65 * SEC, &record(200, 1)), Added::Yes);
+        // the first window is still open for late records
+        assert!(windows.close().is_empty());
+        assert_eq!(windows.add(59 * SEC, &record(200, 20)), Added::Yes);
+
+        assert_eq!(windows.add(70 * SEC, &record(200, 1)), Added::Yes);
+        let mut records = windows.close();
+        assert_eq!(records.len(), 2);
+        // estimated within 1%
+        let p50 = records[0].as_object_mut().unwrap().remove("took_p50");
+        assert!(
+            p50.and_then(|v| v.as_f64())
+                .is_some_and(|v| (v - 20.0).abs() <= 0.2)
+        );
+        assert_eq!(
+            records[0],
+            json::json!({
+                "status": 200,
+                "_timestamp": 0,
+                "window_start": 0,
+                "window_end": 60 * SEC,
+                "requests": 3,
+                "took_sum": 60.0,
+                "took_max": 30.0,
+            })
+        );
+        assert_eq!(records[1]["status"], 500);
+        assert_eq!(records[1]["requests"], 1);
+
+        assert_eq!(windows.add(40 * SEC, &record(200, 1)), Added::Late);
+    }
+
+    #[test]
+    fn test_sliding_windows() {
+        let starts = |records: Vec<Value>| {
+            records
+                .iter()
+                .map(|r| {
+                    (
+                        r["window_start"].as_i64().unwrap() / SEC,
+                        r["requests"].as_u64().unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut windows = Windows::new(params(WindowType::Sliding, 0));
+        windows.add(45 * SEC, &record(200, 10));
+        windows.add(50 * SEC, &record(200, 10));
+        windows.add(90 * SEC, &record(200, 10));
+        assert_eq!(starts(windows.close()), vec![(0, 2), (30, 2)]);
+
+        windows.add(150 * SEC, &record(200, 10));
+        assert_eq!(starts(windows.close()), vec![(60, 1), (90, 1)]);
+    }
+
+    #[test]
+    fn test_close_idle_windows() {
+        let mut windows = Windows::new(params(WindowType::Tumbling, 10));
+        windows.add(5 * SEC, &record(200, 10));
+        windows.updated_at = 0;
+        assert!(windows.close_idle(60 * SEC).is_empty());
+        // 65s later the event time would be past the end and the lateness
+        let records = windows.close_idle(65 * SEC);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["requests"], 1);
+        assert_eq!(windows.add(30 * SEC, &record(200, 1)), Added::Late);
+    }
+
+    #[test]
+    fn test_percentile_sketch() {
+        let mut accumulator = Accumulator::default();
+        for value in 1..=1000 {
+            accumulator.add(value as f64, true);
+        }
+        let p95 = accumulator.sketch.unwrap().quantile(0.95).unwrap().unwrap();
+        assert!((p95 - 950.0).abs() <= 9.5);
+    }
+}
@@ -0,0 +1,481 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Backfill jobs, replaying a time range of the source stream of a realtime
+//! pipeline through one of its versions into its destinations.
+//!
+//! A job is split in partitions of its time range, read page by page with a
+//! search cursor. The cursor of the partition is saved after each page, so a
+//! job resumes where it stopped when the node running it restarts or stops
+//! updating it. A page may be replayed twice when a node stops before saving
+//! its progress.
+//!
+//! The nodes of the replayed pipeline keep their state apart from the running
+//! pipeline. Records replayed into logs streams are written into the WAL keyed
+//! by their own timestamp, so any time range can be replayed into them, while
+//! metrics and traces destinations go through the ingesters and only accept
+//! records within `ZO_INGEST_ALLOWED_UPTO`.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use config::{
+    TIMESTAMP_COL_NAME,
+    cluster::LOCAL_NODE,
+    get_config, ider,
+    meta::{
+        pipeline::{
+            backfill::{
+                BACKFILL_INGEST_METADATA_KEY, BackfillJob, BackfillJobList, BackfillRequest,
+                BackfillStatus,
+            },
+            components::PipelineSource,
+        },
+        search,
+        stream::{StreamParams, StreamType},
+    },
+    utils::{
+        json,
+        time::{hour_micros, now_micros, second_micros},
+    },
+};
+use infra::dist_lock;
+use proto::cluster_rpc;
+
+use super::{batch_execution::ExecutablePipeline, versions};
+use crate::service::{
+    db::{self, pipeline::PipelineError},
+    ingestion::ingestion_service,
+    search as SearchService,
+};
+
+const BACKFILL_LOCK_KEY: &str = "/pipeline_backfill/lock";
+
+pub async fn create(
+    org_id: &str,
+    pipeline_id: &str,
+    req: BackfillRequest,
+    author: &str,
+) -> Result<BackfillJob, PipelineError> {
+    req.validate().map_err(PipelineError::InvalidBackfill)?;
+    let cfg = get_config();
+
+    let current = versions::get_pipeline(org_id, pipeline_id).await?;
+    let pipeline = match req.version {
+        Some(version) if version != current.version => {
+            versions::get_version(org_id, pipeline_id, version)
+                .await?
+                .pipeline
+        }
+        _ => current,
+    };
+    let PipelineSource::Realtime(source) = pipeline.source.clone() else {
+        return Err(PipelineError::InvalidBackfill(
+            "Only realtime pipelines can be backfilled".to_string(),
+        ));
+    };
+    // fail now rather than when the job runs
+    let exec_pl = match ExecutablePipeline::new_dry_run(&pipeline).await {
+        Ok(exec_pl) => exec_pl,
+        Err(e) => return Err(PipelineError::InvalidBackfill(e.to_string())),
+    };
+    // replayed records keep their timestamp, the ingesters reject older metrics and traces
+    let min_ts = now_micros() - hour_micros(cfg.limit.ingest_allowed_upto);
+    let has_ingested_destinations = exec_pl
+        .get_all_destination_streams()
+        .iter()
+        .any(|dest| matches!(dest.stream_type, StreamType::Metrics | StreamType::Traces));
+    if has_ingested_destinations && req.start_time < min_ts {
+        return Err(PipelineError::InvalidBackfill(format!(
+            "Backfill start_time is older than the {} hours allowed for ingestion of metrics and traces by ZO_INGEST_ALLOWED_UPTO",
+            cfg.limit.ingest_allowed_upto
+        )));
+    }
+
+    let max_records_per_sec = match req.max_records_per_sec {
+        Some(rate) if cfg.pipeline.backfill_max_records_per_sec > 0 => {
+            rate.min(cfg.pipeline.backfill_max_records_per_sec)
+        }
+        Some(rate) => rate,
+        None => cfg.pipeline.backfill_max_records_per_sec,
+    };
+    let now = now_micros();
+    let job = BackfillJob {
+        id: ider::uuid(),
+        org_id: org_id.to_string(),
+        pipeline_id: pipeline_id.to_string(),
+        pipeline_version: pipeline.version,
+        pipeline,
+        source,
+        start_time: req.start_time,
+        end_time: req.end_time,
+        max_records_per_sec,
+        status: BackfillStatus::Pending,
+        created_by: author.to_string(),
+        created_at: now,
+        updated_at: now,
+        started_at: None,
+        ended_at: None,
+        node: None,
+        error_message: None,
+        progress: 0.0,
+        records_read: 0,
+        records_written: 0,
+        partitions: req.partitions(),
+    };
+    db::pipeline_backfill::set(&job).await?;
+    Ok(job)
+}
+
+pub async fn list(org_id: &str, pipeline_id: &str) -> Result<BackfillJobList, PipelineError> {
+    versions::get_pipeline(org_id, pipeline_id).await?;
+    let list = db::pipeline_backfill::list(org_id)
+        .await?
+        .into_iter()
+        .filter(|job| job.pipeline_id == pipeline_id)
+        .collect();
+    Ok(BackfillJobList { list })
+}
+
+pub async fn get(
+    org_id: &str,
+    pipeline_id: &str,
+    job_id: &str,
+) -> Result<BackfillJob, PipelineError> {
+    match db::pipeline_backfill::get(org_id, job_id).await {
+        Ok(job) if job.pipeline_id == pipeline_id => Ok(job),
+        _ => Err(PipelineError::BackfillNotFound(job_id.to_string())),
+    }
+}
+
+/// Stops the job, the node running it stops after the page it is replaying
+pub async fn cancel(org_id: &str, pipeline_id: &str, job_id: &str) -> Result<(), PipelineError> {
+    let mut job = get(org_id, pipeline_id, job_id).await?;
+    if job.status.is_done() {
+        return Err(PipelineError::InvalidBackfill(format!(
+            "Backfill job {job_id} already stopped"
+        )));
+    }
+    let now = now_micros();
+    job.status = BackfillStatus::Cancelled;
+    job.ended_at = Some(now);
+    job.updated_at = now;
+    db::pipeline_backfill::set(&job).await?;
+    Ok(())
+}
+
+/// Runs a failed or cancelled job again, from the partitions it didn't finish
+pub async fn retry(org_id: &str, pipeline_id: &str, job_id: &str) -> Result<(), PipelineError> {
+    let mut job = get(org_id, pipeline_id, job_id).await?;
+    if !matches!(
+        job.status,
+        BackfillStatus::Failed | BackfillStatus::Cancelled
+    ) {
+        return Err(PipelineError::InvalidBackfill(format!(
+            "Only failed or cancelled backfill jobs can be retried, job {job_id} is {:?}",
+            job.status
+        )));
+    }
+    job.status = BackfillStatus::Pending;
+    job.node = None;
+    job.error_message = None;
+    job.ended_at = None;
+    job.updated_at = now_micros();
+    db::pipeline_backfill::set(&job).await?;
+    Ok(())
+}
+
+/// Deletes the job, the node running it stops after the page it is replaying
+pub async fn delete(org_id: &str, pipeline_id: &str, job_id: &str) -> Result<(), PipelineError> {
+    get(org_id, pipeline_id, job_id).await?;
+    db::pipeline_backfill::delete(org_id, job_id).await?;
+    Ok(())
+}
+
+/// Deletes the jobs of a deleted pipeline
+pub(crate) async fn delete_pipeline_jobs(org_id: &str, pipeline_id: &str) {
+    let jobs = match db::pipeline_backfill::list(org_id).await {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            log::error!("[Pipeline] {pipeline_id}: error listing the backfill jobs: {e}");
+            return;
+        }
+    };
+    for job in jobs.iter().filter(|job| job.pipeline_id == pipeline_id) {
+        if let Err(e) = db::pipeline_backfill::delete(org_id, &job.id).await {
+            log::error!(
+                "[Pipeline] {pipeline_id}: error deleting backfill job {}: {e}",
+                job.id
+            );
+        }
+    }
+}
+
+/// Runs the oldest job waiting to run, or the oldest running job whose node
+/// stopped updating it
+pub async fn run() -> Result<(), anyhow::Error> {
+    let Some(job) = claim().await? else {
+        return Ok(());
+    };
+    let (org_id, job_id) = (job.org_id.clone(), job.id.clone());
+    log::info!(
+        "[PIPELINE BACKFILL] job_id: {job_id}, start running pipeline {} version {}",
+        job.pipeline_id,
+        job.pipeline_version
+    );
+    let start = Instant::now();
+    match run_job(job).await {
+        Ok(()) => log::info!(
+            "[PIPELINE BACKFILL] job_id: {job_id}, stopped running, time_elapsed: {}ms",
+            start.elapsed().as_millis()
+        ),
+        Err(e) => {
+            log::error!("[PIPELINE BACKFILL] job_id: {job_id}, failed: {e}");
+            let Ok(mut job) = db::pipeline_backfill::get(&org_id, &job_id).await else {
+                return Ok(());
+            };
+            if is_running_here(&job) {
+                let now = now_micros();
+                job.status = BackfillStatus::Failed;
+                job.error_message = Some(e.to_string());
+                job.ended_at = Some(now);
+                job.updated_at = now;
+                db::pipeline_backfill::set(&job).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn claim() -> Result<Option<BackfillJob>, anyhow::Error> {
+    let locker = dist_lock::lock(BACKFILL_LOCK_KEY, 0).await?;
+    let ret = claim_job().await;
+    dist_lock::unlock(&locker).await?;
+    ret
+}
+
+async fn claim_job() -> Result<Option<BackfillJob>, anyhow::Error> {
+    let now = now_micros();
+    let expired_at = now - second_micros(get_config().pipeline.backfill_timeout);
+    let Some(mut job) = db::pipeline_backfill::list("")
+        .await?
+        .into_iter()
+        .find(|job| {
+            job.status == BackfillStatus::Pending
+                || (job.status == BackfillStatus::Running && job.updated_at < expired_at)
+        })
+    else {
+        return Ok(None);
+    };
+    job.status = BackfillStatus::Running;
+    job.node = Some(LOCAL_NODE.name.clone());
+    job.started_at.get_or_insert(now);
+    job.updated_at = now;
+    db::pipeline_backfill::set(&job).await?;
+    Ok(Some(job))
+}
+
+fn is_running_here(job: &BackfillJob) -> bool {
+    job.status == BackfillStatus::Running && job.node.as_deref() == Some(LOCAL_NODE.name.as_str())
+}
+
+async fn run_job(job: BackfillJob) -> Result<(), anyhow::Error> {
+    let mut exec_pl = ExecutablePipeline::new(&job.pipeline).await?;
+    // the replayed records must not consume the state of the live records
+    exec_pl.set_state_namespace(format!("{}/backfill/{}", job.pipeline_id, job.id));
+    let ret = replay_partitions(job, &exec_pl).await;
+    exec_pl.remove_state_namespace();
+    ret
+}
+
+async fn replay_partitions(
+    mut job: BackfillJob,
+    exec_pl: &ExecutablePipeline,
+) -> Result<(), anyhow::Error> {
+    let batch_size = get_config().pipeline.backfill_batch_size.max(1);
+    let trace_id = ider::uuid();
+
+    for idx in 0..job.partitions.len() {
+        while !job.partitions[idx].finished {
+            let start = Instant::now();
+            let (records, cursor) = read_page(&trace_id, &job, idx, batch_size).await?;
+            let num_records = records.len() as u64;
+            let written = if records.is_empty() {
+                0
+            } else {
+                replay(&job, exec_pl, records).await?
+            };
+
+            let partition = &mut job.partitions[idx];
+            partition.records += num_records;
+            partition.finished = cursor.is_none();
+            partition.cursor = cursor;
+            job.records_read += num_records;
+            job.records_written += written;
+            job.update_progress();
+            job.updated_at = now_micros();
+            if !save_progress(&job).await? {
+                log::info!(
+                    "[PIPELINE BACKFILL] job_id: {}, cancelled or taken over, stop running",
+                    job.id
+                );
+                return Ok(());
+            }
+            throttle(start, num_records, job.max_records_per_sec).await;
+        }
+    }
+
+    let now = now_micros();
+    job.status = BackfillStatus::Finished;
+    job.ended_at = Some(now);
+    job.updated_at = now;
+    save_progress(&job).await?;
+    Ok(())
+}
+
+/// Saves the progress of the job unless it was cancelled, deleted or taken
+/// over by another node, returns whether it was saved
+async fn save_progress(job: &BackfillJob) -> Result<bool, anyhow::Error> {
+    match db::pipeline_backfill::get(&job.org_id, &job.id).await {
+        Ok(saved) if is_running_here(&saved) => {
+            db::pipeline_backfill::set(job).await?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Reads the next page of the partition from the source stream, with the
+/// cursor of the page after it, none once the partition is read
+async fn read_page(
+    trace_id: &str,
+    job: &BackfillJob,
+    idx: usize,
+    batch_size: i64,
+) -> Result<(Vec<json::Value>, Option<String>), anyhow::Error> {
+    let partition = &job.partitions[idx];
+    let req = search::Request {
+        query: search::Query {
+            sql: format!(
+                "SELECT * FROM \"{}\" ORDER BY {TIMESTAMP_COL_NAME} ASC",
+                job.source.stream_name
+            ),
+            size: batch_size,
+            start_time: partition.start_time,
+            end_time: partition.end_time,
+            use_cursor: true,
+            cursor: partition.cursor.clone(),
+            ..Default::default()
+        },
+        encoding: search::RequestEncoding::Empty,
+        regions: vec![],
+        clusters: vec![],
+        timeout: 0,
+        search_type: None,
+        search_event_context: None,
+        use_cache: None,
+        local_mode: None,
+    };
+    let res = SearchService::cache::search(
+        trace_id,
+        &job.org_id,
+        job.source.stream_type,
+        None,
+        &req,
+        "".to_string(),
+    )
+    .await?;
+    Ok((res.hits, res.cursor))
+}
+
+/// Runs the records through the pipeline and writes the results into the
+/// destination streams, returns the number of records written. Records the
+/// pipeline sends back to its source stream are already there, they are not
+/// written again.
+async fn replay(
+    job: &BackfillJob,
+    exec_pl: &ExecutablePipeline,
+    records: Vec<json::Value>,
+) -> Result<u64, anyhow::Error> {
+    let results = exec_pl
+        .process_batch(
+            &job.org_id,
+            records,
+            Some(job.source.stream_name.to_string()),
+        )
+        .await?;
+
+    let mut written = 0;
+    let mut records_by_stream: HashMap<StreamParams, Vec<json::Value>> = HashMap::new();
+    for (stream_params, stream_records) in results {
+        if stream_params == job.source
+            || !matches!(
+                stream_params.stream_type,
+                StreamType::Logs | StreamType::Metrics | StreamType::Traces
+            )
+        {
+            continue;
+        }
+        records_by_stream
+            .entry(stream_params)
+            .or_default()
+            .extend(stream_records.into_iter().map(|(_, record)| record));
+    }
+    for (dest_stream, records) in records_by_stream {
+        let num_records = records.len() as u64;
+        let metadata = if dest_stream.stream_type == StreamType::Logs {
+            Some(HashMap::from([(
+                BACKFILL_INGEST_METADATA_KEY.to_string(),
+                job.id.to_string(),
+            )]))
+        } else {
+            job.pipeline.get_metadata_by_stream_params(&dest_stream)
+        };
+        let req = cluster_rpc::IngestionRequest {
+            org_id: dest_stream.org_id.to_string(),
+            stream_name: dest_stream.stream_name.to_string(),
+            stream_type: dest_stream.stream_type.to_string(),
+            data: Some(cluster_rpc::IngestionData::from(records)),
+            ingestion_type: Some(cluster_rpc::IngestionType::Json.into()),
+            metadata: metadata.map(|meta| cluster_rpc::IngestRequestMetadata { data: meta }),
+        };
+        match ingestion_service::ingest(req).await {
+            Ok(resp) if resp.status_code == 200 => written += num_records,
+            error => {
+                let err = error.map_or_else(|e| e.to_string(), |resp| resp.message);
+                return Err(anyhow::anyhow!(
+                    "failed to ingest into destination {}/{}/{}: {err}",
+                    dest_stream.org_id,
+                    dest_stream.stream_name,
+                    dest_stream.stream_type
+                ));
+            }
+        }
+    }
+    Ok(written)
+}
+
+/// Waits long enough for the job to process no more than `max_records_per_sec`
+async fn throttle(started: Instant, num_records: u64, max_records_per_sec: u64) {
+    if max_records_per_sec == 0 {
+        return;
+    }
+    let min_duration = Duration::from_secs_f64(num_records as f64 / max_records_per_sec as f64);
+    if let Some(remaining) = min_duration.checked_sub(started.elapsed()) {
+        tokio::time::sleep(remaining).await;
+    }
+}
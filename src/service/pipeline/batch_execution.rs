@@ -13,7 +13,11 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
@@ -21,10 +25,20 @@ use chrono::Utc;
 use config::{
     meta::{
         function::{Transform, VRLResultResolver},
-        pipeline::{Pipeline, components::NodeData},
-        self_reporting::error::{ErrorData, ErrorSource, PipelineError},
+        pipeline::{
+            Pipeline,
+            components::NodeData,
+            condition::CompiledCondition,
+            parser::{CompiledParser, OnFailure},
+        },
+        redaction::{CompiledRedaction, RedactionAction},
+        self_reporting::{
+            error::{ErrorData, ErrorSource, PipelineError},
+            usage::PipelineNodeData,
+        },
         stream::{StreamParams, StreamType},
     },
+    metrics,
     utils::{
         flatten,
         json::{Value, get_string_value},
@@ -40,7 +54,8 @@ use crate::{
     common::infra::config::QUERY_FUNCTIONS,
     service::{
         ingestion::{apply_vrl_fn, compile_vrl_function},
-        self_reporting::publish_error,
+        pipeline::{aggregation, dedup, rate_limit},
+        self_reporting::{publish_error, publish_pipeline_node_usage},
     },
 };
 
@@ -88,6 +103,20 @@ pub struct ExecutablePipeline {
     sorted_nodes: Vec<String>,
     vrl_map: HashMap<String, VRLResultResolver>,
     node_map: HashMap<String, ExecutableNode>,
+    staged: Option<StagedExecutablePipeline>,
+    /// Id of the state of the nodes, by pipeline id and version so that the versions
+    /// of a pipeline don't share it
+    state_id: String,
+    /// Namespace of the state of the nodes when the pipeline runs apart from the live
+    /// traffic of its source stream
+    state_namespace: Option<String>,
+}
+
+/// Staged version of a pipeline, processing a share of the records of the batches
+#[derive(Debug, Clone)]
+struct StagedExecutablePipeline {
+    percentage: f64,
+    pipeline: Box<ExecutablePipeline>,
 }
 
 #[derive(Debug, Clone)]
@@ -95,6 +124,36 @@ pub struct ExecutableNode {
     id: String,
     node_data: NodeData,
     children: Vec<String>,
+    /// Node params compiled once when the pipeline is built, shared by its batches
+    compiled: CompiledNode,
+}
+
+#[derive(Debug, Clone)]
+enum CompiledNode {
+    None,
+    Condition(Arc<CompiledCondition>),
+    Parser(Arc<CompiledParser>),
+    Redaction(Arc<CompiledRedaction>),
+}
+
+impl CompiledNode {
+    fn compile(node_data: &NodeData) -> std::result::Result<Self, String> {
+        match node_data {
+            NodeData::Condition(condition_params) => condition_params
+                .compile()
+                .map(|condition| Self::Condition(Arc::new(condition)))
+                .map_err(|e| format!("ConditionNode error with conditions: {e}")),
+            NodeData::Parser(parser_params) => parser_params
+                .compile()
+                .map(|parser| Self::Parser(Arc::new(parser)))
+                .map_err(|e| format!("ParserNode error with parser: {e}")),
+            NodeData::Redaction(redaction_params) => redaction_params
+                .compile()
+                .map(|redaction| Self::Redaction(Arc::new(redaction)))
+                .map_err(|e| format!("RedactionNode error with detectors: {e}")),
+            _ => Ok(Self::None),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -114,27 +173,93 @@ pub struct ExecutablePipelineTraceInputs {
     span_durations: Vec<f64>,
 }
 
+/// A record passing through a pipeline during a dry run
+#[derive(Debug)]
+pub enum TraceEvent {
+    /// Record sent by a node to one of its children
+    Edge {
+        source: String,
+        target: String,
+        idx: usize,
+        record: Value,
+    },
+    /// Record sent by a leaf node to its destination stream
+    Destination {
+        source: String,
+        idx: usize,
+        stream_params: StreamParams,
+        record: Value,
+    },
+}
+
+/// Errors of the nodes as (node_id, node_type, error)
+pub type NodeErrorList = Vec<(String, String, String)>;
+
+/// Records emitted by a node during a batch, and the time spent processing them
+#[derive(Debug, Default, Clone, Copy)]
+struct NodeStats {
+    emitted: usize,
+    took: Option<Duration>,
+}
+
+struct BatchOutput {
+    results: HashMap<StreamParams, Vec<(usize, Value)>>,
+    errors: NodeErrorList,
+    node_stats: HashMap<String, NodeStats>,
+}
+
 impl ExecutablePipeline {
     pub async fn new(pipeline: &Pipeline) -> Result<Self> {
-        let node_map = pipeline
-            .nodes
-            .iter()
-            .map(|node| {
-                (
-                    node.get_node_id(),
-                    ExecutableNode {
-                        id: node.get_node_id(),
-                        node_data: node.get_node_data(),
-                        children: pipeline
-                            .edges
-                            .iter()
-                            .filter(|edge| edge.source == node.id)
-                            .map(|edge| edge.target.clone())
-                            .collect(),
-                    },
-                )
-            })
-            .collect();
+        Self::init(pipeline, true).await
+    }
+
+    /// Builds the pipeline for a dry run, without publishing the init errors
+    pub async fn new_dry_run(pipeline: &Pipeline) -> Result<Self> {
+        Self::init(pipeline, false).await
+    }
+
+    async fn init(pipeline: &Pipeline, publish_errors: bool) -> Result<Self> {
+        let mut node_map = HashMap::with_capacity(pipeline.nodes.len());
+        for node in pipeline.nodes.iter() {
+            let node_data = node.get_node_data();
+            let compiled = match CompiledNode::compile(&node_data) {
+                Ok(compiled) => compiled,
+                Err(e) => {
+                    let pipeline_error = PipelineError {
+                        pipeline_id: pipeline.id.to_string(),
+                        pipeline_name: pipeline.name.to_string(),
+                        error: Some(format!(
+                            "Init error: failed to compile node {}: {e}",
+                            node.id
+                        )),
+                        node_errors: HashMap::new(),
+                    };
+                    if publish_errors {
+                        publish_error(ErrorData {
+                            _timestamp: Utc::now().timestamp_micros(),
+                            stream_params: pipeline.get_source_stream_params(),
+                            error_source: ErrorSource::Pipeline(pipeline_error),
+                        })
+                        .await;
+                    }
+                    return Err(anyhow!("failed to compile node {}: {e}", node.id));
+                }
+            };
+            node_map.insert(
+                node.get_node_id(),
+                ExecutableNode {
+                    id: node.get_node_id(),
+                    node_data,
+                    children: pipeline
+                        .edges
+                        .iter()
+                        .filter(|edge| edge.source == node.id)
+                        .map(|edge| edge.target.clone())
+                        .collect(),
+                    compiled,
+                },
+            );
+        }
 
         let vrl_map = match pipeline.register_functions().await {
             Ok(vrl_map) => vrl_map,
@@ -145,12 +270,14 @@ impl ExecutablePipeline {
                     error: Some(format!("Init error: failed to compile function: {e}")),
                     node_errors: HashMap::new(),
                 };
-                publish_error(ErrorData {
-                    _timestamp: Utc::now().timestamp_micros(),
-                    stream_params: pipeline.get_source_stream_params(),
-                    error_source: ErrorSource::Pipeline(pipeline_error),
-                })
-                .await;
+                if publish_errors {
+                    publish_error(ErrorData {
+                        _timestamp: Utc::now().timestamp_micros(),
+                        stream_params: pipeline.get_source_stream_params(),
+                        error_source: ErrorSource::Pipeline(pipeline_error),
+                    })
+                    .await;
+                }
                 return Err(e);
             }
         };
@@ -165,12 +292,14 @@ impl ExecutablePipeline {
                     ),
                     node_errors: HashMap::new(),
                 };
-                publish_error(ErrorData {
-                    _timestamp: Utc::now().timestamp_micros(),
-                    stream_params: pipeline.get_source_stream_params(),
-                    error_source: ErrorSource::Pipeline(pipeline_error),
-                })
-                .await;
+                if publish_errors {
+                    publish_error(ErrorData {
+                        _timestamp: Utc::now().timestamp_micros(),
+                        stream_params: pipeline.get_source_stream_params(),
+                        error_source: ErrorSource::Pipeline(pipeline_error),
+                    })
+                    .await;
+                }
                 return Err(e);
             }
         };
@@ -183,16 +312,194 @@ impl ExecutablePipeline {
             node_map,
             sorted_nodes,
             vrl_map,
+            staged: None,
+            state_id: format!("{}/{}", pipeline.id, pipeline.version),
+            state_namespace: None,
         })
     }
 
+    /// Keeps the state of the nodes under `namespace`, apart from the running pipeline,
+    /// and doesn't report the node metrics of the batches
+    pub fn set_state_namespace(&mut self, namespace: String) {
+        self.state_id = namespace.clone();
+        self.state_namespace = Some(namespace);
+    }
+
+    /// Drops the state of the nodes kept under the namespace of the pipeline
+    pub fn remove_state_namespace(&self) {
+        if let Some(namespace) = &self.state_namespace {
+            Self::remove_state(std::slice::from_ref(namespace));
+        }
+    }
+
+    /// Ids of the state of the nodes of the pipeline and of its staged version
+    pub fn state_ids(&self) -> Vec<String> {
+        std::iter::once(&self.state_id)
+            .chain(self.staged.iter().map(|staged| &staged.pipeline.state_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Drops the state of the nodes kept under the ids, once no cached pipeline uses them
+    pub fn remove_state(state_ids: &[String]) {
+        for state_id in state_ids {
+            aggregation::remove_pipeline(state_id);
+            dedup::remove_pipeline(state_id);
+            rate_limit::remove_pipeline(state_id);
+        }
+    }
+
+    /// Sends `percentage` percent of the records of each batch to the staged version
+    /// of the pipeline instead
+    pub fn set_staged(&mut self, mut staged: ExecutablePipeline, percentage: f64) {
+        // staged on the same version as the pipeline, its nodes keep their own state
+        staged.state_id = format!("{}/staged", self.state_id);
+        self.staged = Some(StagedExecutablePipeline {
+            percentage,
+            pipeline: Box::new(staged),
+        });
+    }
+
     pub async fn process_batch(
         &self,
         org_id: &str,
         records: Vec<Value>,
         stream_name: Option<String>,
     ) -> Result<HashMap<StreamParams, Vec<(usize, Value)>>> {
+        let Some(staged) = &self.staged else {
+            return self.process_records(org_id, records, stream_name).await;
+        };
+
+        // split the batch, keeping the index of each record in the batch
+        let mut current = (Vec::new(), Vec::new());
+        let mut candidate = (Vec::new(), Vec::new());
+        for (idx, record) in records.into_iter().enumerate() {
+            let part = if rand::random::<f64>() * 100.0 < staged.percentage {
+                &mut candidate
+            } else {
+                &mut current
+            };
+            part.0.push(idx);
+            part.1.push(record);
+        }
+
+        let mut results: HashMap<StreamParams, Vec<(usize, Value)>> = HashMap::new();
+        for (pipeline, (indexes, records)) in
+            [(self, current), (staged.pipeline.as_ref(), candidate)]
+        {
+            if records.is_empty() {
+                continue;
+            }
+            let part_results = pipeline
+                .process_records(org_id, records, stream_name.clone())
+                .await?;
+            for (stream_params, stream_records) in part_results {
+                results.entry(stream_params).or_default().extend(
+                    stream_records
+                        .into_iter()
+                        .map(|(idx, record)| (indexes[idx], record)),
+                );
+            }
+        }
+        for stream_records in results.values_mut() {
+            stream_records.sort_by_key(|(idx, _)| *idx);
+        }
+        Ok(results)
+    }
+
+    async fn process_records(
+        &self,
+        org_id: &str,
+        records: Vec<Value>,
+        stream_name: Option<String>,
+    ) -> Result<HashMap<StreamParams, Vec<(usize, Value)>>> {
+        let batch_size = records.len();
+        let BatchOutput {
+            results,
+            errors,
+            node_stats,
+        } = self
+            .execute(org_id, records, stream_name, None, false)
+            .await?;
+        if self.state_namespace.is_none() {
+            self.report_node_stats(org_id, batch_size, &node_stats, &errors)
+                .await;
+        }
+
+        // Publish errors if received any
+        if !errors.is_empty() {
+            let mut pipeline_errors = PipelineError::new(&self.id, &self.name);
+            for (node_id, node_type, error) in errors {
+                pipeline_errors.add_node_error(node_id, node_type, error);
+            }
+            let stream_params = self.get_source_stream_params();
+            let error_data = ErrorData {
+                _timestamp: Utc::now().timestamp_micros(),
+                stream_params,
+                error_source: ErrorSource::Pipeline(pipeline_errors),
+            };
+            log::debug!("[Pipeline]: execution errors occurred and published");
+            publish_error(error_data).await;
+        }
+
+        Ok(results)
+    }
+
+    /// Runs the records through the pipeline without writing to remote destinations or
+    /// publishing errors. Each record sent by a node is reported to `trace_sender`, and the
+    /// errors of the nodes are returned.
+    pub async fn dry_run(
+        &self,
+        org_id: &str,
+        records: Vec<Value>,
+        stream_name: Option<String>,
+        trace_sender: Sender<TraceEvent>,
+    ) -> Result<NodeErrorList> {
+        let output = self
+            .execute(org_id, records, stream_name, Some(trace_sender), false)
+            .await?;
+        Ok(output.errors)
+    }
+
+    /// Closes the windows of the aggregation nodes, of the staged version too, left open
+    /// by the lack of records and runs their records through the rest of the pipeline
+    pub async fn flush(&self, org_id: &str) -> Result<HashMap<StreamParams, Vec<(usize, Value)>>> {
+        let pipelines =
+            std::iter::once(self).chain(self.staged.iter().map(|s| s.pipeline.as_ref()));
+        let mut results: HashMap<StreamParams, Vec<(usize, Value)>> = HashMap::new();
+        for pipeline in pipelines {
+            if !pipeline
+                .node_map
+                .values()
+                .any(|node| matches!(node.node_data, NodeData::Aggregation(_)))
+            {
+                continue;
+            }
+            let output = pipeline.execute(org_id, vec![], None, None, true).await?;
+            for (node_id, node_type, error) in output.errors {
+                log::error!(
+                    "[Pipeline] {} : {node_type} {node_id} error flushing the aggregation windows: {error}",
+                    pipeline.name
+                );
+            }
+            for (stream_params, records) in output.results {
+                results.entry(stream_params).or_default().extend(records);
+            }
+        }
+        Ok(results)
+    }
+
+    async fn execute(
+        &self,
+        org_id: &str,
+        records: Vec<Value>,
+        stream_name: Option<String>,
+        trace_sender: Option<Sender<TraceEvent>>,
+        flush: bool,
+    ) -> Result<BatchOutput> {
         let batch_size = records.len();
+        // a flush has no records but may emit the records of the aggregation nodes
+        let capacity = batch_size.max(1);
         let pipeline_name = self.name.clone();
         log::debug!(
             "[Pipeline] {} : process batch of size {}",
@@ -202,16 +509,24 @@ impl ExecutablePipeline {
 
         // result_channel
         let (result_sender, mut result_receiver) =
-            channel::<(usize, StreamParams, Value)>(batch_size);
+            channel::<(usize, StreamParams, Value)>(capacity);
 
         // error_channel
-        let (error_sender, mut error_receiver) = channel::<(String, String, String)>(batch_size);
+        let (error_sender, mut error_receiver) = channel::<(String, String, String)>(capacity);
+
+        // dry runs keep the state of their nodes apart from the running pipeline
+        let dry_run = trace_sender.is_some();
+        let state_id = if dry_run {
+            format!("{}/dry_run/{}", self.id, config::ider::uuid())
+        } else {
+            self.state_id.clone()
+        };
 
         let mut node_senders = HashMap::new();
         let mut node_receivers = HashMap::new();
 
         for node_id in &self.sorted_nodes {
-            let (sender, receiver) = channel::<(usize, Value, bool)>(batch_size);
+            let (sender, receiver) = channel::<(usize, Value, bool)>(capacity);
             node_senders.insert(node_id.to_string(), sender);
             node_receivers.insert(node_id.to_string(), receiver);
         }
@@ -219,16 +534,37 @@ impl ExecutablePipeline {
         // Spawn tasks for each node
         let mut node_tasks = Vec::new();
         for (idx, node_id) in self.sorted_nodes.iter().enumerate() {
-            let pl_id_cp = self.id.to_string();
+            let pl_id_cp = self.id.clone();
+            let state_id_cp = state_id.clone();
             let org_id_cp = org_id.to_string();
             let node = self.node_map.get(node_id).unwrap().clone();
             let node_receiver = node_receivers.remove(node_id).unwrap();
             let child_senders: Vec<_> = node
                 .children
                 .iter()
-                .map(|child| node_senders.get(child).unwrap().clone())
+                .map(|child| {
+                    let child_sender = node_senders.get(child).unwrap().clone();
+                    match &trace_sender {
+                        Some(trace_sender) => trace_edge(
+                            node_id.to_string(),
+                            child.to_string(),
+                            child_sender,
+                            trace_sender.clone(),
+                            batch_size,
+                        ),
+                        None => child_sender,
+                    }
+                })
                 .collect();
-            let result_sender_cp = node.children.is_empty().then_some(result_sender.clone());
+            let result_sender_cp = node.children.is_empty().then(|| match &trace_sender {
+                Some(trace_sender) => trace_destination(
+                    node_id.to_string(),
+                    result_sender.clone(),
+                    trace_sender.clone(),
+                    batch_size,
+                ),
+                None => result_sender.clone(),
+            });
             let error_sender_cp = error_sender.clone();
             let vrl_runtime = self.vrl_map.get(node_id).cloned();
             let pipeline_name = pipeline_name.clone();
@@ -237,6 +573,7 @@ impl ExecutablePipeline {
             let task = tokio::spawn(async move {
                 process_node(
                     pl_id_cp,
+                    state_id_cp,
                     idx,
                     org_id_cp,
                     node,
@@ -247,6 +584,8 @@ impl ExecutablePipeline {
                     error_sender_cp,
                     pipeline_name,
                     stream_name,
+                    dry_run,
+                    flush,
                 )
                 .await
             });
@@ -270,20 +609,14 @@ impl ExecutablePipeline {
         });
 
         // task to collect errors
-        let mut pipeline_error = PipelineError::new(&self.id, &self.name);
         let error_task = tokio::spawn(async move {
             log::debug!("[Pipeline]: starts error collecting job");
-            let mut count = 0;
-            while let Some((node_id, node_type, error)) = error_receiver.recv().await {
-                pipeline_error.add_node_error(node_id, node_type, error);
-                count += 1;
-            }
-            log::debug!("[Pipeline]: collected {count} errors");
-            if count > 0 {
-                Some(pipeline_error)
-            } else {
-                None
+            let mut errors = Vec::new();
+            while let Some(error) = error_receiver.recv().await {
+                errors.push(error);
             }
+            log::debug!("[Pipeline]: collected {} errors", errors.len());
+            errors
         });
 
         // Send records to the source node to begin processing
@@ -304,34 +637,120 @@ impl ExecutablePipeline {
         drop(result_sender);
         drop(error_sender);
         drop(node_senders);
+        drop(trace_sender);
         log::debug!("[Pipeline]: All records send into pipeline for processing");
 
         // Wait for all node tasks to complete
-        if let Err(e) = try_join_all(node_tasks).await {
-            log::error!("[Pipeline] node processing jobs failed: {}", e);
+        let node_stats = match try_join_all(node_tasks).await {
+            Ok(node_results) => self
+                .sorted_nodes
+                .iter()
+                .cloned()
+                .zip(node_results.into_iter().map(|res| res.unwrap_or_default()))
+                .collect(),
+            Err(e) => {
+                log::error!("[Pipeline] node processing jobs failed: {}", e);
+                HashMap::new()
+            }
+        };
+
+        if dry_run {
+            aggregation::remove_pipeline(&state_id);
+            dedup::remove_pipeline(&state_id);
+            rate_limit::remove_pipeline(&state_id);
         }
 
-        // Publish errors if received any
-        if let Some(pipeline_errors) = error_task.await.map_err(|e| {
+        let errors = error_task.await.map_err(|e| {
             log::error!("[Pipeline] error collecting job failed: {}", e);
             anyhow!("[Pipeline] error collecting job failed: {}", e)
-        })? {
-            let stream_params = self.get_source_stream_params();
-            let error_data = ErrorData {
-                _timestamp: Utc::now().timestamp_micros(),
-                stream_params,
-                error_source: ErrorSource::Pipeline(pipeline_errors),
-            };
-            log::debug!("[Pipeline]: execution errors occurred and published");
-            publish_error(error_data).await;
-        }
+        })?;
 
         let results = result_task.await.map_err(|e| {
             log::error!("[Pipeline] result collecting job failed: {}", e);
             anyhow!("[Pipeline] result collecting job failed: {}", e)
         })?;
 
-        Ok(results)
+        Ok(BatchOutput {
+            results,
+            errors,
+            node_stats,
+        })
+    }
+
+    /// Updates the metrics of the nodes and publishes their usage. A node receives the
+    /// records emitted by its parents, the source node receives the whole batch.
+    async fn report_node_stats(
+        &self,
+        org_id: &str,
+        batch_size: usize,
+        node_stats: &HashMap<String, NodeStats>,
+        errors: &NodeErrorList,
+    ) {
+        let mut received: HashMap<&str, usize> = HashMap::new();
+        received.insert(&self.source_node_id, batch_size);
+        for (node_id, node) in &self.node_map {
+            let emitted = node_stats.get(node_id).map_or(0, |stats| stats.emitted);
+            for child in &node.children {
+                *received.entry(child.as_str()).or_default() += emitted;
+            }
+        }
+        let mut failed: HashMap<&str, usize> = HashMap::new();
+        for (node_id, ..) in errors {
+            *failed.entry(node_id.as_str()).or_default() += 1;
+        }
+
+        let timestamp = Utc::now().timestamp_micros();
+        for node_id in &self.sorted_nodes {
+            let node = self.node_map.get(node_id).unwrap();
+            let node_type = node.node_type();
+            let stats = node_stats.get(node_id).copied().unwrap_or_default();
+            let received = received.get(node_id.as_str()).copied().unwrap_or_default();
+            let failed = failed.get(node_id.as_str()).copied().unwrap_or_default();
+            let dropped = received.saturating_sub(stats.emitted);
+            for (status, value) in [
+                ("received", received),
+                ("emitted", stats.emitted),
+                ("dropped", dropped),
+                ("failed", failed),
+            ] {
+                if value > 0 {
+                    metrics::PIPELINE_NODE_RECORDS
+                        .with_label_values(&[
+                            org_id,
+                            self.id.as_str(),
+                            node_id.as_str(),
+                            node_type.as_str(),
+                            status,
+                        ])
+                        .inc_by(value as u64);
+                }
+            }
+            if let Some(took) = stats.took {
+                metrics::PIPELINE_NODE_TIME
+                    .with_label_values(&[
+                        org_id,
+                        self.id.as_str(),
+                        node_id.as_str(),
+                        node_type.as_str(),
+                    ])
+                    .observe(took.as_secs_f64());
+            }
+            publish_pipeline_node_usage(PipelineNodeData {
+                _timestamp: timestamp,
+                org: org_id.to_string(),
+                pipeline_id: self.id.clone(),
+                pipeline_name: self.name.clone(),
+                node_id: node_id.clone(),
+                node_type,
+                batches: 1,
+                received: received as u64,
+                emitted: stats.emitted as u64,
+                dropped: dropped as u64,
+                failed: failed as u64,
+                took_in_secs: stats.took.map(|took| took.as_secs_f64()),
+            })
+            .await;
+        }
     }
 
     pub fn get_all_destination_streams(&self) -> Vec<StreamParams> {
@@ -362,6 +781,14 @@ impl ExecutablePipeline {
         &self.id
     }
 
+    /// Nodes in their execution order
+    pub fn get_sorted_nodes(&self) -> Vec<&ExecutableNode> {
+        self.sorted_nodes
+            .iter()
+            .map(|node_id| self.node_map.get(node_id).unwrap())
+            .collect()
+    }
+
     fn get_source_stream_params(&self) -> StreamParams {
         // source_node_id must exist in node_map
         match &self.node_map.get(&self.source_node_id).unwrap().node_data {
@@ -380,6 +807,14 @@ impl ExecutableNode {
     pub fn node_type(&self) -> String {
         self.to_string()
     }
+
+    pub fn get_node_id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_node_data(&self) -> &NodeData {
+        &self.node_data
+    }
 }
 
 impl std::fmt::Display for ExecutableNode {
@@ -389,6 +824,11 @@ impl std::fmt::Display for ExecutableNode {
             NodeData::Query(_) => write!(f, "query"),
             NodeData::Function(_) => write!(f, "function"),
             NodeData::Condition(_) => write!(f, "condition"),
+            NodeData::Aggregation(_) => write!(f, "aggregation"),
+            NodeData::Parser(_) => write!(f, "parser"),
+            NodeData::Redaction(_) => write!(f, "redaction"),
+            NodeData::Dedup(_) => write!(f, "dedup"),
+            NodeData::RateLimit(_) => write!(f, "rate_limit"),
             NodeData::RemoteStream(_) => write!(f, "remote_stream"),
         }
     }
@@ -487,6 +927,7 @@ impl Default for ExecutablePipelineTraceInputs {
 #[allow(clippy::too_many_arguments)]
 async fn process_node(
     pipeline_id: String,
+    state_id: String,
     node_idx: usize,
     org_id: String,
     node: ExecutableNode,
@@ -497,9 +938,13 @@ async fn process_node(
     error_sender: Sender<(String, String, String)>,
     pipeline_name: String,
     stream_name: Option<String>,
-) -> Result<()> {
+    dry_run: bool,
+    flush: bool,
+) -> Result<NodeStats> {
     let cfg = config::get_config();
     let mut count: usize = 0;
+    // time spent processing records, not waiting for them
+    let mut took: Option<Duration> = None;
     match &node.node_data {
         NodeData::Stream(stream_params) => {
             if node.children.is_empty() {
@@ -590,8 +1035,11 @@ async fn process_node(
                 );
             }
         }
-        NodeData::Condition(condition_params) => {
+        NodeData::Condition(_) => {
             log::debug!("[Pipeline]: cond node {node_idx} starts processing");
+            let CompiledNode::Condition(condition) = &node.compiled else {
+                return Err(anyhow!("ConditionNode {} is not compiled", node.id));
+            };
             while let Some((idx, mut record, mut flattened)) = receiver.recv().await {
                 // value must be flattened before condition params can take effect
                 if !flattened {
@@ -617,12 +1065,11 @@ async fn process_node(
                     };
                     flattened = true;
                 }
-                // only send to children when passing all condition evaluations
-                if condition_params
-                    .conditions
-                    .iter()
-                    .all(|cond| cond.evaluate(record.as_object().unwrap()))
-                {
+                // only send to children when the conditions evaluate to true
+                let start = Instant::now();
+                let passed = condition.evaluate(record.as_object().unwrap());
+                *took.get_or_insert_default() += start.elapsed();
+                if passed {
                     send_to_children(
                         &mut child_senders,
                         (idx, record, flattened),
@@ -634,6 +1081,142 @@ async fn process_node(
             }
             log::debug!("[Pipeline]: cond node {node_idx} done processing {count} records");
         }
+        NodeData::Parser(_) => {
+            log::debug!("[Pipeline]: parser node {node_idx} starts processing");
+            let CompiledNode::Parser(parser) = &node.compiled else {
+                return Err(anyhow!("ParserNode {} is not compiled", node.id));
+            };
+            while let Some((idx, mut record, mut flattened)) = receiver.recv().await {
+                // the field to parse is named as in the flattened record
+                if !flattened {
+                    record = match flatten::flatten_with_level(
+                        record,
+                        cfg.limit.ingest_flatten_level,
+                    ) {
+                        Ok(flattened) => flattened,
+                        Err(e) => {
+                            let err_msg = format!("ParserNode error with flattening: {}", e);
+                            if let Err(send_err) = error_sender
+                                .send((node.id.to_string(), node.node_type(), err_msg))
+                                .await
+                            {
+                                log::error!(
+                                    "[Pipeline] {} : ParserNode failed sending errors for collection caused by: {send_err}",
+                                    pipeline_name
+                                );
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                    flattened = true;
+                }
+                let start = Instant::now();
+                let parsed = parser.apply(record.as_object_mut().unwrap());
+                *took.get_or_insert_default() += start.elapsed();
+                match parsed {
+                    // parsed field names and JSON values need to be flattened again
+                    Ok(()) => flattened = false,
+                    Err(e) => match parser.on_failure {
+                        OnFailure::Keep => {}
+                        OnFailure::Drop => continue,
+                        OnFailure::Error => {
+                            let err_msg = format!("ParserNode error: {}", e);
+                            if let Err(send_err) = error_sender
+                                .send((node.id.to_string(), node.node_type(), err_msg))
+                                .await
+                            {
+                                log::error!(
+                                    "[Pipeline] {} : ParserNode failed sending errors for collection caused by: {send_err}",
+                                    pipeline_name
+                                );
+                                break;
+                            }
+                        }
+                    },
+                }
+                send_to_children(&mut child_senders, (idx, record, flattened), "ParserNode").await;
+                count += 1;
+            }
+            log::debug!("[Pipeline]: parser node {node_idx} done processing {count} records");
+        }
+        NodeData::Redaction(_) => {
+            log::debug!("[Pipeline]: redaction node {node_idx} starts processing");
+            let CompiledNode::Redaction(redaction) = &node.compiled else {
+                return Err(anyhow!("RedactionNode {} is not compiled", node.id));
+            };
+            let salt = if redaction.action == RedactionAction::Hash {
+                match crate::service::db::redaction::get_salt(&org_id).await {
+                    Ok(salt) => salt,
+                    Err(e) => {
+                        let err_msg = format!("RedactionNode error getting the salt: {}", e);
+                        if let Err(send_err) = error_sender
+                            .send((node.id.to_string(), node.node_type(), err_msg))
+                            .await
+                        {
+                            log::error!(
+                                "[Pipeline] {} : RedactionNode failed sending errors for collection caused by: {send_err}",
+                                pipeline_name
+                            );
+                        }
+                        while receiver.recv().await.is_some() {}
+                        return Ok(NodeStats::default());
+                    }
+                }
+            } else {
+                String::new()
+            };
+            let mut counts = vec![0; redaction.detector_names().count()];
+            while let Some((idx, mut record, mut flattened)) = receiver.recv().await {
+                // the fields to scan are named as in the flattened record
+                if !flattened {
+                    record = match flatten::flatten_with_level(
+                        record,
+                        cfg.limit.ingest_flatten_level,
+                    ) {
+                        Ok(flattened) => flattened,
+                        Err(e) => {
+                            let err_msg = format!("RedactionNode error with flattening: {}", e);
+                            if let Err(send_err) = error_sender
+                                .send((node.id.to_string(), node.node_type(), err_msg))
+                                .await
+                            {
+                                log::error!(
+                                    "[Pipeline] {} : RedactionNode failed sending errors for collection caused by: {send_err}",
+                                    pipeline_name
+                                );
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                    flattened = true;
+                }
+                let start = Instant::now();
+                let found = redaction.apply(record.as_object_mut().unwrap(), &salt, &mut counts);
+                *took.get_or_insert_default() += start.elapsed();
+                if found && redaction.action == RedactionAction::DropRecord {
+                    continue;
+                }
+                send_to_children(
+                    &mut child_senders,
+                    (idx, record, flattened),
+                    "RedactionNode",
+                )
+                .await;
+                count += 1;
+            }
+            if !dry_run {
+                crate::service::redaction::report_redactions(
+                    &org_id,
+                    "pipeline",
+                    &pipeline_name,
+                    redaction,
+                    &counts,
+                );
+            }
+            log::debug!("[Pipeline]: redaction node {node_idx} done processing {count} records");
+        }
         NodeData::Function(func_params) => {
             log::debug!("[Pipeline]: func node {node_idx} starts processing");
             let mut runtime = crate::service::ingestion::init_functions_runtime();
@@ -662,13 +1245,16 @@ async fn process_node(
                             }
                         };
                     }
-                    record = match apply_vrl_fn(
+                    let start = Instant::now();
+                    let vrl_result = apply_vrl_fn(
                         &mut runtime,
                         vrl_runtime,
                         record,
                         &org_id,
                         &[stream_name.clone()],
-                    ) {
+                    );
+                    *took.get_or_insert_default() += start.elapsed();
+                    record = match vrl_result {
                         (res, None) => res,
                         (res, Some(error)) => {
                             let err_msg = format!("FunctionNode error: {}", error);
@@ -693,6 +1279,189 @@ async fn process_node(
             }
             log::debug!("[Pipeline]: func node {node_idx} done processing {count} records");
         }
+        NodeData::Aggregation(aggregation_params) => {
+            log::debug!("[Pipeline]: aggregation node {node_idx} starts processing");
+            let min_ts = (Utc::now()
+                - chrono::Duration::try_hours(cfg.limit.ingest_allowed_upto).unwrap())
+            .timestamp_micros();
+            let mut records = Vec::new();
+            let mut last_idx = None;
+            while let Some((idx, mut record, flattened)) = receiver.recv().await {
+                if !flattened {
+                    record = match flatten::flatten_with_level(
+                        record,
+                        cfg.limit.ingest_flatten_level,
+                    ) {
+                        Ok(flattened) => flattened,
+                        Err(e) => {
+                            let err_msg = format!("AggregationNode error with flattening: {}", e);
+                            if let Err(send_err) = error_sender
+                                .send((node.id.to_string(), node.node_type(), err_msg))
+                                .await
+                            {
+                                log::error!(
+                                    "[Pipeline] {} : AggregationNode failed sending errors for collection caused by: {send_err}",
+                                    pipeline_name
+                                );
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                }
+                let timestamp = match crate::service::logs::ingest::handle_timestamp(
+                    &mut record,
+                    min_ts,
+                ) {
+                    Ok(timestamp) => timestamp,
+                    Err(e) => {
+                        let err_msg = format!("AggregationNode error handling timestamp: {}", e);
+                        if let Err(send_err) = error_sender
+                            .send((node.id.to_string(), node.node_type(), err_msg))
+                            .await
+                        {
+                            log::error!(
+                                "[Pipeline] {} : AggregationNode failed sending errors for collection caused by: {send_err}",
+                                pipeline_name
+                            );
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                if let Value::Object(record) = record {
+                    records.push((timestamp, record));
+                }
+                last_idx = Some(idx);
+            }
+
+            let start = Instant::now();
+            let output = if flush {
+                aggregation::Output {
+                    records: aggregation::flush(
+                        &state_id,
+                        &node.id,
+                        aggregation_params,
+                        Utc::now().timestamp_micros(),
+                    ),
+                    ..Default::default()
+                }
+            } else {
+                aggregation::process(&state_id, &node.id, aggregation_params, records)
+            };
+            took = Some(start.elapsed());
+            if output.late > 0 || output.overflow > 0 {
+                let err_msg = format!(
+                    "AggregationNode dropped {} records arriving after their window closed and {} records over the max number of groups of a window",
+                    output.late, output.overflow
+                );
+                if let Err(send_err) = error_sender
+                    .send((node.id.to_string(), node.node_type(), err_msg))
+                    .await
+                {
+                    log::error!(
+                        "[Pipeline] {} : AggregationNode failed sending errors for collection caused by: {send_err}",
+                        pipeline_name
+                    );
+                }
+            }
+            // the records of the closed windows take the index of the last record of the
+            // batch, they don't come from a single record. A flush has no records.
+            if let Some(idx) = last_idx.or(flush.then_some(0)) {
+                for record in output.records {
+                    send_to_children(&mut child_senders, (idx, record, true), "AggregationNode")
+                        .await;
+                    count += 1;
+                }
+            }
+            log::debug!("[Pipeline]: aggregation node {node_idx} emitted {count} records");
+        }
+        NodeData::Dedup(dedup_params) => {
+            log::debug!("[Pipeline]: dedup node {node_idx} starts processing");
+            let mut records = Vec::new();
+            while let Some((idx, mut record, flattened)) = receiver.recv().await {
+                // the key fields are named as in the flattened record
+                if !flattened {
+                    record = match flatten::flatten_with_level(
+                        record,
+                        cfg.limit.ingest_flatten_level,
+                    ) {
+                        Ok(flattened) => flattened,
+                        Err(e) => {
+                            let err_msg = format!("DedupNode error with flattening: {}", e);
+                            if let Err(send_err) = error_sender
+                                .send((node.id.to_string(), node.node_type(), err_msg))
+                                .await
+                            {
+                                log::error!(
+                                    "[Pipeline] {} : DedupNode failed sending errors for collection caused by: {send_err}",
+                                    pipeline_name
+                                );
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                }
+                records.push((idx, record));
+            }
+
+            // the summaries take the index of the last record of the batch, they
+            // don't come from a single record
+            if let Some(last_idx) = records.last().map(|(idx, _)| *idx) {
+                let start = Instant::now();
+                let output = dedup::process(&state_id, &node.id, dedup_params, records);
+                took = Some(start.elapsed());
+                let summaries = output
+                    .summaries
+                    .into_iter()
+                    .map(|record| (last_idx, record));
+                for (idx, record) in output.records.into_iter().chain(summaries) {
+                    send_to_children(&mut child_senders, (idx, record, true), "DedupNode").await;
+                    count += 1;
+                }
+            }
+            log::debug!("[Pipeline]: dedup node {node_idx} done processing {count} records");
+        }
+        NodeData::RateLimit(rate_limit_params) => {
+            log::debug!("[Pipeline]: rate limit node {node_idx} starts processing");
+            let mut records = Vec::new();
+            while let Some((idx, mut record, flattened)) = receiver.recv().await {
+                // the key fields are named as in the flattened record
+                if !flattened {
+                    record = match flatten::flatten_with_level(
+                        record,
+                        cfg.limit.ingest_flatten_level,
+                    ) {
+                        Ok(flattened) => flattened,
+                        Err(e) => {
+                            let err_msg = format!("RateLimitNode error with flattening: {}", e);
+                            if let Err(send_err) = error_sender
+                                .send((node.id.to_string(), node.node_type(), err_msg))
+                                .await
+                            {
+                                log::error!(
+                                    "[Pipeline] {} : RateLimitNode failed sending errors for collection caused by: {send_err}",
+                                    pipeline_name
+                                );
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                }
+                records.push((idx, record));
+            }
+
+            let start = Instant::now();
+            let records = rate_limit::process(&state_id, &node.id, rate_limit_params, records);
+            took = Some(start.elapsed());
+            for (idx, record) in records {
+                send_to_children(&mut child_senders, (idx, record, true), "RateLimitNode").await;
+                count += 1;
+            }
+            log::debug!("[Pipeline]: rate limit node {node_idx} done processing {count} records");
+        }
         NodeData::Query(_) => {
             // source node for Scheduled pipeline. Directly send to children nodes
             log::debug!("[Pipeline]: query node {node_idx} starts processing");
@@ -702,7 +1471,6 @@ async fn process_node(
             }
             log::debug!("[Pipeline]: query node {node_idx} done processing {count} records");
         }
-        #[cfg(feature = "enterprise")]
         NodeData::RemoteStream(remote_stream) => {
             let mut records = vec![];
             log::debug!(
@@ -755,10 +1523,35 @@ async fn process_node(
                 count += 1;
             }
 
+            // dry runs don't write to the remote destination
+            if dry_run {
+                log::debug!(
+                    "[Pipeline]: DestinationNode {node_idx} skips writing {count} records for dry run"
+                );
+                return Ok(NodeStats {
+                    emitted: count,
+                    took,
+                });
+            }
+            if records.is_empty() {
+                return Ok(NodeStats {
+                    emitted: count,
+                    took,
+                });
+            }
+            let start = Instant::now();
             let mut remote_stream = remote_stream.clone();
             remote_stream.org_id = org_id.into();
-            let writer = get_pipeline_wal_writer(&pipeline_id, remote_stream).await?;
-            if let Err(e) = writer.write_wal(records).await {
+            #[cfg(feature = "enterprise")]
+            let result = get_pipeline_wal_writer(&pipeline_id, remote_stream)
+                .await?
+                .write_wal(records)
+                .await;
+            #[cfg(not(feature = "enterprise"))]
+            let result =
+                super::remote_destination::write(&pipeline_id, &remote_stream, records).await;
+            took = Some(start.elapsed());
+            if let Err(e) = result {
                 let err_msg = format!(
                     "DestinationNode error persisting data to be ingested externally: {}",
                     e
@@ -775,24 +1568,75 @@ async fn process_node(
 
             log::debug!("[Pipeline]: DestinationNode {node_idx} done processing {count} records");
         }
-        #[cfg(not(feature = "enterprise"))]
-        NodeData::RemoteStream(_) => {
-            let err_msg = "[Pipeline]: remote destination is not supported in open source version. Records dropped".to_string();
-            log::error!("{err_msg}");
-            if let Err(send_err) = error_sender
-                .send((node.id.to_string(), node.node_type(), err_msg))
-                .await
-            {
-                log::error!(
-                    "[Pipeline({pipeline_id})]: DestinationNode failed sending errors for collection caused by: {send_err}"
-                );
-            }
-        }
     }
 
     // all cloned senders dropped when function goes out of scope -> close the channel
 
-    Ok(())
+    Ok(NodeStats {
+        emitted: count,
+        took,
+    })
+}
+
+/// Reports the records sent over the edge from `source` to `target` before
+/// forwarding them to the target node
+fn trace_edge(
+    source: String,
+    target: String,
+    target_sender: Sender<(usize, Value, bool)>,
+    trace_sender: Sender<TraceEvent>,
+    batch_size: usize,
+) -> Sender<(usize, Value, bool)> {
+    let (sender, mut receiver) = channel::<(usize, Value, bool)>(batch_size);
+    tokio::spawn(async move {
+        while let Some((idx, record, flattened)) = receiver.recv().await {
+            let event = TraceEvent::Edge {
+                source: source.clone(),
+                target: target.clone(),
+                idx,
+                record: record.clone(),
+            };
+            if let Err(send_err) = trace_sender.send(event).await {
+                log::error!("[Pipeline]: failed sending trace of dry run caused by: {send_err}");
+            }
+            if target_sender.send((idx, record, flattened)).await.is_err() {
+                break;
+            }
+        }
+    });
+    sender
+}
+
+/// Reports the records a leaf node sends to its destination stream before
+/// forwarding them for collection
+fn trace_destination(
+    source: String,
+    result_sender: Sender<(usize, StreamParams, Value)>,
+    trace_sender: Sender<TraceEvent>,
+    batch_size: usize,
+) -> Sender<(usize, StreamParams, Value)> {
+    let (sender, mut receiver) = channel::<(usize, StreamParams, Value)>(batch_size);
+    tokio::spawn(async move {
+        while let Some((idx, stream_params, record)) = receiver.recv().await {
+            let event = TraceEvent::Destination {
+                source: source.clone(),
+                idx,
+                stream_params: stream_params.clone(),
+                record: record.clone(),
+            };
+            if let Err(send_err) = trace_sender.send(event).await {
+                log::error!("[Pipeline]: failed sending trace of dry run caused by: {send_err}");
+            }
+            if result_sender
+                .send((idx, stream_params, record))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+    sender
 }
 
 async fn send_to_children(
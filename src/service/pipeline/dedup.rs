@@ -0,0 +1,260 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Deduplication of the records of the pipelines.
+//!
+//! The keys seen by a DedupNode are kept in memory by each ingester and shared
+//! by the batches it processes concurrently: the duplicates received by
+//! different ingesters aren't detected and the keys are forgotten on restart.
+//! A key is remembered for the window following its first record, in
+//! processing time, the summary of its duplicates is emitted with the first
+//! batch processed after the window ends.
+
+use std::collections::VecDeque;
+
+use chrono::Utc;
+use config::{
+    TIMESTAMP_COL_NAME,
+    meta::pipeline::components::DedupParams,
+    utils::{
+        hash::{Sum64, gxhash},
+        json::{self, Map, Value},
+    },
+};
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+/// Max number of keys remembered by a node, the oldest keys are forgotten
+/// first
+const MAX_KEYS: usize = 100_000;
+
+/// Keys seen by the DedupNodes by pipeline id and node id
+static NODES: Lazy<Mutex<HashMap<(String, String), Dedup>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Output {
+    /// Records seen for the first time within the window, with their index
+    pub records: Vec<(usize, Value)>,
+    /// Number of duplicates of each key whose window ended
+    pub summaries: Vec<Value>,
+}
+
+/// Drops the duplicates of the records and returns the other records and the
+/// summaries of the windows which ended
+pub fn process(
+    pipeline_id: &str,
+    node_id: &str,
+    params: &DedupParams,
+    records: Vec<(usize, Value)>,
+) -> Output {
+    let now = Utc::now().timestamp_micros();
+    let mut nodes = NODES.lock();
+    let dedup = nodes
+        .entry((pipeline_id.to_string(), node_id.to_string()))
+        .or_insert_with(|| Dedup::new(params.clone()));
+    // the node changed with the pipeline
+    if &dedup.params != params {
+        *dedup = Dedup::new(params.clone());
+    }
+    dedup.process(now, records)
+}
+
+/// Forgets the keys of all the DedupNodes of the pipeline
+pub fn remove_pipeline(pipeline_id: &str) {
+    NODES.lock().retain(|(id, _), _| id != pipeline_id);
+}
+
+struct Dedup {
+    params: DedupParams,
+    keys: HashMap<u64, Key>,
+    /// Hashes of the keys by end of their window, the oldest first
+    expiry: VecDeque<(i64, u64)>,
+}
+
+struct Key {
+    window_start: i64,
+    /// Values of the key fields, kept to emit the summary
+    labels: Option<Map<String, Value>>,
+    duplicates: u64,
+}
+
+impl Dedup {
+    fn new(params: DedupParams) -> Self {
+        Self {
+            params,
+            keys: HashMap::new(),
+            expiry: VecDeque::new(),
+        }
+    }
+
+    fn process(&mut self, now: i64, records: Vec<(usize, Value)>) -> Output {
+        let mut output = Output {
+            summaries: self.expire(now),
+            ..Default::default()
+        };
+        for (idx, record) in records {
+            let Value::Object(fields) = &record else {
+                output.records.push((idx, record));
+                continue;
+            };
+            let hash = self.hash(fields);
+            if let Some(key) = self.keys.get_mut(&hash) {
+                key.duplicates += 1;
+                continue;
+            }
+            if self.keys.len() >= MAX_KEYS {
+                if let Some(summary) = self.forget_oldest() {
+                    output.summaries.push(summary);
+                }
+            }
+            let labels = self.params.emit_summary.then(|| self.labels(fields));
+            self.keys.insert(
+                hash,
+                Key {
+                    window_start: now,
+                    labels,
+                    duplicates: 0,
+                },
+            );
+            self.expiry
+                .push_back((now + self.params.window * 1_000_000, hash));
+            output.records.push((idx, record));
+        }
+        output
+    }
+
+    fn labels(&self, record: &Map<String, Value>) -> Map<String, Value> {
+        self.params
+            .key_fields
+            .iter()
+            .map(|field| {
+                let value = record.get(field).cloned().unwrap_or(Value::Null);
+                (field.to_string(), value)
+            })
+            .collect()
+    }
+
+    fn hash(&self, record: &Map<String, Value>) -> u64 {
+        let key = if self.params.key_fields.is_empty() {
+            // the fields are sorted, the records may not list them in the same order
+            let mut fields = record
+                .iter()
+                .filter(|(field, _)| field.as_str() != TIMESTAMP_COL_NAME)
+                .collect::<Vec<_>>();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            json::to_string(&fields).unwrap_or_default()
+        } else {
+            let values = self
+                .params
+                .key_fields
+                .iter()
+                .map(|field| record.get(field).unwrap_or(&Value::Null))
+                .collect::<Vec<_>>();
+            json::to_string(&values).unwrap_or_default()
+        };
+        gxhash::new().sum64(&key)
+    }
+
+    /// Forgets the keys whose window ended and returns their summaries
+    fn expire(&mut self, now: i64) -> Vec<Value> {
+        let mut summaries = Vec::new();
+        while self.expiry.front().is_some_and(|(end, _)| *end <= now) {
+            if let Some(summary) = self.forget_oldest() {
+                summaries.push(summary);
+            }
+        }
+        summaries
+    }
+
+    fn forget_oldest(&mut self) -> Option<Value> {
+        let (window_end, hash) = self.expiry.pop_front()?;
+        let key = self.keys.remove(&hash)?;
+        let mut summary = key.labels.filter(|_| key.duplicates > 0)?;
+        summary.insert(TIMESTAMP_COL_NAME.to_string(), key.window_start.into());
+        summary.insert("window_start".to_string(), key.window_start.into());
+        summary.insert("window_end".to_string(), window_end.into());
+        summary.insert("duplicates".to_string(), key.duplicates.into());
+        Some(Value::Object(summary))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEC: i64 = 1_000_000;
+
+    fn records(values: &[(&str, i64)]) -> Vec<(usize, Value)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(idx, (msg, ts))| (idx, json::json!({"msg": msg, "_timestamp": ts})))
+            .collect()
+    }
+
+    fn kept(output: &Output) -> Vec<usize> {
+        output.records.iter().map(|(idx, _)| *idx).collect()
+    }
+
+    #[test]
+    fn test_dedup_window() {
+        let mut dedup = Dedup::new(DedupParams {
+            key_fields: vec!["msg".to_string()],
+            window: 10,
+            emit_summary: true,
+        });
+        let output = dedup.process(0, records(&[("a", 1), ("b", 2), ("a", 3), ("a", 4)]));
+        assert_eq!(kept(&output), vec![0, 1]);
+        assert!(output.summaries.is_empty());
+
+        let output = dedup.process(5 * SEC, records(&[("a", 5), ("b", 6)]));
+        assert!(output.records.is_empty());
+
+        // the window of both keys ended
+        let output = dedup.process(10 * SEC, records(&[("a", 7)]));
+        assert_eq!(kept(&output), vec![0]);
+        assert_eq!(
+            output.summaries,
+            vec![
+                json::json!({"msg": "a", "_timestamp": 0, "window_start": 0, "window_end": 10 * SEC, "duplicates": 3}),
+                json::json!({"msg": "b", "_timestamp": 0, "window_start": 0, "window_end": 10 * SEC, "duplicates": 1}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedup_whole_record() {
+        let mut dedup = Dedup::new(DedupParams {
+            key_fields: vec![],
+            window: 10,
+            emit_summary: false,
+        });
+        let output = dedup.process(
+            0,
+            vec![
+                (0, json::json!({"a": 1, "b": 2, "_timestamp": 1})),
+                (1, json::json!({"b": 2, "a": 1, "_timestamp": 2})),
+                (2, json::json!({"a": 1, "b": 3, "_timestamp": 3})),
+            ],
+        );
+        assert_eq!(kept(&output), vec![0, 2]);
+
+        let output = dedup.process(11 * SEC, records(&[("x", 1)]));
+        assert_eq!(kept(&output), vec![0]);
+        assert!(output.summaries.is_empty());
+        assert_eq!(dedup.keys.len(), 1);
+    }
+}
@@ -0,0 +1,358 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use config::{
+    meta::{
+        pipeline::{
+            Pipeline,
+            components::{NodeData, PipelineSource},
+        },
+        stream::StreamType,
+    },
+    utils::json::Value,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::channel;
+use utoipa::ToSchema;
+
+use super::batch_execution::{ExecutablePipeline, NodeErrorList, TraceEvent};
+use crate::service::db::pipeline::{self, PipelineError};
+
+/// Max number of sample records of a dry run
+pub const DRY_RUN_MAX_RECORDS: usize = 1000;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DryRunRequest {
+    /// Saved pipeline to run, ignored when `pipeline` is given
+    #[serde(default)]
+    pub pipeline_id: Option<String>,
+    /// Pipeline definition to run, saved or not
+    #[serde(default)]
+    pub pipeline: Option<Pipeline>,
+    #[schema(value_type = Vec<Object>)]
+    pub records: Vec<Value>,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct PipelineTrace {
+    /// Nodes in their execution order
+    pub nodes: Vec<NodeTrace>,
+    /// Path of each sample record, by index in the request
+    pub records: Vec<RecordTrace>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NodeTrace {
+    pub node_id: String,
+    pub node_type: String,
+    pub received: Vec<TracedRecord>,
+    pub emitted: Vec<TracedRecord>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TracedRecord {
+    /// Index of the sample record it comes from
+    pub index: usize,
+    #[schema(value_type = Object)]
+    pub record: Value,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct RecordTrace {
+    pub index: usize,
+    /// Nodes the record reached, in their execution order
+    pub nodes: Vec<String>,
+    /// Result of each ConditionNode the record reached
+    pub conditions: Vec<ConditionBranch>,
+    /// Where the record would be written
+    pub destinations: Vec<TraceDestination>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConditionBranch {
+    pub node_id: String,
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TraceDestination {
+    Stream {
+        stream_name: String,
+        stream_type: StreamType,
+    },
+    RemoteStream {
+        destination_name: String,
+    },
+}
+
+/// Runs the sample records through the pipeline without writing anything and
+/// traces each node.
+///
+/// The records emitted by an AggregationNode carry the index of the last
+/// record the node received, and its windows start empty for each dry run.
+pub async fn dry_run(org_id: &str, req: DryRunRequest) -> Result<PipelineTrace, PipelineError> {
+    if req.records.is_empty() || req.records.len() > DRY_RUN_MAX_RECORDS {
+        return Err(PipelineError::DryRun(format!(
+            "between 1 and {DRY_RUN_MAX_RECORDS} sample records are required"
+        )));
+    }
+    let mut pipeline = match (req.pipeline, req.pipeline_id) {
+        (Some(pipeline), _) => pipeline,
+        (None, Some(pipeline_id)) => match pipeline::get_by_id(&pipeline_id).await {
+            Ok(pipeline) if pipeline.org == org_id => pipeline,
+            _ => return Err(PipelineError::NotFound(pipeline_id)),
+        },
+        (None, None) => {
+            return Err(PipelineError::DryRun(
+                "either pipeline or pipeline_id is required".to_string(),
+            ));
+        }
+    };
+    pipeline.org = org_id.to_string();
+    pipeline
+        .validate()
+        .map_err(|e| PipelineError::InvalidPipeline(e.to_string()))?;
+
+    let executable = ExecutablePipeline::new_dry_run(&pipeline)
+        .await
+        .map_err(|e| PipelineError::InvalidPipeline(e.to_string()))?;
+    let stream_name = match &pipeline.source {
+        PipelineSource::Realtime(stream_params) => Some(stream_params.stream_name.to_string()),
+        PipelineSource::Scheduled(_) => None,
+    };
+
+    let records = req.records;
+    let (trace_sender, mut trace_receiver) = channel::<TraceEvent>(records.len());
+    let trace_task = tokio::spawn(async move {
+        let mut events = Vec::new();
+        while let Some(event) = trace_receiver.recv().await {
+            events.push(event);
+        }
+        events
+    });
+    let errors = executable
+        .dry_run(org_id, records.clone(), stream_name, trace_sender)
+        .await
+        .map_err(|e| PipelineError::DryRun(e.to_string()))?;
+    let events = trace_task
+        .await
+        .map_err(|e| PipelineError::DryRun(e.to_string()))?;
+
+    Ok(build_trace(&executable, records, events, errors))
+}
+
+fn build_trace(
+    executable: &ExecutablePipeline,
+    records: Vec<Value>,
+    events: Vec<TraceEvent>,
+    errors: NodeErrorList,
+) -> PipelineTrace {
+    let nodes = executable.get_sorted_nodes();
+    let mut received: HashMap<&str, Vec<TracedRecord>> = HashMap::new();
+    let mut emitted: HashMap<&str, Vec<TracedRecord>> = HashMap::new();
+    let mut destinations: BTreeMap<usize, BTreeSet<TraceDestination>> = BTreeMap::new();
+    // a node sends the same records to each of its children, only one edge is kept for emitted
+    let first_child: HashMap<&str, String> = events
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::Edge { source, target, .. } => Some((source.as_str(), target.clone())),
+            _ => None,
+        })
+        .rev()
+        .collect();
+
+    let source_node_id = nodes[0].get_node_id();
+    received.insert(
+        source_node_id,
+        records
+            .into_iter()
+            .enumerate()
+            .map(|(index, record)| TracedRecord { index, record })
+            .collect(),
+    );
+    for event in &events {
+        match event {
+            TraceEvent::Edge {
+                source,
+                target,
+                idx,
+                record,
+            } => {
+                let traced = TracedRecord {
+                    index: *idx,
+                    record: record.clone(),
+                };
+                if first_child.get(source.as_str()) == Some(target) {
+                    emitted.entry(source).or_default().push(traced.clone());
+                }
+                received.entry(target).or_default().push(traced);
+            }
+            TraceEvent::Destination {
+                source,
+                idx,
+                stream_params,
+                record,
+            } => {
+                emitted.entry(source).or_default().push(TracedRecord {
+                    index: *idx,
+                    record: record.clone(),
+                });
+                destinations
+                    .entry(*idx)
+                    .or_default()
+                    .insert(TraceDestination::Stream {
+                        stream_name: stream_params.stream_name.to_string(),
+                        stream_type: stream_params.stream_type,
+                    });
+            }
+        }
+    }
+
+    let mut node_errors: HashMap<String, Vec<String>> = HashMap::new();
+    for (node_id, _, error) in errors {
+        node_errors.entry(node_id).or_default().push(error);
+    }
+
+    let num_records = received.get(source_node_id).map_or(0, Vec::len);
+    let mut record_traces: Vec<RecordTrace> = (0..num_records)
+        .map(|index| RecordTrace {
+            index,
+            ..Default::default()
+        })
+        .collect();
+    let mut trace = PipelineTrace::default();
+    for node in nodes {
+        let node_id = node.get_node_id();
+        let node_received = received.remove(node_id).unwrap_or_default();
+        let node_emitted = emitted.remove(node_id).unwrap_or_default();
+        let emitted_indexes: BTreeSet<usize> = node_emitted.iter().map(|r| r.index).collect();
+        let received_indexes: BTreeSet<usize> = node_received.iter().map(|r| r.index).collect();
+        for &index in &received_indexes {
+            let Some(record_trace) = record_traces.get_mut(index) else {
+                continue;
+            };
+            record_trace.nodes.push(node_id.to_string());
+            match node.get_node_data() {
+                NodeData::Condition(_) => record_trace.conditions.push(ConditionBranch {
+                    node_id: node_id.to_string(),
+                    passed: emitted_indexes.contains(&index),
+                }),
+                NodeData::RemoteStream(remote_stream) => {
+                    destinations
+                        .entry(index)
+                        .or_default()
+                        .insert(TraceDestination::RemoteStream {
+                            destination_name: remote_stream.destination_name.to_string(),
+                        });
+                }
+                _ => {}
+            }
+        }
+        trace.nodes.push(NodeTrace {
+            node_id: node_id.to_string(),
+            node_type: node.node_type(),
+            received: node_received,
+            emitted: node_emitted,
+            errors: node_errors.remove(node_id).unwrap_or_default(),
+        });
+    }
+    for (index, record_destinations) in destinations {
+        if let Some(record_trace) = record_traces.get_mut(index) {
+            record_trace.destinations = record_destinations.into_iter().collect();
+        }
+    }
+    trace.records = record_traces;
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use config::utils::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dry_run_trace() {
+        let stream_node = |id: &str, stream_name: &str| {
+            json::json!({
+                "id": id,
+                "data": {
+                    "node_type": "stream",
+                    "org_id": "default",
+                    "stream_name": stream_name,
+                    "stream_type": "logs"
+                },
+                "position": {"x": 0, "y": 0},
+                "io_type": "output"
+            })
+        };
+        let pipeline: Pipeline = json::from_value(json::json!({
+            "name": "dry run",
+            "nodes": [
+                stream_node("1", "default"),
+                {
+                    "id": "2",
+                    "data": {
+                        "node_type": "condition",
+                        "condition": {"type": "condition", "column": "level", "operator": "=", "value": "error"}
+                    },
+                    "position": {"x": 0, "y": 0},
+                    "io_type": "default"
+                },
+                stream_node("3", "errors"),
+                stream_node("4", "all")
+            ],
+            "edges": [
+                {"id": "e1-2", "source": "1", "target": "2"},
+                {"id": "e2-3", "source": "2", "target": "3"},
+                {"id": "e1-4", "source": "1", "target": "4"}
+            ]
+        }))
+        .unwrap();
+        let req = DryRunRequest {
+            pipeline_id: None,
+            pipeline: Some(pipeline),
+            records: vec![
+                json::json!({"level": "error"}),
+                json::json!({"level": "info"}),
+            ],
+        };
+
+        let trace = dry_run("default", req).await.unwrap();
+        assert_eq!(trace.nodes.len(), 4);
+        let condition = trace.nodes.iter().find(|n| n.node_id == "2").unwrap();
+        assert_eq!(condition.received.len(), 2);
+        assert_eq!(condition.emitted.len(), 1);
+
+        let error_record = &trace.records[0];
+        assert_eq!(error_record.conditions.len(), 1);
+        assert!(error_record.conditions[0].passed);
+        assert_eq!(error_record.destinations.len(), 2);
+
+        let info_record = &trace.records[1];
+        assert!(!info_record.conditions[0].passed);
+        assert_eq!(
+            info_record.destinations,
+            vec![TraceDestination::Stream {
+                stream_name: "all".to_string(),
+                stream_type: StreamType::Logs,
+            }]
+        );
+    }
+}
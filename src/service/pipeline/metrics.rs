@@ -0,0 +1,115 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use config::{
+    META_ORG_ID, QUERY_WITH_NO_LIMIT, get_config,
+    meta::{
+        pipeline::NodeMetrics,
+        search::{Query, Request, RequestEncoding},
+        self_reporting::usage::PIPELINES_USAGE_STREAM,
+        stream::StreamType,
+    },
+    utils::json::{self, Value, get_float_value, get_string_value, get_uint_value},
+};
+
+use crate::service::search as SearchService;
+
+/// Period over which the node metrics shown with the pipelines are summed
+const METRICS_PERIOD_HOURS: i64 = 1;
+
+/// Returns the metrics of the nodes of the pipelines of the org, by pipeline id, summed over
+/// the last hour of the pipeline usage reported by the ingesters
+pub async fn get_node_metrics(org_id: &str) -> HashMap<String, Vec<NodeMetrics>> {
+    if !get_config().common.usage_enabled {
+        return HashMap::new();
+    }
+
+    let now = Utc::now();
+    let req = Request {
+        query: Query {
+            sql: format!(
+                "SELECT pipeline_id, node_id, node_type, sum(batches) AS batches, sum(received) AS received, sum(emitted) AS emitted, sum(dropped) AS dropped, sum(failed) AS failed, sum(took_in_secs) AS took_in_secs FROM \"{PIPELINES_USAGE_STREAM}\" WHERE org = $org GROUP BY pipeline_id, node_id, node_type ORDER BY pipeline_id, node_id"
+            ),
+            size: QUERY_WITH_NO_LIMIT as i64,
+            start_time: (now - Duration::hours(METRICS_PERIOD_HOURS)).timestamp_micros(),
+            end_time: now.timestamp_micros(),
+            params: HashMap::from([("org".to_string(), json::json!(org_id))]),
+            ..Default::default()
+        },
+        encoding: RequestEncoding::Empty,
+        regions: vec![],
+        clusters: vec![],
+        timeout: 0,
+        search_type: None,
+        search_event_context: None,
+        use_cache: None,
+        local_mode: None,
+    };
+    match SearchService::search("", META_ORG_ID, StreamType::Logs, None, &req).await {
+        Ok(res) => group_node_metrics(&res.hits),
+        Err(e) => {
+            log::error!("[Pipeline] failed to get the node metrics of org {org_id}: {e}");
+            HashMap::new()
+        }
+    }
+}
+
+fn group_node_metrics(hits: &[Value]) -> HashMap<String, Vec<NodeMetrics>> {
+    let mut metrics: HashMap<String, Vec<NodeMetrics>> = HashMap::new();
+    for hit in hits {
+        let Some(hit) = hit.as_object() else {
+            continue;
+        };
+        let field = |name: &str| hit.get(name).unwrap_or(&Value::Null);
+        metrics
+            .entry(get_string_value(field("pipeline_id")))
+            .or_default()
+            .push(NodeMetrics {
+                node_id: get_string_value(field("node_id")),
+                node_type: get_string_value(field("node_type")),
+                batches: get_uint_value(field("batches")),
+                received: get_uint_value(field("received")),
+                emitted: get_uint_value(field("emitted")),
+                dropped: get_uint_value(field("dropped")),
+                failed: get_uint_value(field("failed")),
+                took_in_secs: get_float_value(field("took_in_secs")),
+            });
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_node_metrics() {
+        let hits = vec![
+            json::json!({"pipeline_id": "p1", "node_id": "n1", "node_type": "stream", "batches": 2, "received": 10, "emitted": 10, "dropped": 0, "failed": 0, "took_in_secs": null}),
+            json::json!({"pipeline_id": "p1", "node_id": "n2", "node_type": "condition", "batches": 2, "received": 10, "emitted": 4, "dropped": 6, "failed": 1, "took_in_secs": 0.5}),
+            json::json!({"pipeline_id": "p2", "node_id": "n1", "node_type": "stream", "batches": 1, "received": 3, "emitted": 3, "dropped": 0, "failed": 0}),
+        ];
+        let metrics = group_node_metrics(&hits);
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics["p1"].len(), 2);
+        assert_eq!(metrics["p1"][1].node_type, "condition");
+        assert_eq!(metrics["p1"][1].dropped, 6);
+        assert_eq!(metrics["p1"][1].took_in_secs, 0.5);
+        assert_eq!(metrics["p2"][0].received, 3);
+    }
+}
@@ -13,22 +13,36 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashMap;
+
 use config::meta::{
     pipeline::{Pipeline, PipelineList, components::PipelineSource},
     search::SearchEventType,
     stream::ListStreamParams,
 };
 
-use super::db::pipeline::{self, PipelineError};
+use super::db::{
+    self,
+    pipeline::{self, PipelineError},
+};
 use crate::common::{
     meta::authz::Authz,
     utils::auth::{remove_ownership, set_ownership},
 };
 
+pub mod aggregation;
+pub mod backfill;
 pub mod batch_execution;
+pub mod dedup;
+pub mod dry_run;
+pub mod metrics;
+pub mod rate_limit;
+#[cfg(not(feature = "enterprise"))]
+pub mod remote_destination;
+pub mod versions;
 
 #[tracing::instrument(skip(pipeline))]
-pub async fn save_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError> {
+pub async fn save_pipeline(mut pipeline: Pipeline, author: &str) -> Result<(), PipelineError> {
     // check if another realtime pipeline with the same source stream already exists
     if let PipelineSource::Realtime(stream) = &pipeline.source {
         if pipeline::list_streams_with_pipeline(&pipeline.org)
@@ -60,13 +74,24 @@ pub async fn save_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError>
         }
     }
 
+    versions::record(&pipeline, author, None).await?;
     pipeline::set(&pipeline).await?;
     set_ownership(&pipeline.org, "pipelines", Authz::new(&pipeline.id)).await;
     Ok(())
 }
 
 #[tracing::instrument(skip(pipeline))]
-pub async fn update_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError> {
+pub async fn update_pipeline(pipeline: Pipeline, author: &str) -> Result<(), PipelineError> {
+    update_pipeline_with_message(pipeline, author, None).await
+}
+
+/// Updates the pipeline and records the new version in its history, with the
+/// reason of the change when it isn't a plain update
+pub(crate) async fn update_pipeline_with_message(
+    mut pipeline: Pipeline,
+    author: &str,
+    message: Option<String>,
+) -> Result<(), PipelineError> {
     let Ok(existing_pipeline) = pipeline::get_by_id(&pipeline.id).await else {
         return Err(PipelineError::NotFound(pipeline.id));
     };
@@ -132,7 +157,17 @@ pub async fn update_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError
         }
     }
 
+    // pipelines created before their history was kept have no version to roll back to
+    versions::record_if_missing(&existing_pipeline).await?;
+    versions::record(&pipeline, author, message).await?;
     pipeline::update(&pipeline, prev_source_stream).await?;
+    // a staged version only applies to the version it was staged on
+    if let Err(e) = db::pipeline_versions::delete_staged(&pipeline.id).await {
+        log::error!(
+            "[Pipeline] {}: error deleting the staged version replaced by the update: {e}",
+            pipeline.id
+        );
+    }
     Ok(())
 }
 
@@ -140,6 +175,7 @@ pub async fn update_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError
 pub async fn list_pipelines(
     org_id: String,
     permitted: Option<Vec<String>>,
+    with_metrics: bool,
 ) -> Result<PipelineList, PipelineError> {
     let list = pipeline::list_by_org(&org_id)
         .await?
@@ -156,7 +192,12 @@ pub async fn list_pipelines(
                     .contains(&format!("pipeline:_all_{}", org_id))
         })
         .collect();
-    Ok(PipelineList { list })
+    let metrics = if with_metrics {
+        metrics::get_node_metrics(&org_id).await
+    } else {
+        HashMap::new()
+    };
+    Ok(PipelineList { list, metrics })
 }
 
 #[tracing::instrument]
@@ -218,6 +259,10 @@ pub async fn delete_pipeline(pipeline_id: &str) -> Result<(), PipelineError> {
     }
 
     pipeline::delete(pipeline_id).await?;
+    if let Err(e) = db::pipeline_versions::delete(pipeline_id).await {
+        log::error!("[Pipeline] {pipeline_id}: error deleting the versions of the pipeline: {e}");
+    }
+    backfill::delete_pipeline_jobs(&existing_pipeline.org, pipeline_id).await;
     remove_ownership(
         &existing_pipeline.org,
         "pipelines",
@@ -0,0 +1,244 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Rate limiting of the records of the pipelines.
+//!
+//! The token buckets of a RateLimitNode are kept in memory by each ingester
+//! and shared by the batches it processes concurrently: the limit applies to
+//! the records of each ingester.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use config::{
+    meta::pipeline::components::{OverLimitAction, RateLimitParams},
+    utils::{
+        hash::{Sum64, gxhash},
+        json::{self, Map, Value},
+    },
+};
+use hashbrown::HashMap;
+use hashlink::LinkedHashMap;
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+
+/// Max number of buckets of a node, the keys over it share a single bucket
+const MAX_BUCKETS: usize = 100_000;
+
+/// Token buckets of the RateLimitNodes by pipeline id and node id, each node
+/// locked on its own
+static NODES: Lazy<RwLock<HashMap<(String, String), Arc<Mutex<Buckets>>>>> =
+    Lazy::new(Default::default);
+
+/// Returns the records within the limit of their key, and the sampled records
+/// over it
+pub fn process(
+    pipeline_id: &str,
+    node_id: &str,
+    params: &RateLimitParams,
+    records: Vec<(usize, Value)>,
+) -> Vec<(usize, Value)> {
+    let now = Utc::now().timestamp_micros();
+    let key = (pipeline_id.to_string(), node_id.to_string());
+    let node = NODES.read().get(&key).cloned();
+    let node = match node {
+        Some(node) => node,
+        None => NODES
+            .write()
+            .entry(key)
+            .or_insert_with(|| Arc::new(Mutex::new(Buckets::new(params.clone(), now))))
+            .clone(),
+    };
+    let mut buckets = node.lock();
+    // the node changed with the pipeline
+    if &buckets.params != params {
+        *buckets = Buckets::new(params.clone(), now);
+    }
+    buckets.expire(now);
+    records
+        .into_iter()
+        .filter(|(_, record)| match record {
+            Value::Object(record) => buckets.allow(now, record),
+            _ => true,
+        })
+        .collect()
+}
+
+/// Drops the buckets of all the RateLimitNodes of the pipeline
+pub fn remove_pipeline(pipeline_id: &str) {
+    NODES.write().retain(|(id, _), _| id != pipeline_id);
+}
+
+struct Buckets {
+    params: RateLimitParams,
+    /// Buckets by hash of their key, the least recently used first
+    buckets: LinkedHashMap<u64, Bucket>,
+    /// Bucket of all the records when there is no key field, or of the keys
+    /// over the max number of buckets
+    shared: Bucket,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: i64,
+    /// Records over the limit since the last sampled one
+    over_limit: u64,
+}
+
+impl Bucket {
+    fn new(capacity: f64, now: i64) -> Self {
+        Self {
+            tokens: capacity,
+            updated_at: now,
+            over_limit: 0,
+        }
+    }
+
+    fn tokens_at(&self, now: i64, params: &RateLimitParams) -> f64 {
+        let elapsed = (now - self.updated_at).max(0) as f64 / 1_000_000.0;
+        (self.tokens + elapsed * params.rate).min(params.capacity())
+    }
+
+    /// Takes a token for a record, or samples the records over the limit
+    fn allow(&mut self, now: i64, params: &RateLimitParams) -> bool {
+        self.tokens = self.tokens_at(now, params);
+        self.updated_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return true;
+        }
+        match params.over_limit {
+            OverLimitAction::Drop => false,
+            OverLimitAction::Sample => {
+                self.over_limit += 1;
+                if self.over_limit >= params.sample_one_in {
+                    self.over_limit = 0;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
+
+impl Buckets {
+    fn new(params: RateLimitParams, now: i64) -> Self {
+        let shared = Bucket::new(params.capacity(), now);
+        Self {
+            params,
+            buckets: LinkedHashMap::new(),
+            shared,
+        }
+    }
+
+    /// Drops the buckets unused for the time to refill an empty bucket, they
+    /// are the same as new ones
+    fn expire(&mut self, now: i64) {
+        let refill = (self.params.capacity() / self.params.rate * 1_000_000.0) as i64;
+        while self
+            .buckets
+            .front()
+            .is_some_and(|(_, bucket)| bucket.updated_at.saturating_add(refill) <= now)
+        {
+            self.buckets.pop_front();
+        }
+    }
+
+    fn allow(&mut self, now: i64, record: &Map<String, Value>) -> bool {
+        if self.params.key_fields.is_empty() {
+            return self.shared.allow(now, &self.params);
+        }
+        let values = self
+            .params
+            .key_fields
+            .iter()
+            .map(|field| record.get(field).unwrap_or(&Value::Null))
+            .collect::<Vec<_>>();
+        let hash = gxhash::new().sum64(&json::to_string(&values).unwrap_or_default());
+        if let Some(bucket) = self.buckets.to_back(&hash) {
+            return bucket.allow(now, &self.params);
+        }
+        if self.buckets.len() >= MAX_BUCKETS {
+            return self.shared.allow(now, &self.params);
+        }
+        let mut bucket = Bucket::new(self.params.capacity(), now);
+        let allowed = bucket.allow(now, &self.params);
+        self.buckets.insert(hash, bucket);
+        allowed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEC: i64 = 1_000_000;
+
+    fn params(over_limit: OverLimitAction) -> RateLimitParams {
+        RateLimitParams {
+            key_fields: vec!["service".to_string()],
+            rate: 2.0,
+            burst: 4,
+            over_limit,
+            sample_one_in: 3,
+        }
+    }
+
+    fn allowed(buckets: &mut Buckets, now: i64, service: &str, n: usize) -> usize {
+        let record = json::json!({ "service": service });
+        (0..n)
+            .filter(|_| buckets.allow(now, record.as_object().unwrap()))
+            .count()
+    }
+
+    #[test]
+    fn test_token_buckets() {
+        let mut buckets = Buckets::new(params(OverLimitAction::Drop), 0);
+        // the burst then nothing until the bucket refills
+        assert_eq!(allowed(&mut buckets, 0, "api", 10), 4);
+        assert_eq!(allowed(&mut buckets, 0, "db", 10), 4);
+        assert_eq!(allowed(&mut buckets, SEC / 2, "api", 10), 1);
+        assert_eq!(allowed(&mut buckets, 2 * SEC, "api", 10), 3);
+        // refilled up to the burst
+        assert_eq!(allowed(&mut buckets, 100 * SEC, "api", 10), 4);
+    }
+
+    #[test]
+    fn test_expire_refilled_buckets() {
+        let mut buckets = Buckets::new(params(OverLimitAction::Drop), 0);
+        allowed(&mut buckets, 0, "api", 1);
+        allowed(&mut buckets, SEC, "db", 1);
+        allowed(&mut buckets, 2 * SEC, "api", 1);
+        // db refilled 2s after its last record, api is still in use
+        buckets.expire(3 * SEC);
+        assert_eq!(buckets.buckets.len(), 1);
+        assert!(
+            buckets
+                .buckets
+                .front()
+                .is_some_and(|(_, b)| b.updated_at == 2 * SEC)
+        );
+        buckets.expire(4 * SEC);
+        assert!(buckets.buckets.is_empty());
+    }
+
+    #[test]
+    fn test_sample_over_limit() {
+        let mut buckets = Buckets::new(params(OverLimitAction::Sample), 0);
+        // 4 within the limit, then 1 of 3 of the 9 others
+        assert_eq!(allowed(&mut buckets, 0, "api", 13), 7);
+    }
+}
@@ -0,0 +1,694 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Remote stream destinations of the pipelines in the open source build.
+//!
+//! The records a pipeline sends to a remote destination are appended to a WAL
+//! queue per pipeline and destination, in
+//! `{remote_stream_wal_dir}{org}/{pipeline_id}/{destination}/`. An exporter
+//! task per queue reads the closed WAL files of the queue in order and
+//! forwards their records in batches to the endpoint of the destination,
+//! retrying the failed requests with a backoff. The position of the exported
+//! records is persisted in the offset file of the queue, so the export resumes
+//! where it stopped after a restart: records may be sent twice. The writers
+//! wait while the queues exceed the WAL size limit.
+//!
+//! Records are dropped when the destination rejects them, with a 4xx answer
+//! other than 408 and 429, when they still fail after the max retry time of
+//! the pipelines, and when their WAL file can't be read. The dropped batches
+//! are counted by the `pipeline_remote_dropped_records` metric and kept, with
+//! the WAL files which can't be read, in the `dead_letter` directory of the
+//! queue until they're removed by hand.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use config::{
+    TIMESTAMP_COL_NAME, get_config,
+    meta::{
+        destinations::{Endpoint, HTTPType, Module, OutputFormat},
+        stream::RemoteStreamParams,
+    },
+    metrics,
+    utils::json::{self, Map, Value},
+};
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, Notify, RwLock, Semaphore};
+use wal::ReadFrom;
+
+use crate::service::db;
+
+/// Size from which the active WAL file of a queue is closed
+const MAX_FILE_SIZE: usize = 64 * 1024 * 1024;
+/// Age from which the active WAL file of a queue is closed to export its
+/// records
+const MAX_FILE_AGE: Duration = Duration::from_secs(5);
+/// Longest wait of a writer for the queues to drain below the size limit
+const BACKPRESSURE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longest wait before retrying a batch, in seconds
+const MAX_BACKOFF_SECS: u64 = 300;
+const OFFSET_FILE: &str = "offset.json";
+const DEAD_LETTER_DIR: &str = "dead_letter";
+const WAL_EXTENSION: &str = "wal";
+
+static QUEUES: Lazy<RwLock<HashMap<PathBuf, Arc<Queue>>>> = Lazy::new(Default::default);
+
+/// Bytes of the WAL files of all the queues
+static PENDING_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Limits the requests sent concurrently to the remote destinations
+static SENDERS: Lazy<Semaphore> = Lazy::new(|| {
+    Semaphore::new(
+        get_config()
+            .pipeline
+            .remote_stream_wal_concurrent_count
+            .max(1),
+    )
+});
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| build_client(false));
+static INSECURE_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| build_client(true));
+
+/// Position of the next record to export
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Offset {
+    /// Sequence number of the WAL file
+    seq: u64,
+    /// Position in the WAL file, 0 for its first entry
+    position: u64,
+}
+
+struct Queue {
+    org_id: String,
+    pipeline_id: String,
+    destination: String,
+    dir: PathBuf,
+    active: Mutex<ActiveFile>,
+    notify: Notify,
+}
+
+/// WAL file the records are appended to, the files with a lower sequence
+/// number are closed
+struct ActiveFile {
+    seq: u64,
+    writer: Option<wal::Writer>,
+    opened_at: Instant,
+}
+
+enum SendError {
+    /// The request can succeed later
+    Retry(anyhow::Error),
+    /// The destination rejected the records
+    Rejected(anyhow::Error),
+}
+
+/// Opens the queues left by a previous run to export their records.
+pub async fn run() {
+    let root = PathBuf::from(&get_config().pipeline.remote_stream_wal_dir);
+    let Ok(orgs) = std::fs::read_dir(&root) else {
+        return;
+    };
+    for org in orgs.flatten() {
+        for pipeline in sub_dirs(&org.path()) {
+            for destination in sub_dirs(&pipeline) {
+                let (Some(org_id), Some(pipeline_id), Some(destination)) = (
+                    file_name(&org.path()),
+                    file_name(&pipeline),
+                    file_name(&destination),
+                ) else {
+                    continue;
+                };
+                if let Err(e) = get_queue(&org_id, &pipeline_id, &destination).await {
+                    log::error!(
+                        "[Pipeline({pipeline_id})] failed opening the WAL of remote destination {destination}: {e}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Appends the records to the WAL of the remote destination, they are
+/// exported in the background.
+pub async fn write(
+    pipeline_id: &str,
+    remote_stream: &RemoteStreamParams,
+    records: Vec<Value>,
+) -> Result<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    let cfg = get_config();
+    let started = Instant::now();
+    while PENDING_BYTES.load(Ordering::Relaxed) >= cfg.pipeline.wal_size_limit {
+        if started.elapsed() >= BACKPRESSURE_TIMEOUT {
+            return Err(anyhow!(
+                "the WAL of the remote destinations is full, {} records dropped",
+                records.len()
+            ));
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let queue = get_queue(
+        &remote_stream.org_id,
+        pipeline_id,
+        &remote_stream.destination_name,
+    )
+    .await?;
+    let data = json::to_vec(&records)?;
+    let mut active = queue.active.lock().await;
+    if active.writer.is_none() {
+        active.writer = Some(wal::Writer::new(
+            &cfg.pipeline.remote_stream_wal_dir,
+            &queue.org_id,
+            &queue.pipeline_id,
+            format!("{}/{:020}", queue.destination, active.seq),
+            0,
+            1024 * 1024,
+        )?);
+        active.opened_at = Instant::now();
+    }
+    let writer = active.writer.as_mut().unwrap();
+    let size = writer.size().0;
+    writer.write(&data)?;
+    writer.sync()?;
+    let written = writer.size().0;
+    PENDING_BYTES.fetch_add((written - size) as u64, Ordering::Relaxed);
+    if written >= MAX_FILE_SIZE {
+        active.close()?;
+    }
+    drop(active);
+    queue.notify.notify_one();
+    Ok(())
+}
+
+async fn get_queue(org_id: &str, pipeline_id: &str, destination: &str) -> Result<Arc<Queue>> {
+    let dir = PathBuf::from(&get_config().pipeline.remote_stream_wal_dir)
+        .join(org_id)
+        .join(pipeline_id)
+        .join(destination);
+    if let Some(queue) = QUEUES.read().await.get(&dir) {
+        return Ok(queue.clone());
+    }
+    let mut queues = QUEUES.write().await;
+    if let Some(queue) = queues.get(&dir) {
+        return Ok(queue.clone());
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    let files = wal_files(&dir);
+    for seq in files.iter() {
+        if let Ok(metadata) = std::fs::metadata(file_path(&dir, *seq)) {
+            PENDING_BYTES.fetch_add(metadata.len(), Ordering::Relaxed);
+        }
+    }
+    // never append to the files of a previous run
+    let seq = files.last().map(|seq| seq + 1).unwrap_or_default();
+    let queue = Arc::new(Queue {
+        org_id: org_id.to_string(),
+        pipeline_id: pipeline_id.to_string(),
+        destination: destination.to_string(),
+        dir: dir.clone(),
+        active: Mutex::new(ActiveFile {
+            seq: seq.max(load_offset(&dir).seq),
+            writer: None,
+            opened_at: Instant::now(),
+        }),
+        notify: Notify::new(),
+    });
+    queues.insert(dir, queue.clone());
+    tokio::task::spawn(export(queue.clone()));
+    Ok(queue)
+}
+
+impl ActiveFile {
+    fn close(&mut self) -> Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            self.seq += 1;
+            writer.close()?;
+        }
+        Ok(())
+    }
+}
+
+/// Exports the closed WAL files of the queue in order, deleting them once
+/// exported.
+async fn export(queue: Arc<Queue>) {
+    let mut offset = load_offset(&queue.dir);
+    loop {
+        let active_seq = queue.active.lock().await.seq;
+        let Some(seq) = wal_files(&queue.dir)
+            .into_iter()
+            .find(|seq| *seq >= offset.seq && *seq < active_seq)
+        else {
+            let mut active = queue.active.lock().await;
+            if active.writer.is_some() && active.opened_at.elapsed() >= MAX_FILE_AGE {
+                if let Err(e) = active.close() {
+                    log::error!(
+                        "[Pipeline({})] failed closing the WAL file of remote destination {}: {e}",
+                        queue.pipeline_id,
+                        queue.destination
+                    );
+                }
+                continue;
+            }
+            drop(active);
+            _ = tokio::time::timeout(Duration::from_secs(1), queue.notify.notified()).await;
+            continue;
+        };
+        if seq != offset.seq {
+            offset = Offset { seq, position: 0 };
+        }
+
+        let path = file_path(&queue.dir, seq);
+        let size = std::fs::metadata(&path)
+            .map(|m| m.len())
+            .unwrap_or_default();
+        if let Err(e) = queue.export_file(&mut offset).await {
+            // a corrupted file can't be exported further
+            log::error!(
+                "[Pipeline({})] failed reading the WAL file {seq} of remote destination {}, the rest of the file is moved to the dead letters from position {}: {e}",
+                queue.pipeline_id,
+                queue.destination,
+                offset.position
+            );
+            if let Err(e) = queue.dead_letter_file(&path) {
+                log::error!(
+                    "[Pipeline] failed moving the WAL file {path:?} to the dead letters: {e}"
+                );
+            }
+        } else if let Err(e) = std::fs::remove_file(&path) {
+            log::error!("[Pipeline] failed removing the WAL file {path:?}: {e}");
+        }
+        _ = PENDING_BYTES.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+            Some(v.saturating_sub(size))
+        });
+        offset = Offset {
+            seq: seq + 1,
+            position: 0,
+        };
+        if let Err(e) = save_offset(&queue.dir, offset) {
+            log::error!(
+                "[Pipeline({})] failed saving the offset of remote destination {}: {e}",
+                queue.pipeline_id,
+                queue.destination
+            );
+        }
+    }
+}
+
+impl Queue {
+    /// Sends the records of the WAL file from the offset, moving it forward
+    async fn export_file(&self, offset: &mut Offset) -> Result<()> {
+        let cfg = get_config();
+        let mut reader = wal::Reader::from_path_position(
+            file_path(&self.dir, offset.seq),
+            ReadFrom::Checkpoint(offset.position),
+        )?;
+        let mut saved_at = Instant::now();
+        loop {
+            let mut records = Vec::new();
+            while records.len() < cfg.pipeline.remote_batch_size {
+                match reader.read_entry()? {
+                    Some(entry) => records.extend(json::from_slice::<Vec<Value>>(&entry)?),
+                    None => break,
+                }
+            }
+            if records.is_empty() {
+                return Ok(());
+            }
+            self.send(records).await;
+
+            offset.position = reader.current_position()?;
+            if saved_at.elapsed().as_secs() >= cfg.pipeline.offset_flush_interval {
+                if let Err(e) = save_offset(&self.dir, *offset) {
+                    log::error!(
+                        "[Pipeline({})] failed saving the offset of remote destination {}: {e}",
+                        self.pipeline_id,
+                        self.destination
+                    );
+                }
+                saved_at = Instant::now();
+            }
+        }
+    }
+
+    /// Sends the records, retrying until the max retry time of the pipelines
+    /// after which the records go to the dead letters
+    async fn send(&self, records: Vec<Value>) {
+        let max_retry_time = get_config().pipeline.remote_request_max_retry_time;
+        let started = Instant::now();
+        let mut retries = 0;
+        loop {
+            let result = match self.endpoint().await {
+                Ok(endpoint) => {
+                    let _permit = SENDERS.acquire().await;
+                    send_records(&endpoint, &records).await
+                }
+                Err(e) => Err(SendError::Retry(e)),
+            };
+            let e = match result {
+                Ok(()) => return,
+                Err(SendError::Rejected(e)) => {
+                    log::error!(
+                        "[Pipeline({})] remote destination {} rejected {} records, moved to the dead letters: {e}",
+                        self.pipeline_id,
+                        self.destination,
+                        records.len()
+                    );
+                    self.dead_letter(&records, "rejected");
+                    return;
+                }
+                Err(SendError::Retry(e)) => e,
+            };
+            if started.elapsed().as_secs() >= max_retry_time {
+                log::error!(
+                    "[Pipeline({})] failed sending {} records to remote destination {} for {}s, moved to the dead letters: {e}",
+                    self.pipeline_id,
+                    records.len(),
+                    self.destination,
+                    max_retry_time
+                );
+                self.dead_letter(&records, "retry_timeout");
+                return;
+            }
+            let wait = backoff(retries);
+            log::warn!(
+                "[Pipeline({})] failed sending records to remote destination {}, retry in {wait}s: {e}",
+                self.pipeline_id,
+                self.destination
+            );
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+            retries += 1;
+        }
+    }
+
+    /// Counts the dropped records and writes them to a dead letter file
+    fn dead_letter(&self, records: &[Value], reason: &str) {
+        metrics::PIPELINE_REMOTE_DROPPED_RECORDS
+            .with_label_values(&[
+                self.org_id.as_str(),
+                self.pipeline_id.as_str(),
+                self.destination.as_str(),
+                reason,
+            ])
+            .inc_by(records.len() as u64);
+        let dir = self.dir.join(DEAD_LETTER_DIR);
+        let path = dir.join(format!("{}.json", Utc::now().timestamp_micros()));
+        let result = std::fs::create_dir_all(&dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Ok(std::fs::write(&path, json::to_vec(records)?)?));
+        if let Err(e) = result {
+            log::error!(
+                "[Pipeline({})] failed writing the dead letter file {path:?}, {} records lost: {e}",
+                self.pipeline_id,
+                records.len()
+            );
+        }
+    }
+
+    /// Moves a WAL file which can't be read to the dead letters
+    fn dead_letter_file(&self, path: &Path) -> Result<()> {
+        metrics::PIPELINE_REMOTE_DROPPED_RECORDS
+            .with_label_values(&[
+                &self.org_id,
+                &self.pipeline_id,
+                &self.destination,
+                "wal_read",
+            ])
+            .inc();
+        let dir = self.dir.join(DEAD_LETTER_DIR);
+        std::fs::create_dir_all(&dir)?;
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("invalid WAL file path {path:?}"))?;
+        std::fs::rename(path, dir.join(file_name))?;
+        Ok(())
+    }
+
+    async fn endpoint(&self) -> Result<Endpoint> {
+        let destination = db::alerts::destinations::get(&self.org_id, &self.destination).await?;
+        match destination.module {
+            Module::Pipeline { endpoint } => Ok(endpoint),
+            Module::Alert { .. } => Err(anyhow!(
+                "destination {} is not a pipeline destination",
+                self.destination
+            )),
+        }
+    }
+}
+
+async fn send_records(endpoint: &Endpoint, records: &[Value]) -> Result<(), SendError> {
+    let (url, body) = match endpoint.output_format {
+        OutputFormat::Json => (endpoint.url.clone(), json::to_vec(records)),
+        OutputFormat::OpenObserve => (ingestion_url(&endpoint.url), json::to_vec(records)),
+        OutputFormat::Otlp => (endpoint.url.clone(), json::to_vec(&otlp_logs(records))),
+    };
+    let body = body.map_err(|e| SendError::Rejected(e.into()))?;
+    let client = if endpoint.skip_tls_verify {
+        &INSECURE_CLIENT
+    } else {
+        &CLIENT
+    };
+    let mut req = match endpoint.method {
+        HTTPType::POST => client.post(&url),
+        HTTPType::PUT => client.put(&url),
+        HTTPType::GET => client.get(&url),
+    };
+    let mut has_content_type = false;
+    for (key, value) in endpoint.headers.iter().flatten() {
+        if !key.is_empty() && !value.is_empty() {
+            has_content_type |= key.trim().eq_ignore_ascii_case("content-type");
+            req = req.header(key, value);
+        }
+    }
+    if !has_content_type {
+        req = req.header("Content-Type", "application/json");
+    }
+
+    let resp = req
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| SendError::Retry(e.into()))?;
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = resp.text().await.unwrap_or_default();
+    let e = anyhow!("{url} answered {status}: {body}");
+    if status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+    {
+        Err(SendError::Retry(e))
+    } else {
+        Err(SendError::Rejected(e))
+    }
+}
+
+/// `_json` ingestion url of an OpenObserve stream url
+fn ingestion_url(url: &str) -> String {
+    let url = url.trim_end_matches('/');
+    if url.ends_with("/_json") {
+        url.to_string()
+    } else {
+        format!("{url}/_json")
+    }
+}
+
+/// OTLP/HTTP logs export request of the records, in JSON
+fn otlp_logs(records: &[Value]) -> Value {
+    let log_records = records
+        .iter()
+        .filter_map(|record| record.as_object())
+        .map(otlp_log_record)
+        .collect::<Vec<_>>();
+    json::json!({
+        "resourceLogs": [{
+            "resource": {},
+            "scopeLogs": [{
+                "scope": {"name": "openobserve"},
+                "logRecords": log_records,
+            }],
+        }],
+    })
+}
+
+fn otlp_log_record(record: &Map<String, Value>) -> Value {
+    let body_key = ["body", "message", "log"]
+        .into_iter()
+        .find(|k| record.contains_key(*k));
+    let severity_key = ["severity", "level"]
+        .into_iter()
+        .find(|k| record.get(*k).is_some_and(|v| v.is_string()));
+    let mut log = Map::new();
+    if let Some(ts) = record.get(TIMESTAMP_COL_NAME).and_then(|v| v.as_i64()) {
+        // microseconds
+        log.insert(
+            "timeUnixNano".to_string(),
+            Value::String((ts * 1000).to_string()),
+        );
+    }
+    if let Some(key) = severity_key {
+        log.insert("severityText".to_string(), record[key].clone());
+    }
+    let body = match body_key {
+        Some(key) => otlp_value(&record[key]),
+        None => otlp_value(&Value::String(json::to_string(record).unwrap_or_default())),
+    };
+    log.insert("body".to_string(), body);
+    let attributes = record
+        .iter()
+        .filter(|(k, _)| {
+            k.as_str() != TIMESTAMP_COL_NAME
+                && Some(k.as_str()) != body_key
+                && Some(k.as_str()) != severity_key
+        })
+        .map(|(k, v)| json::json!({"key": k, "value": otlp_value(v)}))
+        .collect::<Vec<_>>();
+    log.insert("attributes".to_string(), Value::Array(attributes));
+    Value::Object(log)
+}
+
+fn otlp_value(value: &Value) -> Value {
+    match value {
+        Value::String(v) => json::json!({"stringValue": v}),
+        Value::Bool(v) => json::json!({"boolValue": v}),
+        // 64 bits integers are strings in the JSON encoding of OTLP
+        Value::Number(v) if v.is_i64() => json::json!({"intValue": v.to_string()}),
+        Value::Number(v) => json::json!({"doubleValue": v.as_f64()}),
+        Value::Null => json::json!({"stringValue": ""}),
+        v => json::json!({"stringValue": v.to_string()}),
+    }
+}
+
+/// Seconds to wait before the retry following `retries` failed retries
+fn backoff(retries: u32) -> u64 {
+    (1u64 << retries.min(16)).min(MAX_BACKOFF_SECS)
+}
+
+fn build_client(skip_tls_verify: bool) -> reqwest::Client {
+    let cfg = get_config();
+    reqwest::Client::builder()
+        .danger_accept_invalid_certs(skip_tls_verify)
+        .timeout(Duration::from_secs(cfg.pipeline.remote_request_timeout))
+        .pool_max_idle_per_host(cfg.pipeline.max_connections)
+        .build()
+        .expect("failed building the http client of the remote destinations")
+}
+
+fn file_path(dir: &Path, seq: u64) -> PathBuf {
+    dir.join(format!("{seq:020}.{WAL_EXTENSION}"))
+}
+
+/// Sequence numbers of the WAL files of the queue, in order
+fn wal_files(dir: &Path) -> Vec<u64> {
+    let mut files = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == WAL_EXTENSION) {
+                path.file_stem()?.to_str()?.parse::<u64>().ok()
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    files.sort_unstable();
+    files
+}
+
+fn load_offset(dir: &Path) -> Offset {
+    std::fs::read(dir.join(OFFSET_FILE))
+        .ok()
+        .and_then(|v| json::from_slice(&v).ok())
+        .unwrap_or_default()
+}
+
+fn save_offset(dir: &Path, offset: Offset) -> Result<()> {
+    let tmp = dir.join(format!("{OFFSET_FILE}.tmp"));
+    std::fs::write(&tmp, json::to_vec(&offset)?)?;
+    std::fs::rename(tmp, dir.join(OFFSET_FILE))?;
+    Ok(())
+}
+
+fn sub_dirs(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+fn file_name(path: &Path) -> Option<String> {
+    path.file_name()?.to_str().map(|v| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_otlp_log_record() {
+        let record = json::json!({
+            "_timestamp": 1700000000000000i64,
+            "message": "login failed",
+            "level": "warn",
+            "user": "alice",
+            "attempts": 3,
+            "ratio": 0.5,
+        });
+        let log = otlp_log_record(record.as_object().unwrap());
+        assert_eq!(log["timeUnixNano"], "1700000000000000000");
+        assert_eq!(log["severityText"], "warn");
+        assert_eq!(log["body"], json::json!({"stringValue": "login failed"}));
+        let attributes = log["attributes"].as_array().unwrap();
+        assert_eq!(attributes.len(), 3);
+        assert!(attributes.contains(&json::json!({"key": "attempts", "value": {"intValue": "3"}})));
+        assert!(attributes.contains(&json::json!({"key": "ratio", "value": {"doubleValue": 0.5}})));
+    }
+
+    #[test]
+    fn test_ingestion_url() {
+        assert_eq!(
+            ingestion_url("https://o2.example.com/api/default/siem/"),
+            "https://o2.example.com/api/default/siem/_json"
+        );
+        assert_eq!(
+            ingestion_url("https://o2.example.com/api/default/siem/_json"),
+            "https://o2.example.com/api/default/siem/_json"
+        );
+        assert_eq!(backoff(0), 1);
+        assert_eq!(backoff(3), 8);
+        assert_eq!(backoff(30), MAX_BACKOFF_SECS);
+    }
+}
@@ -0,0 +1,217 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Version history, rollback and staged versions of pipelines.
+//!
+//! Each change of a pipeline records an immutable snapshot of it. Rolling back
+//! or promoting a staged version updates the pipeline in a single write, as a
+//! new version with the content of the old or staged one.
+
+use chrono::Utc;
+use config::meta::pipeline::{
+    Pipeline,
+    version::{
+        PipelineDiff, PipelineVersion, PipelineVersionList, StagePipelineRequest, StagedPipeline,
+    },
+};
+
+use super::batch_execution::ExecutablePipeline;
+use crate::service::db::{
+    self,
+    pipeline::{self, PipelineError},
+};
+
+/// Records the state of the pipeline as a version, before the pipeline is
+/// saved: a change of the pipeline fails when its version can't be recorded.
+pub(crate) async fn record(
+    pipeline: &Pipeline,
+    author: &str,
+    message: Option<String>,
+) -> Result<(), PipelineError> {
+    let version = PipelineVersion {
+        version: pipeline.version,
+        author: author.to_string(),
+        created_at: Utc::now().timestamp_micros(),
+        message,
+        pipeline: pipeline.clone(),
+    };
+    let max_versions = config::get_config().pipeline.max_versions;
+    db::pipeline_versions::set(&version, max_versions)
+        .await
+        .map_err(|e| {
+            log::error!(
+                "[Pipeline] {}: error recording version {}: {e}",
+                pipeline.id,
+                pipeline.version
+            );
+            PipelineError::from(e)
+        })
+}
+
+/// Records the pipeline as a version unless it already is
+pub(crate) async fn record_if_missing(pipeline: &Pipeline) -> Result<(), PipelineError> {
+    if db::pipeline_versions::get(&pipeline.id, pipeline.version)
+        .await
+        .is_err()
+    {
+        record(pipeline, "", None).await?;
+    }
+    Ok(())
+}
+
+pub(super) async fn get_pipeline(
+    org_id: &str,
+    pipeline_id: &str,
+) -> Result<Pipeline, PipelineError> {
+    match pipeline::get_by_id(pipeline_id).await {
+        Ok(pipeline) if pipeline.org == org_id => Ok(pipeline),
+        _ => Err(PipelineError::NotFound(pipeline_id.to_string())),
+    }
+}
+
+pub async fn list_versions(
+    org_id: &str,
+    pipeline_id: &str,
+) -> Result<PipelineVersionList, PipelineError> {
+    get_pipeline(org_id, pipeline_id).await?;
+    let list = db::pipeline_versions::list(pipeline_id).await?;
+    Ok(PipelineVersionList {
+        list: list.iter().map(Into::into).collect(),
+    })
+}
+
+pub async fn get_version(
+    org_id: &str,
+    pipeline_id: &str,
+    version: i32,
+) -> Result<PipelineVersion, PipelineError> {
+    get_pipeline(org_id, pipeline_id).await?;
+    db::pipeline_versions::get(pipeline_id, version)
+        .await
+        .map_err(|_| PipelineError::VersionNotFound(pipeline_id.to_string(), version))
+}
+
+pub async fn diff_versions(
+    org_id: &str,
+    pipeline_id: &str,
+    from: i32,
+    to: i32,
+) -> Result<PipelineDiff, PipelineError> {
+    let from = get_version(org_id, pipeline_id, from).await?;
+    let to = get_version(org_id, pipeline_id, to).await?;
+    Ok(PipelineDiff::new(&from, &to))
+}
+
+/// Restores the pipeline as it was at `version`, keeping its enabled state
+pub async fn rollback(
+    org_id: &str,
+    pipeline_id: &str,
+    version: i32,
+    author: &str,
+) -> Result<(), PipelineError> {
+    let current = get_pipeline(org_id, pipeline_id).await?;
+    let target = get_version(org_id, pipeline_id, version).await?;
+    let mut pipeline = target.pipeline;
+    pipeline.id = current.id;
+    pipeline.org = current.org;
+    pipeline.version = current.version;
+    pipeline.enabled = current.enabled;
+    super::update_pipeline_with_message(
+        pipeline,
+        author,
+        Some(format!("Rolled back to version {version}")),
+    )
+    .await
+}
+
+/// Stages a new version of the pipeline on a percentage of the records of its
+/// source stream. Replaces the version already staged, if any.
+pub async fn stage(
+    org_id: &str,
+    pipeline_id: &str,
+    req: StagePipelineRequest,
+    author: &str,
+) -> Result<(), PipelineError> {
+    let current = get_pipeline(org_id, pipeline_id).await?;
+    req.validate().map_err(PipelineError::InvalidPipeline)?;
+    let mut staged_pipeline = req.pipeline;
+    staged_pipeline.id = current.id.clone();
+    staged_pipeline.org = current.org.clone();
+    staged_pipeline.version = current.version;
+    staged_pipeline.enabled = current.enabled;
+    staged_pipeline
+        .validate()
+        .map_err(|e| PipelineError::InvalidPipeline(e.to_string()))?;
+    if staged_pipeline.source != current.source {
+        return Err(PipelineError::InvalidPipeline(
+            "The staged version must have the same source stream as the pipeline".to_string(),
+        ));
+    }
+    // fail now rather than on each ingester when the functions don't compile
+    ExecutablePipeline::new_dry_run(&staged_pipeline)
+        .await
+        .map_err(|e| PipelineError::InvalidPipeline(e.to_string()))?;
+
+    let staged = StagedPipeline {
+        base_version: current.version,
+        percentage: req.percentage,
+        author: author.to_string(),
+        created_at: Utc::now().timestamp_micros(),
+        pipeline: staged_pipeline,
+    };
+    db::pipeline_versions::set_staged(pipeline_id, &staged).await?;
+    pipeline::refresh_cache(&current).await;
+    Ok(())
+}
+
+pub async fn get_staged(org_id: &str, pipeline_id: &str) -> Result<StagedPipeline, PipelineError> {
+    get_pipeline(org_id, pipeline_id).await?;
+    db::pipeline_versions::get_staged(pipeline_id)
+        .await
+        .map_err(|_| PipelineError::StagedNotFound(pipeline_id.to_string()))
+}
+
+/// Replaces the pipeline with its staged version for all the records
+pub async fn promote_staged(
+    org_id: &str,
+    pipeline_id: &str,
+    author: &str,
+) -> Result<(), PipelineError> {
+    let current = get_pipeline(org_id, pipeline_id).await?;
+    let staged = get_staged(org_id, pipeline_id).await?;
+    if staged.base_version != current.version {
+        return Err(PipelineError::Modified(pipeline_id.to_string()));
+    }
+    let mut pipeline = staged.pipeline;
+    pipeline.enabled = current.enabled;
+    super::update_pipeline_with_message(
+        pipeline,
+        author,
+        Some(format!(
+            "Promoted the version staged by {} on {}% of the records",
+            staged.author, staged.percentage
+        )),
+    )
+    .await
+}
+
+/// Sends all the records back to the current version of the pipeline
+pub async fn discard_staged(org_id: &str, pipeline_id: &str) -> Result<(), PipelineError> {
+    let current = get_pipeline(org_id, pipeline_id).await?;
+    get_staged(org_id, pipeline_id).await?;
+    db::pipeline_versions::delete_staged(pipeline_id).await?;
+    pipeline::refresh_cache(&current).await;
+    Ok(())
+}
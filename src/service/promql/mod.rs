@@ -31,7 +31,7 @@ mod binaries;
 pub mod common;
 mod engine;
 mod exec;
-mod functions;
+pub(crate) mod functions;
 pub mod name_visitor;
 pub mod search;
 pub mod selector_visitor;
@@ -0,0 +1,198 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use config::{
+    meta::{
+        redaction::{CompiledRedaction, MaskingPolicy, RedactionAction},
+        search::{Query, Response},
+        sql::resolve_stream_names,
+    },
+    metrics,
+    utils::{json::Value, sql::resolve_projection},
+};
+
+use crate::{
+    common::{infra::config::MASKING_POLICIES, utils::auth::is_root_user},
+    service::{db, users},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MaskingPolicyError {
+    #[error("Invalid masking policy: {0}")]
+    Invalid(String),
+    #[error("Masking policy {0} already exists")]
+    AlreadyExists(String),
+    #[error("Masking policy {0} not found")]
+    NotFound(String),
+    #[error("Error saving masking policy: {0}")]
+    Db(#[from] anyhow::Error),
+}
+
+pub async fn create(
+    org_id: &str,
+    policy: MaskingPolicy,
+) -> Result<MaskingPolicy, MaskingPolicyError> {
+    policy.validate().map_err(MaskingPolicyError::Invalid)?;
+    if db::redaction::get(org_id, &policy.name).await.is_ok() {
+        return Err(MaskingPolicyError::AlreadyExists(policy.name));
+    }
+    db::redaction::set(org_id, &policy).await?;
+    Ok(policy)
+}
+
+pub async fn update(
+    org_id: &str,
+    name: &str,
+    mut policy: MaskingPolicy,
+) -> Result<MaskingPolicy, MaskingPolicyError> {
+    policy.name = name.to_string();
+    policy.validate().map_err(MaskingPolicyError::Invalid)?;
+    if db::redaction::get(org_id, name).await.is_err() {
+        return Err(MaskingPolicyError::NotFound(name.to_string()));
+    }
+    db::redaction::set(org_id, &policy).await?;
+    Ok(policy)
+}
+
+pub async fn get(org_id: &str, name: &str) -> Result<MaskingPolicy, MaskingPolicyError> {
+    db::redaction::get(org_id, name)
+        .await
+        .map_err(|_| MaskingPolicyError::NotFound(name.to_string()))
+}
+
+pub async fn list(org_id: &str) -> Result<Vec<MaskingPolicy>, MaskingPolicyError> {
+    Ok(db::redaction::list(org_id).await?)
+}
+
+pub async fn delete(org_id: &str, name: &str) -> Result<(), MaskingPolicyError> {
+    if db::redaction::get(org_id, name).await.is_err() {
+        return Err(MaskingPolicyError::NotFound(name.to_string()));
+    }
+    db::redaction::delete(org_id, name).await?;
+    Ok(())
+}
+
+/// Adds the values redacted by a pipeline node or a masking policy to the
+/// redaction counters, `counts` are by detector as counted by
+/// [CompiledRedaction::apply]
+pub fn report_redactions(
+    org_id: &str,
+    source: &str,
+    name: &str,
+    redaction: &CompiledRedaction,
+    counts: &[u64],
+) {
+    let action = redaction.action.to_string();
+    for (detector, count) in redaction.detector_names().zip(counts) {
+        if *count > 0 {
+            metrics::REDACTIONS
+                .with_label_values(&[org_id, source, name, detector, action.as_str()])
+                .inc_by(*count);
+        }
+    }
+}
+
+/// Masks the hits of the search with the masking policies of the role of the
+/// user. The streams and the aliases of the columns are resolved from the sql
+/// of the query, the policies of all the streams and all the fields apply when
+/// they can't be.
+pub async fn mask_search_response(
+    org_id: &str,
+    user_id: Option<&str>,
+    query: &Query,
+    res: &mut Response,
+) {
+    let Some(user_id) = user_id.filter(|user_id| !user_id.is_empty()) else {
+        return;
+    };
+    if res.hits.is_empty() {
+        return;
+    }
+    let prefix = format!("{org_id}/");
+    if !MASKING_POLICIES
+        .iter()
+        .any(|entry| entry.key().starts_with(&prefix))
+    {
+        return;
+    }
+    let role = if is_root_user(user_id) {
+        "root".to_string()
+    } else {
+        match users::get_user(Some(org_id), user_id).await {
+            Some(user) => user.role.to_string(),
+            None => return,
+        }
+    };
+    let streams = resolve_stream_names(&query.sql).ok();
+    let policies: Vec<MaskingPolicy> = MASKING_POLICIES
+        .iter()
+        .filter(|entry| {
+            entry.key().starts_with(&prefix)
+                && match streams.as_deref() {
+                    Some(streams) => entry.applies_to(&role, streams),
+                    None => entry.roles.iter().any(|r| *r == role),
+                }
+        })
+        .map(|entry| entry.value().clone())
+        .collect();
+    if policies.is_empty() {
+        return;
+    }
+    // the fields renamed by a function can't be followed
+    let projection = if query.query_fn.as_deref().is_none_or(|v| v.is_empty()) {
+        resolve_projection(&query.sql).ok()
+    } else {
+        None
+    };
+
+    for policy in policies {
+        let mut redaction = match policy.redaction.compile() {
+            Ok(redaction) => redaction,
+            Err(e) => {
+                log::error!(
+                    "[MASKING] org {org_id} policy {} is invalid: {e}",
+                    policy.name
+                );
+                continue;
+            }
+        };
+        redaction.follow_projection(projection.as_ref());
+        let salt = if redaction.action == RedactionAction::Hash {
+            match db::redaction::get_salt(org_id).await {
+                Ok(salt) => salt,
+                Err(e) => {
+                    // the results can't be returned unmasked
+                    log::error!("[MASKING] org {org_id} error getting the salt: {e}");
+                    res.hits.clear();
+                    res.is_partial = true;
+                    res.function_error = format!(
+                        "Results withheld, masking policy {} couldn't be applied",
+                        policy.name
+                    );
+                    return;
+                }
+            }
+        } else {
+            String::new()
+        };
+        let mut counts = vec![0; redaction.detector_names().count()];
+        for hit in res.hits.iter_mut() {
+            if let Value::Object(hit) = hit {
+                redaction.apply(hit, &salt, &mut counts);
+            }
+        }
+        report_redactions(org_id, "search", &policy.name, &redaction, &counts);
+    }
+}
@@ -19,13 +19,18 @@ use chrono::{TimeZone, Utc};
 use config::{
     TIMESTAMP_COL_NAME, get_config,
     meta::{
-        search::{self, ResponseTook},
+        search::{self, ResponseTook, SearchCursor},
         self_reporting::usage::{RequestStats, UsageType},
-        sql::resolve_stream_names,
+        sql::{OrderBy, resolve_stream_names},
         stream::StreamType,
     },
     metrics,
-    utils::{base64, hash::Sum64, json, sql::is_aggregate_query},
+    utils::{
+        base64,
+        hash::Sum64,
+        json,
+        sql::{is_aggregate_query, parse_explain},
+    },
 };
 use infra::{
     cache::{file_data::disk::QUERY_RESULT_CACHE, meta::ResultCacheMeta},
@@ -59,11 +64,24 @@ pub async fn search(
     in_req: &search::Request,
     range_error: String,
 ) -> Result<search::Response, Error> {
+    // the result cache and the cursor are keyed by the expanded sql. The search
+    // handlers expanded it already, this covers the internal callers like the
+    // pipeline backfill, an expanded sql is kept as is
+    let mut expanded_req = in_req.clone();
+    crate::service::sql_macros::expand_query(org_id, &mut expanded_req.query)?;
+    let in_req = &expanded_req;
+
+    // EXPLAIN doesn't return hits, skip the result cache
+    if parse_explain(&in_req.query.sql).0.is_some() {
+        return SearchService::search(trace_id, org_id, stream_type, user_id, in_req).await;
+    }
+
     let start = std::time::Instant::now();
     let started_at = Utc::now().timestamp_micros();
     let cfg = get_config();
+    let use_cursor = in_req.query.use_cursor || in_req.query.cursor.is_some();
     // result cache can be enable only when its from the start
-    let use_cache = if in_req.query.from == 0 {
+    let use_cache = if in_req.query.from == 0 && !use_cursor {
         in_req.use_cache.unwrap_or(false)
     } else {
         false
@@ -112,6 +130,7 @@ pub async fn search(
 
     let mut should_exec_query = true;
     let mut ext_took_wait = 0;
+    let mut cursor_order_by = None;
 
     let mut file_path = format!(
         "{}/{}/{}/{}",
@@ -137,6 +156,11 @@ pub async fn search(
                 let (ts_column, is_descending) =
                     cacher::get_ts_col_order_by(&v, TIMESTAMP_COL_NAME, is_aggregate)
                         .unwrap_or_default();
+                cursor_order_by = v
+                    .order_by
+                    .first()
+                    .filter(|(field, _)| field == TIMESTAMP_COL_NAME)
+                    .map(|(_, order_by)| *order_by);
 
                 MultiCachedQueryResponse {
                     ts_column,
@@ -151,6 +175,19 @@ pub async fn search(
         }
     };
 
+    // search-after pagination, continue from the cursor of the previous page
+    let page_size = req.query.size;
+    let cursor = if use_cursor {
+        Some(get_cursor(
+            &mut req,
+            is_aggregate,
+            cursor_order_by,
+            &c_resp.ts_column,
+        )?)
+    } else {
+        None
+    };
+
     // No cache data present, add delta for full query
     if !c_resp.has_cached_data && c_resp.deltas.is_empty() {
         c_resp.deltas.push(QueryDelta {
@@ -260,7 +297,8 @@ pub async fn search(
                         );
                     }
 
-                    SearchService::search(&trace_id, &org_id, stream_type, user_id, &req).await
+                    SearchService::search_unmasked(&trace_id, &org_id, stream_type, user_id, &req)
+                        .await
                 })
                 .instrument(enter_span),
             );
@@ -300,6 +338,14 @@ pub async fn search(
     res.set_trace_id(trace_id.to_string());
     res.set_local_took(start.elapsed().as_millis() as usize, ext_took_wait);
 
+    if let Some(cursor) = cursor {
+        cursor.filter(&mut res.hits, page_size, &c_resp.ts_column);
+        res.cursor = cursor
+            .next(&res.hits, page_size, &c_resp.ts_column)
+            .map_err(|e| Error::Message(e.to_string()))?
+            .map(|v| v.encode());
+    }
+
     if is_aggregate
         && res.histogram_interval.is_none()
         && !c_resp.ts_column.is_empty()
@@ -316,7 +362,7 @@ pub async fn search(
         size: res.scan_size as f64,
         request_body: Some(req.query.sql),
         function: req.query.query_fn,
-        user_email: user_id,
+        user_email: user_id.clone(),
         min_ts: Some(req.query.start_time),
         max_ts: Some(req.query.end_time),
         cached_ratio: Some(res.cached_ratio),
@@ -395,9 +441,50 @@ pub async fn search(
     }
     // result cache save changes Ends
 
+    // masked after the result cache is saved, the cache is shared by all the users
+    crate::service::redaction::mask_search_response(
+        org_id,
+        user_id.as_deref(),
+        &in_req.query,
+        &mut res,
+    )
+    .await;
+
     Ok(res)
 }
 
+// validate the cursor of the request and narrow the query to the hits after it,
+// the cursor of the first page takes the snapshot of the time range
+fn get_cursor(
+    req: &mut search::Request,
+    is_aggregate: bool,
+    order_by: Option<OrderBy>,
+    ts_column: &str,
+) -> Result<SearchCursor, Error> {
+    let Some(order_by) = order_by.filter(|_| !is_aggregate && ts_column == TIMESTAMP_COL_NAME)
+    else {
+        return Err(Error::Message(format!(
+            "cursor pagination requires a non-aggregate query ordered by {TIMESTAMP_COL_NAME}"
+        )));
+    };
+    let cursor = match req.query.cursor.as_deref().filter(|v| !v.is_empty()) {
+        Some(v) => {
+            let cursor = SearchCursor::decode(v).map_err(|e| Error::Message(e.to_string()))?;
+            if cursor.sql_hash != SearchCursor::hash_sql(&req.query.sql)
+                || cursor.order_by != order_by
+            {
+                return Err(Error::Message(
+                    "cursor doesn't belong to this query".to_string(),
+                ));
+            }
+            cursor
+        }
+        None => SearchCursor::new(&req.query, order_by),
+    };
+    cursor.apply(&mut req.query);
+    Ok(cursor)
+}
+
 // based on _timestamp of first record in config::meta::search::Response either add it in start
 // or end to cache response
 pub fn merge_response(
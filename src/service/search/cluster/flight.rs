@@ -29,7 +29,7 @@ use config::{
         stream::{FileKey, QueryPartitionStrategy, StreamType},
     },
     metrics,
-    utils::{inverted_index::split_token, json, time::BASE_TIME},
+    utils::{inverted_index::split_token, json, sql::ExplainMode, time::BASE_TIME},
 };
 use datafusion::{
     common::{TableReference, tree_node::TreeNode},
@@ -59,10 +59,17 @@ use crate::{
                 rewrite::{RemoteScanRewriter, StreamingAggsRewriter},
             },
             exec::{prepare_datafusion_context, register_udf},
+            explain::{
+                ExplainRows, PlanMetricsVisitor, file_list_summary, optimize_logical_plan,
+                plan_with_metrics,
+            },
             optimizer::generate_optimizer_rules,
-            table_provider::{catalog::StreamTypeProvider, empty_table::NewEmptyTable},
+            table_provider::{
+                catalog::StreamTypeProvider, empty_table::NewEmptyTable,
+                enrichment_table::EnrichmentTable,
+            },
         },
-        generate_filter_from_equal_items,
+        generate_filter_from_equal_items, quota,
         request::Request,
         sql::Sql,
         utils::{AsyncDefer, ScanStatsVisitor},
@@ -100,6 +107,8 @@ pub async fn search(
         return Ok((vec![], ScanStats::new(), 0, false, 0, "".to_string()));
     }
 
+    quota::check_time_range(&sql.org_id, sql.time_range).await?;
+
     // 1. get file id list
     let file_id_list = get_file_id_lists(
         &sql.org_id,
@@ -121,6 +130,7 @@ pub async fn search(
         original_size: file_id_list_vec.iter().map(|v| v.original_size).sum(),
         ..Default::default()
     };
+    quota::check_scan_size(&sql.org_id, scan_stats.original_size).await?;
 
     // 2. get inverted index file list
     let (use_ttv_inverted_index, idx_file_list, idx_scan_size, idx_took) =
@@ -157,6 +167,9 @@ pub async fn search(
         querier_num,
     );
 
+    // wait for a query slot of the org and the user, released when search done
+    let _permit = quota::acquire(trace_id, &req.org_id, req.user_id.as_deref(), timeout).await?;
+
     // waiting in work group queue
     metrics::QUERY_PENDING_NUMS
         .with_label_values(&[&req.org_id])
@@ -359,6 +372,34 @@ pub async fn run_datafusion(
 
     let plan = ctx.state().create_logical_plan(&sql.sql).await?;
 
+    let explain = req.explain;
+    let nodes = nodes.into_arc_vec();
+    let mut explain_rows = ExplainRows::default();
+    if explain.is_some() {
+        let (logical_plan, optimizer_rules) = optimize_logical_plan(&ctx.state(), &plan)?;
+        let (file_list, node_list) = file_list_summary(&nodes, &partitioned_file_lists);
+        explain_rows.push("logical_plan", logical_plan);
+        explain_rows.push("optimizer_rules", optimizer_rules.join(", "));
+        explain_rows.push("file_list", file_list);
+        explain_rows.push(
+            "inverted_index",
+            format!(
+                "use_inverted_index: {}, index_condition: {}, index_optimize_mode: {}, idx_files: {}",
+                req.use_inverted_index,
+                sql.index_condition
+                    .as_ref()
+                    .map(|v| v.to_query())
+                    .unwrap_or_default(),
+                sql.index_optimize_mode
+                    .as_ref()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+                idx_file_list.len(),
+            ),
+        );
+        explain_rows.push("nodes", node_list);
+    }
+
     let mut physical_plan = ctx.state().create_physical_plan(&plan).await?;
 
     if cfg.common.print_key_sql {
@@ -401,7 +442,7 @@ pub async fn run_datafusion(
     let context = tracing::Span::current().context();
     let mut rewrite = RemoteScanRewriter::new(
         req,
-        nodes.into_arc_vec(),
+        nodes,
         partitioned_file_lists,
         idx_file_list,
         equal_keys,
@@ -437,6 +478,19 @@ pub async fn run_datafusion(
             "flight->search: physical plan visit error: there is no EmptyTable".to_string(),
         ));
     }
+    if explain == Some(ExplainMode::Plan) {
+        explain_rows.push(
+            "physical_plan",
+            displayable(physical_plan.as_ref())
+                .indent(false)
+                .to_string(),
+        );
+        return Ok((
+            vec![explain_rows.into_batch()?],
+            ScanStats::default(),
+            "".to_string(),
+        ));
+    }
     if visitor.get_data().is_some() {
         return Ok((vec![], ScanStats::default(), "".to_string()));
     }
@@ -452,6 +506,29 @@ pub async fn run_datafusion(
     if let Err(e) = ret {
         log::error!("[trace_id {trace_id}] flight->search: datafusion collect error: {e}");
         Err(e.into())
+    } else if explain == Some(ExplainMode::Analyze) {
+        log::info!("[trace_id {trace_id}] flight->search: datafusion analyze done");
+        explain_rows.push(
+            "physical_plan_with_metrics",
+            plan_with_metrics(physical_plan.as_ref()),
+        );
+        let mut metrics_visitor = PlanMetricsVisitor::default();
+        let _ = visit_execution_plan(physical_plan.as_ref(), &mut metrics_visitor);
+        for node in metrics_visitor.nodes {
+            explain_rows.push(
+                format!("physical_plan_with_metrics on {}", node.node),
+                format!(
+                    "{}\nscan_stats: {}",
+                    node.plan,
+                    json::to_string(&node.scan_stats).unwrap_or_default()
+                ),
+            );
+        }
+        Ok((
+            vec![explain_rows.into_batch()?],
+            visit.scan_stats,
+            visit.partial_err,
+        ))
     } else {
         log::info!("[trace_id {trace_id}] flight->search: datafusion collect done");
         ret.map(|data| (data, visit.scan_stats, visit.partial_err))
@@ -786,9 +863,27 @@ pub async fn register_table(ctx: &SessionContext, sql: &Sql) -> Result<()> {
     }
 
     // register table
+    let use_lookup_join = get_config().common.feature_enrichment_lookup_join_enabled
+        && sql.schemas.len() > 1
+        && sql
+            .schemas
+            .keys()
+            .any(|stream| stream.get_stream_type(sql.stream_type) != StreamType::EnrichmentTables);
     for (stream, schema) in &sql.schemas {
         let schema = schema.schema().as_ref().clone();
         let stream_name = stream.to_quoted_string();
+        // enrichment tables in a join are read from memory on each querier
+        if use_lookup_join
+            && stream.get_stream_type(sql.stream_type) == StreamType::EnrichmentTables
+        {
+            let table = Arc::new(EnrichmentTable::new(
+                &sql.org_id,
+                &stream.stream_name(),
+                Arc::new(schema),
+            ));
+            ctx.register_table(&stream_name, table)?;
+            continue;
+        }
         let table = Arc::new(
             NewEmptyTable::new(&stream_name, Arc::new(schema))
                 .with_partitions(ctx.state().config().target_partitions())
@@ -33,7 +33,7 @@ use datafusion_proto::{
 use prost::Message;
 use proto::cluster_rpc;
 
-use super::empty_exec::NewEmptyExec;
+use super::{empty_exec::NewEmptyExec, enrichment_exec::EnrichmentExec};
 
 /// A PhysicalExtensionCodec that can serialize and deserialize ChildExec
 #[derive(Debug)]
@@ -108,6 +108,50 @@ impl PhysicalExtensionCodec for EmptyExecPhysicalExtensionCodec {
     }
 }
 
+/// A PhysicalExtensionCodec that can serialize and deserialize EnrichmentExec
+#[derive(Debug)]
+pub struct EnrichmentExecPhysicalExtensionCodec;
+
+impl PhysicalExtensionCodec for EnrichmentExecPhysicalExtensionCodec {
+    fn try_decode(
+        &self,
+        buf: &[u8],
+        _inputs: &[Arc<dyn ExecutionPlan>],
+        _registry: &dyn FunctionRegistry,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let proto = cluster_rpc::EnrichmentExecNode::decode(buf).map_err(|e| {
+            DataFusionError::Internal(format!(
+                "failed to decode EnrichmentExecNode execution plan: {e:?}"
+            ))
+        })?;
+        let schema = Arc::new(convert_required!(proto.schema)?);
+        Ok(Arc::new(EnrichmentExec::new(
+            &proto.org_id,
+            &proto.name,
+            schema,
+            proto.version,
+        )))
+    }
+
+    fn try_encode(&self, node: Arc<dyn ExecutionPlan>, buf: &mut Vec<u8>) -> Result<()> {
+        let Some(node) = node.as_any().downcast_ref::<EnrichmentExec>() else {
+            return internal_err!("Not supported");
+        };
+        let proto = cluster_rpc::EnrichmentExecNode {
+            org_id: node.org_id().to_string(),
+            name: node.name().to_string(),
+            schema: Some(node.schema().as_ref().try_into()?),
+            version: node.version(),
+        };
+        proto.encode(buf).map_err(|e| {
+            DataFusionError::Internal(format!(
+                "failed to encode EnrichmentExecNode execution plan: {e:?}"
+            ))
+        })?;
+        Ok(())
+    }
+}
+
 /// A PhysicalExtensionCodec that tries one of multiple inner codecs
 /// until one works
 #[derive(Debug)]
@@ -210,4 +254,38 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_enrichment_exec_codec() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]));
+        let plan: Arc<dyn ExecutionPlan> = Arc::new(EnrichmentExec::new(
+            "default",
+            "ip_table",
+            Arc::clone(&schema),
+            1000,
+        ));
+
+        // encode
+        let proto = ComposedPhysicalExtensionCodec {
+            codecs: vec![
+                Arc::new(EmptyExecPhysicalExtensionCodec {}),
+                Arc::new(EnrichmentExecPhysicalExtensionCodec {}),
+            ],
+        };
+        let plan_bytes = physical_plan_to_bytes_with_extension_codec(plan.clone(), &proto).unwrap();
+
+        // decode
+        let ctx = datafusion::prelude::SessionContext::new();
+        let plan2 = physical_plan_from_bytes_with_extension_codec(&plan_bytes, &ctx, &proto)?;
+        let plan2 = plan2.as_any().downcast_ref::<EnrichmentExec>().unwrap();
+        let plan = plan.as_any().downcast_ref::<EnrichmentExec>().unwrap();
+
+        // check
+        assert_eq!(plan.org_id(), plan2.org_id());
+        assert_eq!(plan.name(), plan2.name());
+        assert_eq!(plan.schema(), plan2.schema());
+        assert_eq!(plan.version(), plan2.version());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,183 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{any::Any, sync::Arc};
+
+use datafusion::{
+    arrow::datatypes::SchemaRef,
+    common::{Result, Statistics, internal_err},
+    execution::{SendableRecordBatchStream, TaskContext},
+    physical_expr::{EquivalenceProperties, Partitioning},
+    physical_plan::{
+        DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties,
+        execution_plan::{Boundedness, EmissionType},
+        stream::RecordBatchStreamAdapter,
+    },
+};
+use futures::{StreamExt, TryStreamExt};
+
+use crate::service::search::datafusion::table_provider::enrichment_table;
+
+/// Execution plan that reads an enrichment table from the in-memory lookup
+/// cache of the node that executes it.
+///
+/// The plan carries the version (upload time) of the table seen by the leader,
+/// so a querier whose cache is older than that reloads the table before
+/// answering.
+#[derive(Debug)]
+pub struct EnrichmentExec {
+    org_id: String,
+    name: String,      // stream name of the enrichment table
+    schema: SchemaRef, // The schema for the produced row
+    version: i64,
+    cache: PlanProperties,
+}
+
+impl EnrichmentExec {
+    /// Create a new EnrichmentExec
+    pub fn new(org_id: &str, name: &str, schema: SchemaRef, version: i64) -> Self {
+        let cache = Self::compute_properties(Arc::clone(&schema));
+        EnrichmentExec {
+            org_id: org_id.to_string(),
+            name: name.to_string(),
+            schema,
+            version,
+            cache,
+        }
+    }
+
+    /// This function creates the cache object that stores the plan properties such as schema,
+    /// equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(schema: SchemaRef) -> PlanProperties {
+        let eq_properties = EquivalenceProperties::new(schema);
+        PlanProperties::new(
+            eq_properties,
+            // Output Partitioning, the whole table is always one partition
+            Partitioning::UnknownPartitioning(1),
+            // Execution Mode
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        )
+    }
+
+    pub fn org_id(&self) -> &str {
+        &self.org_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> i64 {
+        self.version
+    }
+}
+
+impl DisplayAs for EnrichmentExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "EnrichmentExec: name={:?}, projection={:?}, version={}",
+                    self.name,
+                    self.schema
+                        .fields()
+                        .iter()
+                        .map(|f| f.name())
+                        .collect::<Vec<_>>(),
+                    self.version
+                )
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for EnrichmentExec {
+    fn name(&self) -> &'static str {
+        "EnrichmentExec"
+    }
+
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return internal_err!(
+                "EnrichmentExec invalid partition {} (expected 0)",
+                partition
+            );
+        }
+
+        let org_id = self.org_id.clone();
+        let name = self.name.clone();
+        let schema = self.schema.clone();
+        let version = self.version;
+        let fut = async move {
+            let batches =
+                enrichment_table::get_lookup_batches(&org_id, &name, &schema, version).await?;
+            Ok::<_, datafusion::error::DataFusionError>(
+                futures::stream::iter(batches.into_iter().map(Ok)).boxed(),
+            )
+        };
+        let stream = futures::stream::once(fut).try_flatten();
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream,
+        )))
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        Ok(Statistics::new_unknown(&self.schema))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::*;
+
+    #[test]
+    fn test_enrichment_exec() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]));
+        let exec = EnrichmentExec::new("default", "ip_table", schema, 1000);
+        assert_eq!(exec.org_id(), "default");
+        assert_eq!(exec.name(), "ip_table");
+        assert_eq!(exec.version(), 1000);
+        assert_eq!(exec.properties().output_partitioning().partition_count(), 1);
+    }
+}
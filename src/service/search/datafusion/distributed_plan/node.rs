@@ -17,7 +17,7 @@ use std::sync::Arc;
 
 use config::{
     meta::{cluster::NodeInfo, inverted_index::InvertedIndexOptimizeMode, stream::FileKey},
-    utils::json,
+    utils::{json, sql::ExplainMode},
 };
 use datafusion::common::TableReference;
 use hashbrown::HashMap;
@@ -91,6 +91,7 @@ impl RemoteScanNodes {
             start_time: self.req.time_range.as_ref().map(|x| x.0).unwrap_or(0),
             end_time: self.req.time_range.as_ref().map(|x| x.1).unwrap_or(0),
             timeout: self.req.timeout as u64,
+            is_analyze: self.req.explain == Some(ExplainMode::Analyze),
         };
 
         let index_condition = match &self.index_condition {
@@ -194,6 +195,7 @@ pub struct SearchInfos {
     pub start_time: i64,
     pub end_time: i64,
     pub timeout: u64,
+    pub is_analyze: bool,
 }
 
 impl SearchInfos {
@@ -221,6 +223,7 @@ impl SearchInfos {
             start_time: self.start_time,
             end_time: self.end_time,
             timeout: self.timeout as i64,
+            is_analyze: self.is_analyze,
         }
     }
 }
@@ -52,10 +52,16 @@ use tonic::{
 };
 
 use super::{
-    codec::{ComposedPhysicalExtensionCodec, EmptyExecPhysicalExtensionCodec},
+    codec::{
+        ComposedPhysicalExtensionCodec, EmptyExecPhysicalExtensionCodec,
+        EnrichmentExecPhysicalExtensionCodec,
+    },
     node::RemoteScanNode,
 };
-use crate::service::{grpc::get_cached_channel, search::MetadataMap};
+use crate::service::{
+    grpc::get_cached_channel,
+    search::{MetadataMap, datafusion::explain::NodePlanMetrics},
+};
 
 /// Execution plan for empty relation with produce_one_row=false
 #[derive(Debug)]
@@ -66,6 +72,7 @@ pub struct RemoteScanExec {
     cache: PlanProperties,
     pub scan_stats: Arc<Mutex<ScanStats>>,
     pub partial_err: Arc<Mutex<String>>,
+    pub plan_metrics: Arc<Mutex<Vec<NodePlanMetrics>>>, // for EXPLAIN ANALYZE
 }
 
 impl RemoteScanExec {
@@ -79,7 +86,10 @@ impl RemoteScanExec {
 
         // serialize the input plan and set it as the plan for the remote scan node
         let proto = ComposedPhysicalExtensionCodec {
-            codecs: vec![Arc::new(EmptyExecPhysicalExtensionCodec {})],
+            codecs: vec![
+                Arc::new(EmptyExecPhysicalExtensionCodec {}),
+                Arc::new(EnrichmentExecPhysicalExtensionCodec {}),
+            ],
         };
         let physical_plan_bytes =
             physical_plan_to_bytes_with_extension_codec(input.clone(), &proto)?;
@@ -92,6 +102,7 @@ impl RemoteScanExec {
             cache,
             scan_stats: Arc::new(Mutex::new(ScanStats::default())),
             partial_err: Arc::new(Mutex::new(String::new())),
+            plan_metrics: Arc::new(Mutex::new(Vec::new())),
         })
     }
 
@@ -169,6 +180,7 @@ impl ExecutionPlan for RemoteScanExec {
             self.input.schema().clone(),
             self.scan_stats.clone(),
             self.partial_err.clone(),
+            self.plan_metrics.clone(),
         );
         let stream = futures::stream::once(fut).try_flatten();
         Ok(Box::pin(RecordBatchStreamAdapter::new(
@@ -188,6 +200,7 @@ async fn get_remote_batch(
     schema: SchemaRef,
     scan_stats: Arc<Mutex<ScanStats>>,
     partial_err: Arc<Mutex<String>>,
+    plan_metrics: Arc<Mutex<Vec<NodePlanMetrics>>>,
 ) -> Result<SendableRecordBatchStream> {
     let start = std::time::Instant::now();
     let cfg = config::get_config();
@@ -380,6 +393,7 @@ async fn get_remote_batch(
         files,
         scan_size,
         partial_err,
+        plan_metrics,
         start,
         timeout,
     )))
@@ -428,6 +442,7 @@ struct FlightStream {
     files: i64,
     scan_size: i64,
     partial_err: Arc<Mutex<String>>,
+    plan_metrics: Arc<Mutex<Vec<NodePlanMetrics>>>,
     start: std::time::Instant,
     timeout: u64,
 }
@@ -443,6 +458,7 @@ impl FlightStream {
         files: i64,
         scan_size: i64,
         partial_err: Arc<Mutex<String>>,
+        plan_metrics: Arc<Mutex<Vec<NodePlanMetrics>>>,
         start: std::time::Instant,
         timeout: u64,
     ) -> Self {
@@ -455,10 +471,26 @@ impl FlightStream {
             files,
             scan_size,
             partial_err,
+            plan_metrics,
             start,
             timeout,
         }
     }
+
+    fn add_plan_metrics(&self, data: &[u8]) {
+        match serde_json::from_slice::<NodePlanMetrics>(data) {
+            Ok(mut metrics) => {
+                metrics.node = self.node_addr.clone();
+                self.plan_metrics.lock().push(metrics);
+            }
+            Err(e) => log::error!(
+                "[trace_id {}] flight->search: response node: {}, parse plan metrics err: {}",
+                self.trace_id,
+                self.node_addr,
+                e
+            ),
+        }
+    }
 }
 
 impl Stream for FlightStream {
@@ -485,6 +517,12 @@ impl Stream for FlightStream {
         let dictionaries_by_field = HashMap::new();
         match self.stream.poll_next_unpin(cx) {
             Poll::Ready(Some(Ok(flight_data))) => {
+                // the plan metrics of EXPLAIN ANALYZE come after the last batch
+                if flight_data.data_header.is_empty() && !flight_data.app_metadata.is_empty() {
+                    self.add_plan_metrics(&flight_data.app_metadata);
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
                 let record_batch = flight_data_to_arrow_batch(
                     &flight_data,
                     self.schema.clone(),
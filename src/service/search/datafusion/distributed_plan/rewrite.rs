@@ -82,7 +82,7 @@ impl TreeNodeRewriter for RemoteScanRewriter {
         if node.name() == "RepartitionExec" || node.name() == "CoalescePartitionsExec" {
             let mut visitor = TableNameVisitor::new();
             node.visit(&mut visitor)?;
-            if visitor.is_remote_scan {
+            if visitor.is_remote_scan && visitor.table_name.is_some() {
                 let table_name = visitor.table_name.clone().unwrap();
                 let input = node.children()[0];
                 let remote_scan = Arc::new(RemoteScanExec::new(
@@ -100,7 +100,7 @@ impl TreeNodeRewriter for RemoteScanRewriter {
         } else if node.name() == "SortPreservingMergeExec" {
             let mut visitor = TableNameVisitor::new();
             node.visit(&mut visitor)?;
-            if visitor.is_remote_scan {
+            if visitor.is_remote_scan && visitor.table_name.is_some() {
                 let table_name = visitor.table_name.clone().unwrap();
                 let follow_merge_node = node.clone();
                 let new_input =
@@ -118,11 +118,16 @@ impl TreeNodeRewriter for RemoteScanRewriter {
             let mut visitor = TableNameVisitor::new();
             node.visit(&mut visitor)?;
             // add each remote scan for each child
-            if visitor.is_remote_scan {
+            if visitor.is_remote_scan && visitor.table_name.is_some() {
                 let mut new_children: Vec<Arc<dyn ExecutionPlan>> = vec![];
                 for child in node.children() {
                     let mut visitor = TableNameVisitor::new();
                     child.visit(&mut visitor)?;
+                    // lookup tables (enrichment tables) are read on the current node
+                    if visitor.table_name.is_none() {
+                        new_children.push(child.clone());
+                        continue;
+                    }
                     // For sort, we should add a SortPreservingMergeExec
                     if child.name() == "SortExec" {
                         let table_name = visitor.table_name.clone().unwrap();
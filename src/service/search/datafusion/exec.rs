@@ -61,7 +61,7 @@ use {
 
 use super::{
     file_type::{FileType, GetExt},
-    optimizer::join_reorder::JoinReorderRule,
+    optimizer::{join_reorder::JoinReorderRule, lookup_join::LookupJoinRule},
     planner::extension_planner::OpenobserveQueryPlanner,
     storage::file_list,
     table_provider::{NewListingTable, uniontable::NewUnionTable},
@@ -417,6 +417,12 @@ pub async fn prepare_datafusion_context(
     #[cfg(feature = "enterprise")]
     let (target_partition, memory_size) =
         get_cpu_and_mem_limit(_work_group.clone(), target_partition, memory_size).await?;
+    // memory budget of a query, exceeding it fails the query with resources exhausted
+    let memory_size = if cfg.limit.query_memory_limit > 0 {
+        memory_size.min(cfg.limit.query_memory_limit * 1024 * 1024)
+    } else {
+        memory_size
+    };
 
     let session_config = create_session_config(sorted_by_time, target_partition)?;
     let runtime_env = Arc::new(create_runtime_env(memory_size).await?);
@@ -428,6 +434,9 @@ pub async fn prepare_datafusion_context(
         builder = builder
             .with_optimizer_rules(optimizer_rules)
             .with_physical_optimizer_rule(Arc::new(JoinReorderRule::new()));
+        if cfg.common.feature_enrichment_lookup_join_enabled {
+            builder = builder.with_physical_optimizer_rule(Arc::new(LookupJoinRule::new()));
+        }
     }
     if cfg.common.feature_join_match_one_enabled {
         builder = builder.with_query_planner(Arc::new(OpenobserveQueryPlanner::new()));
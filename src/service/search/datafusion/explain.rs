@@ -0,0 +1,200 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use arrow::array::{RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use config::meta::{cluster::NodeInfo, search::ScanStats};
+use datafusion::{
+    common::{Result, TableReference, internal_err},
+    execution::SessionState,
+    logical_expr::{LogicalPlan, LogicalPlanBuilder, PlanType},
+    physical_plan::{ExecutionPlan, ExecutionPlanVisitor, display::DisplayableExecutionPlan},
+};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use super::distributed_plan::remote_scan::RemoteScanExec;
+
+/// Plan with execution metrics of a remote node for `EXPLAIN ANALYZE`, sent
+/// by the node after the last record batch.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NodePlanMetrics {
+    #[serde(default)]
+    pub node: String, // set by the node that receives the metrics
+    pub scan_stats: ScanStats,
+    pub plan: String,
+}
+
+impl NodePlanMetrics {
+    pub fn new(scan_stats: ScanStats, plan: &dyn ExecutionPlan) -> Self {
+        Self {
+            node: String::new(),
+            scan_stats,
+            plan: plan_with_metrics(plan),
+        }
+    }
+}
+
+/// Rows of the `EXPLAIN` output, `(plan_type, plan)`
+#[derive(Debug, Default)]
+pub struct ExplainRows {
+    rows: Vec<(String, String)>,
+}
+
+impl ExplainRows {
+    pub fn push(&mut self, plan_type: impl Into<String>, plan: impl Into<String>) {
+        self.rows.push((plan_type.into(), plan.into()));
+    }
+
+    pub fn into_batch(self) -> Result<RecordBatch> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("plan_type", DataType::Utf8, false),
+            Field::new("plan", DataType::Utf8, false),
+        ]));
+        let (plan_types, plans): (Vec<_>, Vec<_>) = self.rows.into_iter().unzip();
+        Ok(RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(plan_types)),
+                Arc::new(StringArray::from(plans)),
+            ],
+        )?)
+    }
+}
+
+/// Optimizes the logical plan, returns the optimized plan and the names of the
+/// optimizer rules that changed it
+pub fn optimize_logical_plan(
+    state: &SessionState,
+    plan: &LogicalPlan,
+) -> Result<(String, Vec<String>)> {
+    let explain = LogicalPlanBuilder::from(plan.clone())
+        .explain(true, false)?
+        .build()?;
+    let LogicalPlan::Explain(explain) = state.optimize(&explain)? else {
+        return internal_err!("optimize explain plan should return an explain plan");
+    };
+
+    let mut rules: Vec<String> = Vec::new();
+    let mut last_plan = None;
+    for plan in explain.stringified_plans.iter() {
+        match &plan.plan_type {
+            PlanType::FinalAnalyzedLogicalPlan => last_plan = Some(plan.plan.clone()),
+            PlanType::OptimizedLogicalPlan { optimizer_name } => {
+                if last_plan.as_ref() != Some(&plan.plan) && !rules.contains(optimizer_name) {
+                    rules.push(optimizer_name.clone());
+                }
+                last_plan = Some(plan.plan.clone());
+            }
+            _ => {}
+        }
+    }
+    Ok((explain.plan.display_indent().to_string(), rules))
+}
+
+/// Describes how the files of every table are split across the nodes
+pub fn file_list_summary(
+    nodes: &[Arc<dyn NodeInfo>],
+    file_id_lists: &HashMap<TableReference, Vec<Vec<i64>>>,
+) -> (String, String) {
+    let mut tables = Vec::with_capacity(file_id_lists.len());
+    let mut node_files = vec![Vec::new(); nodes.len()];
+    for (table, lists) in file_id_lists.iter() {
+        tables.push(format!(
+            "{table}: files={}",
+            lists.iter().map(|v| v.len()).sum::<usize>()
+        ));
+        for (i, list) in lists.iter().enumerate().take(nodes.len()) {
+            node_files[i].push(format!("{table}: files={}", list.len()));
+        }
+    }
+    tables.sort();
+    let nodes = nodes
+        .iter()
+        .zip(node_files)
+        .map(|(node, mut files)| {
+            files.sort();
+            let role = match (node.is_querier(), node.is_ingester()) {
+                (true, true) => "querier,ingester",
+                (false, true) => "ingester",
+                _ => "querier",
+            };
+            format!("{} ({role}): {}", node.get_grpc_addr(), files.join(", "))
+        })
+        .collect::<Vec<_>>();
+    (tables.join("\n"), nodes.join("\n"))
+}
+
+/// Displays the plan with the metrics of every operator aggregated over its
+/// partitions
+pub fn plan_with_metrics(plan: &dyn ExecutionPlan) -> String {
+    DisplayableExecutionPlan::with_metrics(plan)
+        .indent(false)
+        .to_string()
+}
+
+/// Collects the plan metrics the remote nodes sent to the [`RemoteScanExec`]s
+#[derive(Default)]
+pub struct PlanMetricsVisitor {
+    pub nodes: Vec<NodePlanMetrics>,
+}
+
+impl ExecutionPlanVisitor for PlanMetricsVisitor {
+    type Error = datafusion::common::DataFusionError;
+
+    fn pre_visit(&mut self, plan: &dyn ExecutionPlan) -> Result<bool, Self::Error> {
+        if let Some(remote_scan_exec) = plan.as_any().downcast_ref::<RemoteScanExec>() {
+            self.nodes
+                .extend(remote_scan_exec.plan_metrics.lock().iter().cloned());
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::{
+        arrow::datatypes::{DataType, Field, Schema},
+        prelude::SessionContext,
+    };
+
+    use super::*;
+    use crate::service::search::datafusion::table_provider::empty_table::NewEmptyTable;
+
+    #[tokio::test]
+    async fn test_optimize_logical_plan() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("_timestamp", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let ctx = SessionContext::new();
+        ctx.register_table("t", Arc::new(NewEmptyTable::new("t", schema)))?;
+
+        let sql = "SELECT name FROM t WHERE _timestamp > 10 AND 1 = 1";
+        let plan = ctx.state().create_logical_plan(sql).await?;
+        let (plan, rules) = optimize_logical_plan(&ctx.state(), &plan)?;
+        assert!(plan.contains("TableScan: t"));
+        assert!(rules.iter().any(|v| v == "simplify_expressions"));
+
+        let mut rows = ExplainRows::default();
+        rows.push("logical_plan", plan);
+        rows.push("optimizer_rules", rules.join(", "));
+        let batch = rows.into_batch()?;
+        assert_eq!(batch.num_rows(), 2);
+        Ok(())
+    }
+}
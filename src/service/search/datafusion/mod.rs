@@ -17,6 +17,7 @@ use std::str::FromStr;
 
 pub mod distributed_plan;
 pub mod exec;
+pub mod explain;
 pub mod file_type;
 pub mod optimizer;
 pub mod plan;
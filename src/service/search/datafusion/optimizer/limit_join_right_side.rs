@@ -18,8 +18,9 @@ use std::sync::Arc;
 use datafusion::{
     common::{
         Result,
-        tree_node::{Transformed, TreeNode},
+        tree_node::{Transformed, TreeNode, TreeNodeRecursion},
     },
+    datasource::source_as_provider,
     logical_expr::LogicalPlan,
     optimizer::{OptimizerConfig, OptimizerRule, optimizer::ApplyOrder},
     prelude::Expr,
@@ -27,6 +28,7 @@ use datafusion::{
 use itertools::Itertools;
 
 use super::utils::AddSortAndLimit;
+use crate::service::search::datafusion::table_provider::enrichment_table::EnrichmentTable;
 
 #[derive(Default, Debug)]
 pub struct LimitJoinRightSide {
@@ -58,6 +60,10 @@ impl OptimizerRule for LimitJoinRightSide {
         _config: &dyn OptimizerConfig,
     ) -> Result<Transformed<LogicalPlan>> {
         match plan {
+            // enrichment tables are joined from memory, no need to limit them
+            LogicalPlan::Join(join) if is_lookup_table(&join.right) => {
+                Ok(Transformed::no(LogicalPlan::Join(join)))
+            }
             LogicalPlan::Join(mut join) => {
                 let right_column = join
                     .on
@@ -95,6 +101,20 @@ impl OptimizerRule for LimitJoinRightSide {
     }
 }
 
+// check if the plan reads an enrichment table registered for lookup join
+fn is_lookup_table(plan: &LogicalPlan) -> bool {
+    let mut is_lookup = false;
+    let _ = plan.apply(|plan| {
+        if let LogicalPlan::TableScan(scan) = plan {
+            is_lookup = source_as_provider(&scan.source)
+                .is_ok_and(|provider| provider.as_any().is::<EnrichmentTable>());
+            return Ok(TreeNodeRecursion::Stop);
+        }
+        Ok(TreeNodeRecursion::Continue)
+    });
+    is_lookup
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -0,0 +1,213 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use datafusion::{
+    common::{
+        JoinType, Result,
+        tree_node::{Transformed, TransformedResult, TreeNode, TreeNodeRecursion},
+    },
+    config::ConfigOptions,
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::{
+        ExecutionPlan,
+        joins::{HashJoinExec, PartitionMode},
+        repartition::RepartitionExec,
+    },
+};
+
+/// Rewrite joins against an enrichment table into a lookup join.
+///
+/// The enrichment table side is read from memory by `EnrichmentExec`, so we
+/// build the hash table from it directly (`CollectLeft`) and drop the
+/// repartitions of both sides. Without a repartition between the join and the
+/// stream scan, `RemoteScanRewriter` pushes the whole join down to the
+/// queriers and every querier probes its own files against the lookup table.
+#[derive(Default, Debug)]
+pub struct LookupJoinRule;
+
+impl LookupJoinRule {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for LookupJoinRule {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(rewrite_lookup_join).data()
+    }
+
+    fn name(&self) -> &str {
+        "lookup_join"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+fn rewrite_lookup_join(
+    plan: Arc<dyn ExecutionPlan>,
+) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
+    let Some(hash_join) = plan.as_any().downcast_ref::<HashJoinExec>() else {
+        return Ok(Transformed::no(plan));
+    };
+    let left_is_lookup = is_scan(hash_join.left(), "EnrichmentExec");
+    let right_is_lookup = is_scan(hash_join.right(), "EnrichmentExec");
+    if left_is_lookup == right_is_lookup {
+        return Ok(Transformed::no(plan));
+    }
+    // already rewritten
+    if left_is_lookup && hash_join.mode == PartitionMode::CollectLeft {
+        return Ok(Transformed::no(plan));
+    }
+
+    // the other side should be a plain scan of a stream, we can't drop the
+    // repartition of an aggregation or another join
+    let fact_side = if left_is_lookup {
+        hash_join.right()
+    } else {
+        hash_join.left()
+    };
+    if !is_scan(fact_side, "NewEmptyExec") {
+        return Ok(Transformed::no(plan));
+    }
+
+    // the lookup side can't produce unmatched rows, otherwise each querier
+    // would emit them once
+    let join_type = *hash_join.join_type();
+    let supported = if left_is_lookup {
+        matches!(
+            join_type,
+            JoinType::Inner | JoinType::Right | JoinType::RightSemi | JoinType::RightAnti
+        )
+    } else {
+        matches!(
+            join_type,
+            JoinType::Inner | JoinType::Left | JoinType::LeftSemi | JoinType::LeftAnti
+        )
+    };
+    if !supported {
+        return Ok(Transformed::no(plan));
+    }
+
+    let join = HashJoinExec::try_new(
+        remove_repartition(hash_join.left().clone())?,
+        remove_repartition(hash_join.right().clone())?,
+        hash_join.on().to_vec(),
+        hash_join.filter().cloned(),
+        &join_type,
+        hash_join.projection.clone(),
+        PartitionMode::CollectLeft,
+        hash_join.null_equals_null(),
+    )?;
+    let plan: Arc<dyn ExecutionPlan> = if left_is_lookup {
+        Arc::new(join)
+    } else {
+        join.swap_inputs(PartitionMode::CollectLeft)?
+    };
+    Ok(Transformed::new(plan, true, TreeNodeRecursion::Jump))
+}
+
+fn remove_repartition(plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+    plan.transform_up(|plan| {
+        if plan.as_any().is::<RepartitionExec>() {
+            Ok(Transformed::yes(plan.children()[0].clone()))
+        } else {
+            Ok(Transformed::no(plan))
+        }
+    })
+    .data()
+}
+
+// check if the plan only reads one table with the given leaf node, allowing
+// projections, filters and repartitions on top of it
+fn is_scan(plan: &Arc<dyn ExecutionPlan>, leaf_name: &str) -> bool {
+    let name = plan.name();
+    if name == leaf_name {
+        return true;
+    }
+    match name {
+        "ProjectionExec"
+        | "FilterExec"
+        | "CoalesceBatchesExec"
+        | "CoalescePartitionsExec"
+        | "RepartitionExec" => {
+            let children = plan.children();
+            children.len() == 1 && is_scan(children[0], leaf_name)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::{
+        common::Result,
+        execution::{runtime_env::RuntimeEnvBuilder, session_state::SessionStateBuilder},
+        physical_plan::get_plan_string,
+        prelude::{SessionConfig, SessionContext},
+    };
+
+    use super::LookupJoinRule;
+    use crate::service::search::datafusion::table_provider::{
+        empty_table::NewEmptyTable, enrichment_table::EnrichmentTable,
+    };
+
+    #[tokio::test]
+    async fn test_lookup_join() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("_timestamp", DataType::Int64, false),
+            Field::new("ip", DataType::Utf8, false),
+        ]));
+        let lookup_schema = Arc::new(Schema::new(vec![
+            Field::new("ip", DataType::Utf8, false),
+            Field::new("country", DataType::Utf8, false),
+        ]));
+
+        let state = SessionStateBuilder::new()
+            .with_config(SessionConfig::new().with_target_partitions(12))
+            .with_runtime_env(Arc::new(RuntimeEnvBuilder::new().build().unwrap()))
+            .with_default_features()
+            .with_physical_optimizer_rule(Arc::new(LookupJoinRule::new()))
+            .build();
+        let ctx = SessionContext::new_with_state(state);
+        let logs = NewEmptyTable::new("logs", schema).with_partitions(12);
+        let lookup = EnrichmentTable::new("default", "ip_table", lookup_schema);
+        ctx.register_table("logs", Arc::new(logs))?;
+        ctx.register_table("ip_table", Arc::new(lookup))?;
+
+        let sql = "SELECT logs.ip, ip_table.country FROM logs LEFT JOIN ip_table ON logs.ip = ip_table.ip";
+        let plan = ctx.state().create_logical_plan(sql).await?;
+        let physical_plan = ctx.state().create_physical_plan(&plan).await?;
+        let plan = get_plan_string(&physical_plan);
+
+        let join = plan.iter().find(|v| v.contains("HashJoinExec")).unwrap();
+        assert!(join.contains("mode=CollectLeft"));
+        assert!(join.contains("join_type=Right"));
+        assert!(!plan.iter().any(|v| v.contains("RepartitionExec")));
+        assert!(plan.iter().any(|v| v.contains("EnrichmentExec")));
+
+        Ok(())
+    }
+}
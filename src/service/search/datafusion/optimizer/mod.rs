@@ -47,6 +47,7 @@ pub mod add_timestamp;
 pub mod cipher;
 pub mod join_reorder;
 pub mod limit_join_right_side;
+pub mod lookup_join;
 pub mod rewrite_histogram;
 pub mod rewrite_match;
 pub mod utils;
@@ -0,0 +1,250 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{any::Any, sync::Arc};
+
+use arrow::{
+    array::{RecordBatch, new_null_array},
+    compute::cast,
+};
+use async_trait::async_trait;
+use config::{
+    RwHashMap,
+    meta::stream::StreamType,
+    utils::{json, record_batch_ext::convert_json_to_record_batch},
+};
+use datafusion::{
+    arrow::datatypes::SchemaRef,
+    catalog::Session,
+    common::{Result, project_schema},
+    datasource::{TableProvider, TableType},
+    error::DataFusionError,
+    physical_plan::ExecutionPlan,
+    prelude::Expr,
+};
+use once_cell::sync::Lazy;
+
+use crate::{
+    common::infra::config::ENRICHMENT_TABLES,
+    service::{
+        db::enrichment_table, search::datafusion::distributed_plan::enrichment_exec::EnrichmentExec,
+    },
+};
+
+/// Enrichment tables converted to arrow, keyed by `org_id/enrichment_tables/name`
+static LOOKUP_TABLES: Lazy<RwHashMap<String, Arc<LookupTable>>> = Lazy::new(Default::default);
+
+#[derive(Debug)]
+pub struct LookupTable {
+    pub version: i64,
+    pub schema: SchemaRef,
+    pub batches: Vec<RecordBatch>,
+}
+
+/// A table provider for enrichment tables used on the build side of a join.
+///
+/// Instead of scanning the files of the table on every querier, the scan is
+/// planned as an [`EnrichmentExec`] which is answered from memory.
+#[derive(Debug, Clone)]
+pub struct EnrichmentTable {
+    org_id: String,
+    name: String,
+    schema: SchemaRef,
+    version: i64,
+}
+
+impl EnrichmentTable {
+    pub fn new(org_id: &str, name: &str, schema: SchemaRef) -> Self {
+        let version = get_version(org_id, name);
+        Self {
+            org_id: org_id.to_string(),
+            name: name.to_string(),
+            schema,
+            version,
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for EnrichmentTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let projected_schema = project_schema(&self.schema, projection)?;
+        Ok(Arc::new(EnrichmentExec::new(
+            &self.org_id,
+            &self.name,
+            projected_schema,
+            self.version,
+        )))
+    }
+}
+
+fn cache_key(org_id: &str, name: &str) -> String {
+    format!("{org_id}/{}/{name}", StreamType::EnrichmentTables)
+}
+
+/// Returns the upload time of the enrichment table cached on this node
+pub fn get_version(org_id: &str, name: &str) -> i64 {
+    ENRICHMENT_TABLES
+        .get(&cache_key(org_id, name))
+        .map(|t| t.updated_at)
+        .unwrap_or_default()
+}
+
+/// Returns the enrichment table as record batches with the given schema.
+///
+/// The arrow representation is cached per node, dropped whenever the
+/// enrichment table is reloaded and rebuilt when its version is older. If the local copy is older
+/// than the `version` the leader planned with, the table is reloaded first.
+pub async fn get_lookup_batches(
+    org_id: &str,
+    name: &str,
+    schema: &SchemaRef,
+    version: i64,
+) -> Result<Vec<RecordBatch>> {
+    let key = cache_key(org_id, name);
+    let local_version = get_version(org_id, name);
+    if let Some(table) = LOOKUP_TABLES.get(&key) {
+        if table.version >= version.max(local_version) {
+            return project_batches(&table, schema);
+        }
+    }
+
+    if local_version < version {
+        log::info!(
+            "[LOOKUP_JOIN] enrichment table {key} is older than version {version}, reloading"
+        );
+        let table = enrichment_table::get_table(org_id, name)
+            .await
+            .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+        ENRICHMENT_TABLES.insert(key.clone(), table);
+    }
+
+    let table = Arc::new(build_lookup_table(org_id, name, &key).await?);
+    LOOKUP_TABLES.insert(key, table.clone());
+    project_batches(&table, schema)
+}
+
+/// Drops the cached arrow representation of an enrichment table
+pub fn remove(org_id: &str, name: &str) {
+    LOOKUP_TABLES.remove(&cache_key(org_id, name));
+}
+
+async fn build_lookup_table(org_id: &str, name: &str, key: &str) -> Result<LookupTable> {
+    let schema = infra::schema::get(org_id, name, StreamType::EnrichmentTables)
+        .await
+        .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+    let schema = Arc::new(schema.with_metadata(Default::default()));
+    let Some(table) = ENRICHMENT_TABLES.get(key).map(|t| t.clone()) else {
+        return Ok(LookupTable {
+            version: 0,
+            schema,
+            batches: vec![],
+        });
+    };
+
+    let rows = table
+        .data
+        .iter()
+        .filter(|v| v.is_object())
+        .map(|v| json::to_value(v).map(Arc::new))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+    let batches = if rows.is_empty() {
+        vec![]
+    } else {
+        vec![convert_json_to_record_batch(&schema, &rows)?]
+    };
+    Ok(LookupTable {
+        version: table.updated_at,
+        schema,
+        batches,
+    })
+}
+
+// pick the requested columns by name, the cached table always uses the latest
+// schema of the enrichment table
+fn project_batches(table: &LookupTable, schema: &SchemaRef) -> Result<Vec<RecordBatch>> {
+    let mut batches = Vec::with_capacity(table.batches.len());
+    for batch in table.batches.iter() {
+        let mut columns = Vec::with_capacity(schema.fields().len());
+        for field in schema.fields() {
+            let column = match batch.column_by_name(field.name()) {
+                Some(column) if column.data_type() == field.data_type() => column.clone(),
+                Some(column) => cast(column, field.data_type())?,
+                None => new_null_array(field.data_type(), batch.num_rows()),
+            };
+            columns.push(column);
+        }
+        batches.push(RecordBatch::try_new(schema.clone(), columns)?);
+    }
+    Ok(batches)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int64Array, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+
+    use super::*;
+
+    #[test]
+    fn test_project_batches() {
+        let full_schema = Arc::new(Schema::new(vec![
+            Field::new("ip", DataType::Utf8, true),
+            Field::new("score", DataType::Int64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            full_schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["10.0.0.1", "10.0.0.2"])),
+                Arc::new(Int64Array::from(vec![1, 2])),
+            ],
+        )
+        .unwrap();
+        let table = LookupTable {
+            version: 1,
+            schema: full_schema,
+            batches: vec![batch],
+        };
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("score", DataType::Utf8, true),
+            Field::new("country", DataType::Utf8, true),
+        ]));
+        let batches = project_batches(&table, &schema).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].schema(), schema);
+        assert_eq!(batches[0].num_rows(), 2);
+        assert_eq!(batches[0].column(1).null_count(), 2);
+    }
+}
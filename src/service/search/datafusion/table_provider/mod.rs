@@ -66,6 +66,7 @@ use crate::service::search::index::IndexCondition;
 
 pub mod catalog;
 pub mod empty_table;
+pub mod enrichment_table;
 mod helpers;
 pub mod memtable;
 pub mod uniontable;
@@ -51,7 +51,10 @@ use crate::service::{
         datafusion::{
             distributed_plan::{
                 NewEmptyExecVisitor, ReplaceTableScanExec,
-                codec::{ComposedPhysicalExtensionCodec, EmptyExecPhysicalExtensionCodec},
+                codec::{
+                    ComposedPhysicalExtensionCodec, EmptyExecPhysicalExtensionCodec,
+                    EnrichmentExecPhysicalExtensionCodec,
+                },
                 empty_exec::NewEmptyExec,
             },
             exec::{prepare_datafusion_context, register_udf},
@@ -89,7 +92,10 @@ pub async fn search(
 
     // Decode physical plan from bytes
     let proto = ComposedPhysicalExtensionCodec {
-        codecs: vec![Arc::new(EmptyExecPhysicalExtensionCodec {})],
+        codecs: vec![
+            Arc::new(EmptyExecPhysicalExtensionCodec {}),
+            Arc::new(EnrichmentExecPhysicalExtensionCodec {}),
+        ],
     };
     let mut physical_plan =
         physical_plan_from_bytes_with_extension_codec(&req.search_info.plan, &ctx, &proto)?;
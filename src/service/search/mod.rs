@@ -31,7 +31,7 @@ use config::{
     utils::{
         base64, json,
         schema::filter_source_by_partition_key,
-        sql::{is_aggregate_query, is_simple_aggregate_query},
+        sql::{is_aggregate_query, is_simple_aggregate_query, parse_explain},
     },
 };
 use datafusion::distributed_plan::streaming_aggs_exec;
@@ -69,6 +69,7 @@ pub(crate) mod datafusion;
 pub(crate) mod grpc;
 pub(crate) mod grpc_search;
 pub(crate) mod index;
+pub(crate) mod quota;
 pub(crate) mod request;
 pub(crate) mod sql;
 #[cfg(feature = "enterprise")]
@@ -97,15 +98,37 @@ pub static DATAFUSION_RUNTIME: Lazy<Runtime> = Lazy::new(|| {
         .unwrap()
 });
 
+/// Searches and masks the hits with the masking policies of the user, all the
+/// searches of the users go through it or through [cache::search].
 // Please note: `query_fn` which is the vrl needs to be base64::decoded
 // when using this search
-#[tracing::instrument(name = "service:search:enter", skip_all)]
 pub async fn search(
     trace_id: &str,
     org_id: &str,
     stream_type: StreamType,
     user_id: Option<String>,
     in_req: &search::Request,
+) -> Result<search::Response, Error> {
+    let mut res = search_unmasked(trace_id, org_id, stream_type, user_id.clone(), in_req).await?;
+    crate::service::redaction::mask_search_response(
+        org_id,
+        user_id.as_deref(),
+        &in_req.query,
+        &mut res,
+    )
+    .await;
+    Ok(res)
+}
+
+/// Searches without masking the hits, for the result cache which is shared by
+/// all the users and masks the hits it returns
+#[tracing::instrument(name = "service:search:enter", skip_all)]
+pub(crate) async fn search_unmasked(
+    trace_id: &str,
+    org_id: &str,
+    stream_type: StreamType,
+    user_id: Option<String>,
+    in_req: &search::Request,
 ) -> Result<search::Response, Error> {
     let start = std::time::Instant::now();
     let started_at = chrono::Utc::now().timestamp_micros();
@@ -158,7 +181,15 @@ pub async fn search(
     #[cfg(feature = "enterprise")]
     let req_clusters = in_req.clusters.clone();
 
-    let query: SearchQuery = in_req.query.clone().into();
+    let mut query: SearchQuery = in_req.query.clone().into();
+    // EXPLAIN [ANALYZE] returns the plans of the query instead of the hits
+    let (explain, explain_sql) = parse_explain(&query.sql);
+    if explain.is_some() {
+        query.sql = explain_sql.to_string();
+        query.track_total_hits = false;
+        query.uses_zo_fn = false;
+        query.query_fn = "".to_string();
+    }
     let req_query = query.clone();
     let mut request = crate::service::search::request::Request::new(
         trace_id.clone(),
@@ -175,6 +206,7 @@ pub async fn search(
     if let Some(v) = in_req.local_mode {
         request.set_local_mode(Some(v));
     }
+    request.set_explain(explain);
     let span = tracing::span::Span::current();
     let handle = tokio::task::spawn(
         async move { cluster::http::search(request, query, req_regions, req_clusters, true).await }
@@ -560,6 +592,11 @@ pub async fn search_partition(
         start_time: req.start_time,
         end_time: req.end_time,
         sql: req.sql.to_string(),
+        params: req
+            .params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
         ..Default::default()
     };
     let sql = Sql::new(&query, org_id, stream_type).await?;
@@ -1124,6 +1161,7 @@ pub async fn search_partition_multi(
                 clusters: req.clusters.clone(),
                 query_fn: req.query_fn.clone(),
                 streaming_output: req.streaming_output,
+                params: Default::default(),
             },
             false,
         )
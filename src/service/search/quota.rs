@@ -0,0 +1,276 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Query governance of the leader node: concurrent queries per org and per
+//! user, scanned bytes and time range of a query. The memory budget of a query
+//! is enforced by the DataFusion memory pool, see `prepare_datafusion_context`.
+//!
+//! The limits come from the config and can be overridden by the settings of
+//! the org. They are enforced by each node for the queries it leads and aren't
+//! shared by the cluster, with N queriers an org can run up to N times its
+//! concurrency limit.
+
+use std::sync::Arc;
+
+use config::{RwHashMap, get_config, metrics};
+use infra::errors::{Error, ErrorCodes, Result};
+use once_cell::sync::Lazy;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{
+    common::infra::config::ORGANIZATION_SETTING, service::db::organization::ORG_SETTINGS_KEY_PREFIX,
+};
+
+static ORG_QUERIES: Lazy<RwHashMap<String, Slots>> = Lazy::new(Default::default);
+static USER_QUERIES: Lazy<RwHashMap<String, Slots>> = Lazy::new(Default::default);
+
+/// Query slots of an org or a user, created with the limit in force and
+/// removed once no query holds or waits for one
+#[derive(Debug)]
+struct Slots {
+    limit: usize,
+    semaphore: Arc<Semaphore>,
+}
+
+impl Slots {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            semaphore: Arc::new(Semaphore::new(limit)),
+        }
+    }
+}
+
+/// Slot of a running query, released on drop
+#[derive(Debug, Default)]
+pub struct QueryPermit {
+    _org: Option<SlotPermit>,
+    _user: Option<SlotPermit>,
+}
+
+#[derive(Debug)]
+struct SlotPermit {
+    slots: &'static RwHashMap<String, Slots>,
+    key: String,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for SlotPermit {
+    fn drop(&mut self) {
+        drop(self.permit.take());
+        // the map holds the last reference of idle slots
+        self.slots
+            .remove_if(&self.key, |_, v| Arc::strong_count(&v.semaphore) == 1);
+    }
+}
+
+/// Query limits of an org, 0 means no limit
+#[derive(Debug)]
+struct QueryLimits {
+    max_concurrent_per_org: usize,
+    max_concurrent_per_user: usize,
+    max_scan_size: usize,
+    max_time_range: i64,
+}
+
+async fn get_limits(org_id: &str) -> QueryLimits {
+    let cfg = get_config();
+    let mut limits = QueryLimits {
+        max_concurrent_per_org: cfg.limit.query_max_concurrent_per_org,
+        max_concurrent_per_user: cfg.limit.query_max_concurrent_per_user,
+        max_scan_size: cfg.limit.query_max_scan_size,
+        max_time_range: cfg.limit.query_max_time_range,
+    };
+    let key = format!("{ORG_SETTINGS_KEY_PREFIX}/{org_id}");
+    if let Some(setting) = ORGANIZATION_SETTING.read().await.get(&key) {
+        if let Some(v) = setting.query_max_concurrent_per_org {
+            limits.max_concurrent_per_org = v;
+        }
+        if let Some(v) = setting.query_max_concurrent_per_user {
+            limits.max_concurrent_per_user = v;
+        }
+        if let Some(v) = setting.query_max_scan_size {
+            limits.max_scan_size = v;
+        }
+        if let Some(v) = setting.query_max_time_range {
+            limits.max_time_range = v;
+        }
+    }
+    limits
+}
+
+/// Waits for a query slot of the org and the user.
+///
+/// Queries over the limit wait up to `ZO_QUERY_QUOTA_WAIT_TIMEOUT` seconds
+/// (bounded by the query timeout) and are rejected after that.
+pub async fn acquire(
+    trace_id: &str,
+    org_id: &str,
+    user_id: Option<&str>,
+    query_timeout: u64,
+) -> Result<QueryPermit> {
+    let limits = get_limits(org_id).await;
+    let wait = get_config()
+        .limit
+        .query_quota_wait_timeout
+        .min(query_timeout);
+    let org = acquire_slot(&ORG_QUERIES, org_id, limits.max_concurrent_per_org, wait)
+        .await
+        .map_err(|_| {
+            log::warn!("[trace_id {trace_id}] search->quota: org {org_id} is over the concurrency");
+            metrics::QUERY_QUOTA_REJECTED_NUMS
+                .with_label_values(&[org_id, "org_concurrency"])
+                .inc();
+            quota_error(format!(
+                "too many concurrent queries in organization {org_id}, the limit is {}",
+                limits.max_concurrent_per_org
+            ))
+        })?;
+    let user = match user_id.filter(|v| !v.is_empty()) {
+        Some(user_id) => acquire_slot(
+            &USER_QUERIES,
+            &format!("{org_id}/{user_id}"),
+            limits.max_concurrent_per_user,
+            wait,
+        )
+        .await
+        .map_err(|_| {
+            log::warn!(
+                "[trace_id {trace_id}] search->quota: user {user_id} is over the concurrency"
+            );
+            metrics::QUERY_QUOTA_REJECTED_NUMS
+                .with_label_values(&[org_id, "user_concurrency"])
+                .inc();
+            quota_error(format!(
+                "too many concurrent queries of user {user_id}, the limit is {}",
+                limits.max_concurrent_per_user
+            ))
+        })?,
+        None => None,
+    };
+    Ok(QueryPermit {
+        _org: org,
+        _user: user,
+    })
+}
+
+async fn acquire_slot(
+    slots: &'static RwHashMap<String, Slots>,
+    key: &str,
+    limit: usize,
+    wait: u64,
+) -> std::result::Result<Option<SlotPermit>, ()> {
+    if limit == 0 {
+        return Ok(None);
+    }
+    let semaphore = {
+        let mut entry = slots
+            .entry(key.to_string())
+            .or_insert_with(|| Slots::new(limit));
+        // the limit changed, the running queries release the slots of the
+        // previous semaphore
+        if entry.limit != limit {
+            *entry = Slots::new(limit);
+        }
+        entry.semaphore.clone()
+    };
+    let permit = match semaphore.clone().try_acquire_owned() {
+        Ok(permit) => permit,
+        Err(_) if wait == 0 => return Err(()),
+        Err(_) => match tokio::time::timeout(
+            std::time::Duration::from_secs(wait),
+            semaphore.acquire_owned(),
+        )
+        .await
+        {
+            Ok(Ok(permit)) => permit,
+            _ => return Err(()),
+        },
+    };
+    Ok(Some(SlotPermit {
+        slots,
+        key: key.to_string(),
+        permit: Some(permit),
+    }))
+}
+
+/// Rejects queries over the max time range
+pub async fn check_time_range(org_id: &str, time_range: Option<(i64, i64)>) -> Result<()> {
+    let max_hours = get_limits(org_id).await.max_time_range;
+    let Some((start_time, end_time)) = time_range else {
+        return Ok(());
+    };
+    if max_hours > 0 && end_time - start_time > max_hours * 3600 * 1_000_000 {
+        metrics::QUERY_QUOTA_REJECTED_NUMS
+            .with_label_values(&[org_id, "time_range"])
+            .inc();
+        return Err(quota_error(format!(
+            "query time range exceeds the limit of {max_hours} hours"
+        )));
+    }
+    Ok(())
+}
+
+/// Rejects queries which need to scan more than the max scan size
+pub async fn check_scan_size(org_id: &str, scan_size: i64) -> Result<()> {
+    let max_size = get_limits(org_id).await.max_scan_size;
+    if max_size > 0 && scan_size > (max_size * 1024 * 1024) as i64 {
+        metrics::QUERY_QUOTA_REJECTED_NUMS
+            .with_label_values(&[org_id, "scan_size"])
+            .inc();
+        return Err(quota_error(format!(
+            "query needs to scan {} MB, exceeds the limit of {max_size} MB, please narrow the time range or add filters",
+            scan_size / 1024 / 1024
+        )));
+    }
+    Ok(())
+}
+
+fn quota_error(msg: String) -> Error {
+    Error::ErrorCode(ErrorCodes::SearchQuotaExceeded(msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_acquire_slot() {
+        let slots: &'static RwHashMap<String, Slots> = Box::leak(Box::default());
+        let first = acquire_slot(slots, "org", 1, 0).await.unwrap();
+        assert!(first.is_some());
+        assert!(acquire_slot(slots, "org", 1, 0).await.is_err());
+        assert!(acquire_slot(slots, "other", 1, 0).await.unwrap().is_some());
+        // the idle slots are removed
+        assert!(!slots.contains_key("other"));
+        drop(first);
+        assert!(slots.is_empty());
+        assert!(acquire_slot(slots, "org", 1, 0).await.unwrap().is_some());
+        // no limit
+        assert!(acquire_slot(slots, "org", 0, 0).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_slot_limit_changed() {
+        let slots: &'static RwHashMap<String, Slots> = Box::leak(Box::default());
+        let first = acquire_slot(slots, "org", 1, 0).await.unwrap();
+        assert!(acquire_slot(slots, "org", 1, 0).await.is_err());
+        let second = acquire_slot(slots, "org", 2, 0).await.unwrap();
+        assert!(second.is_some());
+        assert!(acquire_slot(slots, "org", 2, 0).await.unwrap().is_some());
+        drop((first, second));
+        assert!(slots.is_empty());
+    }
+}
@@ -13,7 +13,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use config::meta::stream::StreamType;
+use config::{meta::stream::StreamType, utils::sql::ExplainMode};
 use proto::cluster_rpc::{self, IndexInfo, QueryIdentifier, SearchInfo, SuperClusterInfo};
 
 #[derive(Debug, Clone)]
@@ -30,6 +30,7 @@ pub struct Request {
     pub streaming_output: bool,
     pub streaming_id: Option<String>,
     pub local_mode: Option<bool>,
+    pub explain: Option<ExplainMode>,
 }
 
 impl Default for Request {
@@ -47,6 +48,7 @@ impl Default for Request {
             streaming_output: false,
             streaming_id: None,
             local_mode: None,
+            explain: None,
         }
     }
 }
@@ -75,6 +77,7 @@ impl Request {
             streaming_output: false,
             streaming_id: None,
             local_mode: None,
+            explain: None,
         }
     }
 
@@ -106,6 +109,10 @@ impl Request {
     pub fn set_local_mode(&mut self, local_mode: Option<bool>) {
         self.local_mode = local_mode;
     }
+
+    pub fn set_explain(&mut self, explain: Option<ExplainMode>) {
+        self.explain = explain;
+    }
 }
 
 impl From<FlightSearchRequest> for Request {
@@ -123,6 +130,7 @@ impl From<FlightSearchRequest> for Request {
             streaming_output: false,
             streaming_id: None,
             local_mode: req.super_cluster_info.local_mode,
+            explain: None,
         }
     }
 }
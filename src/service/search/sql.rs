@@ -104,7 +104,9 @@ impl Sql {
         stream_type: StreamType,
     ) -> Result<Sql, Error> {
         let cfg = get_config();
-        let sql = query.sql.clone();
+        // 0. expand the sql macros and parameters
+        let params = crate::service::sql_macros::decode_params(&query.params);
+        let sql = crate::service::sql_macros::expand(org_id, &query.sql, &params)?;
         let limit = query.size as i64;
         let offset = query.from as i64;
 
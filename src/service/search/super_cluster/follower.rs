@@ -47,7 +47,10 @@ use crate::service::search::{
     datafusion::{
         distributed_plan::{
             NewEmptyExecVisitor,
-            codec::{ComposedPhysicalExtensionCodec, EmptyExecPhysicalExtensionCodec},
+            codec::{
+                ComposedPhysicalExtensionCodec, EmptyExecPhysicalExtensionCodec,
+                EnrichmentExecPhysicalExtensionCodec,
+            },
             empty_exec::NewEmptyExec,
             node::{RemoteScanNode, SearchInfos},
             remote_scan::RemoteScanExec,
@@ -92,7 +95,10 @@ pub async fn search(
 
     // Decode physical plan from bytes
     let proto = ComposedPhysicalExtensionCodec {
-        codecs: vec![Arc::new(EmptyExecPhysicalExtensionCodec {})],
+        codecs: vec![
+            Arc::new(EmptyExecPhysicalExtensionCodec {}),
+            Arc::new(EnrichmentExecPhysicalExtensionCodec {}),
+        ],
     };
     let mut physical_plan = physical_plan_from_bytes_with_extension_codec(
         &flight_request.search_info.plan,
@@ -227,6 +233,7 @@ pub async fn search(
         start_time: req.time_range.as_ref().map(|x| x.0).unwrap_or(0),
         end_time: req.time_range.as_ref().map(|x| x.1).unwrap_or(0),
         timeout: req.timeout as u64,
+        is_analyze: flight_request.search_info.is_analyze,
     };
 
     let context = tracing::Span::current().context();
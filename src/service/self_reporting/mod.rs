@@ -24,7 +24,9 @@ use config::{
         self_reporting::{
             ReportingData,
             error::ErrorData,
-            usage::{RequestStats, TriggerData, UsageData, UsageEvent, UsageType},
+            usage::{
+                PipelineNodeData, RequestStats, TriggerData, UsageData, UsageEvent, UsageType,
+            },
         },
         stream::StreamType,
     },
@@ -222,6 +224,29 @@ pub async fn publish_triggers_usage(trigger: TriggerData) {
     }
 }
 
+pub async fn publish_pipeline_node_usage(node_data: PipelineNodeData) {
+    let cfg = get_config();
+    if !cfg.common.usage_enabled {
+        return;
+    }
+
+    match queues::USAGE_QUEUE
+        .enqueue(ReportingData::PipelineNode(Box::new(node_data)))
+        .await
+    {
+        Err(e) => {
+            log::error!(
+                "[SELF-REPORTING] Failed to send pipeline node usage data to background ingesting job: {e}"
+            )
+        }
+        Ok(()) => {
+            log::debug!(
+                "[SELF-REPORTING] Successfully queued pipeline node usage data to be ingested"
+            )
+        }
+    }
+}
+
 pub async fn publish_error(error_data: ErrorData) {
     let cfg = get_config();
     if !cfg.common.usage_enabled {
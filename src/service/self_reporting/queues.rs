@@ -13,7 +13,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use config::{
     META_ORG_ID, get_config,
@@ -21,7 +21,10 @@ use config::{
         self_reporting::{
             ReportingData, ReportingMessage, ReportingQueue, ReportingRunner,
             error::ErrorData,
-            usage::{ERROR_STREAM, TRIGGERS_USAGE_STREAM, TriggerData},
+            usage::{
+                ERROR_STREAM, PIPELINES_USAGE_STREAM, PipelineNodeData, TRIGGERS_USAGE_STREAM,
+                TriggerData,
+            },
         },
         stream::{StreamParams, StreamType},
     },
@@ -144,6 +147,7 @@ async fn ingest_buffered_data(thread_id: usize, buffered: Vec<ReportingData>) {
         buffered.len()
     );
 
+    let mut pipeline_nodes: HashMap<(String, String, String), PipelineNodeData> = HashMap::new();
     let (usages, triggers, errors) = buffered.into_iter().fold(
         (Vec::new(), Vec::new(), Vec::new()),
         |(mut usages, mut triggers, mut errors), item| {
@@ -151,6 +155,20 @@ async fn ingest_buffered_data(thread_id: usize, buffered: Vec<ReportingData>) {
                 ReportingData::Usage(usage) => usages.push(*usage),
                 ReportingData::Trigger(trigger) => triggers.push(json::to_value(*trigger).unwrap()),
                 ReportingData::Error(error) => errors.push(json::to_value(*error).unwrap()),
+                ReportingData::PipelineNode(node) => {
+                    // one record per node for all the batches buffered
+                    let key = (
+                        node.org.clone(),
+                        node.pipeline_id.clone(),
+                        node.node_id.clone(),
+                    );
+                    match pipeline_nodes.get_mut(&key) {
+                        Some(existing) => existing.merge(&node),
+                        None => {
+                            pipeline_nodes.insert(key, *node);
+                        }
+                    }
+                }
             }
             (usages, triggers, errors)
         },
@@ -200,6 +218,33 @@ async fn ingest_buffered_data(thread_id: usize, buffered: Vec<ReportingData>) {
         }
     }
 
+    if !pipeline_nodes.is_empty() {
+        let pipeline_stream =
+            StreamParams::new(META_ORG_ID, PIPELINES_USAGE_STREAM, StreamType::Logs);
+        let pipeline_nodes: Vec<json::Value> = pipeline_nodes
+            .into_values()
+            .map(|node| json::to_value(node).unwrap())
+            .collect();
+        if super::ingestion::ingest_reporting_data(pipeline_nodes.clone(), pipeline_stream)
+            .await
+            .is_err()
+            && &cfg.common.usage_reporting_mode != "both"
+        {
+            // on error in ingesting pipeline node data, push back the data
+            for node_json in pipeline_nodes {
+                let node: PipelineNodeData = json::from_value(node_json).unwrap();
+                if let Err(e) = USAGE_QUEUE
+                    .enqueue(ReportingData::PipelineNode(Box::new(node)))
+                    .await
+                {
+                    log::error!(
+                        "[SELF-REPORTING] Error in pushing back un-ingested PipelineNodeData to UsageQueue: {e}"
+                    );
+                }
+            }
+        }
+    }
+
     if !errors.is_empty() {
         let error_stream = StreamParams::new(META_ORG_ID, ERROR_STREAM, StreamType::Logs);
         if super::ingestion::ingest_reporting_data(errors.clone(), error_stream)
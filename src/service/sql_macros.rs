@@ -0,0 +1,162 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use config::{
+    meta::{search, sql_macro::SqlMacro},
+    utils::json,
+};
+use infra::errors::{Error, ErrorCodes};
+
+use crate::{common::infra::config::SQL_MACROS, service::db};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SqlMacroError {
+    #[error("Invalid SQL macro: {0}")]
+    Invalid(String),
+    #[error("SQL macro {0} already exists")]
+    AlreadyExists(String),
+    #[error("SQL macro {0} not found")]
+    NotFound(String),
+    #[error("Error saving SQL macro: {0}")]
+    Db(#[from] anyhow::Error),
+}
+
+pub async fn create(org_id: &str, mut sql_macro: SqlMacro) -> Result<SqlMacro, SqlMacroError> {
+    sql_macro.name = sql_macro.name.trim().to_string();
+    sql_macro.validate().map_err(SqlMacroError::Invalid)?;
+    if get_cached(org_id, &sql_macro.name).is_some() {
+        return Err(SqlMacroError::AlreadyExists(sql_macro.name));
+    }
+    db::sql_macros::set(org_id, &sql_macro).await?;
+    Ok(sql_macro)
+}
+
+pub async fn update(
+    org_id: &str,
+    name: &str,
+    mut sql_macro: SqlMacro,
+) -> Result<SqlMacro, SqlMacroError> {
+    sql_macro.name = name.to_string();
+    sql_macro.validate().map_err(SqlMacroError::Invalid)?;
+    if db::sql_macros::get(org_id, name).await.is_err() {
+        return Err(SqlMacroError::NotFound(name.to_string()));
+    }
+    db::sql_macros::set(org_id, &sql_macro).await?;
+    Ok(sql_macro)
+}
+
+pub async fn get(org_id: &str, name: &str) -> Result<SqlMacro, SqlMacroError> {
+    db::sql_macros::get(org_id, name)
+        .await
+        .map_err(|_| SqlMacroError::NotFound(name.to_string()))
+}
+
+pub async fn list(org_id: &str) -> Result<Vec<SqlMacro>, SqlMacroError> {
+    Ok(db::sql_macros::list(org_id).await?)
+}
+
+pub async fn delete(org_id: &str, name: &str) -> Result<(), SqlMacroError> {
+    if db::sql_macros::get(org_id, name).await.is_err() {
+        return Err(SqlMacroError::NotFound(name.to_string()));
+    }
+    db::sql_macros::delete(org_id, name).await?;
+    Ok(())
+}
+
+fn get_cached(org_id: &str, name: &str) -> Option<SqlMacro> {
+    SQL_MACROS
+        .get(&format!("{org_id}/{name}"))
+        .map(|v| v.value().clone())
+}
+
+/// Expands the sql macros and parameters of the org in the sql, see
+/// [`config::meta::sql_macro::expand`]
+pub fn expand(
+    org_id: &str,
+    sql: &str,
+    params: &HashMap<String, json::Value>,
+) -> Result<String, Error> {
+    config::meta::sql_macro::expand(sql, params, |name| get_cached(org_id, name))
+        .map_err(|e| Error::ErrorCode(ErrorCodes::SearchSQLNotValid(e)))
+}
+
+/// Checks that the macros and parameters of a stored query, which has no
+/// parameter values of its own, expand with the defaults
+pub fn validate(org_id: &str, sql: &str) -> Result<(), String> {
+    config::meta::sql_macro::expand(sql, &HashMap::new(), |name| get_cached(org_id, name))
+        .map(|_| ())
+}
+
+/// Expands the sql macros and parameters of the query in place. The search
+/// handlers expand the query before resolving its streams, so that the streams
+/// read by the macros are checked for permissions too.
+pub fn expand_query(org_id: &str, query: &mut search::Query) -> Result<(), Error> {
+    expand_sql(org_id, &mut query.sql, &mut query.params)
+}
+
+/// Expands the sql macros and parameters in place, the parameters are used up
+pub fn expand_sql(
+    org_id: &str,
+    sql: &mut String,
+    params: &mut HashMap<String, json::Value>,
+) -> Result<(), Error> {
+    if sql.contains('$') {
+        *sql = expand(org_id, sql, params)?;
+    }
+    params.clear();
+    Ok(())
+}
+
+/// Decodes the json encoded parameter values of a [`proto::cluster_rpc::SearchQuery`],
+/// a value which isn't valid json is taken as a string
+pub fn decode_params(params: &HashMap<String, String>) -> HashMap<String, json::Value> {
+    params
+        .iter()
+        .map(|(k, v)| {
+            let value = json::from_str(v).unwrap_or_else(|_| json::Value::String(v.to_string()));
+            (k.to_string(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use config::meta::sql::resolve_stream_names;
+
+    use super::*;
+
+    #[test]
+    fn test_expand_query_streams() {
+        SQL_MACROS.insert(
+            "macro_org/failed_users".to_string(),
+            SqlMacro {
+                name: "failed_users".to_string(),
+                sql: Some("SELECT user_id FROM audit WHERE status = 'failed'".to_string()),
+                ..Default::default()
+            },
+        );
+        let mut query = search::Query {
+            sql: "SELECT * FROM logs WHERE user_id IN $failed_users".to_string(),
+            ..Default::default()
+        };
+        expand_query("macro_org", &mut query).unwrap();
+        // the stream read by the macro is checked for permissions as well
+        let mut streams = resolve_stream_names(&query.sql).unwrap();
+        streams.sort();
+        assert_eq!(streams, vec!["audit".to_string(), "logs".to_string()]);
+    }
+}
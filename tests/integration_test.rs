@@ -160,6 +160,7 @@ mod tests {
         // search
         e2e_search().await;
         e2e_search_around().await;
+        e2e_search_cursor().await;
 
         // users
         e2e_post_user().await;
@@ -581,6 +582,63 @@ mod tests {
         assert!(resp.status().is_success());
     }
 
+    async fn e2e_search_cursor() {
+        let auth = setup();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::JsonConfig::default().limit(get_config().limit.req_json_limit))
+                .app_data(web::PayloadConfig::new(
+                    get_config().limit.req_payload_limit,
+                ))
+                .configure(get_service_routes)
+                .configure(get_basic_routes),
+        )
+        .await;
+        let end_time = Utc::now().timestamp_micros();
+        let start_time = end_time - Duration::hours(1).num_microseconds().unwrap();
+        let search = |query: json::Value| {
+            let req = test::TestRequest::post()
+                .uri(&format!("/api/{}/_search", "e2e"))
+                .insert_header(ContentType::json())
+                .append_header(auth)
+                .set_payload(json::json!({ "query": query }).to_string())
+                .to_request();
+            test::call_and_read_body(&app, req)
+        };
+
+        // the records ingested above are still in the WAL
+        let body = search(json::json!({
+            "sql": "select count(*) as cnt from olympics_schema",
+            "start_time": start_time,
+            "end_time": end_time,
+        }))
+        .await;
+        let body: json::Value = json::from_slice(&body).unwrap();
+        let total = body["hits"][0]["cnt"].as_u64().unwrap();
+        assert!(total > 0);
+
+        let mut paged = 0;
+        let mut cursor: Option<String> = None;
+        loop {
+            let body = search(json::json!({
+                "sql": "select * from olympics_schema order by _timestamp desc",
+                "size": 1000,
+                "start_time": start_time,
+                "end_time": end_time,
+                "use_cursor": true,
+                "cursor": cursor,
+            }))
+            .await;
+            let body: json::Value = json::from_slice(&body).unwrap();
+            paged += body["hits"].as_array().unwrap().len() as u64;
+            cursor = body["cursor"].as_str().map(|v| v.to_string());
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(paged, total);
+    }
+
     async fn e2e_list_users() {
         let auth = setup();
         let app = test::init_service(